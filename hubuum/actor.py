@@ -0,0 +1,52 @@
+"""Attribution of writes to the actor performing them.
+
+The actor is request-scoped. It is set when a request arrives (with the source IP
+only), and updated once the request is authenticated. Writes outside of requests,
+ie from management commands or background jobs, are attributed to the system actor.
+"""
+import dataclasses
+from contextvars import ContextVar
+from typing import Optional
+
+
+@dataclasses.dataclass(frozen=True)
+class Actor:
+    """The actor performing a write, the authenticated user and their token."""
+
+    user: Optional[str] = None
+    token_id: Optional[str] = None
+    ip: Optional[str] = None
+
+    @classmethod
+    def from_request(cls, request, user=None, token=None):
+        """Create an actor from a request, and optionally the authenticated user."""
+        username = user.username if user is not None else None
+        return cls(
+            user=username,
+            token_id=getattr(token, "token_key", None),
+            ip=getattr(request, "client_ip", None) or request.META.get("REMOTE_ADDR"),
+        )
+
+    def as_dict(self):
+        """Return the actor as a dictionary, suitable for logging."""
+        return dataclasses.asdict(self)
+
+
+SYSTEM = Actor(user="system")
+
+_actor = ContextVar("hubuum_actor", default=SYSTEM)
+
+
+def get_actor():
+    """Return the current actor."""
+    return _actor.get()
+
+
+def set_actor(actor):
+    """Set the current actor, returns a token to pass to reset_actor."""
+    return _actor.set(actor)
+
+
+def reset_actor(token):
+    """Reset the current actor to what it was before set_actor returned token."""
+    _actor.reset(token)
@@ -2,7 +2,7 @@
 
 from django.contrib import admin
 
-from hubuum.models.base import (
+from hubuum.models.objects import (
     Host,
     HostType,
     Jack,
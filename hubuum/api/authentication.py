@@ -0,0 +1,55 @@
+"""Authentication classes for hubuum that record the authenticated actor."""
+from knox.auth import TokenAuthentication
+from rest_framework.authentication import BasicAuthentication
+from rest_framework.exceptions import AuthenticationFailed
+
+from hubuum.actor import Actor, set_actor
+from hubuum.models.auth import TokenMetadata
+
+
+class ActorAuthenticationMixin:  # pylint: disable=too-few-public-methods
+    """Set the actor for the request once it has been authenticated.
+
+    The user is recorded as seen (see User.last_seen_at), and auth_method names the
+    method in the login history of users (see LoginEvent).
+    """
+
+    auth_method = ""
+
+    def authenticate(self, request):
+        """Authenticate the request, and record the actor."""
+        result = super().authenticate(request)
+        if result is not None:
+            user, token = result
+            set_actor(Actor.from_request(request, user=user, token=token))
+            user.record_seen()
+        return result
+
+
+class ActorTokenAuthentication(ActorAuthenticationMixin, TokenAuthentication):
+    """Knox token authentication, recording the actor and the use of the token."""
+
+    auth_method = "token"
+
+    def authenticate(self, request):
+        """Authenticate the request, and record the use of the token.
+
+        Tokens bound to networks (see TokenMetadata.bind_cidr) are rejected from
+        addresses outside of them. The address is that of the client, as found by
+        ClientIPMiddleware using the trusted proxies.
+        """
+        result = super().authenticate(request)
+        if result is not None:
+            address = getattr(request, "client_ip", None) or request.META.get(
+                "REMOTE_ADDR", ""
+            )
+            if not TokenMetadata.accepts(result[1], address):
+                raise AuthenticationFailed(f"The token is not valid from {address}.")
+            TokenMetadata.record_use(result[1], request)
+        return result
+
+
+class ActorBasicAuthentication(ActorAuthenticationMixin, BasicAuthentication):
+    """Basic authentication, recording the actor."""
+
+    auth_method = "basic"
@@ -1,16 +1,39 @@
 """Non-versioned URLs for hubuum."""
 
 from django.urls import re_path
-from knox import views as knox_views
 
 from . import views
 
 urlpatterns = [
-    re_path(r"auth/login/", views.LoginView.as_view(), name="knox_login"),
-    re_path(r"auth/logout/", knox_views.LogoutView.as_view(), name="knox_logout"),
+    re_path(r"^auth/login/$", views.LoginView.as_view(), name="knox_login"),
+    re_path(r"^auth/logout/$", views.LogoutView.as_view(), name="knox_logout"),
     re_path(
-        r"auth/logoutall/",
-        knox_views.LogoutAllView.as_view(),
+        r"^auth/logoutall/$",
+        views.LogoutAllView.as_view(),
         name="knox_logoutall",
     ),
+    re_path(r"^auth/logins/$", views.LoginList.as_view(), name="logins"),
+    re_path(r"^auth/tokens/$", views.TokenList.as_view(), name="tokens"),
+    re_path(
+        r"^auth/tokens/(?P<token_key>\w+)$",
+        views.TokenDetail.as_view(),
+        name="token",
+    ),
+    re_path(r"^metrics/$", views.MetricsView.as_view(), name="metrics"),
+    re_path(r"^denials/$", views.DenialsView.as_view(), name="denials"),
+    re_path(r"^server-info/$", views.ServerInfoView.as_view(), name="server_info"),
+    re_path(r"^maintenance/$", views.MaintenanceView.as_view(), name="maintenance"),
+    re_path(r"^read-only/$", views.ReadOnlyView.as_view(), name="read_only"),
+    re_path(
+        r"^read-only/(?P<namespace>[^/]+)$",
+        views.ReadOnlyView.as_view(),
+        name="read_only_namespace",
+    ),
+    re_path(
+        r"^runtime-settings/$",
+        views.RuntimeSettingsView.as_view(),
+        name="runtime_settings",
+    ),
+    re_path(r"^seed/$", views.SeedView.as_view(), name="seed"),
+    re_path(r"^dump/$", views.DumpView.as_view(), name="dump"),
 ]
@@ -3,27 +3,39 @@ from django.contrib.auth.hashers import make_password
 from django.contrib.auth.models import Group
 from django.contrib.contenttypes.models import ContentType
 from rest_framework import serializers
-from rest_framework.exceptions import ValidationError
+from rest_framework.exceptions import ErrorDetail, PermissionDenied, ValidationError
 from rest_framework.fields import empty
+from rest_framework.settings import api_settings
 
-from hubuum.models.auth import User
+from hubuum.models.auth import GroupExternalID, LoginEvent, User, UserExternalID
 from hubuum.models.base import (
-    Extension,
-    ExtensionData,
+    DeletePolicy,
     ExtensionsModel,
+    Lifecycle,
+    NamespacedHubuumModel,
+    TaggedModel,
+)
+from hubuum.models.extensions import Extension, ExtensionData, SchemaDefinition
+from hubuum.models.namespace import (
+    ActivityEntry,
+    ModelPermission,
+    Namespace,
+    NamespaceAlias,
+    Permission,
+)
+from hubuum.models.objects import (
     Host,
     HostType,
     Jack,
-    Namespace,
-    Permission,
     Person,
     PurchaseDocuments,
     PurchaseOrder,
     Room,
     Vendor,
 )
+from hubuum.models.revisions import ExtensionDataRevision
 from hubuum.tools import get_model
-from hubuum.validators import url_interpolation_fields
+from hubuum.validators import url_interpolation_fields, validate_name
 
 
 class ErrorOnBadFieldMixin:  # pylint: disable=too-few-public-methods
@@ -34,6 +46,9 @@ class ErrorOnBadFieldMixin:  # pylint: disable=too-few-public-methods
     This mixin changes that behaviour to raise a Validation error which
     again causes the response "400 Bad Request".
     See https://github.com/encode/django-rest-framework/issues/6508
+
+    Every bad field is reported, keyed by the name of the field, as for the other
+    validation errors of serializers.
     """
 
     def run_validation(self, data=empty):
@@ -41,30 +56,32 @@ class ErrorOnBadFieldMixin:  # pylint: disable=too-few-public-methods
         if not isinstance(data, dict):
             raise ValidationError(
                 code="expected_dict",
-                detail={"typeerror": "API expected a dictionary."},
-            )
-
-        provided_keys = data.keys()
-        items = self.fields.items()
-
-        for fieldname, field in items:
-            if field.read_only and fieldname in provided_keys:
-                raise ValidationError(
-                    code="write_on_read_only_field",
-                    detail={  # pylint: disable=undefined-loop-variable
-                        fieldname: (f"'{fieldname}' is a read-only field.")
-                    },
-                )
-
-        extra_keys = set(provided_keys) - set(self.fields.keys())
-        if extra_keys:
-            raise ValidationError(
-                code="write_on_non_existent_field",
-                detail={  # pylint: disable=undefined-loop-variable
-                    "extra_keys": f"{extra_keys} do not exist."
+                detail={
+                    api_settings.NON_FIELD_ERRORS_KEY: ["API expected a dictionary."]
                 },
             )
 
+        errors = {}
+        for fieldname in data:
+            field = self.fields.get(fieldname)
+            if field is None:
+                errors[fieldname] = [
+                    ErrorDetail(
+                        f"'{fieldname}' does not exist.",
+                        code="write_on_non_existent_field",
+                    )
+                ]
+            elif field.read_only:
+                errors[fieldname] = [
+                    ErrorDetail(
+                        f"'{fieldname}' is a read-only field.",
+                        code="write_on_read_only_field",
+                    )
+                ]
+
+        if errors:
+            raise ValidationError(detail=errors)
+
         return super().run_validation(data)
 
 
@@ -72,7 +89,7 @@ class HubuumMetaSerializer(ErrorOnBadFieldMixin, serializers.ModelSerializer):
     """General Hubuum Serializer."""
 
     def __init__(self, *args, **kwargs):
-        """For methods that are subclasses of ExtensionsModel, enable relevant fields."""
+        """For subclasses of ExtensionsModel and TaggedModel, enable relevant fields."""
         super().__init__(*args, **kwargs)
 
         if "request" not in self.context:
@@ -90,20 +107,45 @@ class HubuumMetaSerializer(ErrorOnBadFieldMixin, serializers.ModelSerializer):
             self.fields["extensions"] = serializers.SerializerMethodField()
             self.fields["extension_data"] = serializers.SerializerMethodField()
             self.fields["extension_urls"] = serializers.SerializerMethodField()
+        if issubclass(self.Meta.model, TaggedModel):
+            self.fields["tags"] = serializers.SerializerMethodField()
         return
 
+    def validate(self, attrs):
+        """Validate the names of namespaces and namespaced objects.
+
+        See hubuum.validators.validate_name for the naming rules.
+        """
+        model = self.Meta.model
+        if model is Namespace and "name" in attrs:
+            # The rules of a namespace apply to the last part of the names below it.
+            parent, _, local_name = attrs["name"].rpartition(".")
+            namespace = Namespace.objects.filter(name=parent).first()
+            rules = namespace.naming_rules if namespace else None
+            validate_name(attrs["name"], rules, local_name=local_name)
+        elif issubclass(model, NamespacedHubuumModel) and "name" in attrs:
+            namespace = getattr(self.instance, "namespace", None)
+            namespace = attrs.get("namespace", namespace)
+            validate_name(attrs["name"], namespace.naming_rules if namespace else None)
+
+        return super().validate(attrs)
+
     def get_extension_urls(self, obj):
         """Deliver the endpoint for the URL for this specific object."""
         return obj.extension_urls()
 
     def get_extension_data(self, obj):
-        """Display extension data."""
-        return obj.extension_data()
+        """Display extension data, with sensitive data redacted for the user."""
+        return obj.extension_data(self.context["request"].user)
 
     def get_extensions(self, obj):
         """Display active extensions for the object."""
         return sorted(o.name for o in obj.extensions())
 
+    def get_tags(self, obj):
+        """Display the tags of the object."""
+        return obj.tags()
+
     class Meta:
         """Meta class for HubuumMetaSerializer."""
 
@@ -133,6 +175,16 @@ class UserSerializer(HubuumMetaSerializer):
         fields = "__all__"
 
 
+class LoginEventSerializer(serializers.ModelSerializer):
+    """Serialize a LoginEvent object, a login in the login history of a user."""
+
+    class Meta:
+        """How to serialize the object."""
+
+        model = LoginEvent
+        fields = ("created_at", "ip", "user_agent", "method")
+
+
 class GroupSerializer(HubuumMetaSerializer):
     """Serialize a Group object."""
 
@@ -143,6 +195,26 @@ class GroupSerializer(HubuumMetaSerializer):
         fields = "__all__"
 
 
+class UserExternalIDSerializer(HubuumMetaSerializer):
+    """Serialize a UserExternalID object."""
+
+    class Meta:
+        """How to serialize the object."""
+
+        model = UserExternalID
+        fields = ("source", "external_id")
+
+
+class GroupExternalIDSerializer(HubuumMetaSerializer):
+    """Serialize a GroupExternalID object."""
+
+    class Meta:
+        """How to serialize the object."""
+
+        model = GroupExternalID
+        fields = ("source", "external_id")
+
+
 class ExtensionSerializer(HubuumMetaSerializer):
     """Serialize an Extension object."""
 
@@ -151,6 +223,9 @@ class ExtensionSerializer(HubuumMetaSerializer):
 
         This doesn't even get triggered unless we have a working extension object.
         """
+        if attrs.get("json_schema") is not None:
+            SchemaDefinition.validate_references(attrs["json_schema"])
+
         require_interpolation = True  # This should fetch the default for the field
 
         if self.partial and self.instance:
@@ -197,6 +272,24 @@ class ExtensionSerializer(HubuumMetaSerializer):
         fields = "__all__"
 
 
+class SchemaDefinitionSerializer(HubuumMetaSerializer):
+    """Serialize a SchemaDefinition object."""
+
+    ref = serializers.ReadOnlyField()
+
+    def validate(self, attrs):
+        """Validate that the references of the schema resolve."""
+        if "json_schema" in attrs:
+            SchemaDefinition.validate_references(attrs["json_schema"])
+        return super().validate(attrs)
+
+    class Meta:
+        """How to serialize the object."""
+
+        model = SchemaDefinition
+        fields = "__all__"
+
+
 class ExtensionDataSerializer(HubuumMetaSerializer):
     """Serialize an ExtensionData object."""
 
@@ -218,8 +311,23 @@ class ExtensionDataSerializer(HubuumMetaSerializer):
         if not extension.model == model_name:
             raise ValidationError({"model": "Extension does not apply to this model."})
 
-        super().validate(self)
-        return attrs
+        extension.check_schema(attrs.get("json_data"))
+
+        request = self.context.get("request")
+        if request is not None and extension.reference_paths:
+            errors = extension.reference_errors(attrs.get("json_data"), request.user)
+            if errors:
+                raise ValidationError({"json_data": errors})
+
+        return super().validate(attrs)
+
+    def to_representation(self, instance):
+        """Represent the object, with sensitive data redacted for the user."""
+        data = super().to_representation(instance)
+        request = self.context.get("request")
+        if request is not None:
+            data["json_data"] = instance.redact_for(request.user, data["json_data"])
+        return data
 
     class Meta:
         """How to serialize the object."""
@@ -228,6 +336,16 @@ class ExtensionDataSerializer(HubuumMetaSerializer):
         fields = "__all__"
 
 
+class ExtensionDataRevisionSerializer(HubuumMetaSerializer):
+    """Serialize an ExtensionDataRevision object."""
+
+    class Meta:
+        """How to serialize the object."""
+
+        model = ExtensionDataRevision
+        fields = "__all__"
+
+
 class HostSerializer(HubuumMetaSerializer):
     """Serialize a Host object."""
 
@@ -235,6 +353,15 @@ class HostSerializer(HubuumMetaSerializer):
     #    externals = serializers.SerializerMethodField()
     #    _mod_dns = serializers.PrimaryKeyRelatedField(many=True, queryset=Snippet.objects.all())
 
+    def validate_parent(self, value):
+        """Ensure that a host isn't contained within itself, directly or indirectly."""
+        if value and self.instance:
+            ancestors = [host.id for _, host in value.ancestors()]
+            if self.instance.id in [value.id, *ancestors]:
+                raise ValidationError("A host can not be contained within itself.")
+
+        return value
+
     class Meta:
         """How to serialize the object."""
 
@@ -246,6 +373,42 @@ class HostSerializer(HubuumMetaSerializer):
 class NamespaceSerializer(HubuumMetaSerializer):
     """Serialize a Namespace object."""
 
+    def validate_name(self, value):
+        """Ensure that the name isn't an alias for another namespace."""
+        aliases = NamespaceAlias.objects.filter(name=value)
+        if self.instance:
+            aliases = aliases.exclude(namespace=self.instance)
+
+        if aliases.exists():
+            raise ValidationError(f"'{value}' is an alias for another namespace.")
+
+        return value
+
+    def validate(self, attrs):
+        """Ensure that only admins change the write limits and owner of the namespace.
+
+        The owner group may be set when creating a namespace, to a group the user
+        is a member of.
+        """
+        request = self.context.get("request")
+        for field in ("write_rate_limit", "max_json_data_size"):
+            changed = attrs.get(field, None) != getattr(self.instance, field, None)
+            if field in attrs and changed and request and not request.user.is_admin():
+                raise PermissionDenied(f"Only admins may set {field} of namespaces.")
+
+        owner = attrs.get("owner_group")
+        changed = owner != getattr(self.instance, "owner_group", None)
+        if "owner_group" in attrs and changed and request:
+            user = request.user
+            if self.instance is not None and not user.is_admin():
+                raise PermissionDenied("Only admins may transfer namespaces.")
+            if owner is not None and not (user.is_admin() or user.is_member_of(owner)):
+                raise ValidationError(
+                    {"owner_group": "The user is not a member of the group."}
+                )
+
+        return super().validate(attrs)
+
     class Meta:
         """How to serialize the object."""
 
@@ -253,6 +416,33 @@ class NamespaceSerializer(HubuumMetaSerializer):
         fields = "__all__"
 
 
+class ActivityEntrySerializer(HubuumMetaSerializer):
+    """Serialize an ActivityEntry object."""
+
+    class Meta:
+        """How to serialize the object."""
+
+        model = ActivityEntry
+        fields = "__all__"
+
+
+class NamespaceAliasSerializer(HubuumMetaSerializer):
+    """Serialize a NamespaceAlias object."""
+
+    def validate_name(self, value):
+        """Ensure that the alias isn't the name of an existing namespace."""
+        if Namespace.objects.filter(name=value).exists():
+            raise ValidationError(f"'{value}' is the name of an existing namespace.")
+
+        return value
+
+    class Meta:
+        """How to serialize the object."""
+
+        model = NamespaceAlias
+        fields = "__all__"
+
+
 class PermissionSerializer(ErrorOnBadFieldMixin, serializers.ModelSerializer):
     """Serialize a Permission object."""
 
@@ -263,6 +453,56 @@ class PermissionSerializer(ErrorOnBadFieldMixin, serializers.ModelSerializer):
         fields = "__all__"
 
 
+class ModelPermissionSerializer(ErrorOnBadFieldMixin, serializers.ModelSerializer):
+    """Serialize a ModelPermission object."""
+
+    class Meta:
+        """How to serialize the object."""
+
+        model = ModelPermission
+        fields = "__all__"
+
+
+class DeletePolicySerializer(HubuumMetaSerializer):
+    """Serialize a DeletePolicy object."""
+
+    def validate(self, attrs):
+        """Validate that the policy applies to a relation, see DeletePolicy."""
+        relation = attrs.get("relation", getattr(self.instance, "relation", None))
+        policy = attrs.get("policy", getattr(self.instance, "policy", None))
+        DeletePolicy.validate_policy(relation, policy)
+        return super().validate(attrs)
+
+    class Meta:
+        """How to serialize the object."""
+
+        model = DeletePolicy
+        fields = "__all__"
+
+
+class LifecycleSerializer(HubuumMetaSerializer):
+    """Serialize a Lifecycle object."""
+
+    def validate_model(self, value):
+        """Store the model by its name in lower case, as objects refer to it."""
+        return value.lower()
+
+    def validate(self, attrs):
+        """Validate the states and transitions, see Lifecycle."""
+        states = attrs.get("states", getattr(self.instance, "states", None))
+        transitions = attrs.get(
+            "transitions", getattr(self.instance, "transitions", {})
+        )
+        Lifecycle.validate_definition(states, transitions)
+        return super().validate(attrs)
+
+    class Meta:
+        """How to serialize the object."""
+
+        model = Lifecycle
+        fields = "__all__"
+
+
 class HostTypeSerializer(HubuumMetaSerializer):
     """Serialize a HostType object."""
 
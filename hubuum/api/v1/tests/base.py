@@ -171,6 +171,14 @@ class HubuumAPITestCase(APITestCase):  # pylint: disable=too-many-public-methods
         self._assert_status_and_debug(response, status_code)
         return response
 
+    def _assert_put_and_status(self, path, status_code, data=None, client=None):
+        """Put and assert status."""
+        if client is None:
+            client = self.client
+        response = client.put(self._create_path(path), data)
+        self._assert_status_and_debug(response, status_code)
+        return response
+
     def _assert_post_and_status(self, path, status_code, data=None, client=None):
         """Post and assert status."""
         if client is None:
@@ -195,10 +203,22 @@ class HubuumAPITestCase(APITestCase):  # pylint: disable=too-many-public-methods
         """Delete and assert status as 204."""
         return self.assert_delete_and_204(path, **kwargs)
 
+    def assert_delete_and_200(self, path, **kwargs):
+        """Delete and assert status as 200."""
+        return self._assert_delete_and_status(path, 200, **kwargs)
+
+    def assert_delete_and_202(self, path, **kwargs):
+        """Delete and assert status as 202."""
+        return self._assert_delete_and_status(path, 202, **kwargs)
+
     def assert_delete_and_204(self, path, **kwargs):
         """Delete and assert status as 204."""
         return self._assert_delete_and_status(path, 204, **kwargs)
 
+    def assert_delete_and_400(self, path, **kwargs):
+        """Delete and assert status as 400."""
+        return self._assert_delete_and_status(path, 400, **kwargs)
+
     def assert_delete_and_401(self, path, **kwargs):
         """Delete and assert status as 401."""
         return self._assert_delete_and_status(path, 401, **kwargs)
@@ -211,9 +231,9 @@ class HubuumAPITestCase(APITestCase):  # pylint: disable=too-many-public-methods
         """Delete and assert status as 404."""
         return self._assert_delete_and_status(path, 404, **kwargs)
 
-    #    def assert_delete_and_409(self, path, **kwargs):
-    #        """Delete and assert status as 409."""
-    #        return self._assert_delete_and_status(path, 409, **kwargs)
+    def assert_delete_and_409(self, path, **kwargs):
+        """Delete and assert status as 409."""
+        return self._assert_delete_and_status(path, 409, **kwargs)
 
     def assert_get_elements(self, path, element_count, **kwargs):
         """Get and assert (status == 200 and element_count == elements)."""
@@ -281,9 +301,33 @@ class HubuumAPITestCase(APITestCase):  # pylint: disable=too-many-public-methods
         """Patch and assert status as 405."""
         return self._assert_patch_and_status(path, 405, *args, **kwargs)
 
-    #    def assert_patch_and_409(self, path, *args, **kwargs):
-    #        """Patch and assert status as 409."""
-    #        return self._assert_patch_and_status(path, 409, *args, **kwargs)
+    def assert_patch_and_409(self, path, *args, **kwargs):
+        """Patch and assert status as 409."""
+        return self._assert_patch_and_status(path, 409, *args, **kwargs)
+
+    def assert_put(self, path, *args, **kwargs):
+        """Put and assert status as 200."""
+        return self.assert_put_and_200(path, *args, **kwargs)
+
+    def assert_put_and_200(self, path, *args, **kwargs):
+        """Put and assert status as 200."""
+        return self._assert_put_and_status(path, 200, *args, **kwargs)
+
+    def assert_put_and_201(self, path, *args, **kwargs):
+        """Put and assert status as 201."""
+        return self._assert_put_and_status(path, 201, *args, **kwargs)
+
+    def assert_put_and_400(self, path, *args, **kwargs):
+        """Put and assert status as 400."""
+        return self._assert_put_and_status(path, 400, *args, **kwargs)
+
+    def assert_put_and_403(self, path, *args, **kwargs):
+        """Put and assert status as 403."""
+        return self._assert_put_and_status(path, 403, *args, **kwargs)
+
+    def assert_put_and_409(self, path, *args, **kwargs):
+        """Put and assert status as 409."""
+        return self._assert_put_and_status(path, 409, *args, **kwargs)
 
     def assert_post(self, path, *args, **kwargs):
         """Post and assert status as 201."""
@@ -325,6 +369,14 @@ class HubuumAPITestCase(APITestCase):  # pylint: disable=too-many-public-methods
         """Post and assert status as 409."""
         return self._assert_post_and_status(path, 409, *args, **kwargs)
 
+    def assert_post_and_413(self, path, *args, **kwargs):
+        """Post and assert status as 413."""
+        return self._assert_post_and_status(path, 413, *args, **kwargs)
+
+    def assert_post_and_429(self, path, *args, **kwargs):
+        """Post and assert status as 429."""
+        return self._assert_post_and_status(path, 429, *args, **kwargs)
+
 
 # def clean_and_save(entity):
 #    """Perform a full clean and a save on the object.
@@ -0,0 +1,45 @@
+"""Test suspending and reactivating users."""
+from structlog import get_logger
+from structlog.testing import capture_logs
+
+from .base import HubuumAPITestCase
+
+
+class APIUserSuspension(HubuumAPITestCase):
+    """Test the suspension lifecycle of users."""
+
+    def test_suspend_and_reactivate(self):
+        """Test that suspended users' tokens stop working until reactivated."""
+        admin = self.client
+        user = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.client = user
+        self.assert_get("/users/")
+
+        self.client = admin
+        with capture_logs() as cap_logs:
+            get_logger().bind()
+            response = self.assert_post_and_200("/users/tmp/suspend")
+        self.assertFalse(response.data["is_active"])
+        suspended = [log for log in cap_logs if log["event"] == "suspended"]
+        self.assertEqual(len(suspended), 1)
+        self.assertEqual(suspended[0]["actor"]["user"], "superuser")
+
+        self.client = user
+        self.assert_get_and_401("/users/")
+
+        self.client = admin
+        response = self.assert_post_and_200("/users/tmp/reactivate")
+        self.assertTrue(response.data["is_active"])
+
+        self.client = user
+        self.assert_get("/users/")
+
+    def test_suspension_permissions(self):
+        """Test that only admins can suspend others."""
+        self.assert_post_and_400("/users/superuser/suspend")
+        self.assert_post_and_404("/users/nosuchuser/suspend")
+
+        self.get_user_client(username="other", groupname="othergroup")
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_post_and_403("/users/other/suspend")
+        self.assert_post_and_403("/users/other/reactivate")
@@ -0,0 +1,143 @@
+"""Test listing, revoking, and binding tokens."""
+from django.test import override_settings
+from knox.models import AuthToken
+from rest_framework.test import APIClient
+
+from hubuum.models.auth import User
+
+from .base import HubuumAPITestCase
+
+
+class APITokens(HubuumAPITestCase):
+    """Test the token introspection endpoints."""
+
+    def test_token_listing(self):
+        """Test that the user sees their own tokens, with usage metadata."""
+        response = self.assert_get("/api/auth/tokens/")
+        self.assertEqual(len(response.data), 1)
+        current = response.data[0]
+        self.assertTrue(current["current"])
+        self.assertIsNotNone(current["last_used"])
+        self.assertEqual(current["ip"], "127.0.0.1")
+        self.assertNotIn("digest", current)
+
+        token, _ = AuthToken.objects.create(self.user)
+        self.get_user_client(username="other", groupname="othergroup")
+        response = self.assert_get("/api/auth/tokens/")
+        self.assertEqual(len(response.data), 2)
+        self.assertEqual(response.data[1]["id"], token.token_key)
+        self.assertFalse(response.data[1]["current"])
+        self.assertIsNone(response.data[1]["last_used"])
+
+    def test_token_revocation(self):
+        """Test revoking tokens."""
+        superuser = self.user
+        other = self.get_user_client(username="other", groupname="othergroup")
+        other_token = AuthToken.objects.filter(user=self.user).first()
+        token, _ = AuthToken.objects.create(superuser)
+
+        self.client = self.get_superuser_client()
+        self.assert_delete(f"/api/auth/tokens/{token.token_key}")
+        self.assert_delete_and_404(f"/api/auth/tokens/{token.token_key}")
+        self.assert_delete_and_404(f"/api/auth/tokens/{other_token.token_key}")
+
+        self.client = other
+        self.assert_delete(f"/api/auth/tokens/{other_token.token_key}")
+        self.assert_get_and_401("/api/auth/tokens/")
+
+        self.client = APIClient()
+        self.assert_get_and_401("/api/auth/tokens/")
+
+    def test_admin_token_revocation(self):
+        """Test that admins list and revoke the tokens of users, and of groups."""
+        admin = self.client
+        one = self.get_user_client(username="one", groupname="leaked")
+        AuthToken.objects.create(self.user)
+        two = self.get_user_client(username="two", groupname="leaked")
+        three = self.get_user_client(username="three", groupname="other")
+
+        self.client = three
+        self.assert_get_and_403("/users/one/tokens")
+        self.assert_delete_and_403("/groups/leaked/tokens")
+
+        self.client = admin
+        response = self.assert_get("/users/one/tokens")
+        self.assertEqual(len(response.data), 2)
+        self.assertNotIn("digest", response.data[0])
+        response = self.assert_get("/groups/leaked/tokens")
+        self.assertEqual(
+            {name: len(tokens) for name, tokens in response.data.items()},
+            {"one": 2, "two": 1},
+        )
+
+        response = self.assert_delete_and_200("/groups/leaked/tokens")
+        self.assertEqual(response.data["revoked"], {"one": 2, "two": 1})
+        for client in (one, two):
+            self.client = client
+            self.assert_get_and_401("/api/auth/tokens/")
+
+        self.client = three
+        self.assert_get("/api/auth/tokens/")
+        self.client = admin
+        response = self.assert_delete_and_200("/users/three/tokens")
+        self.assertEqual(response.data["revoked"], 1)
+        self.assert_get_elements("/users/three/tokens", 0)
+        self.assert_delete_and_404("/users/nosuchuser/tokens")
+        self.client = three
+        self.assert_get_and_401("/api/auth/tokens/")
+
+    def _login(self, **data):
+        """Log in as a new user with basic auth, return the response."""
+        user = User.objects.create(username="bound")
+        user.set_password("secret")
+        user.save()
+        self.client = APIClient()
+        self.client.credentials(HTTP_AUTHORIZATION=self.basic_auth("bound", "secret"))
+        return self.client.post("/api/auth/login/", data)
+
+    def _token_client(self, token, address):
+        """Return a client using the token from the given address."""
+        client = APIClient()
+        client.credentials(HTTP_AUTHORIZATION=f"Token {token}", REMOTE_ADDR=address)
+        return client
+
+    def test_token_binding(self):
+        """Test that bound tokens are only accepted from the networks given."""
+        response = self._login(bind_cidr="10.0.0.0/24, 192.0.2.1")
+        self.assertEqual(response.status_code, 200)
+        self.assertEqual(response.data["bind_cidr"], "10.0.0.0/24,192.0.2.1/32")
+        token = response.data["token"]
+
+        self.client = self._token_client(token, "10.0.0.5")
+        listing = self.assert_get("/api/auth/tokens/").data
+        self.assertEqual(listing[0]["bind_cidr"], "10.0.0.0/24,192.0.2.1/32")
+        self.client = self._token_client(token, "192.0.2.1")
+        self.assert_get("/api/auth/tokens/")
+        self.client = self._token_client(token, "10.0.1.5")
+        self.assert_get_and_401("/api/auth/tokens/")
+        self.client = self._token_client(token, "127.0.0.1")
+        self.assert_get_and_401("/api/auth/tokens/")
+
+    @override_settings(IP_TRUSTED_PROXIES="127.0.0.1")
+    def test_token_binding_behind_proxies(self):
+        """Test that the client address is found through trusted proxies."""
+        token = self._login(bind_cidr="10.0.0.0/24").data["token"]
+        client = self._token_client(token, "127.0.0.1")
+        response = client.get("/api/auth/tokens/", HTTP_X_FORWARDED_FOR="10.0.0.5")
+        self.assertEqual(response.status_code, 200)
+        response = client.get("/api/auth/tokens/", HTTP_X_FORWARDED_FOR="10.0.1.5")
+        self.assertEqual(response.status_code, 401)
+
+    def test_token_binding_validation(self):
+        """Test that invalid bindings are rejected, without creating tokens."""
+        response = self._login(bind_cidr="10.0.0.0/33")
+        self.assertEqual(response.status_code, 400)
+        self.assertIn("bind_cidr", response.data["detail"])
+        response = self.client.post("/api/auth/login/", ["10.0.0.0/24"], format="json")
+        self.assertEqual(response.status_code, 400)
+        self.assertFalse(AuthToken.objects.filter(user__username="bound").exists())
+
+        # Without a binding, tokens are accepted from anywhere.
+        token = self.client.post("/api/auth/login/").data["token"]
+        self.client = self._token_client(token, "198.51.100.7")
+        self.assertEqual(self.assert_get("/api/auth/tokens/").data[0]["bind_cidr"], "")
@@ -0,0 +1,70 @@
+"""Test delegated management of group members."""
+from .base import HubuumAPITestCase
+
+
+class APIGroupManagers(HubuumAPITestCase):
+    """Test that group managers can modify the members of their groups only."""
+
+    def setUp(self):
+        """Create a manager, a user to manage, and two groups."""
+        super().setUp()
+        self.assert_post("/groups/", {"name": "managed"})
+        self.assert_post("/groups/", {"name": "unmanaged"})
+        self.get_user_client(username="member", groupname="othergroup")
+        self.manager = self.get_user_client(username="manager", groupname="tmpgroup")
+
+    def test_appointing_managers(self):
+        """Test that admins can appoint and dismiss managers."""
+        self.assert_get_elements("/groups/managed/managers/", 0)
+        self.assert_get_and_404("/groups/managed/managers/manager")
+        self.assert_post_and_201("/groups/managed/managers/manager")
+        self.assert_post_and_200("/groups/managed/managers/manager")
+        self.assert_get("/groups/managed/managers/manager")
+        self.assert_get_elements("/groups/managed/managers/", 1)
+        self.assert_post_and_404("/groups/managed/managers/nosuchuser")
+
+        self.client = self.manager
+        self.assert_post_and_403("/groups/managed/managers/member")
+        self.assert_delete_and_403("/groups/managed/managers/manager")
+
+        self.client = self.get_superuser_client()
+        self.assert_delete("/groups/managed/managers/manager")
+        self.assert_get_elements("/groups/managed/managers/", 0)
+
+    def test_managing_members(self):
+        """Test that managers can add and remove members of their groups."""
+        self.client = self.manager
+        self.assert_post_and_403("/groups/managed/members/member")
+
+        self.client = self.get_superuser_client()
+        self.assert_post_and_201("/groups/managed/managers/manager")
+
+        self.client = self.manager
+        self.assert_post_and_201("/groups/managed/members/member")
+        self.assert_get_elements("/groups/managed/members/", 1)
+        self.assert_delete("/groups/managed/members/member")
+        self.assert_get_elements("/groups/managed/members/", 0)
+
+        self.assert_post_and_403("/groups/unmanaged/members/member")
+        self.assert_patch_and_403("/groups/managed", {"name": "renamed"})
+        self.assert_delete_and_403("/groups/managed")
+
+    def test_member_pagination(self):
+        """Test that members and managers are paginated, and may be sorted."""
+        self.assert_post_and_201("/groups/managed/members/member")
+        self.assert_post_and_201("/groups/managed/members/manager")
+        self.assert_post_and_201("/groups/managed/managers/manager")
+
+        response = self.assert_get_elements("/groups/managed/members/?page_size=1", 1)
+        self.assertEqual(response["X-Total-Count"], "2")
+        self.assertIn('rel="next"', response["Link"])
+
+        for ordering, expected in (
+            ("username", ["manager", "member"]),
+            ("-username", ["member", "manager"]),
+        ):
+            path = f"/groups/managed/members/?ordering={ordering}"
+            response = self.assert_get(path)
+            self.assertEqual([user["username"] for user in response.data], expected)
+        self.assert_get_elements("/groups/managed/managers/?ordering=-id", 1)
+        self.assert_get_and_400("/groups/managed/members/?ordering=password")
@@ -0,0 +1,99 @@
+"""Test bulk provisioning of users and groups."""
+from django.contrib.auth.models import Group
+
+from hubuum.models.auth import User
+
+from .base import HubuumAPITestCase
+
+
+class APIProvisioning(HubuumAPITestCase):
+    """Test creating, updating, and deactivating many users and groups at once."""
+
+    def _statuses(self, results):
+        """Return the statuses of the results of a section."""
+        return [result["status"] for result in results]
+
+    def test_provisioning(self):
+        """Test that users and groups are created, updated, and deactivated."""
+        data = {
+            "groups": [{"name": "operators"}, {"name": "auditors"}],
+            "users": [
+                {
+                    "username": "alice",
+                    "email": "alice@example.com",
+                    "password": "secret",
+                    "groups": ["operators", "auditors"],
+                },
+                {"username": "bob", "groups": ["operators"]},
+            ],
+        }
+        response = self.assert_post_and_200("/provisioning/", data)
+        self.assertEqual(self._statuses(response.data["groups"]), ["created"] * 2)
+        self.assertEqual(self._statuses(response.data["users"]), ["created"] * 2)
+        self.assertEqual(response.data["summary"], {"created": 4})
+        alice = User.objects.get(username="alice")
+        self.assertTrue(alice.check_password("secret"))
+        self.assertEqual(sorted(alice.group_list), ["auditors", "operators"])
+        self.assertFalse(User.objects.get(username="bob").has_usable_password())
+
+        # Applying the same request again changes nothing.
+        response = self.assert_post_and_200("/provisioning/", data)
+        self.assertEqual(response.data["summary"], {"unchanged": 4})
+
+        data = {
+            "groups": [{"name": "auditors", "delete": True}],
+            "users": [
+                {"username": "alice", "first_name": "Alice", "groups": ["operators"]},
+                {"username": "bob", "active": False},
+            ],
+        }
+        response = self.assert_post_and_200("/provisioning/", data)
+        self.assertEqual(self._statuses(response.data["groups"]), ["deleted"])
+        self.assertEqual(
+            self._statuses(response.data["users"]), ["updated", "deactivated"]
+        )
+        alice = User.objects.get(username="alice")
+        self.assertEqual(alice.first_name, "Alice")
+        self.assertEqual(alice.group_list, ["operators"])
+        self.assertFalse(User.objects.get(username="bob").is_active)
+        self.assertFalse(Group.objects.filter(name="auditors").exists())
+
+    def test_provisioning_failures(self):
+        """Test that failing entries are reported, and do not stop the others."""
+        self.assert_post("/users/", {"username": "carol", "password": "secret"})
+        self.assert_patch("/users/carol", {"email": "carol@example.com"})
+        data = {
+            "groups": [{"name": "operators"}, {"name": "operators"}, {"id": 1}],
+            "users": [
+                {"username": "dave", "email": "carol@example.com"},
+                {"username": "erin", "groups": ["operators", "nosuchgroup"]},
+                {"username": "frank", "email": "not an email"},
+                {"username": "grace", "shell": "/bin/sh"},
+                {"username": "heidi"},
+            ],
+        }
+        response = self.assert_post_and_200("/provisioning/", data)
+        self.assertEqual(
+            self._statuses(response.data["groups"]), ["created", "conflict", "invalid"]
+        )
+        self.assertEqual(
+            self._statuses(response.data["users"]),
+            ["conflict", "invalid", "invalid", "invalid", "created"],
+        )
+        self.assertIn("another user", response.data["users"][0]["detail"])
+        self.assertIn("nosuchgroup", response.data["users"][1]["detail"])
+        self.assertIn("shell", response.data["users"][3]["detail"])
+
+        # Failing entries are rolled back.
+        for username in ["dave", "erin", "frank", "grace"]:
+            self.assertFalse(User.objects.filter(username=username).exists())
+        self.assertTrue(User.objects.filter(username="heidi").exists())
+
+        self.assert_post_and_400("/provisioning/", {"people": []})
+        self.assert_post_and_400("/provisioning/", {"users": {"username": "x"}})
+
+    def test_provisioning_permissions(self):
+        """Test that only admins can provision users and groups."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_post_and_403("/provisioning/", {"groups": [{"name": "new"}]})
+        self.assertFalse(Group.objects.filter(name="new").exists())
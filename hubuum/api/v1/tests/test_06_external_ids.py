@@ -0,0 +1,67 @@
+"""Test the identities of users and groups in external sources."""
+from .base import HubuumAPITestCase
+
+
+class APIExternalIDs(HubuumAPITestCase):
+    """Test mapping users and groups to their identities in LDAP, OIDC, SCIM, etc."""
+
+    def setUp(self):
+        """Create a user and a group."""
+        super().setUp()
+        self.assert_post("/users/", {"username": "alice", "password": "secret"})
+        self.assert_post("/users/", {"username": "bob", "password": "secret"})
+        self.assert_post("/groups/", {"name": "operators"})
+
+    def test_user_external_ids(self):
+        """Test setting, finding, and removing the identities of a user."""
+        dn = "uid=alice,ou=people,dc=example,dc=com"
+        self.assert_put_and_201("/users/alice/external-ids/ldap", {"external_id": dn})
+        self.assert_put_and_201(
+            "/users/alice/external-ids/oidc", {"external_id": "idp.example.com/alice"}
+        )
+        self.assert_get_elements("/users/alice/external-ids/", 2)
+        identity = self.assert_get("/users/alice/external-ids/ldap").data
+        self.assertEqual(identity, {"source": "ldap", "external_id": dn})
+
+        user = self.assert_get(f"/users/by-external-id/ldap/{dn}").data
+        self.assertEqual(user["username"], "alice")
+        user = self.assert_get("/users/by-external-id/oidc/idp.example.com/alice").data
+        self.assertEqual(user["username"], "alice")
+        self.assert_get_and_404("/users/by-external-id/ldap/uid=nobody")
+        self.assert_get_and_404("/users/by-external-id/scim/" + dn)
+
+        # Every user has at most one identity per source, setting it replaces it.
+        self.assert_put_and_200("/users/alice/external-ids/ldap", {"external_id": "1"})
+        self.assert_get_and_404(f"/users/by-external-id/ldap/{dn}")
+        self.assert_get("/users/by-external-id/ldap/1")
+
+        self.assert_delete("/users/alice/external-ids/ldap")
+        self.assert_get_and_404("/users/alice/external-ids/ldap")
+        self.assert_get_elements("/users/alice/external-ids/", 1)
+
+    def test_external_ids_are_unique_per_source(self):
+        """Test that an identity in a source belongs to a single user or group."""
+        self.assert_put_and_201("/users/alice/external-ids/ldap", {"external_id": "1"})
+        self.assert_put_and_409("/users/bob/external-ids/ldap", {"external_id": "1"})
+        self.assert_put_and_201("/users/bob/external-ids/scim", {"external_id": "1"})
+
+        # Users and groups are identified separately.
+        self.assert_put_and_201(
+            "/groups/operators/external-ids/ldap", {"external_id": "1"}
+        )
+        group = self.assert_get("/groups/by-external-id/ldap/1").data
+        self.assertEqual(group["name"], "operators")
+        self.assert_get_elements("/groups/operators/external-ids/", 1)
+
+        path = "/users/alice/external-ids"
+        self.assert_put_and_400(f"{path}/l.d.a.p", {"external_id": "1"})
+        self.assert_put_and_400(f"{path}/ldap", {"external_id": ""})
+        self.assert_put_and_400(f"{path}/ldap", {"id": "1"})
+
+    def test_external_id_permissions(self):
+        """Test that only admins set identities, while users may look them up."""
+        self.assert_put_and_201("/users/alice/external-ids/ldap", {"external_id": "1"})
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get("/users/by-external-id/ldap/1")
+        self.assert_put_and_403("/users/bob/external-ids/ldap", {"external_id": "2"})
+        self.assert_delete_and_403("/users/alice/external-ids/ldap")
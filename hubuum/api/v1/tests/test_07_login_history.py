@@ -0,0 +1,81 @@
+"""Test the login history and last seen time of users."""
+from django.test import override_settings
+from rest_framework.test import APIClient
+
+from hubuum.models.auth import User
+
+from .base import HubuumAPITestCase
+
+
+class APILoginHistory(HubuumAPITestCase):
+    """Test that logins are recorded, and who may see them."""
+
+    def setUp(self):
+        """Create a user to log in as."""
+        super().setUp()
+        user = User.objects.create(username="alice")
+        user.set_password("secret")
+        user.save()
+
+    def _login(self):
+        """Log in as alice with basic auth, and return a client with the token."""
+        client = APIClient()
+        client.credentials(
+            HTTP_AUTHORIZATION=self.basic_auth("alice", "secret"),
+            HTTP_USER_AGENT="hubuum-tests/1.0",
+        )
+        response = client.post("/api/auth/login/")
+        self._assert_status_and_debug(response, 200)
+
+        client = APIClient()
+        client.credentials(HTTP_AUTHORIZATION="Token " + response.data["token"])
+        return client
+
+    def test_login_history(self):
+        """Test that users see their own logins, and admins those of anyone."""
+        self.client = self._login()
+        response = self.assert_get("/api/auth/logins/")
+        self.assertEqual(len(response.data), 1)
+        self.assertEqual(response.data[0]["ip"], "127.0.0.1")
+        self.assertEqual(response.data[0]["user_agent"], "hubuum-tests/1.0")
+        self.assertEqual(response.data[0]["method"], "basic")
+        self.assert_get_elements("/users/alice/logins", 1)
+        self.assert_get_and_403("/users/superuser/logins")
+
+        self._login()
+        self.client = self.get_superuser_client()
+        self.assert_get_elements("/users/alice/logins", 2)
+        self.assert_get_elements("/api/auth/logins/", 0)
+
+    @override_settings(LOGIN_HISTORY_SIZE=2)
+    def test_login_history_size(self):
+        """Test that only the latest logins are kept."""
+        for _ in range(3):
+            self._login()
+        self.assert_get_elements("/users/alice/logins", 2)
+
+    def test_last_seen(self):
+        """Test that logins and requests with tokens record when users were seen."""
+        alice = User.objects.get(username="alice")
+        self.assertIsNone(alice.last_seen_at)
+        self.assertIsNone(alice.last_login)
+
+        self.client = self._login()
+        alice.refresh_from_db()
+        self.assertIsNotNone(alice.last_login)
+        seen = alice.last_seen_at
+        self.assertIsNotNone(seen)
+
+        # Within the interval, requests do not update the time seen.
+        self.assert_get("/api/auth/logins/")
+        alice.refresh_from_db()
+        self.assertEqual(alice.last_seen_at, seen)
+
+        User.objects.filter(pk=alice.pk).update(last_seen_at=seen - User.SEEN_INTERVAL)
+        self.assert_get("/api/auth/logins/")
+        alice.refresh_from_db()
+        self.assertGreater(alice.last_seen_at, seen - User.SEEN_INTERVAL)
+
+        self.client = self.get_superuser_client()
+        response = self.assert_get("/users/alice")
+        self.assertIsNotNone(response.data["last_seen_at"])
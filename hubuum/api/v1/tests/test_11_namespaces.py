@@ -1,6 +1,8 @@
 """Test namespaces."""
 from rest_framework.test import APIClient
 
+from hubuum.models.namespace import Permission
+
 from .base import HubuumAPITestCase
 
 
@@ -34,6 +36,23 @@ class APINamespace(HubuumAPITestCase):
         self.assert_patch_and_400("/namespaces/namespaceone", {"not_a", "dict"})
         self.assert_delete("/namespaces/namespaceone")
 
+    def test_field_errors(self):
+        """Test that every bad field is reported, keyed by the name of the field."""
+        self.assert_post("/namespaces/", {"name": "namespaceone"})
+        response = self.assert_patch_and_400(
+            "/namespaces/namespaceone", {"created_at": "2022-01-01", "nosuchkey": 1}
+        )
+        self.assertEqual(sorted(response.data), ["created_at", "nosuchkey"])
+        self.assertIn("read-only", str(response.data["created_at"][0]))
+
+        response = self.assert_post_and_400("/namespaces/", {"name": ""})
+        self.assertIn("name", response.data)
+        for group in ("nosuchgroup", 0, -1, 1000000):
+            response = self.assert_post_and_400(
+                "/namespaces/", {"name": "namespacetwo", "group": group}
+            )
+            self.assertIn("group", response.data)
+
     def test_namespaces_as_superuser(self):
         """Test namespaces as a superuser."""
         self.client = self.get_superuser_client()
@@ -184,3 +203,42 @@ class APINamespace(HubuumAPITestCase):
             "/namespaces/", {"name": "yes.subnamespace", "group": grouptwo.data["id"]}
         )
         self.assert_get("/namespaces/yes.subnamespace")
+
+    def test_namespace_owner_group(self):
+        """Test owning namespaces by groups, and transferring them."""
+        userclient = self.get_user_client(username="userone", groupname="groupone")
+        self.client = self.get_superuser_client()
+        self.assert_post("/namespaces/", {"name": "yes"})
+        grouptwo = self.assert_post("/groups/", {"name": "grouptwo"}).data
+        groupthree = self.assert_post("/groups/", {"name": "groupthree"}).data
+        self.assert_post_and_204(
+            "/namespaces/yes/groups/groupone", {"has_namespace": True}
+        )
+
+        self.client = userclient
+        self.add_user_to_groups(["groupone", "grouptwo"])
+        # The owner must be a group the user is a member of.
+        self.assert_post_and_400(
+            "/namespaces/", {"name": "yes.sub", "owner_group": groupthree["id"]}
+        )
+        # The owner is the default grantee.
+        namespace = self.assert_post(
+            "/namespaces/", {"name": "yes.sub", "owner_group": grouptwo["id"]}
+        ).data
+        self.assertEqual(namespace["owner_group"], grouptwo["id"])
+        grantees = Permission.objects.filter(namespace__name="yes.sub")
+        self.assertEqual([p.group.name for p in grantees], ["grouptwo"])
+
+        # Only admins transfer namespaces.
+        self.assert_patch_and_403(
+            "/namespaces/yes.sub", {"owner_group": groupthree["id"]}
+        )
+        self.client = self.get_superuser_client()
+        self.assert_patch("/namespaces/yes.sub", {"owner_group": groupthree["id"]})
+        response = self.assert_get_elements("/namespaces/?owner=groupthree", 1)
+        self.assertEqual(response.data[0]["name"], "yes.sub")
+        self.assert_get_elements(f"/namespaces/?owner_group={groupthree['id']}", 1)
+        self.assert_get_elements("/namespaces/?owner=grouptwo", 0)
+
+        self.assert_delete("/groups/groupthree")
+        self.assertIsNone(self.assert_get("/namespaces/yes.sub").data["owner_group"])
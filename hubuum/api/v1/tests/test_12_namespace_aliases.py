@@ -0,0 +1,80 @@
+"""Test namespace aliases."""
+from .base import HubuumAPITestCase
+
+
+class APINamespaceAliases(HubuumAPITestCase):
+    """Test namespace aliases."""
+
+    def test_rename_creates_alias(self):
+        """Test that renaming a namespace keeps the old name as an alias."""
+        nsblob = self.assert_post("/namespaces/", {"name": "yes"})
+        self.assert_patch("/namespaces/yes", {"name": "maybe"})
+
+        response = self.assert_get("/namespaces/yes")
+        self.assertEqual(response.data["id"], nsblob.data["id"])
+        self.assertEqual(response.data["name"], "maybe")
+        self.assert_get_elements("/namespaces/yes/groups/", 0)
+
+        response = self.assert_get_elements("/namespaces/maybe/aliases/", 1)
+        self.assertEqual(response.data[0]["name"], "yes")
+
+        # Renaming back removes the alias for the current name.
+        self.assert_patch("/namespaces/yes", {"name": "yes"})
+        response = self.assert_get_elements("/namespaces/yes/aliases/", 1)
+        self.assertEqual(response.data[0]["name"], "maybe")
+        self.assert_get("/namespaces/maybe")
+
+    def test_alias_collisions(self):
+        """Test that aliases and names can't collide."""
+        self.assert_post("/namespaces/", {"name": "one"})
+        self.assert_patch("/namespaces/one", {"name": "two"})
+        self.assert_post("/namespaces/", {"name": "three"})
+
+        # "one" is an alias for "two".
+        self.assert_post_and_400("/namespaces/", {"name": "one"})
+        self.assert_patch_and_400("/namespaces/three", {"name": "one"})
+        # "three" is an existing namespace.
+        self.assert_post_and_400("/namespaces/two/aliases/", {"name": "three"})
+        # "one" is already in use as an alias.
+        self.assert_post_and_400("/namespaces/three/aliases/", {"name": "one"})
+        self.assert_post_and_400("/namespaces/three/aliases/", ["not", "a", "dict"])
+
+    def test_alias_management(self):
+        """Test adding and removing aliases."""
+        self.assert_post("/namespaces/", {"name": "yes"})
+        self.assert_get_and_404("/namespaces/alias")
+        self.assert_post("/namespaces/yes/aliases/", {"name": "alias"})
+        self.assert_get("/namespaces/yes/aliases/alias")
+        self.assert_get("/namespaces/alias/aliases/alias")
+        response = self.assert_get("/namespaces/alias")
+        self.assertEqual(response.data["name"], "yes")
+
+        self.assert_get_and_404("/namespaces/yes/aliases/nosuchalias")
+        self.assert_delete_and_404("/namespaces/yes/aliases/nosuchalias")
+        self.assert_delete("/namespaces/yes/aliases/alias")
+        self.assert_get_and_404("/namespaces/alias")
+        self.assert_get_elements("/namespaces/yes/aliases/", 0)
+
+    def test_alias_permissions(self):
+        """Test alias access as a normal user."""
+        userclient = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.client = self.get_superuser_client()
+        self.assert_post("/namespaces/", {"name": "yes"})
+        self.assert_post("/namespaces/yes/aliases/", {"name": "alias"})
+
+        self.client = userclient
+        self.assert_get_and_403("/namespaces/yes/aliases/")
+        self.grant("tmpgroup", "yes", ["has_read"])
+        self.assert_get_elements("/namespaces/yes/aliases/", 1)
+        self.assert_get("/namespaces/alias")
+        self.assert_post_and_403("/namespaces/yes/aliases/", {"name": "other"})
+        self.assert_delete_and_403("/namespaces/yes/aliases/alias")
+
+        self.client = self.get_superuser_client()
+        self.assert_patch_and_204(
+            "/namespaces/yes/groups/tmpgroup", {"has_namespace": True}
+        )
+
+        self.client = userclient
+        self.assert_post("/namespaces/yes/aliases/", {"name": "other"})
+        self.assert_delete("/namespaces/yes/aliases/alias")
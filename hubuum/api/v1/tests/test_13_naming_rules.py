@@ -0,0 +1,83 @@
+"""Test the naming rules for namespaces and objects."""
+from django.test import override_settings
+
+from .base import HubuumAPITestCase
+
+
+class APINamingRules(HubuumAPITestCase):
+    """Test deployment and namespace naming rules."""
+
+    def _rules(self, response):
+        """Return the (scope, rule) pairs of the name errors in a response."""
+        return [(error["scope"], error["rule"]) for error in response.data["name"]]
+
+    @override_settings(
+        NAME_PATTERN=r"[a-z0-9.]+", RESERVED_NAMES=["admin"], NAME_MAX_LENGTH=12
+    )
+    def test_deployment_rules(self):
+        """Test that the deployment rules apply to namespaces and objects."""
+        response = self.assert_post_and_400("/namespaces/", {"name": "Uppercase"})
+        self.assertEqual(self._rules(response), [("deployment", "pattern")])
+
+        response = self.assert_post_and_400("/namespaces/", {"name": "ADMIN"})
+        self.assertEqual(
+            self._rules(response),
+            [("deployment", "pattern"), ("deployment", "reserved")],
+        )
+
+        response = self.assert_post_and_400("/namespaces/", {"name": "a" * 13})
+        self.assertEqual(self._rules(response), [("deployment", "max_length")])
+
+        namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        data = {"namespace": namespace.data["id"]}
+        self.assert_post_and_400("/hosts/", {**data, "name": "admin"})
+        self.assert_post("/hosts/", {**data, "name": "host1"})
+        self.assert_patch_and_400("/hosts/host1", {"name": "Host1"})
+        self.assert_post_and_400("/hosts/host1/clone", {"name": "Host2"})
+
+    def test_namespace_rules(self):
+        """Test that the rules of a namespace apply to what is placed in it."""
+        namespace = self.assert_post(
+            "/namespaces/",
+            {"name": "namespace1", "naming_rules": {"pattern": r"web\d+"}},
+        )
+        data = {"namespace": namespace.data["id"]}
+
+        response = self.assert_post_and_400("/hosts/", {**data, "name": "db1"})
+        self.assertEqual(self._rules(response), [("namespace", "pattern")])
+        self.assert_post("/hosts/", {**data, "name": "web1"})
+        self.assert_post("/hosts/web1/clone", {"name": "web2"})
+        self.assert_post_and_400("/hosts/web1/clone", {"name": "db2"})
+
+        self.assert_post_and_400("/namespaces/", {"name": "namespace1.db"})
+        self.assert_patch(
+            "/namespaces/namespace1",
+            {"naming_rules": {"reserved": ["db"], "max_length": 16}},
+        )
+        self.assert_post_and_400("/namespaces/", {"name": "namespace1.db"})
+        self.assert_post("/namespaces/", {"name": "namespace1.db1"})
+
+        # The rules are not inherited any further down.
+        self.assert_post("/namespaces/", {"name": "namespace1.db1.a-very-long-name"})
+
+    def test_invalid_rules(self):
+        """Test that invalid naming rules are rejected."""
+        for rules in [
+            ["not", "a", "dict"],
+            {"nosuchrule": True},
+            {"pattern": "["},
+            {"pattern": 1},
+            {"pattern": "(a+)+$"},
+            {"pattern": "(x|y*)*"},
+            {"pattern": "(a|a)*c"},
+            {"pattern": "(?:a|a?)*c"},
+            {"pattern": "(?:a?)*c"},
+            {"pattern": "a" * 257},
+            {"reserved": "admin"},
+            {"reserved": [1]},
+            {"max_length": 0},
+            {"max_length": True},
+        ]:
+            self.assert_post_and_400(
+                "/namespaces/", {"name": "namespace1", "naming_rules": rules}
+            )
@@ -0,0 +1,97 @@
+"""Test the activity feed of namespaces."""
+from hubuum.models.namespace import ActivityEntry
+
+from .base import HubuumAPITestCase
+
+
+class APINamespaceActivity(HubuumAPITestCase):
+    """Test the activity feed of namespaces."""
+
+    def setUp(self):
+        """Create a namespace with some activity."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        data = {"namespace": self.namespace.data["id"], "name": "host1"}
+        self.host = self.assert_post("/hosts/", data)
+        self.assert_patch("/hosts/host1", {"fqdn": "host1.example.com"})
+        self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.grant("tmpgroup", "namespace1", ["has_read"])
+        self.assert_delete("/hosts/host1")
+
+    def _entries(self, response):
+        """Return the (action, model) pairs of the entries in a response."""
+        return [(entry["action"], entry["model"]) for entry in response.data]
+
+    def test_activity(self):
+        """Test that the feed lists the activity in the namespace, newest first."""
+        response = self.assert_get("/namespaces/namespace1/activity")
+        self.assertEqual(
+            self._entries(response),
+            [
+                ("deleted", "Host"),
+                ("created", "Permission"),
+                ("updated", "Host"),
+                ("created", "Host"),
+                ("created", "Namespace"),
+            ],
+        )
+        self.assertEqual(response["X-Total-Count"], "5")
+        self.assertEqual(response.data[0]["object_id"], self.host.data["id"])
+        self.assertEqual(response.data[0]["object_repr"], "host1")
+        self.assertEqual(response.data[0]["actor"]["user"], "superuser")
+
+        response = self.assert_get("/namespaces/namespace1/activity?page_size=2")
+        self.assertEqual(len(response.data), 2)
+        self.assertIn('rel="next"', response["Link"])
+
+    def test_activity_filtering(self):
+        """Test limiting the feed to a model or an action."""
+        response = self.assert_get("/namespaces/namespace1/activity?model=host")
+        self.assertEqual(
+            self._entries(response),
+            [("deleted", "Host"), ("updated", "Host"), ("created", "Host")],
+        )
+        response = self.assert_get("/namespaces/namespace1/activity?action=created")
+        self.assertEqual(len(response.data), 3)
+
+    def test_changes(self):
+        """Test listing the changes after a cursor, oldest first."""
+        changes = "/namespaces/namespace1/changes"
+        response = self.assert_get(f"{changes}?limit=2")
+        self.assertEqual(
+            [(entry["action"], entry["model"]) for entry in response.data["changes"]],
+            [("created", "namespace"), ("created", "host")],
+        )
+        self.assertTrue(response.data["more"])
+
+        response = self.assert_get(f"{changes}?since={response.data['cursor']}")
+        self.assertEqual(
+            [(entry["action"], entry["model"]) for entry in response.data["changes"]],
+            [("updated", "host"), ("created", "permission"), ("deleted", "host")],
+        )
+        last = response.data["changes"][-1]
+        self.assertEqual(last["object_id"], self.host.data["id"])
+        self.assertFalse(response.data["more"])
+
+        cursor = response.data["cursor"]
+        response = self.assert_get(f"{changes}?since={cursor}")
+        self.assertEqual(response.data["changes"], [])
+        self.assertEqual(response.data["cursor"], cursor)
+
+        self.assert_get_and_400(f"{changes}?since=start")
+        self.assert_get_and_400(f"{changes}?limit=0")
+
+    def test_activity_permissions(self):
+        """Test that reading the feed requires read access to the namespace."""
+        self.assert_post("/namespaces/", {"name": "namespace2"})
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get("/namespaces/namespace1/activity")
+        self.assert_get_and_403("/namespaces/namespace2/activity")
+        self.assert_get_and_403("/namespaces/namespace2/changes")
+        self.assert_get_and_404("/namespaces/nosuchnamespace/activity")
+
+    def test_activity_is_deleted_with_namespace(self):
+        """Test that deleting a namespace removes its activity."""
+        self.assert_delete("/namespaces/namespace1")
+        entries = ActivityEntry.objects.filter(namespace_id=self.namespace.data["id"])
+        self.assertFalse(entries.exists())
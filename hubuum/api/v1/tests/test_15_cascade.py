@@ -1,4 +1,6 @@
 """Test cascading effects on models."""
+from hubuum.models.namespace import ActivityEntry, Namespace
+
 from .base import HubuumAPITestCase
 
 
@@ -62,3 +64,132 @@ class APICascade(HubuumAPITestCase):
         self.assert_get_elements("/permissions/", 0)
         self.assert_get_elements("/namespaces/", 1)
         self.assert_get_elements("/groups/", 0)
+
+
+
+class APIDeletePolicies(HubuumAPITestCase):
+    """Test the delete policies of relations."""
+
+    def setUp(self):
+        """Create a room with a host and a jack."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "yes"})
+        data = {"namespace": self.namespace.data["id"]}
+        room = self.assert_post("/rooms/", {**data, "room_id": "BL14-01-100"})
+        self.room = f"/rooms/{room.data['id']}"
+        data["room"] = room.data["id"]
+        self.assert_post("/hosts/", {**data, "name": "host1"})
+        self.assert_post("/jacks/", {**data, "name": "jack1"})
+
+    def tearDown(self):
+        """Clean up the namespaces."""
+        Namespace.objects.filter(name__in=["yes", "no"]).delete()
+
+    def _policy(self, relation, policy, status=201):
+        """Set the delete policy of a relation."""
+        data = {"relation": relation, "policy": policy}
+        return self._assert_post_and_status("/delete_policies/", status, data)
+
+    def test_restrict(self):
+        """Test that restrict blocks deletes, with a clear error."""
+        response = self.assert_delete_and_409(self.room)
+        self.assertIn("host.room", response.data["detail"])
+        self.assert_get(self.room)
+        self.assert_get_elements("/jacks/", 1)
+
+    def test_detach(self):
+        """Test that detach clears the references."""
+        self._policy("host.room", "detach")
+        self.assert_delete(self.room)
+        self.assertIsNone(self.assert_get("/hosts/host1").data["room"])
+        self.assert_get_elements("/jacks/", 0)
+
+    def test_cascade(self):
+        """Test that cascade deletes the referring objects."""
+        self._policy("host.room", "cascade")
+        self._policy("jack.room", "restrict")
+        response = self.assert_delete_and_409(self.room)
+        self.assertIn("jack.room", response.data["detail"])
+        self.assert_get_elements("/hosts/", 1)
+
+        self.assert_patch("/delete_policies/jack.room", {"policy": "cascade"})
+        self.assert_delete(self.room)
+        self.assert_get_elements("/hosts/", 0)
+        self.assert_get_elements("/jacks/", 0)
+
+    def test_namespaces(self):
+        """Test that policies apply to references from other namespaces."""
+        other = self.assert_post("/namespaces/", {"name": "no"})
+        self.assert_patch("/hosts/host1", {"namespace": other.data["id"]})
+        self.assert_delete_and_409("/namespaces/yes")
+
+        self._policy("host.room", "detach")
+        self.assert_delete("/namespaces/yes")
+        self.assertIsNone(self.assert_get("/hosts/host1").data["room"])
+
+    def test_permissions(self):
+        """Test that policies only change objects the user may change."""
+        other = self.assert_post("/namespaces/", {"name": "no"})
+        host = self.assert_patch("/hosts/host1", {"namespace": other.data["id"]})
+        self._policy("host.room", "detach")
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.grant("tmpgroup", "yes", ["has_read", "has_delete"])
+        self.grant("tmpgroup", "no", ["has_read"])
+        response = self.assert_delete_and_403(self.room)
+        self.assertIn("host.room", response.data["detail"])
+        self.assert_get(self.room)
+        response = self.assert_delete_and_200("/rooms/?room_id=BL14-01-100&confirm=1")
+        self.assertEqual(response.data["deleted"], 0)
+        self.assertIn("host.room", response.data["skipped"][0]["reason"])
+
+        # Detached objects are saved, and so recorded in the activity feed.
+        updates = ActivityEntry.objects.filter(
+            model="Host", object_id=host.data["id"], action="updated"
+        )
+        count = updates.count()
+        self.grant("tmpgroup", "no", ["has_read", "has_update"])
+        self.assert_delete(self.room)
+        self.assertIsNone(self.assert_get("/hosts/host1").data["room"])
+        self.assertEqual(updates.count(), count + 1)
+        self.assert_get_elements("/jacks/", 0)
+
+    def test_bulk_delete(self):
+        """Test that bulk deletes skip objects protected by restrict."""
+        host1 = self.assert_get("/hosts/host1")
+        self.assert_post(
+            "/hosts/",
+            {
+                "namespace": self.namespace.data["id"],
+                "name": "host2",
+                "parent": host1.data["id"],
+            },
+        )
+        self._policy("host.parent", "restrict")
+
+        response = self.assert_delete_and_200("/hosts/?name=host1&confirm=true")
+        self.assertEqual(response.data["deleted"], 0)
+        self.assertIn("host.parent", response.data["skipped"][0]["reason"])
+
+        response = self.assert_delete_and_200(
+            "/hosts/?name__startswith=host&confirm=true"
+        )
+        self.assertEqual(response.data["deleted"], 2)
+
+    def test_managing_policies(self):
+        """Test validation and permissions of policies."""
+        response = self.assert_get("/delete_policies/effective")
+        self.assertEqual(response.data["host.room"], "restrict")
+        self.assertEqual(response.data["jack.room"], "cascade")
+
+        self._policy("nosuch.relation", "cascade", status=400)
+        self._policy("host.room", "nosuch", status=400)
+        self._policy("purchasedocuments.purchase_order", "detach", status=400)
+        self._policy("host.room", "cascade")
+        self._policy("host.room", "detach", status=400)
+        self.assert_get("/delete_policies/host.room")
+        self.assert_delete("/delete_policies/host.room")
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get("/delete_policies/effective")
+        self._policy("host.room", "cascade", status=403)
@@ -0,0 +1,149 @@
+"""Test the export of the permission matrix."""
+import csv
+import io
+from urllib.parse import urlsplit
+
+from django.utils import timezone
+from rest_framework.test import APIClient
+
+from hubuum.api.v1.views import DOWNLOAD_SALT
+from hubuum.models.auth import User
+from hubuum.tools import sign_expiring
+
+from .base import HubuumAPITestCase
+
+
+class PermissionExportTestCase(HubuumAPITestCase):
+    """Base class for testing permission exports."""
+
+    def setUp(self):
+        """Create two namespaces and two groups, with some permissions."""
+        super().setUp()
+        self.assert_post("/namespaces/", {"name": "namespace1"})
+        self.assert_post("/namespaces/", {"name": "namespace2"})
+        self.get_user_client(username="reader", groupname="readers")
+        self.get_user_client(username="writer", groupname="writers")
+        self.grant("readers", "namespace1", ["has_read"])
+        self.grant("writers", "namespace1", ["has_create", "has_update"])
+
+    def _csv(self, path):
+        """Get the CSV export at path, as a list of rows."""
+        response = self.assert_get(path)
+        self.assertEqual(response["Content-Type"], "text/csv; charset=utf-8")
+        content = b"".join(response.streaming_content).decode("utf-8")
+        return list(csv.reader(io.StringIO(content)))
+
+
+class APIPermissionExport(PermissionExportTestCase):
+    """Test exporting the permission matrix."""
+
+    def test_export_csv(self):
+        """Test the CSV export of the full matrix."""
+        rows = self._csv("/permissions/export?format=csv")
+        self.assertEqual(
+            rows[0],
+            [
+                "group",
+                "namespace",
+                "has_create",
+                "has_read",
+                "has_update",
+                "has_delete",
+                "has_namespace",
+                "has_read_sensitive",
+            ],
+        )
+        self.assertEqual({row[7] for row in rows[1:]}, {"False"})
+        self.assertEqual(
+            [row[:7] for row in rows[1:]],
+            [
+                ["readers", "namespace1", "False", "True", "False", "False", "False"],
+                ["readers", "namespace2", "False", "False", "False", "False", "False"],
+                ["writers", "namespace1", "True", "True", "True", "False", "False"],
+                ["writers", "namespace2", "False", "False", "False", "False", "False"],
+            ],
+        )
+
+    def test_export_filtering(self):
+        """Test filtering the export by namespace and group."""
+        rows = self._csv("/permissions/export?format=csv&namespace=namespace2")
+        self.assertEqual(
+            [row[:2] for row in rows[1:]],
+            [["readers", "namespace2"], ["writers", "namespace2"]],
+        )
+
+        response = self.assert_get(
+            "/permissions/export?group=writers&namespace=namespace1"
+        )
+        self.assertEqual(
+            response.data,
+            [
+                {
+                    "group": "writers",
+                    "namespace": "namespace1",
+                    "has_create": True,
+                    "has_read": True,
+                    "has_update": True,
+                    "has_delete": False,
+                    "has_namespace": False,
+                    "has_read_sensitive": False,
+                }
+            ],
+        )
+
+        self.assert_get_and_404("/permissions/export?format=csv&group=nosuchgroup")
+        self.assert_get_and_404("/permissions/export?namespace=nosuchnamespace")
+
+    def test_export_visibility(self):
+        """Test that users only export the namespaces they can read."""
+        self.client = self.get_user_client(username="reader", groupname="readers")
+        response = self.assert_get("/permissions/export")
+        self.assertEqual({row["namespace"] for row in response.data}, {"namespace1"})
+
+
+class APIPermissionExportLinks(PermissionExportTestCase):
+    """Test signed download links for the permission export."""
+
+    def _link(self, path="/permissions/export/link"):
+        """Create a link, and return it as a path below /api/v1."""
+        response = self.assert_get(path)
+        self.assertTrue(response.data["expires"] > timezone.now())
+        return urlsplit(response.data["url"]).path.replace("/api/v1", "", 1)
+
+    def test_download(self):
+        """Test downloading through a link, without authentication."""
+        link = self._link("/permissions/export/link?namespace=namespace2")
+        self.client = APIClient()
+        rows = self._csv(link)
+        self.assertEqual(
+            [row[:2] for row in rows[1:]],
+            [["readers", "namespace2"], ["writers", "namespace2"]],
+        )
+
+    def test_links_are_scoped_to_the_user(self):
+        """Test that the link exports what its creator could see."""
+        self.client = self.get_user_client(username="reader", groupname="readers")
+        link = self._link()
+        self.client = APIClient()
+        rows = self._csv(link)
+        self.assertEqual({row[1] for row in rows[1:]}, {"namespace1"})
+
+        # Suspended users' links stop working.
+        User.objects.filter(username="reader").update(is_active=False)
+        self.assert_get_and_404(link)
+
+    def test_invalid_links(self):
+        """Test expired, tampered, and invalid requests for links."""
+        self.assert_get_and_400("/permissions/export/link?expires_in=0")
+        self.assert_get_and_400("/permissions/export/link?expires_in=soon")
+        self.assert_get_and_400("/permissions/export/link?expires_in=99999999")
+        self.assert_get_and_404("/permissions/export/link?group=nosuchgroup")
+
+        link = self._link()
+        self.client = APIClient()
+        self.assert_get_and_404(link[:-1] + ("A" if link[-1] != "A" else "B"))
+
+        token, _ = sign_expiring(
+            {"export": "permissions", "user": 1, "params": {}}, -1, DOWNLOAD_SALT
+        )
+        self.assert_get_and_403(f"/downloads/{token}")
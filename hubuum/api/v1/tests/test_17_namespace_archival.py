@@ -0,0 +1,64 @@
+"""Test archiving namespaces."""
+from hubuum.models.namespace import Namespace
+
+from .base import HubuumAPITestCase
+
+
+class APINamespaceArchival(HubuumAPITestCase):
+    """Test that archived namespaces are read-only and hidden from listings."""
+
+    def setUp(self):
+        """Create an archived namespace with a host."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        self.assert_post("/namespaces/", {"name": "namespace2"})
+        self.assert_post(
+            "/hosts/", {"namespace": self.namespace.data["id"], "name": "web1"}
+        )
+        self.assert_patch("/namespaces/namespace1", {"archived": True})
+
+    def tearDown(self):
+        """Clean up the namespaces."""
+        Namespace.objects.filter(archived=True).update(archived=False)
+        self.client = self.get_superuser_client()
+        self.assert_delete("/namespaces/namespace1")
+        self.assert_delete("/namespaces/namespace2")
+
+    def test_listing(self):
+        """Test that archived namespaces are only listed when asked for."""
+        self.assert_get_elements("/namespaces/", 1)
+        self.assert_get_elements("/namespaces/?include_archived=true", 2)
+        self.assert_get_elements("/namespaces/?archived=true", 1)
+        self.assert_get_elements("/namespaces/?archived=false", 1)
+        response = self.assert_get("/namespaces/namespace1")
+        self.assertTrue(response.data["archived"])
+
+    def test_read_only(self):
+        """Test that archived namespaces and their objects are read-only."""
+        namespace = self.namespace.data["id"]
+        self.assert_get("/hosts/web1")
+        self.assert_post_and_409("/hosts/", {"namespace": namespace, "name": "web2"})
+        self.assert_patch_and_409("/hosts/web1", {"fqdn": "web1.domain.tld"})
+        self.assert_put_and_409("/hosts/web1/tags/production")
+        self.assert_delete_and_409("/hosts/web1")
+        self.assert_patch_and_409("/namespaces/namespace1", {"description": "no"})
+        self.assert_delete_and_409("/namespaces/namespace1")
+
+        response = self.assert_delete_and_200("/hosts/?name=web1&confirm=true")
+        self.assertEqual(response.data["deleted"], 0)
+        self.assertEqual(
+            response.data["skipped"][0]["reason"], "The namespace is archived."
+        )
+        self.assert_get("/hosts/web1")
+
+    def test_unarchiving(self):
+        """Test that unarchiving requires has_namespace, and makes writes possible."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.grant("tmpgroup", "namespace1", ["has_read"])
+        self.assert_patch_and_403("/namespaces/namespace1", {"archived": False})
+
+        self.client = self.get_superuser_client()
+        self.assert_patch("/namespaces/namespace1", {"archived": False})
+        self.assert_get_elements("/namespaces/", 2)
+        self.assert_patch("/hosts/web1", {"fqdn": "web1.domain.tld"})
+        self.assert_delete("/hosts/web1")
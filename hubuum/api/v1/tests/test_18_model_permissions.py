@@ -0,0 +1,84 @@
+"""Test permissions for specific models within a namespace."""
+from django.contrib.auth.models import Group
+
+from .base import HubuumAPITestCase
+
+
+class APIModelPermissions(HubuumAPITestCase):
+    """Test that model permissions refine the permissions for a namespace."""
+
+    def setUp(self):
+        """Create a namespace with a host and a room, and a user that may read it."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        data = {"namespace": self.namespace.data["id"]}
+        self.assert_post("/hosts/", {**data, "name": "web1"})
+        self.room = self.assert_post("/rooms/", {**data, "room_id": "BL14-01-100"})
+        Group.objects.get_or_create(name="tmpgroup")
+        self.grant("tmpgroup", "namespace1", ["has_read"])
+        self.models = "/namespaces/namespace1/groups/tmpgroup/models/"
+
+    def tearDown(self):
+        """Clean up the namespace."""
+        self.client = self.get_superuser_client()
+        self.assert_delete("/namespaces/namespace1")
+
+    def test_refining_permissions(self):
+        """Test that model permissions replace the namespace permissions."""
+        room = f"/rooms/{self.room.data['id']}"
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_elements("/rooms/", 1)
+        self.assert_delete_and_403("/hosts/web1")
+
+        self.client = self.get_superuser_client()
+        self.assert_post_and_201(f"{self.models}room", {"has_read": False})
+        self.assert_post_and_201(
+            f"{self.models}host", {"has_read": True, "has_delete": True}
+        )
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_elements("/rooms/", 0)
+        self.assert_get_and_403(room)
+        self.assert_get_elements("/hosts/", 1)
+        self.assert_delete("/hosts/web1")
+
+        self.client = self.get_superuser_client()
+        self.assert_delete(f"{self.models}room")
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get(room)
+
+    def test_managing_model_permissions(self):
+        """Test creating, modifying, and revoking model permissions."""
+        self.assert_get_elements(self.models, 0)
+        self.assert_get_and_404(f"{self.models}host")
+        self.assert_post_and_404(f"{self.models}nosuchmodel", {"has_read": True})
+        self.assert_post_and_404(f"{self.models}user", {"has_read": True})
+        self.assert_post_and_400(f"{self.models}host", {})
+        self.assert_post_and_400(f"{self.models}host", {"has_namespace": True})
+
+        response = self.assert_post_and_201(f"{self.models}host", {"has_read": True})
+        self.assertEqual(response.data["model"], "host")
+        self.assertTrue(response.data["has_read"])
+        self.assertFalse(response.data["has_update"])
+        self.assert_post_and_409(f"{self.models}host", {"has_read": True})
+
+        response = self.assert_patch(f"{self.models}host", {"has_update": True})
+        self.assertTrue(response.data["has_read"])
+        self.assertTrue(response.data["has_update"])
+        self.assert_get_elements(self.models, 1)
+
+        self.assert_delete(f"{self.models}host")
+        self.assert_delete_and_404(f"{self.models}host")
+        self.assert_get_elements(self.models, 0)
+
+    def test_managing_requires_has_namespace(self):
+        """Test that only groups with has_namespace may manage model permissions."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_post_and_403(f"{self.models}host", {"has_read": True})
+
+        self.client = self.get_superuser_client()
+        self.assert_patch_and_204(
+            "/namespaces/namespace1/groups/tmpgroup", {"has_namespace": True}
+        )
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_post_and_201(f"{self.models}host", {"has_read": True})
@@ -0,0 +1,118 @@
+"""Test exporting the relations between objects in a namespace as a graph."""
+from django.contrib.auth.models import Group
+
+from .base import HubuumAPITestCase
+
+
+class APINamespaceGraph(HubuumAPITestCase):
+    """Test /namespaces/<namespace>/graph."""
+
+    def setUp(self):
+        """Create a room with a rack of hosts, and a host elsewhere."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        self.other = self.assert_post("/namespaces/", {"name": "namespace2"})
+        data = {"namespace": self.namespace.data["id"]}
+        self.room = self.assert_post("/rooms/", {**data, "room_id": "BL14-01-100"})
+        self.rack = self.assert_post(
+            "/hosts/", {**data, "name": "rack", "room": self.room.data["id"]}
+        )
+        self.blade = self.assert_post(
+            "/hosts/", {**data, "name": "blade", "parent": self.rack.data["id"]}
+        )
+        self.assert_post(
+            "/hosts/",
+            {
+                "namespace": self.other.data["id"],
+                "name": "vm",
+                "parent": self.blade.data["id"],
+            },
+        )
+        self.graph = "/namespaces/namespace1/graph"
+
+    def tearDown(self):
+        """Clean up the namespaces."""
+        self.client = self.get_superuser_client()
+        self.assert_delete("/namespaces/namespace2")
+        self.assert_delete("/namespaces/namespace1")
+
+    def _node(self, model, response):
+        """Return the node id of an object."""
+        return f"{model}:{response.data['id']}"
+
+    def _edges(self, response):
+        """Return the edges of a graph as (source, relation, target)."""
+        return sorted(
+            (edge["source"], edge["relation"], edge["target"])
+            for edge in response.data["edges"]
+        )
+
+    def test_graph(self):
+        """Test that the graph has the objects in the namespace and their relations."""
+        rack, blade = self._node("host", self.rack), self._node("host", self.blade)
+        room = self._node("room", self.room)
+
+        response = self.assert_get(self.graph)
+        self.assertEqual(response.data["name"], "namespace1")
+        self.assertEqual(
+            sorted(node["id"] for node in response.data["nodes"]), [blade, rack, room]
+        )
+        self.assertEqual(
+            self._edges(response),
+            [(blade, "host.parent", rack), (rack, "host.room", room)],
+        )
+
+        response = self.assert_get(f"{self.graph}?models=host")
+        self.assertEqual(self._edges(response), [(blade, "host.parent", rack)])
+        self.assert_get_and_400(f"{self.graph}?models=host,nosuchmodel")
+
+    def test_depth(self):
+        """Test limiting the graph to the objects near an object."""
+        blade = self._node("host", self.blade)
+        response = self.assert_get(f"{self.graph}?root={blade}")
+        self.assertEqual(len(response.data["nodes"]), 2)
+        response = self.assert_get(f"{self.graph}?root={blade}&depth=2")
+        self.assertEqual(len(response.data["nodes"]), 3)
+        response = self.assert_get(f"{self.graph}?root={blade}&depth=0")
+        self.assertEqual(response.data["nodes"][0]["label"], "blade")
+
+        self.assert_get_and_400(f"{self.graph}?root=host:0")
+        self.assert_get_and_400(f"{self.graph}?root={blade}&depth=deep")
+        self.assert_get_and_400(f"{self.graph}?root={blade}&depth=-1")
+        self.assert_get_and_400(f"{self.graph}?root={blade}&depth=1000000000")
+        response = self.assert_get(f"{self.graph}?root={blade}&depth=16")
+        self.assertEqual(len(response.data["nodes"]), 3)
+
+    def test_formats(self):
+        """Test rendering the graph as DOT and GraphML."""
+        rack, room = self._node("host", self.rack), self._node("room", self.room)
+
+        response = self.assert_get(f"{self.graph}?format=dot")
+        self.assertEqual(response["Content-Type"], "text/vnd.graphviz; charset=utf-8")
+        content = response.content.decode()
+        self.assertTrue(content.startswith('digraph "namespace1" {'))
+        self.assertIn(f'"{rack}" -> "{room}" [label="host.room"];', content)
+
+        response = self.assert_get(f"{self.graph}?format=graphml")
+        content = response.content.decode()
+        self.assertIn(f'<edge source="{rack}" target="{room}">', content)
+        self.assertIn('<data key="label">rack</data>', content)
+
+    def test_permissions(self):
+        """Test that the graph only has the models the user can read."""
+        Group.objects.get_or_create(name="tmpgroup")
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_and_403(self.graph)
+
+        self.grant("tmpgroup", "namespace1", ["has_read"])
+        self.client = self.get_superuser_client()
+        self.assert_post_and_201(
+            "/namespaces/namespace1/groups/tmpgroup/models/room", {"has_read": False}
+        )
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        response = self.assert_get(self.graph)
+        self.assertEqual(
+            sorted(node["model"] for node in response.data["nodes"]), ["host", "host"]
+        )
+        self.assertEqual(len(response.data["edges"]), 1)
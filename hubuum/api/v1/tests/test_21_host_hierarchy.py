@@ -0,0 +1,257 @@
+"""Test the containment hierarchy of hosts."""
+from .base import HubuumAPITestCase
+
+
+class APIHostHierarchy(HubuumAPITestCase):
+    """Test host parents, children, and ancestors."""
+
+    def setUp(self):
+        """Create a chain of hosts: rack -> chassis -> blade -> vm."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        self.other = self.assert_post("/namespaces/", {"name": "namespace2"})
+
+        data = {"namespace": self.namespace.data["id"]}
+        for name in ["rack", "chassis", "blade", "vm"]:
+            host = self.assert_post("/hosts/", {**data, "name": name})
+            data["parent"] = host.data["id"]
+
+    def tearDown(self):
+        """Clean up the namespaces."""
+        self.client = self.get_superuser_client()
+        self.assert_delete("/namespaces/namespace1")
+        self.assert_delete("/namespaces/namespace2")
+
+    def _names(self, response):
+        """Return the (depth, name) pairs in a response."""
+        return [(host["depth"], host["name"]) for host in response.data]
+
+    def test_children(self):
+        """Test listing children with and without depth."""
+        response = self.assert_get("/hosts/rack/children/")
+        self.assertEqual(self._names(response), [(1, "chassis")])
+
+        response = self.assert_get("/hosts/rack/children/?depth=3")
+        self.assertEqual(
+            self._names(response), [(1, "chassis"), (2, "blade"), (3, "vm")]
+        )
+        self.assert_get_elements("/hosts/vm/children/", 0)
+
+    def test_ancestors(self):
+        """Test listing ancestors with and without depth."""
+        response = self.assert_get("/hosts/vm/ancestors/")
+        self.assertEqual(
+            self._names(response), [(1, "blade"), (2, "chassis"), (3, "rack")]
+        )
+
+        response = self.assert_get("/hosts/vm/ancestors/?depth=1")
+        self.assertEqual(self._names(response), [(1, "blade")])
+        self.assert_get_elements("/hosts/rack/ancestors/", 0)
+
+    def test_filter_by_parent(self):
+        """Test filtering hosts on their parent."""
+        rack = self.assert_get("/hosts/rack")
+        response = self.assert_get_elements(f"/hosts/?parent={rack.data['id']}", 1)
+        self.assertEqual(response.data[0]["name"], "chassis")
+
+    def test_invalid_requests(self):
+        """Test invalid depths and cycles."""
+        self.assert_get_and_400("/hosts/rack/children/?depth=0")
+        self.assert_get_and_400("/hosts/rack/children/?depth=many")
+        self.assert_get_and_400("/hosts/vm/ancestors/?depth=-1")
+        self.assert_get_and_404("/hosts/nosuchhost/children/")
+
+        vm = self.assert_get("/hosts/vm")
+        rack = self.assert_get("/hosts/rack")
+        self.assert_patch_and_400("/hosts/rack", {"parent": vm.data["id"]})
+        self.assert_patch_and_400("/hosts/rack", {"parent": rack.data["id"]})
+
+    def test_deleting_parent(self):
+        """Test that deleting a parent detaches its children."""
+        self.assert_delete("/hosts/chassis")
+        response = self.assert_get("/hosts/blade")
+        self.assertIsNone(response.data["parent"])
+
+    def test_visibility(self):
+        """Test that users only see hosts they can read."""
+        blade = self.assert_get("/hosts/blade")
+        self.assert_patch("/hosts/chassis", {"namespace": self.other.data["id"]})
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_and_403("/hosts/rack/children/")
+
+        self.grant("tmpgroup", "namespace1", ["has_read"])
+        self.assert_get_elements("/hosts/rack/children/", 0)
+        response = self.assert_get(f"/hosts/{blade.data['id']}/ancestors/")
+        self.assertEqual(self._names(response), [(1, "rack")])
+
+        # The depths count the readable hosts only.
+        response = self.assert_get("/hosts/rack/children/?depth=3")
+        self.assertEqual(self._names(response), [(1, "blade"), (2, "vm")])
+        response = self.assert_get("/hosts/vm/ancestors/")
+        self.assertEqual(self._names(response), [(1, "blade"), (2, "rack")])
+
+
+class APIHostOrdering(HubuumAPITestCase):
+    """Test the ordering of contained hosts."""
+
+    def setUp(self):
+        """Create a patch panel with three ports."""
+        super().setUp()
+        namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        panel = self.assert_post(
+            "/hosts/", {"name": "panel", "namespace": namespace.data["id"]}
+        )
+        for name in ["port1", "port2", "port3"]:
+            self.assert_post(
+                "/hosts/",
+                {
+                    "name": name,
+                    "namespace": namespace.data["id"],
+                    "parent": panel.data["id"],
+                },
+            )
+
+    def tearDown(self):
+        """Clean up the namespace."""
+        self.client = self.get_superuser_client()
+        self.assert_delete("/namespaces/namespace1")
+
+    def _order(self, path="/hosts/panel/children/"):
+        """Return the names of the children of the panel, in order."""
+        return [host["name"] for host in self.assert_get(path).data]
+
+    def test_reorder(self):
+        """Test reordering children by name and ID."""
+        self.assertEqual(self._order(), ["port1", "port2", "port3"])
+
+        port3 = self.assert_get("/hosts/port3")
+        response = self.assert_put(
+            "/hosts/panel/children/", {"children": [port3.data["id"], "port1"]}
+        )
+        self.assertEqual(
+            [(host["name"], host["position"]) for host in response.data],
+            [("port3", 1), ("port1", 2), ("port2", 3)],
+        )
+        self.assertEqual(self._order(), ["port3", "port1", "port2"])
+
+    def test_position_is_optional(self):
+        """Test that hosts without a position are listed last."""
+        self.assert_patch("/hosts/port3", {"position": 1})
+        self.assertEqual(self._order(), ["port3", "port1", "port2"])
+        self.assert_get_elements("/hosts/?position__gte=1", 1)
+
+    def test_reorder_errors(self):
+        """Test invalid reordering requests."""
+        self.assert_put_and_400("/hosts/panel/children/", {"children": ["panel"]})
+        self.assert_put_and_400("/hosts/panel/children/", {"children": "port1"})
+        self.assert_put_and_400("/hosts/panel/children/", ["port1"])
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.grant("tmpgroup", "namespace1", ["has_read"])
+        self.assert_put_and_403("/hosts/panel/children/", {"children": ["port1"]})
+
+        self.client = self.get_user_client(username="tmp2", groupname="tmpgroup2")
+        self.grant("tmpgroup2", "namespace1", ["has_read", "has_update"])
+        self.assert_put("/hosts/panel/children/", {"children": ["port2"]})
+        self.assertEqual(self._order(), ["port2", "port1", "port3"])
+
+
+class APIHostCloning(HubuumAPITestCase):
+    """Test cloning of hosts."""
+
+    def setUp(self):
+        """Create a host with relations and extension data."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        self.other = self.assert_post("/namespaces/", {"name": "namespace2"})
+
+        data = {"namespace": self.namespace.data["id"]}
+        person = self.assert_post("/persons/", {**data, "username": "someone"})
+        rack = self.assert_post("/hosts/", {**data, "name": "rack"})
+        self.host = self.assert_post(
+            "/hosts/",
+            {
+                **data,
+                "name": "blade",
+                "fqdn": "blade.example.com",
+                "person": person.data["id"],
+                "parent": rack.data["id"],
+            },
+        )
+        extension = self.assert_post(
+            "/extensions/",
+            {
+                **data,
+                "name": "fleet",
+                "model": "host",
+                "url": "https://fleet.example.com/{fqdn}",
+                "header": "Authorization: Bearer sh...==",
+            },
+        )
+        self.assert_post(
+            "/extension_data/",
+            {
+                **data,
+                "extension": extension.data["id"],
+                "content_type": "host",
+                "object_id": self.host.data["id"],
+                "json_data": {"key": "value"},
+            },
+        )
+
+    def tearDown(self):
+        """Clean up the namespaces."""
+        self.client = self.get_superuser_client()
+        self.assert_delete("/namespaces/namespace1")
+        self.assert_delete("/namespaces/namespace2")
+
+    def test_clone(self):
+        """Test cloning with the default options."""
+        response = self.assert_post("/hosts/blade/clone", {"name": "blade2"})
+        self.assertNotEqual(response.data["id"], self.host.data["id"])
+        self.assertEqual(response.data["name"], "blade2")
+        for field in ["namespace", "fqdn", "person", "parent", "serial"]:
+            self.assertEqual(response.data[field], self.host.data[field])
+        self.assertEqual(response.data["extension_data"], {"fleet": None})
+
+        # The host itself is left untouched.
+        self.assert_get_elements("/hosts/rack/children/", 2)
+        response = self.assert_get("/hosts/blade")
+        self.assertEqual(response.data["extension_data"], {"fleet": {"key": "value"}})
+
+    def test_clone_options(self):
+        """Test cloning into another namespace, with and without relations."""
+        response = self.assert_post(
+            "/hosts/blade/clone",
+            {
+                "name": "blade2",
+                "fqdn": "blade2.example.com",
+                "namespace": "namespace2",
+                "relations": False,
+                "extension_data": True,
+            },
+        )
+        self.assertEqual(response.data["namespace"], self.other.data["id"])
+        self.assertEqual(response.data["fqdn"], "blade2.example.com")
+        self.assertIsNone(response.data["person"])
+        self.assertIsNone(response.data["parent"])
+        self.assertEqual(response.data["extension_data"], {"fleet": {"key": "value"}})
+
+        self.assert_post_and_400("/hosts/blade/clone", {"fqdn": "noname"})
+        self.assert_post_and_400("/hosts/blade/clone", {"name": "x", "relations": 1})
+        self.assert_post_and_404("/hosts/blade/clone", {"name": "x", "namespace": 0})
+        self.assert_post_and_404("/hosts/nosuchhost/clone", {"name": "x"})
+
+    def test_clone_permissions(self):
+        """Test that cloning requires read on the host and create in the target."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_post_and_403("/hosts/blade/clone", {"name": "blade2"})
+
+        self.grant("tmpgroup", "namespace1", ["has_read"])
+        self.assert_post_and_403("/hosts/blade/clone", {"name": "blade2"})
+
+        self.grant("tmpgroup", "namespace2", ["has_read", "has_create"])
+        self.assert_post(
+            "/hosts/blade/clone", {"name": "blade2", "namespace": "namespace2"}
+        )
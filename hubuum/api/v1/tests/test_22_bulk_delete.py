@@ -0,0 +1,80 @@
+"""Test bulk deletion of filtered objects."""
+from .base import HubuumAPITestCase
+
+
+class APIBulkDelete(HubuumAPITestCase):
+    """Test deleting all objects matching a filter."""
+
+    def setUp(self):
+        """Create temporary and permanent hosts in two namespaces."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        self.other = self.assert_post("/namespaces/", {"name": "namespace2"})
+        for namespace in [self.namespace, self.other]:
+            data = {"namespace": namespace.data["id"]}
+            for name in ["tmp-1", "tmp-2", "web"]:
+                name = f"{name}.{namespace.data['name']}"
+                self.assert_post("/hosts/", {**data, "name": name})
+
+    def tearDown(self):
+        """Clean up the namespaces."""
+        self.client = self.get_superuser_client()
+        self.assert_delete("/namespaces/namespace1")
+        self.assert_delete("/namespaces/namespace2")
+
+    def test_dry_run(self):
+        """Test that a dry run reports what would be deleted, deleting nothing."""
+        response = self.assert_delete_and_200(
+            "/hosts/?name__startswith=tmp-&dry_run=true"
+        )
+        self.assertEqual(
+            response.data,
+            {"dry_run": True, "matched": 4, "deleted": 4, "skipped": []},
+        )
+        self.assert_get_elements("/hosts/?name__startswith=tmp-", 4)
+
+    def test_confirmed_delete(self):
+        """Test deleting with confirmation."""
+        namespace = self.namespace.data["id"]
+        response = self.assert_delete_and_200(
+            f"/hosts/?name__startswith=tmp-&namespace={namespace}&confirm=true"
+        )
+        self.assertEqual(response.data["dry_run"], False)
+        self.assertEqual(response.data["deleted"], 2)
+        self.assert_get_elements("/hosts/?name__startswith=tmp-", 2)
+        self.assert_get_elements("/hosts/", 4)
+
+    def test_invalid_requests(self):
+        """Test that bulk deletes require filters and a confirmation."""
+        self.assert_delete_and_400("/hosts/?confirm=true")
+        self.assert_delete_and_400("/hosts/?name__startswith=tmp-")
+        self.assert_delete_and_400("/hosts/?name__startswith=tmp-&confirm=no")
+        self.assert_get_elements("/hosts/", 6)
+
+    def test_misspelled_filters(self):
+        """Test that unknown or empty filters are refused, even when not strict."""
+        response = self.assert_delete_and_400(
+            "/hosts/?nmae__startswith=tmp-&confirm=true&strict=false"
+        )
+        self.assertIn("nmae__startswith", response.data)
+        self.assert_delete_and_400("/hosts/?name__startswith=&confirm=true")
+        self.assert_get_elements("/hosts/", 6)
+
+    def test_permissions(self):
+        """Test that objects the user can see but not delete are skipped."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.grant("tmpgroup", "namespace1", ["has_read", "has_delete"])
+        self.grant("tmpgroup", "namespace2", ["has_read"])
+
+        response = self.assert_delete_and_200(
+            "/hosts/?name__startswith=tmp-&confirm=true"
+        )
+        self.assertEqual(response.data["matched"], 4)
+        self.assertEqual(response.data["deleted"], 2)
+        self.assertEqual(len(response.data["skipped"]), 2)
+
+        response = self.assert_get_elements("/hosts/?name__startswith=tmp-", 2)
+        self.assertEqual(
+            [host["name"] for host in response.data],
+            ["tmp-1.namespace2", "tmp-2.namespace2"],
+        )
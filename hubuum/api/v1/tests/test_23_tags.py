@@ -0,0 +1,80 @@
+"""Test tagging of objects."""
+from .base import HubuumAPITestCase
+
+
+class APITags(HubuumAPITestCase):
+    """Test adding, removing, and filtering on tags."""
+
+    def setUp(self):
+        """Create a namespace with a few hosts and a room."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        data = {"namespace": self.namespace.data["id"]}
+        for name in ["web1", "web2", "db1"]:
+            self.assert_post("/hosts/", {**data, "name": name})
+        room = self.assert_post("/rooms/", {**data, "room_id": "BL14-01-100"})
+        self.room = room.data["id"]
+
+    def tearDown(self):
+        """Clean up the namespace."""
+        self.client = self.get_superuser_client()
+        self.assert_delete("/namespaces/namespace1")
+
+    def test_tagging(self):
+        """Test adding and removing tags."""
+        self.assert_get_elements("/hosts/web1/tags/", 0)
+        response = self.assert_put_and_201("/hosts/web1/tags/production")
+        self.assertEqual(response.data, ["production"])
+        # Tagging again changes nothing.
+        self.assert_put("/hosts/web1/tags/production")
+        response = self.assert_put_and_201("/hosts/web1/tags/frontend")
+        self.assertEqual(response.data, ["frontend", "production"])
+
+        response = self.assert_get("/hosts/web1")
+        self.assertEqual(response.data["tags"], ["frontend", "production"])
+
+        self.assert_delete_and_204("/hosts/web1/tags/frontend")
+        self.assert_delete_and_404("/hosts/web1/tags/frontend")
+        self.assert_get_elements("/hosts/web1/tags/", 1)
+
+        self.assert_put_and_201(f"/rooms/{self.room}/tags/production")
+        self.assert_get_elements(f"/rooms/{self.room}/tags/", 1)
+
+        # Deleting an object removes its tags.
+        self.assert_delete("/hosts/web1")
+        self.assert_get_elements("/hosts/?tags=production", 0)
+
+    def test_invalid_tags(self):
+        """Test that invalid tags and objects are rejected."""
+        self.assert_put_and_400("/hosts/web1/tags/not;valid")
+        self.assert_put_and_400(f"/hosts/web1/tags/{'x' * 65}")
+        self.assert_get_and_404("/hosts/nosuchhost/tags/")
+        self.assert_delete_and_404("/hosts/web1/tags/nosuchtag")
+
+    def test_tag_filtering(self):
+        """Test filtering objects on their tags."""
+        self.assert_put_and_201("/hosts/web1/tags/production")
+        self.assert_put_and_201("/hosts/web1/tags/frontend")
+        self.assert_put_and_201("/hosts/web2/tags/frontend")
+        self.assert_put_and_201("/hosts/db1/tags/production-db")
+        self.assert_put_and_201(f"/rooms/{self.room}/tags/production")
+
+        self.assert_get_elements("/hosts/?tags=production", 1)
+        self.assert_get_elements("/hosts/?tags=frontend", 2)
+        self.assert_get_elements("/hosts/?tags=frontend,production", 1)
+        self.assert_get_elements("/hosts/?tags__contains=production", 2)
+        self.assert_get_elements("/hosts/?tags__startswith=PROD", 0)
+        self.assert_get_elements("/hosts/?tags__istartswith=PROD", 2)
+        self.assert_get_elements("/hosts/?tags__contains=o&name=web1", 1)
+        self.assert_get_elements("/rooms/?tags=production", 1)
+
+    def test_tag_permissions(self):
+        """Test that reading tags requires has_read, and tagging has_update."""
+        self.assert_put_and_201("/hosts/web1/tags/production")
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_and_403("/hosts/web1/tags/")
+
+        self.grant("tmpgroup", "namespace1", ["has_read"])
+        self.assert_get_elements("/hosts/web1/tags/", 1)
+        self.assert_put_and_403("/hosts/web1/tags/frontend")
+        self.assert_delete_and_403("/hosts/web1/tags/production")
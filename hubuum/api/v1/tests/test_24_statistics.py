@@ -0,0 +1,104 @@
+"""Test statistics for the objects of a model."""
+from django.core.cache import cache
+from django.test import override_settings
+
+from .base import HubuumAPITestCase
+
+
+class APIStatistics(HubuumAPITestCase):
+    """Test /stats/<model>."""
+
+    def setUp(self):
+        """Create two namespaces with hosts, a room, and extension data."""
+        super().setUp()
+        cache.clear()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        other = self.assert_post("/namespaces/", {"name": "namespace2"})
+        data = {"namespace": self.namespace.data["id"]}
+        room = self.assert_post("/rooms/", {**data, "room_id": "BL14-01-100"})
+        self.assert_post("/hosts/", {**data, "name": "web1", "room": room.data["id"]})
+        self.assert_post("/hosts/", {**data, "name": "web2"})
+        self.assert_post("/hosts/", {"namespace": other.data["id"], "name": "db1"})
+        self.assert_put_and_201("/hosts/web1/tags/production")
+
+        extension = self.assert_post(
+            "/extensions/",
+            {
+                **data,
+                "name": "test_extension",
+                "model": "host",
+                "url": "https://www.example.com/{name}",
+            },
+        )
+        web1 = self.assert_get("/hosts/web1")
+        self.assert_post(
+            "/extension_data/",
+            {
+                **data,
+                "extension": extension.data["id"],
+                "content_type": "host",
+                "object_id": web1.data["id"],
+                "json_data": {"key": "value"},
+            },
+        )
+
+    def tearDown(self):
+        """Clean up the namespaces."""
+        self.client = self.get_superuser_client()
+        self.assert_delete("/namespaces/namespace1")
+        self.assert_delete("/namespaces/namespace2")
+
+    def test_statistics(self):
+        """Test the statistics of hosts and rooms."""
+        response = self.assert_get("/stats/hosts")
+        self.assertEqual(response.data["count"], 3)
+        self.assertIsNotNone(response.data["last_modified"])
+        self.assertEqual(response.data["relations"]["room"], 1)
+        self.assertEqual(response.data["relations"]["type"], 0)
+        self.assertEqual(response.data["extension_data"]["count"], 1)
+        self.assertEqual(
+            response.data["extension_data"]["average_size"], len('{"key": "value"}')
+        )
+        self.assertEqual(response.data["tags"], 1)
+
+        response = self.assert_get("/stats/rooms")
+        self.assertEqual(response.data["count"], 1)
+        self.assertEqual(response.data["relations"]["hosts"], 1)
+        self.assertEqual(response.data["extension_data"]["count"], 0)
+        self.assertIsNone(response.data["extension_data"]["average_size"])
+
+        namespace = self.namespace.data["id"]
+        response = self.assert_get(f"/stats/hosts?namespace={namespace}")
+        self.assertEqual(response.data["count"], 2)
+
+        response = self.assert_get("/stats/vendors")
+        self.assertEqual(response.data["count"], 0)
+        self.assertIsNone(response.data["last_modified"])
+
+    def test_statistics_distinct(self):
+        """Test that objects joined in more than once by filters count once."""
+        self.assert_put_and_201("/hosts/web1/tags/prod-web")
+        response = self.assert_get("/stats/hosts?tags__startswith=prod")
+        self.assertEqual(response.data["count"], 1)
+        self.assertEqual(response.data["relations"]["room"], 1)
+        self.assertEqual(response.data["extension_data"]["count"], 1)
+        self.assertEqual(response.data["tags"], 2)
+
+    def test_statistics_permissions(self):
+        """Test that only objects the user can read are counted."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assertEqual(self.assert_get("/stats/hosts").data["count"], 0)
+        self.assert_post_and_405("/stats/hosts", {})
+
+        self.grant("tmpgroup", "namespace2", ["has_read"])
+        cache.clear()
+        self.assertEqual(self.assert_get("/stats/hosts").data["count"], 1)
+
+    @override_settings(STATISTICS_CACHE_TIME=3600)
+    def test_statistics_caching(self):
+        """Test that the statistics are cached."""
+        self.assertEqual(self.assert_get("/stats/hosts").data["count"], 3)
+        self.assert_delete("/hosts/web2")
+        self.assertEqual(self.assert_get("/stats/hosts").data["count"], 3)
+        cache.clear()
+        self.assertEqual(self.assert_get("/stats/hosts").data["count"], 2)
@@ -0,0 +1,69 @@
+"""Test the lifecycles of objects."""
+from .base import HubuumAPITestCase
+
+
+class APILifecycles(HubuumAPITestCase):
+    """Test states, and the transitions between them, of the objects of a model."""
+
+    lifecycle = {
+        "model": "host",
+        "states": ["planned", "active", "retired"],
+        "transitions": {"planned": ["active"], "active": ["retired"]},
+    }
+
+    def setUp(self):
+        """Create a namespace, and a lifecycle for hosts."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"}).data
+        self.assert_post("/lifecycles/", self.lifecycle)
+
+    def tearDown(self):
+        """Clean up the namespace."""
+        self.client = self.get_superuser_client()
+        self.assert_delete("/namespaces/namespace1")
+
+    def _host(self, name, **fields):
+        """Create a host in the namespace."""
+        data = {"namespace": self.namespace["id"], "name": name, **fields}
+        return self.assert_post("/hosts/", data).data
+
+    def test_transitions(self):
+        """Test that objects start in the first state, and follow the transitions."""
+        self.assertEqual(self._host("web1")["state"], "planned")
+        self.assertEqual(self._host("web2", state="active")["state"], "active")
+        data = {"namespace": self.namespace["id"], "name": "web3", "state": "broken"}
+        self.assert_post_and_400("/hosts/", data)
+
+        response = self.assert_patch_and_400("/hosts/web1", {"state": "retired"})
+        self.assertIn("allowed: active", str(response.data["state"]))
+        self.assert_patch("/hosts/web1", {"state": "active"})
+        self.assert_patch("/hosts/web1", {"state": "retired"})
+        response = self.assert_patch_and_400("/hosts/web1", {"state": "active"})
+        self.assertIn("allowed: none", str(response.data["state"]))
+
+        # Other changes to objects leave the state alone.
+        self.assert_patch("/hosts/web1", {"fqdn": "web1.domain.tld"})
+
+        self.assert_get_elements("/hosts/?state=retired", 1)
+        self.assert_get_elements("/hosts/?state__in=planned,active", 1)
+
+        # Without the lifecycle, objects may be in any state.
+        self.assert_delete("/lifecycles/host")
+        self.assert_patch("/hosts/web1", {"state": "scrapped"})
+        self.assertEqual(self._host("web4")["state"], "")
+
+    def test_invalid_lifecycles(self):
+        """Test that lifecycles must have states, and transitions between them."""
+        lifecycle = {**self.lifecycle, "model": "room"}
+        self.assert_post_and_400("/lifecycles/", {**lifecycle, "states": []})
+        self.assert_post_and_400("/lifecycles/", {**lifecycle, "states": ["a", "a"]})
+        self.assert_post_and_400(
+            "/lifecycles/", {**lifecycle, "transitions": {"planned": ["gone"]}}
+        )
+        self.assert_post_and_400("/lifecycles/", {**lifecycle, "model": "namespace"})
+        self.assert_post_and_400("/lifecycles/", self.lifecycle)
+        self.assert_patch_and_400("/lifecycles/host", {"states": ["planned"]})
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get("/lifecycles/host")
+        self.assert_post_and_403("/lifecycles/", lifecycle)
@@ -0,0 +1,91 @@
+"""Test traversing the relations between objects, and listing related models."""
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host, Person, Room
+
+from .base import HubuumAPITestCase
+
+
+class APITraverse(HubuumAPITestCase):
+    """Test /<model>/<val>/traverse?path=..."""
+
+    def setUp(self):
+        """Create a room with persons and hosts, and a host within a host."""
+        super().setUp()
+        self.namespace = Namespace.objects.create(name="namespace1")
+        self.secret = Namespace.objects.create(name="secret")
+        namespace = self.namespace
+        self.room = Room.objects.create(room_id="101", namespace=namespace)
+        Person.objects.create(username="alice", room=self.room, namespace=namespace)
+        Person.objects.create(username="bob", room=self.room, namespace=self.secret)
+        rack = Host.objects.create(name="rack", room=self.room, namespace=namespace)
+        Host.objects.create(name="blade", parent=rack, namespace=namespace)
+
+    def _traverse(self, path, start="/hosts/rack"):
+        """Traverse a path, and return the path, the model, and the objects reached."""
+        response = self.assert_get(f"{start}/traverse?path={path}")
+        return response.data["path"], response.data["model"], response.data["objects"]
+
+    def test_traverse(self):
+        """Test following relations by name, and by the names of models."""
+        path, model, objects = self._traverse("Room.Person")
+        self.assertEqual(path, ["room", "person_set"])
+        self.assertEqual(model, "person")
+        self.assertEqual([obj["username"] for obj in objects], ["alice", "bob"])
+
+        _, model, objects = self._traverse("children.parent.room")
+        self.assertEqual(model, "room")
+        self.assertEqual([obj["id"] for obj in objects], [self.room.id])
+
+        _, _, objects = self._traverse("hosts", start=f"/rooms/{self.room.id}")
+        self.assertEqual([obj["name"] for obj in objects], ["rack"])
+        _, _, objects = self._traverse("parent", start="/hosts/rack")
+        self.assertEqual(objects, [])
+
+    def test_traverse_errors(self):
+        """Test that unknown, ambiguous, and too long paths get 400."""
+        self.assert_get_and_400("/hosts/rack/traverse")
+        self.assert_get_and_400("/hosts/rack/traverse?path=room..person")
+        self.assert_get_and_400("/hosts/rack/traverse?path=vendor")
+        # Hosts relate to hosts both as parent and children.
+        response = self.assert_get_and_400("/hosts/rack/traverse?path=host")
+        self.assertIn("parent", response.data["detail"])
+        self.assert_get_and_400(f"/hosts/rack/traverse?path={'.'.join(['room'] * 9)}")
+        self.assert_get_and_404("/hosts/nosuchhost/traverse?path=room")
+
+    def test_traverse_permissions(self):
+        """Test that objects the user can not read are left out."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_and_403("/hosts/rack/traverse?path=room")
+
+        self.grant("tmpgroup", "namespace1", ["has_read"])
+        _, _, objects = self._traverse("room.Person")
+        self.assertEqual([obj["username"] for obj in objects], ["alice"])
+
+    def test_related_models(self):
+        """Test listing the models related to a model, by their shortest path."""
+        response = self.assert_get("/related/vendors")
+        self.assertEqual(response.data["model"], "vendor")
+        related = {entry["model"]: entry for entry in response.data["related"]}
+        self.assertNotIn("vendor", related)
+        depths = [entry["depth"] for entry in response.data["related"]]
+        self.assertEqual(depths, [1, 2, 2, 3, 3, 3, 3])
+        self.assertEqual(related["host"]["path"], "purchaseorder_set.hosts")
+        self.assertEqual(
+            [step["direction"] for step in related["host"]["steps"]],
+            ["reverse", "reverse"],
+        )
+
+        response = self.assert_get("/related/hosts?depth=1")
+        related = {entry["model"]: entry for entry in response.data["related"]}
+        self.assertEqual(len(related), 6)
+        self.assertEqual(related["host"]["path"], "children")
+        self.assertEqual(related["room"]["steps"][0]["direction"], "forward")
+
+        # The paths may be traversed.
+        path = response.data["related"][0]["path"]
+        self.assert_get(f"/hosts/rack/traverse?path={path}")
+
+        self.assert_get_and_400("/related/hosts?depth=0")
+        self.assert_get_and_400("/related/hosts?depth=deep")
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get("/related/hosts")
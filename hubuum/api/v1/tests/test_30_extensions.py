@@ -1,5 +1,7 @@
 """Test hubuum extensions."""
-from hubuum.models.base import ExtensionData, Host, Namespace
+from hubuum.models.extensions import ExtensionData
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host
 
 from .base import HubuumAPITestCase
 
@@ -189,3 +191,16 @@ class APIExtensionsData(HubuumExtensionTestCase):
         hblob = self.assert_get("/hosts/test2")
         self.assertIsNone(hblob.data["extension_data"]["fleet"])
         self.assertIsNone(hblob.data["extension_data"]["ansible"])
+
+    def test_extension_data_field_errors(self):
+        """Test that malformed extension data gets 400 with errors by field."""
+        exblob = self.assert_post("/extensions/", self.extension_blob)
+        response = self.assert_post_and_400("/extension_data/", {"json_data": {}})
+        for field in ("namespace", "extension", "content_type", "object_id"):
+            self.assertIn(field, response.data)
+
+        blob = self._extension_data_blob(exblob.data["id"])
+        response = self.assert_post_and_400(
+            "/extension_data/", {**blob, "extension": "fleet", "nosuchkey": 1}
+        )
+        self.assertEqual(sorted(response.data), ["nosuchkey"])
@@ -0,0 +1,81 @@
+"""Test revisions and diffs of extension data."""
+from .test_30_extensions import HubuumExtensionTestCase
+
+
+class APIExtensionDataRevisions(HubuumExtensionTestCase):
+    """Test revisions of extension data."""
+
+    def _create_revisions(self):
+        """Create an extension data object with two revisions, return its ID."""
+        exblob = self.assert_post("/extensions/", self.extension_blob)
+        extension_id = exblob.data["id"]
+
+        exdblob = self.assert_post(
+            "/extension_data/", self._extension_data_blob(extension_id)
+        )
+        # Posting identical data does not create a new revision.
+        self.assert_post("/extension_data/", self._extension_data_blob(extension_id))
+        self.assert_post(
+            "/extension_data/",
+            self._extension_data_blob(extension_id, value="newvalue"),
+        )
+        return exdblob.data["id"]
+
+    def test_revisions(self):
+        """Test that revisions are recorded as the data changes."""
+        exdid = self._create_revisions()
+        response = self.assert_get_elements(f"/extension_data/{exdid}/revisions/", 2)
+        self.assertEqual(response.data[0]["version"], 1)
+        self.assertEqual(response.data[0]["json_data"]["key"], "value")
+        self.assertEqual(response.data[1]["version"], 2)
+        self.assertEqual(response.data[1]["json_data"]["key"], "newvalue")
+        self.assert_get_and_404("/extension_data/99999/revisions/")
+
+    def test_diff(self):
+        """Test diffing between revisions."""
+        exdid = self._create_revisions()
+
+        response = self.assert_get(f"/extension_data/{exdid}/diff")
+        self.assertEqual(response.data["from"], 1)
+        self.assertEqual(response.data["to"], 2)
+        self.assertEqual(
+            response.data["changed"], {"key": {"from": "value", "to": "newvalue"}}
+        )
+        self.assertEqual(response.data["added"], {})
+        self.assertEqual(response.data["removed"], {})
+
+        response = self.assert_get(f"/extension_data/{exdid}/diff?from=2&to=1")
+        self.assertEqual(
+            response.data["changed"], {"key": {"from": "newvalue", "to": "value"}}
+        )
+
+        # The first revision is diffed against nothing.
+        response = self.assert_get(f"/extension_data/{exdid}/diff?to=1")
+        self.assertIsNone(response.data["from"])
+        self.assertEqual(response.data["added"], {"key": "value", "listkey": [1, 2, 3]})
+
+    def test_diff_by_timestamp(self):
+        """Test diffing using timestamps."""
+        exdid = self._create_revisions()
+
+        response = self.assert_get(
+            f"/extension_data/{exdid}/diff?from=2999-01-01T00:00:00Z"
+        )
+        self.assertEqual(response.data["from"], 2)
+        self.assertEqual(response.data["changed"], {})
+
+        response = self.assert_get(f"/extension_data/{exdid}/diff?from=2999-01-01")
+        self.assertEqual(response.data["from"], 2)
+
+        self.assert_get_and_404(f"/extension_data/{exdid}/diff?to=2000-01-01")
+
+    def test_diff_errors(self):
+        """Test invalid diff requests."""
+        exdid = self._create_revisions()
+        self.assert_get_and_400(f"/extension_data/{exdid}/diff?from=notadate")
+        self.assert_get_and_404(f"/extension_data/{exdid}/diff?to=99")
+        self.assert_get_and_404("/extension_data/99999/diff")
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_and_403(f"/extension_data/{exdid}/diff")
+        self.assert_get_and_403(f"/extension_data/{exdid}/revisions/")
@@ -0,0 +1,156 @@
+"""Test JSON schemas for extensions and migrating data between them."""
+from .test_30_extensions import HubuumExtensionTestCase
+
+
+class APIExtensionSchemas(HubuumExtensionTestCase):
+    """Test schemas for extensions."""
+
+    schema = {
+        "type": "object",
+        "properties": {
+            "key": {"type": "string"},
+            "state": {"type": "string", "default": "active"},
+        },
+        "required": ["key", "state"],
+    }
+
+    def _create_data(self):
+        """Create an extension with data for two hosts, return the extension ID."""
+        exblob = self.assert_post("/extensions/", self.extension_blob)
+        extension_id = exblob.data["id"]
+
+        self.assert_post("/extension_data/", self._extension_data_blob(extension_id))
+        self.assert_post(
+            "/extension_data/",
+            {
+                **self._extension_data_blob(extension_id, value=5),
+                "object_id": self.host2.id,
+            },
+        )
+        return extension_id
+
+    def _migrate(self, extension_id, **kwargs):
+        """Post a schema migration for the extension."""
+        return self.assert_post_and_200(
+            f"/extensions/{extension_id}/migrate_schema",
+            {"json_schema": self.schema, **kwargs},
+        )
+
+    def _data(self, extension_id):
+        """Return the extension data for the extension, ordered by ID."""
+        return self.assert_get(f"/extension_data/?extension={extension_id}").data
+
+    def test_invalid_schema(self):
+        """Test that invalid schemas are rejected."""
+        self.assert_post_and_400(
+            "/extensions/", {**self.extension_blob, "json_schema": {"type": 5}}
+        )
+        self.assert_post_and_400(
+            "/extensions/", {**self.extension_blob, "json_schema": ["type"]}
+        )
+
+        extension_id = self._create_data()
+        self.assert_post_and_400(
+            f"/extensions/{extension_id}/migrate_schema",
+            {"json_schema": {"type": "nosuchtype"}},
+        )
+        self.assert_post_and_400(f"/extensions/{extension_id}/migrate_schema", {})
+        self.assert_post_and_400(
+            f"/extensions/{extension_id}/migrate_schema",
+            {"json_schema": self.schema, "dry_run": "no"},
+        )
+
+    def test_dry_run(self):
+        """Test that a dry run reports without changing anything."""
+        extension_id = self._create_data()
+        first, second = [data["id"] for data in self._data(extension_id)]
+
+        response = self._migrate(extension_id)
+        self.assertTrue(response.data["dry_run"])
+        self.assertEqual(response.data["valid"], [])
+        self.assertEqual(
+            [invalid["id"] for invalid in response.data["invalid"]], [first, second]
+        )
+
+        response = self._migrate(extension_id, apply_defaults=True)
+        self.assertEqual(response.data["valid"], [first])
+        self.assertEqual(
+            response.data["invalid"],
+            [
+                {
+                    "id": second,
+                    "errors": [{"path": "key", "message": "5 is not of type 'string'"}],
+                }
+            ],
+        )
+
+        extension = self.assert_get(f"/extensions/{extension_id}")
+        self.assertIsNone(extension.data["json_schema"])
+        for data in self._data(extension_id):
+            self.assertIsNone(data["schema_valid"])
+            self.assertNotIn("state", data["json_data"])
+
+    def test_apply(self):
+        """Test applying a schema migration with defaults."""
+        extension_id = self._create_data()
+        response = self._migrate(extension_id, apply_defaults=True, dry_run=False)
+        self.assertFalse(response.data["dry_run"])
+
+        extension = self.assert_get(f"/extensions/{extension_id}")
+        self.assertEqual(extension.data["json_schema"], self.schema)
+
+        first, second = self._data(extension_id)
+        self.assertTrue(first["schema_valid"])
+        self.assertEqual(first["json_data"]["state"], "active")
+        self.assertFalse(second["schema_valid"])
+        self.assertEqual(second["schema_errors"][0]["path"], "key")
+
+        # New data is validated against the schema, also when updating.
+        self.assert_post_and_400(
+            "/extension_data/", self._extension_data_blob(extension_id, value=5)
+        )
+        self.assert_patch_and_400(
+            f"/extension_data/{first['id']}", {"schema_valid": False}
+        )
+
+        # Removing the schema clears the validation status.
+        self.schema = None
+        self._migrate(extension_id, dry_run=False)
+        for data in self._data(extension_id):
+            self.assertIsNone(data["schema_valid"])
+
+    def test_schema_validation_levels(self):
+        """Test schemas that warn about invalid data, and schemas that are off."""
+        extension_id = self._create_data()
+        self._migrate(extension_id, apply_defaults=True, dry_run=False)
+        invalid = self._extension_data_blob(extension_id, value=5)
+        self.assert_post_and_400("/extension_data/", invalid)
+        self.assert_patch_and_400(
+            f"/extensions/{extension_id}", {"schema_validation": "loudly"}
+        )
+
+        self.assert_patch(f"/extensions/{extension_id}", {"schema_validation": "warn"})
+        data = self.assert_post("/extension_data/", invalid).data
+        self.assertFalse(data["schema_valid"])
+        self.assertEqual(data["schema_errors"][0]["path"], "key")
+        path = f"/extension_data/?extension={extension_id}&validation_status="
+        self.assert_get_elements(f"{path}invalid", 2)
+        self.assert_get_elements(f"{path}valid", 0)
+        self.assert_get_and_400(f"{path}maybe")
+
+        self.assert_patch(f"/extensions/{extension_id}", {"schema_validation": "off"})
+        data = self.assert_post("/extension_data/", invalid).data
+        self.assertIsNone(data["schema_valid"])
+        self.assert_get_elements(f"{path}unvalidated", 1)
+        self.assert_get(f"/extensions/{extension_id}/validation")
+        self.assert_get_elements(f"{path}unvalidated", 2)
+
+    def test_permissions(self):
+        """Test that migrating schemas requires namespace permissions."""
+        extension_id = self._create_data()
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.grant("tmpgroup", "test", ["has_read", "has_update"])
+        self.assert_post_and_403(
+            f"/extensions/{extension_id}/migrate_schema", {"json_schema": None}
+        )
+        self.assert_post_and_404("/extensions/nosuchextension/migrate_schema", {})
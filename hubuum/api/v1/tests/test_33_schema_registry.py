@@ -0,0 +1,229 @@
+"""Test the schema registry, and references to it from extension schemas."""
+from hubuum.models.extensions import Extension, SchemaDefinition
+
+from .test_30_extensions import HubuumExtensionTestCase
+
+
+class APISchemaRegistry(HubuumExtensionTestCase):
+    """Test shared schema definitions and resolving references to them."""
+
+    def _define(self, name, json_schema, **kwargs):
+        """Add a schema definition to the registry."""
+        return self.assert_post(
+            "/schemas/",
+            {
+                "namespace": self.namespace.id,
+                "name": name,
+                "json_schema": json_schema,
+                **kwargs,
+            },
+        )
+
+    def _extension(self, key_schema):
+        """Create an extension with the given schema for its key, return the ID."""
+        json_schema = {"type": "object", "properties": {"key": key_schema}}
+        extension = self.assert_post(
+            "/extensions/", {**self.extension_blob, "json_schema": json_schema}
+        )
+        return extension.data["id"]
+
+    def test_managing_definitions(self):
+        """Test creating, modifying, and deleting schema definitions."""
+        definition = self._define("key", {"type": "string"}, description="A key.")
+        self.assertEqual(definition.data["ref"], "hubuum:schemas/test/key")
+        self.assert_get_elements("/schemas/", 1)
+        self.assert_get_elements("/schemas/?name__startswith=k", 1)
+
+        self.assert_post_and_400(
+            "/schemas/",
+            {"namespace": self.namespace.id, "name": "key", "json_schema": {}},
+        )
+        self.assert_post_and_400(
+            "/schemas/",
+            {"namespace": self.namespace.id, "name": "bad", "json_schema": {"type": 5}},
+        )
+
+        self.assert_patch("/schemas/key", {"json_schema": {"type": "integer"}})
+        self.assertEqual(
+            self.assert_get("/schemas/key").data["json_schema"], {"type": "integer"}
+        )
+        self.assert_delete("/schemas/key")
+        self.assert_get_elements("/schemas/", 0)
+
+    def test_references_are_resolved(self):
+        """Test that data is validated against the definitions referred to."""
+        self._define("key", {"type": "string"})
+        self._define(
+            "shared", {"definitions": {"key": {"$ref": "hubuum:schemas/test/key"}}}
+        )
+        extension_id = self._extension(
+            {"$ref": "hubuum:schemas/test/shared#/definitions/key"}
+        )
+
+        self.assert_post_and_400(
+            "/extension_data/", self._extension_data_blob(extension_id, value=5)
+        )
+        data = self.assert_post(
+            "/extension_data/", self._extension_data_blob(extension_id)
+        )
+        self.assertTrue(data.data["schema_valid"])
+
+        # Changes to the definitions apply when the data is validated next.
+        self.assert_patch("/schemas/key", {"json_schema": {"type": "integer"}})
+        self.assert_post_and_400(
+            "/extension_data/", self._extension_data_blob(extension_id)
+        )
+        self.assert_post(
+            "/extension_data/", self._extension_data_blob(extension_id, value=5)
+        )
+
+    def test_references_survive_renaming_namespaces(self):
+        """Test that references using a former name of the namespace resolve."""
+        self._define("key", {"type": "string"})
+        extension_id = self._extension({"$ref": "hubuum:schemas/test/key"})
+
+        self.namespace.name = "renamed"
+        self.namespace.save()
+        self.assert_post_and_400(
+            "/extension_data/", self._extension_data_blob(extension_id, value=5)
+        )
+        self.assert_get_elements("/schemas/dangling", 0)
+        self.assertEqual(
+            self.assert_get("/schemas/key").data["ref"], "hubuum:schemas/renamed/key"
+        )
+
+    def test_dangling_references(self):
+        """Test that dangling references are rejected, and reported."""
+        self.assert_post_and_400(
+            "/extensions/",
+            {**self.extension_blob, "json_schema": {"$ref": "hubuum:schemas/test/no"}},
+        )
+        self.assert_post_and_400(
+            "/schemas/",
+            {
+                "namespace": self.namespace.id,
+                "name": "key",
+                "json_schema": {"$ref": "hubuum:schemas/nosuchnamespace/key"},
+            },
+        )
+
+        definition = self._define("key", {"type": "string"})
+        self._define("alias", {"$ref": "hubuum:schemas/test/key"})
+        extension_id = self._extension({"$ref": "hubuum:schemas/test/key"})
+        self.assert_post("/extension_data/", self._extension_data_blob(extension_id))
+        self.assert_get_elements("/schemas/dangling", 0)
+
+        self.assert_delete(f"/schemas/{definition.data['id']}")
+        dangling = self.assert_get_elements("/schemas/dangling", 2).data
+        self.assertEqual(
+            [(entry["model"], entry["name"]) for entry in dangling],
+            [("extension", "fleet"), ("schemadefinition", "alias")],
+        )
+        self.assertEqual(dangling[0]["references"], ["hubuum:schemas/test/key"])
+
+        # Data can not be validated against a schema with dangling references.
+        response = self.assert_post_and_400(
+            "/extension_data/", self._extension_data_blob(extension_id)
+        )
+        self.assertIn("Unresolvable reference", str(response.data))
+        self.assert_post_and_400(
+            f"/extensions/{extension_id}/migrate_schema",
+            {"json_schema": {"$ref": "hubuum:schemas/test/key"}},
+        )
+
+    def test_external_references(self):
+        """Test that only local references and references to the registry are used."""
+        for ref in ("file:///etc/passwd", "https://example.com/schema.json", "x.json"):
+            self.assert_post_and_400(
+                "/extensions/", {**self.extension_blob, "json_schema": {"$ref": ref}}
+            )
+            self.assert_post_and_400(
+                "/schemas/",
+                {
+                    "namespace": self.namespace.id,
+                    "name": "key",
+                    "json_schema": {"properties": {"key": {"$ref": ref}}},
+                },
+            )
+
+        # Schemas stored before references were checked are not resolved either.
+        extension_id = self._extension({"type": "string"})
+        Extension.objects.filter(pk=extension_id).update(
+            json_schema={"$ref": "file:///etc/passwd"}
+        )
+        response = self.assert_post_and_400(
+            "/extension_data/", self._extension_data_blob(extension_id)
+        )
+        self.assertIn("are not allowed", str(response.data))
+
+        self._define("local", {"definitions": {"k": {}}, "$ref": "#/definitions/k"})
+
+    def test_dangling_requires_read(self):
+        """Test that only readable objects are reported as dangling."""
+        SchemaDefinition.objects.create(
+            namespace=self.namespace,
+            name="alias",
+            json_schema={"$ref": "hubuum:schemas/test/key"},
+        )
+        self.assert_get_elements("/schemas/dangling", 1)
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_elements("/schemas/dangling", 0)
+        self.grant("tmpgroup", "test", ["has_read"])
+        self.assert_get_elements("/schemas/dangling", 1)
+
+    def test_validation_report(self):
+        """Test re-validating existing data after tightening a definition."""
+        self._define("key", {"type": ["string", "integer"]})
+        extension_id = self._extension({"$ref": "hubuum:schemas/test/key"})
+        validation = f"/extensions/{extension_id}/validation"
+        first = self.assert_post(
+            "/extension_data/", self._extension_data_blob(extension_id)
+        ).data["id"]
+        second = self.assert_post(
+            "/extension_data/",
+            {
+                **self._extension_data_blob(extension_id, value=5),
+                "object_id": self.host2.id,
+            },
+        ).data["id"]
+
+        response = self.assert_get(validation)
+        self.assertEqual((response.data["valid"], response.data["invalid"]), (2, 0))
+
+        self.assert_patch("/schemas/key", {"json_schema": {"type": "string"}})
+        response = self.assert_get(validation)
+        self.assertTrue(response.data["schema"])
+        self.assertEqual((response.data["valid"], response.data["invalid"]), (1, 1))
+        self.assertEqual(
+            [(entry["id"], entry["valid"]) for entry in response.data["objects"]],
+            [(first, True), (second, False)],
+        )
+
+        response = self.assert_get(f"{validation}?invalid=true")
+        self.assertEqual(
+            response.data["objects"],
+            [
+                {
+                    "id": second,
+                    "object_id": self.host2.id,
+                    "valid": False,
+                    "errors": [{"path": "key", "message": "5 is not of type 'string'"}],
+                }
+            ],
+        )
+
+        # The recorded status is refreshed.
+        data = self.assert_get(f"/extension_data/{second}").data
+        self.assertFalse(data["schema_valid"])
+
+    def test_validation_report_requires_read(self):
+        """Test that the report requires read access, and lists readable data."""
+        extension_id = self._extension({"type": "string"})
+        self.assert_post("/extension_data/", self._extension_data_blob(extension_id))
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_and_403(f"/extensions/{extension_id}/validation")
+        self.grant("tmpgroup", "test", ["has_read"])
+        response = self.assert_get(f"/extensions/{extension_id}/validation")
+        self.assertEqual(len(response.data["objects"]), 1)
@@ -0,0 +1,88 @@
+"""Test redacting the sensitive paths of extension data."""
+from hubuum.tools import REDACTED
+
+from .test_30_extensions import HubuumExtensionTestCase
+
+
+class APISensitivePaths(HubuumExtensionTestCase):
+    """Test that sensitive data requires has_read_sensitive."""
+
+    def setUp(self):
+        """Create an extension with sensitive paths, and data for a host."""
+        super().setUp()
+        extension = self.assert_post(
+            "/extensions/", {**self.extension_blob, "sensitive_paths": ["key"]}
+        )
+        self.data = self.assert_post(
+            "/extension_data/", self._extension_data_blob(extension.data["id"])
+        ).data
+        self.user = self.get_user_client(username="tmp", groupname="tmpgroup")
+
+    def _json_data(self):
+        """Return the json_data as seen through the different endpoints."""
+        data_id = self.data["id"]
+        revisions = self.assert_get(f"/extension_data/{data_id}/revisions/").data
+        return {
+            "list": self.assert_get("/extension_data/").data[0]["json_data"],
+            "detail": self.assert_get(f"/extension_data/{data_id}").data["json_data"],
+            "host": self.assert_get(f"/hosts/{self.host.id}").data["extension_data"],
+            "revision": revisions[0]["json_data"],
+            "diff": self.assert_get(f"/extension_data/{data_id}/diff").data["added"],
+        }
+
+    def test_redaction(self):
+        """Test that the sensitive paths are redacted without has_read_sensitive."""
+        data = {"key": "value", "listkey": [1, 2, 3]}
+        redacted = {"key": REDACTED, "listkey": [1, 2, 3]}
+        seen = self._json_data()
+        self.assertEqual(seen, {**dict.fromkeys(seen, data), "host": {"fleet": data}})
+
+        self.client = self.user
+        self.grant("tmpgroup", "test", ["has_read"])
+        seen = self._json_data()
+        self.assertEqual(
+            seen, {**dict.fromkeys(seen, redacted), "host": {"fleet": redacted}}
+        )
+
+        self.client = self.get_superuser_client()
+        self.assert_patch_and_204(
+            "/namespaces/test/groups/tmpgroup", {"has_read_sensitive": True}
+        )
+        self.client = self.user
+        self.assertEqual(self._json_data()["detail"], data)
+
+    def test_invalid_sensitive_paths(self):
+        """Test that the sensitive paths must be a list of strings."""
+        extension = self.assert_get("/extensions/fleet").data
+        self.assertEqual(extension["sensitive_paths"], ["key"])
+        self.assert_patch_and_400("/extensions/fleet", {"sensitive_paths": "key"})
+        self.assert_patch_and_400("/extensions/fleet", {"sensitive_paths": [""]})
+
+    def test_filtering_sensitive_paths(self):
+        """Test that filtering on sensitive paths requires has_read_sensitive."""
+        sensitive = (
+            "json_data_lookup=key__startswith=v",
+            "json_data_lookup=key=value",
+            'json_data_contains={"key":"value"}',
+            "json_data_contains=[]",
+            "json_data_has_key=key",
+            "json_data_has_any_key=listkey,key",
+        )
+        self.client = self.user
+        self.grant("tmpgroup", "test", ["has_read"])
+        for query in sensitive:
+            with self.subTest(query=query):
+                self.assert_get_and_400(f"/extension_data/?{query}")
+        path = "/extension_data/?json_data_"
+        self.assert_get_elements(f"{path}lookup=listkey__has=1", 1)
+        self.assert_get_elements(f'{path}contains={{"listkey":[2]}}', 1)
+        self.assert_get_elements(f"{path}has_key=listkey", 1)
+
+        self.client = self.get_superuser_client()
+        self.assert_patch_and_204(
+            "/namespaces/test/groups/tmpgroup", {"has_read_sensitive": True}
+        )
+        self.client = self.user
+        for query in sensitive[:3]:
+            with self.subTest(query=query):
+                self.assert_get_elements(f"/extension_data/?{query}", 1)
@@ -0,0 +1,95 @@
+"""Test aggregating the data of extensions."""
+from hubuum.models.objects import Host
+
+from .test_30_extensions import HubuumExtensionTestCase
+
+
+class APIExtensionAggregation(HubuumExtensionTestCase):
+    """Test grouping and aggregating json_data in the database."""
+
+    def setUp(self):
+        """Create an extension with data for four hosts."""
+        super().setUp()
+        extension = self.assert_post("/extensions/", self.extension_blob)
+        hosts = [
+            self.host,
+            self.host2,
+            Host.objects.create(name="test3", namespace=self.namespace),
+            Host.objects.create(name="test4", namespace=self.namespace),
+        ]
+        json_data = [
+            {"location": "oslo", "ram_gb": 16},
+            {"location": "oslo", "ram_gb": 32},
+            {"location": "bergen", "ram_gb": "unknown"},
+            {"ram_gb": 8},
+        ]
+        for host, data in zip(hosts, json_data):
+            blob = self._extension_data_blob(extension.data["id"])
+            self.assert_post(
+                "/extension_data/", {**blob, "object_id": host.id, "json_data": data}
+            )
+
+    def test_aggregation(self):
+        """Test grouping, and that only numbers are aggregated."""
+        response = self.assert_get(
+            "/extensions/fleet/aggregate?group_by=json_data.location"
+            "&agg=count,sum:json_data.ram_gb"
+        )
+        self.assertEqual(response.data["group_by"], ["json_data.location"])
+        self.assertEqual(response.data["agg"], ["count", "sum:json_data.ram_gb"])
+        location, ram = "json_data.location", "sum:json_data.ram_gb"
+        self.assertEqual(
+            response.data["groups"],
+            [
+                {location: "bergen", "count": 1, ram: None},
+                {location: "oslo", "count": 2, ram: 48},
+                {location: None, "count": 1, ram: 8},
+            ],
+        )
+
+        response = self.assert_get(
+            "/extensions/fleet/aggregate?agg=count,min:json_data.ram_gb,"
+            "max:json_data.ram_gb,avg:json_data.ram_gb"
+        )
+        [group] = response.data["groups"]
+        self.assertEqual(group["count"], 4)
+        self.assertEqual(group["min:json_data.ram_gb"], 8)
+        self.assertEqual(group["max:json_data.ram_gb"], 32)
+        self.assertAlmostEqual(group["avg:json_data.ram_gb"], 56 / 3)
+        response = self.assert_get("/extensions/fleet/aggregate")
+        self.assertEqual(response.data["groups"], [{"count": 4}])
+
+    def test_aggregation_errors(self):
+        """Test that malformed aggregations get 400."""
+        path = "/extensions/fleet/aggregate"
+        self.assert_get_and_400(f"{path}?agg=median:json_data.ram_gb")
+        self.assert_get_and_400(f"{path}?agg=sum")
+        self.assert_get_and_400(f"{path}?agg=count:json_data.ram_gb")
+        self.assert_get_and_400(f"{path}?group_by=location")
+        self.assert_get_and_400(f"{path}?group_by=json_data.")
+        self.assert_get_and_404("/extensions/nosuchextension/aggregate")
+
+    def test_aggregation_permissions(self):
+        """Test that sensitive paths are only aggregated with has_read_sensitive."""
+        self.assert_patch("/extensions/fleet", {"sensitive_paths": ["ram_gb"]})
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_and_403("/extensions/fleet/aggregate")
+
+        self.grant("tmpgroup", "test", ["has_read"])
+        path = "/extensions/fleet/aggregate?agg=count,sum:json_data.ram_gb"
+        response = self.assert_get(path)
+        self.assertEqual(
+            response.data["groups"], [{"count": 0, "sum:json_data.ram_gb": None}]
+        )
+        response = self.assert_get("/extensions/fleet/aggregate?agg=count")
+        self.assertEqual(response.data["groups"], [{"count": 4}])
+
+        self.client = self.get_superuser_client()
+        self._assert_patch_and_status(
+            "/namespaces/test/groups/tmpgroup", 204, {"has_read_sensitive": True}
+        )
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        response = self.assert_get(path)
+        self.assertEqual(
+            response.data["groups"], [{"count": 4, "sum:json_data.ram_gb": 56}]
+        )
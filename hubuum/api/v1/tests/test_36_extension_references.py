@@ -0,0 +1,66 @@
+"""Test references to objects within the data of extensions."""
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host
+
+from .test_30_extensions import HubuumExtensionTestCase
+
+
+class APIExtensionReferences(HubuumExtensionTestCase):
+    """Test declaring, validating, and listing references in json_data."""
+
+    def setUp(self):
+        """Create an extension where peer and uplinks refer to hosts."""
+        super().setUp()
+        self.extension = self.assert_post(
+            "/extensions/",
+            {
+                **self.extension_blob,
+                "reference_paths": {"peer": "host", "uplinks__*": "host"},
+            },
+        ).data
+
+    def _post_data(self, json_data, status=201):
+        """Post the data of the extension for self.host."""
+        blob = self._extension_data_blob(self.extension["id"])
+        return self._assert_post_and_status(
+            "/extension_data/", status, {**blob, "json_data": json_data}
+        )
+
+    def test_reference_paths_validation(self):
+        """Test that reference paths must refer to models with extensions."""
+        blob = {**self.extension_blob2, "reference_paths": {"peer": "nosuchmodel"}}
+        self.assert_post_and_400("/extensions/", blob)
+        blob["reference_paths"] = {"peer": "namespace"}
+        self.assert_post_and_400("/extensions/", blob)
+        blob["reference_paths"] = ["peer"]
+        self.assert_post_and_400("/extensions/", blob)
+
+    def test_references(self):
+        """Test that references must resolve, and are listed for the target."""
+        self._post_data({"peer": "test2"}, 400)
+        self._post_data({"peer": 1000000}, 400)
+        self._post_data({"uplinks": [self.host2.id, True]}, 400)
+
+        data = self._post_data(
+            {"peer": None, "uplinks": [self.host2.id, self.host.id]}
+        ).data
+        response = self.assert_get(f"/hosts/{self.host2.id}/references")
+        self.assertEqual([row["id"] for row in response.data], [data["id"]])
+        response = self.assert_get(f"/hosts/{self.host.name}/references")
+        self.assertEqual([row["id"] for row in response.data], [data["id"]])
+
+        self.assert_patch(f"/extension_data/{data['id']}", {**data, "json_data": {}})
+        self.assertEqual(self.assert_get(f"/hosts/{self.host2.id}/references").data, [])
+
+    def test_reference_permissions(self):
+        """Test that users can only refer to objects they can read."""
+        secret = Namespace.objects.create(name="secret")
+        hidden = Host.objects.create(name="hidden", namespace=secret)
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.grant("tmpgroup", "test", ["has_read", "has_create", "has_update"])
+
+        self._post_data({"peer": hidden.id}, 400)
+        self._post_data({"peer": self.host2.id})
+        response = self.assert_get(f"/hosts/{self.host2.id}/references")
+        self.assertEqual(len(response.data), 1)
+        self.assert_get_and_403(f"/hosts/{hidden.id}/references")
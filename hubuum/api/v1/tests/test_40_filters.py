@@ -1,6 +1,12 @@
 """Test the filter interface."""
-from hubuum.models.auth import User
-from hubuum.models.base import Host, Namespace, Room
+from datetime import timedelta
+
+from django.contrib.auth.models import Group
+from django.utils import timezone
+
+from hubuum.models.auth import GroupManager, User
+from hubuum.models.namespace import Namespace, Permission
+from hubuum.models.objects import Host, Room
 
 from .base import HubuumAPITestCase
 
@@ -118,6 +124,54 @@ class HubuumFilterTestCase(HubuumAPITestCase):
         test.delete()
         staff.delete()
 
+    def test_user_group_and_permission_filtering(self):
+        """Test finding users by their groups, and the permissions of their groups."""
+        alice = User.objects.create(username="alice")
+        bob = User.objects.create(username="bob")
+        User.objects.create(username="carol")
+        operators = Group.objects.create(name="operators")
+        developers = Group.objects.create(name="developers")
+        operators.user_set.add(alice)
+        developers.user_set.add(bob)
+        Permission.objects.create(
+            namespace=self.namespace, group=operators, has_read=True, has_update=True
+        )
+        Permission.objects.create(
+            namespace=Namespace.objects.create(name="other"),
+            group=developers,
+            has_read=True,
+            has_delete=True,
+        )
+
+        self.assert_get_elements(f"/users/?groups={operators.id}", 1)
+        self.assert_get_elements(f"/users/?groups__not_in={operators.id}", 3)
+        self.assert_get_elements("/users/?can=has_update&namespace=test", 1)
+        response = self.assert_get_elements("/users/?can=read&namespace=test", 1)
+        self.assertEqual(response.data[0]["username"], "alice")
+        self.assert_get_elements(f"/users/?can=read&namespace={self.namespace.id}", 1)
+        # The grant to delete is for another namespace.
+        self.assert_get_elements("/users/?can=delete&namespace=test", 0)
+        self.assert_get_and_400("/users/?can=fly&namespace=test")
+        self.assert_get_and_400("/users/?can=read")
+
+    def test_group_filtering(self):
+        """Test filtering groups by name, and their number of members and managers."""
+        alice = User.objects.create(username="alice")
+        bob = User.objects.create(username="bob")
+        operators = Group.objects.create(name="operators")
+        operators.user_set.add(alice, bob)
+        GroupManager.objects.create(group=operators, user=alice)
+        Group.objects.create(name="developers").user_set.add(bob)
+        Group.objects.create(name="empty")
+
+        self.assert_get_elements("/groups/?name__endswith=s", 2)
+        self.assert_get_elements("/groups/?user_count=2", 1)
+        self.assert_get_elements("/groups/?user_count__gte=1", 2)
+        self.assert_get_elements("/groups/?user_count=0&name=empty", 1)
+        self.assert_get_elements("/groups/?managers_count__gt=0", 1)
+        self.assert_get_elements("/groups/?user_count__gt=0&managers_count=0", 1)
+        self.assert_get_and_400("/groups/?user_count=many")
+
     #        self.assert_get_elements("/users/?fqdn__contains=other", 2)
 
     def test_host_filtering(self):
@@ -130,6 +184,83 @@ class HubuumFilterTestCase(HubuumAPITestCase):
         self.assert_get_elements(f"/hosts/?namespace={self.namespace.id}", 3)
         self.assert_get_elements("/hosts/?name__contains=test&fqdn__contains=domain", 1)
 
+    def test_in_filtering(self):
+        """Test filtering on any of several values, and on none of them."""
+        self.assert_get_elements("/hosts/?name__in=test1,test3", 2)
+        self.assert_get_elements("/hosts/?name__in=test1,nosuchhost", 1)
+        self.assert_get_elements("/hosts/?name__not_in=test1,test3", 1)
+        self.assert_get_elements("/hosts/?name__in=test1,test3&fqdn__contains=other", 1)
+        self.assert_get_elements("/hosts/?position__in=1,2", 0)
+        self.assert_get_and_400("/hosts/?position__in=1,two")
+
+        first, second, _ = self.hosts
+        self.assert_get_elements(f"/namespaces/?id__in={self.namespace.id}", 1)
+        self.assert_get_elements(f"/hosts/?parent__in={first.id},{second.id}", 0)
+        self.assert_get_elements(
+            f"/extension_data/?json_data_lookup=id__in={first.id},{second.id}", 2
+        )
+        self.assert_get_elements(
+            "/extension_data/?json_data_lookup=dns__fqdn__in=test1.domain.tld,x", 1
+        )
+
+    def test_strict_filtering(self):
+        """Test that strict mode rejects unknown filters and filters without values."""
+        self.assert_get_elements("/hosts/?nmae=test1", 3)
+        self.assert_get_elements("/hosts/?name__gt=test1", 3)
+        self.assert_get_elements("/hosts/?name=", 3)
+
+        response = self.assert_get_and_400("/hosts/?strict=true&nmae=test1")
+        self.assertIn("did you mean name", response.data["nmae"])
+        response = self.assert_get_and_400("/hosts/?strict=true&name__gt=test1")
+        self.assertIn("Unknown filter for host", response.data["name__gt"])
+        response = self.assert_get_and_400("/hosts/?strict=true&name=&fqdn=")
+        self.assertEqual(set(response.data), {"name", "fqdn"})
+
+        self.assert_get_elements("/hosts/?strict=true&name=test1&page_size=10", 1)
+        self.assert_get_elements("/namespaces/?strict=true&include_archived=true", 1)
+        with self.settings(STRICT_FILTERS=True):
+            self.assert_get_and_400("/users/?usrname=test")
+            self.assert_get_elements("/hosts/?strict=false&nmae=test1", 3)
+
+    def test_between_filtering(self):
+        """Test filtering on timestamps between two bounds."""
+        today = timezone.localdate()
+        tomorrow = today + timedelta(days=1)
+        between = "/hosts/?registration_date__between="
+        self.assert_get_elements(f"{between}{today}--{today}", 3)
+        self.assert_get_elements(f"{between}{today}--", 3)
+        self.assert_get_elements(f"{between}--{today}", 3)
+        self.assert_get_elements(f"{between}[{today}--{tomorrow})", 3)
+        self.assert_get_elements(f"{between}({today}--{tomorrow}]", 0)
+        self.assert_get_elements(f"{between}{tomorrow}--", 0)
+        self.assert_get_elements(f"{between}--{today}T00:00:00", 0)
+        self.assert_get_elements(
+            f"/namespaces/?name=test&created_at__between={today}--{today}", 1
+        )
+
+        self.assert_get_and_400(f"{between}{today}")
+        self.assert_get_and_400(f"{between}{tomorrow}--{today}")
+        self.assert_get_and_400(f"{between}yesterday--{today}")
+
+    def test_related_count_filtering(self):
+        """Test that filtering on the number of related objects works."""
+        parent, *children = self.hosts
+        for child in children:
+            child.parent = parent
+            child.save()
+        room = Room.objects.get(room_id="BL01-02-345")
+        parent.room = room
+        parent.save()
+
+        self.assert_get_elements("/hosts/?children_count=0", 2)
+        self.assert_get_elements("/hosts/?children_count__gt=1", 1)
+        self.assert_get_elements("/hosts/?children_count__gte=1&name=test2", 0)
+        self.assert_get_elements("/hosts/?children_count__lte=2&name=test1", 1)
+        self.assert_get_elements("/rooms/?hosts_count=1", 1)
+        self.assert_get_elements("/rooms/?hosts_count__gt=1", 0)
+        self.assert_get_elements("/rooms/?jack_count=0&person_count=0", 1)
+        self.assert_get_and_400("/hosts/?children_count__gt=many")
+
     def test_extension_data_basic_filtering(self):
         """Test that we can filter into the JSON blobs that extensions deliver."""
         self.assert_get_elements("/extension_data/", 4)
@@ -170,6 +301,82 @@ class HubuumFilterTestCase(HubuumAPITestCase):
             "/extension_data/?json_data_lookup=list__1__two__icontains=value", 1
         )
 
+    def test_extension_data_json_array_elements(self):
+        """Test matching any element of JSON arrays, by lookup or by the schema."""
+        path = "/extension_data/?json_data_lookup="
+        self.assert_get_elements(path + "list__has=one", 1)
+        self.assert_get_elements(path + "list__has=two", 0)
+        self.assert_get_elements(path + "list=one", 0)
+
+        blob = self._make_extension_blob(name="tagged")
+        blob["json_schema"] = {
+            "type": "object",
+            "properties": {"tags": {"type": "array", "items": {"type": "string"}}},
+        }
+        extension = self.assert_post("/extensions/", blob).data
+        self.assert_post(
+            "/extension_data/",
+            self._make_extension_data_blob(
+                extension["id"], "host", self.hosts[0].id, {"tags": ["web", "db"]}
+            ),
+        )
+        self.assert_get_elements(path + "tags__has=web", 1)
+        self.assert_get_elements(path + "tags=web", 0)
+        self.assert_get_elements(f"{path}tags=web&extension={extension['id']}", 1)
+        self.assert_get_elements(f"{path}tags=mail&extension={extension['id']}", 0)
+
+    def test_extension_data_containment_filtering(self):
+        """Test that we can filter on contained JSON documents."""
+        path = "/extension_data/?json_data_contains="
+        self.assert_get_elements(path + '{"key":"value"}', 4)
+        self.assert_get_elements(path + '{"dns":{"fqdn":"test2.other.com"}}', 1)
+        self.assert_get_elements(path + '{"list":["one"]}', 1)
+        self.assert_get_elements(path + '{"key":"nope"}', 0)
+        self.assert_get_and_400(path + "{notjson")
+        self.assert_get_and_400(path + '"value"')
+
+    def test_extension_data_key_filtering(self):
+        """Test that we can filter on the existence of JSON keys."""
+        self.assert_get_elements("/extension_data/?json_data_has_key=key", 4)
+        self.assert_get_elements("/extension_data/?json_data_has_key=dns__fqdn", 3)
+        self.assert_get_elements("/extension_data/?json_data_has_key=fqdn,room_id", 0)
+        self.assert_get_elements("/extension_data/?json_data_has_key=nosuchkey", 0)
+        self.assert_get_elements(
+            "/extension_data/?json_data_has_any_key=fqdn,room_id", 4
+        )
+        self.assert_get_elements("/extension_data/?json_data_has_any_key=room_id", 1)
+        self.assert_get_elements(
+            "/extension_data/?json_data_has_any_key=weird__exact,dns__nope", 1
+        )
+        self.assert_get_and_400("/extension_data/?json_data_has_key=dns__")
+
+    def test_extension_data_explain_matches(self):
+        """Test that explain_matches tells which keys of the filters matched."""
+        path = (
+            "/extension_data/?json_data_has_any_key=fqdn,room_id"
+            '&json_data_contains={"key":"value"}&strict=true'
+        )
+        response = self.assert_get_elements(path, 4)
+        self.assertNotIn("matched", response.data[0])
+
+        response = self.assert_get_elements(path + "&explain_matches=true", 4)
+        matched = [row["matched"] for row in response.data]
+        self.assertEqual(
+            sorted(match["json_data_has_any_key"] for match in matched),
+            [["fqdn"]] * 3 + [["room_id"]],
+        )
+        for match in matched:
+            self.assertEqual(match["json_data_contains"], ["key"])
+
+        response = self.assert_get_elements(
+            "/extension_data/?json_data_lookup=dns__fqdn__icontains=other"
+            "&explain_matches=true",
+            2,
+        )
+        for row in response.data:
+            expected = {"json_data_lookup": ["dns__fqdn__icontains"]}
+            self.assertEqual(row["matched"], expected)
+
     def test_extension_data_filtering_mismatches(self):
         """Test that we validate JSON lookups correctly."""
         # Missing value
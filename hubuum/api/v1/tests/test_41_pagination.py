@@ -1,7 +1,8 @@
 """Test the pagination in hubuum."""
 from urllib.parse import parse_qs, urlparse
 
-from hubuum.models.base import Host, Namespace
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host
 
 from .base import HubuumAPITestCase
 
@@ -48,9 +49,22 @@ class HubuumPaginationTestCase(HubuumAPITestCase):
         max_page_size_url = f"{self.hosts_url}?page_size=200"
         self.assert_get_elements(max_page_size_url, 200)
 
-        # Test maximum pagination size limit, but we're capped at 200.
-        max_page_size_url = f"{self.hosts_url}?page_size=300"
-        self.assert_get_elements(max_page_size_url, 200)
+        # Asking for more than the maximum, or for nonsense, gets 400.
+        self.assert_get_and_400(f"{self.hosts_url}?page_size=300")
+        self.assert_get_and_400(f"{self.hosts_url}?page_size=0")
+        self.assert_get_and_400(f"{self.hosts_url}?page_size=many")
+
+    def test_page_size_headers(self):
+        """Test that the applied page size and the maximum are given as headers."""
+        response = self.assert_get(f"{self.hosts_url}?page_size=50")
+        self.assertEqual(response["X-Page-Size"], "50")
+        self.assertEqual(response["X-Max-Page-Size"], "200")
+
+        with self.settings(PAGE_SIZE=10, MAX_PAGE_SIZE=20):
+            response = self.assert_get_elements(self.hosts_url, 10)
+            self.assertEqual(response["X-Page-Size"], "10")
+            self.assertEqual(response["X-Max-Page-Size"], "20")
+            self.assert_get_and_400(f"{self.hosts_url}?page_size=50")
 
     def test_next_and_prev_links(self):
         """Test the next and prev links in the Link header."""
@@ -68,8 +82,8 @@ class HubuumPaginationTestCase(HubuumAPITestCase):
         response = self.assert_get(custom_page_size_url)
         self.check_next_and_prev_links(response, expected_prev=1, expected_next=3)
 
-        # Test next and prev links when going past the maximum page size limit
-        max_page_size_url = f"{self.hosts_url}?page_size=300"
+        # Test next and prev links at the maximum page size limit
+        max_page_size_url = f"{self.hosts_url}?page_size=200"
         response = self.assert_get(max_page_size_url)
         self.check_next_and_prev_links(response, expected_prev=None, expected_next=2)
 
@@ -78,6 +92,31 @@ class HubuumPaginationTestCase(HubuumAPITestCase):
         response = self.assert_get(max_page_size_url)
         self.check_next_and_prev_links(response, expected_prev=1, expected_next=None)
 
+    def test_envelope(self):
+        """Test that clients may opt in to an envelope with metadata."""
+        url = f"{self.hosts_url}?envelope=true&page_size=20&page=3"
+        response = self.assert_get(url)
+        self.assertEqual(len(response.data["data"]), 20)
+        self.assertEqual(response.data["data"][0]["name"], "hostname-40")
+        meta = response.data["meta"]
+        self.assertEqual((meta["count"], meta["limit"], meta["offset"]), (250, 20, 40))
+        self.assertGreaterEqual(meta["duration_ms"], 0)
+        self.assertEqual(response["X-Total-Count"], "250")
+
+        response = self.client.get(
+            self._create_path(self.hosts_url), HTTP_X_HUBUUM_ENVELOPE="true"
+        )
+        self.assertEqual(response.status_code, 200)
+        self.assertEqual(response.data["meta"]["limit"], 100)
+
+        # The envelope is opt-in, and may be declined with the parameter.
+        self.assert_get_elements(self.hosts_url, 100)
+        response = self.client.get(
+            self._create_path(f"{self.hosts_url}?envelope=false"),
+            HTTP_X_HUBUUM_ENVELOPE="true",
+        )
+        self.assertEqual(len(response.data), 100)
+
     def check_next_and_prev_links(self, response, expected_prev, expected_next):
         """
         Check the next and prev links in the Link header of the given response.
@@ -7,10 +7,13 @@ from rest_framework.test import APIClient
 from structlog import get_logger
 from structlog.testing import capture_logs
 
+from hubuum.actor import SYSTEM
 from hubuum.api.v1.tests.base import HubuumAPITestCase
 from hubuum.log import critical, debug, error, info, warning
 from hubuum.models.auth import User
-from hubuum.models.base import Host, HubuumModel, Namespace
+from hubuum.models.base import HubuumModel
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host
 
 
 class HubuumLoggingTestCase(HubuumAPITestCase):
@@ -179,6 +182,26 @@ class HubuumLoggingTestCase(HubuumAPITestCase):
         )
         self._check_request_finished(cap_logs[4], "POST /api/v1/hosts/", 201)
 
+    def test_logging_of_actor(self):
+        """Test that writes are attributed to the actor performing them."""
+        with capture_logs() as cap_logs:
+            get_logger().bind()
+            self.assert_post("/hosts/", self.host_data)
+
+        created = [log for log in cap_logs if log["event"] == "created"]
+        self.assertEqual(len(created), 2)
+        for log in created:
+            self.assertEqual(log["actor"]["user"], "superuser")
+            self.assertEqual(log["actor"]["ip"], "127.0.0.1")
+            self.assertIsNotNone(log["actor"]["token_id"])
+
+        # Outside of requests, writes are attributed to the system.
+        with capture_logs() as cap_logs:
+            get_logger().bind()
+            Host.objects.create(name="direct", namespace=self.namespace)
+
+        self.assertEqual(cap_logs[0]["actor"], SYSTEM.as_dict())
+
     def test_manual_logging(self):
         """Test manual logging."""
         with capture_logs() as cap_logs:
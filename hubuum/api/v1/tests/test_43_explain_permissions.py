@@ -0,0 +1,75 @@
+"""Test explaining permission checks."""
+import json
+
+from django.test import override_settings
+
+from hubuum.middleware.explain_permissions import TRACE_HEADER
+
+from .base import HubuumAPITestCase
+
+
+class APIExplainPermissions(HubuumAPITestCase):
+    """Test the permission trace returned with ?explain_permissions=true."""
+
+    def setUp(self):
+        """Create a namespace to test against."""
+        super().setUp()
+        self.assert_post("/namespaces/", {"name": "namespace1"})
+
+    def tearDown(self):
+        """Clean up the namespace."""
+        self.client = self.get_superuser_client()
+        self.assert_delete("/namespaces/namespace1")
+
+    def _trace(self, response):
+        """Return the decoded permission trace of a response, or None."""
+        trace = response.headers.get(TRACE_HEADER)
+        if trace is None:
+            return None
+        return json.loads(trace)
+
+    def test_admin_trace(self):
+        """Test that admins get a trace when asking for it."""
+        response = self.assert_get("/namespaces/namespace1?explain_permissions=true")
+        self.assertEqual(
+            self._trace(response),
+            [{"check": "admin", "granted": True}, {"check": "admin", "granted": True}],
+        )
+        self.assertIsNone(self._trace(self.assert_get("/namespaces/namespace1")))
+
+    def test_user_trace(self):
+        """Test that users only get a trace if the policy allows it."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        response = self.assert_get_and_403(
+            "/namespaces/namespace1?explain_permissions=true"
+        )
+        self.assertIsNone(self._trace(response))
+
+        with override_settings(EXPLAIN_PERMISSIONS="all"):
+            response = self.assert_get_and_403(
+                "/namespaces/namespace1?explain_permissions=1"
+            )
+            self.assertEqual(
+                self._trace(response),
+                [
+                    {
+                        "check": "namespace",
+                        "granted": False,
+                        "permission": "has_read",
+                        "namespace": "namespace1",
+                        "groups": [],
+                    }
+                ],
+            )
+
+            self.grant("tmpgroup", "namespace1", ["has_read"])
+            response = self.assert_get("/namespaces/namespace1?explain_permissions=yes")
+            trace = self._trace(response)
+            self.assertTrue(trace[0]["granted"])
+            self.assertEqual(trace[0]["groups"], ["tmpgroup"])
+
+    @override_settings(EXPLAIN_PERMISSIONS="none")
+    def test_disabled(self):
+        """Test that nobody gets a trace if explanations are disabled."""
+        response = self.assert_get("/namespaces/namespace1?explain_permissions=true")
+        self.assertIsNone(self._trace(response))
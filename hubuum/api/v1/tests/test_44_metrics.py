@@ -0,0 +1,55 @@
+"""Test the metrics endpoint."""
+from rest_framework.test import APIClient
+
+from hubuum import metrics
+
+from .base import HubuumAPITestCase
+
+
+class APIMetrics(HubuumAPITestCase):
+    """Test the domain counters exposed in the metrics endpoint."""
+
+    def test_object_counters(self):
+        """Test that creating and deleting objects is counted."""
+        created = metrics.value("hubuum_objects_created", model="hubuum.namespace")
+        deleted = metrics.value("hubuum_objects_deleted", model="hubuum.namespace")
+
+        self.assert_post("/namespaces/", {"name": "namespace1"})
+        self.assert_delete("/namespaces/namespace1")
+
+        self.assertEqual(
+            metrics.value("hubuum_objects_created", model="hubuum.namespace"),
+            created + 1,
+        )
+        self.assertEqual(
+            metrics.value("hubuum_objects_deleted", model="hubuum.namespace"),
+            deleted + 1,
+        )
+
+        response = self.assert_get("/api/metrics/")
+        self.assertEqual(response["Content-Type"], metrics.CONTENT_TYPE)
+        content = response.content.decode("utf-8")
+        self.assertIn("# TYPE hubuum_objects_created counter", content)
+        self.assertIn(
+            f'hubuum_objects_created_total{{model="hubuum.namespace"}} {created + 1}',
+            content,
+        )
+        self.assertTrue(content.endswith("# EOF\n"))
+
+    def test_membership_and_denial_counters(self):
+        """Test that group memberships and permission denials are counted."""
+        added = metrics.value("hubuum_group_memberships", action="add")
+        denied = metrics.value("hubuum_permission_denials", method="GET")
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assertEqual(
+            metrics.value("hubuum_group_memberships", action="add"), added + 1
+        )
+
+        self.assert_get_and_403("/api/metrics/")
+        self.assertEqual(
+            metrics.value("hubuum_permission_denials", method="GET"), denied + 1
+        )
+
+        self.client = APIClient()
+        self.assert_get_and_401("/api/metrics/")
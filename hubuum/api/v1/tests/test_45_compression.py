@@ -0,0 +1,72 @@
+"""Test compressed requests and responses."""
+import gzip
+import json
+import zlib
+
+from django.conf import settings
+from django.test import override_settings
+
+from .base import HubuumAPITestCase
+
+
+class APICompression(HubuumAPITestCase):
+    """Test compression of request and response bodies."""
+
+    def _post_encoded(self, body, encoding):
+        """POST an encoded body to the namespace list."""
+        return self.client.generic(
+            "POST",
+            "/api/v1/namespaces/",
+            body,
+            content_type="application/json",
+            HTTP_CONTENT_ENCODING=encoding,
+        )
+
+    def test_compressed_requests(self):
+        """Test that gzip and deflate encoded request bodies are accepted."""
+        body = json.dumps({"name": "gzipped"}).encode("utf-8")
+        response = self._post_encoded(gzip.compress(body), "gzip")
+        self._assert_status_and_debug(response, 201)
+        self.assertEqual(response.data["name"], "gzipped")
+
+        body = json.dumps({"name": "deflated"}).encode("utf-8")
+        response = self._post_encoded(zlib.compress(body), "deflate")
+        self._assert_status_and_debug(response, 201)
+
+        self.assert_get("/namespaces/gzipped")
+        self.assert_get("/namespaces/deflated")
+
+    def test_compressed_request_errors(self):
+        """Test that bad or unsupported encodings are rejected."""
+        body = json.dumps({"name": "broken"}).encode("utf-8")
+        self._assert_status_and_debug(self._post_encoded(body, "br"), 415)
+        self._assert_status_and_debug(self._post_encoded(body, "gzip"), 400)
+        self._assert_status_and_debug(
+            self._post_encoded(gzip.compress(body)[:-10], "gzip"), 400
+        )
+
+        with override_settings(DATA_UPLOAD_MAX_MEMORY_SIZE=1024):
+            body = json.dumps({"name": "x" * 2048}).encode("utf-8")
+            self._assert_status_and_debug(
+                self._post_encoded(gzip.compress(body), "gzip"), 413
+            )
+
+        self.assert_get_and_404("/namespaces/broken")
+
+    def test_compressed_responses(self):
+        """Test that responses are compressed when enabled."""
+        for index in range(10):
+            self.assert_post("/namespaces/", {"name": f"namespace{index}"})
+
+        middleware = ["django.middleware.gzip.GZipMiddleware", *settings.MIDDLEWARE]
+        with override_settings(MIDDLEWARE=middleware):
+            # A new client, to load the overridden middleware.
+            client = self.get_superuser_client()
+            response = client.get("/api/v1/namespaces/", HTTP_ACCEPT_ENCODING="gzip")
+            self._assert_status_and_debug(response, 200)
+            self.assertEqual(response["Content-Encoding"], "gzip")
+            namespaces = json.loads(gzip.decompress(response.content))
+            self.assertEqual(len(namespaces), 10)
+
+            response = client.get("/api/v1/namespaces/")
+            self.assertFalse(response.has_header("Content-Encoding"))
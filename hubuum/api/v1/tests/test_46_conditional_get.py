@@ -0,0 +1,63 @@
+"""Test caching headers and conditional GETs of single objects."""
+from django.utils.http import http_date
+
+from .base import HubuumAPITestCase
+
+
+class APIConditionalGet(HubuumAPITestCase):
+    """Test ETag and Last-Modified handling."""
+
+    def setUp(self):
+        """Create a namespace with a host."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        self.assert_post(
+            "/hosts/", {"namespace": self.namespace.data["id"], "name": "web1"}
+        )
+
+    def tearDown(self):
+        """Clean up the namespace."""
+        self.assert_delete("/namespaces/namespace1")
+
+    def _get(self, path, **headers):
+        """GET an object with the given request headers."""
+        return self.client.get(self._create_path(path), **headers)
+
+    def test_headers(self):
+        """Test that single objects have an ETag and Last-Modified header."""
+        for path in ["/hosts/web1", "/namespaces/namespace1"]:
+            response = self.assert_get(path)
+            self.assertTrue(response["ETag"].startswith('"'))
+            self.assertIn("Last-Modified", response)
+
+        self.assertNotIn("ETag", self.assert_get("/hosts/"))
+
+    def test_if_none_match(self):
+        """Test that a matching ETag gives a 304, until the object changes."""
+        etag = self.assert_get("/hosts/web1")["ETag"]
+        response = self._get("/hosts/web1", HTTP_IF_NONE_MATCH=etag)
+        self.assertEqual(response.status_code, 304)
+        self.assertEqual(response["ETag"], etag)
+        self.assertEqual(response.content, b"")
+
+        response = self._get("/hosts/web1", HTTP_IF_NONE_MATCH='"other"')
+        self.assertEqual(response.status_code, 200)
+
+        # Tags do not change updated_at, but do change the representation.
+        self.assert_put_and_201("/hosts/web1/tags/production")
+        response = self._get("/hosts/web1", HTTP_IF_NONE_MATCH=etag)
+        self.assertEqual(response.status_code, 200)
+
+        self.assert_patch("/hosts/web1", {"fqdn": "web1.domain.tld"})
+        response = self._get("/hosts/web1", HTTP_IF_NONE_MATCH=etag)
+        self.assertEqual(response.status_code, 200)
+        self.assertNotEqual(response["ETag"], etag)
+
+    def test_if_modified_since(self):
+        """Test that If-Modified-Since gives a 304 for unmodified objects."""
+        last_modified = self.assert_get("/hosts/web1")["Last-Modified"]
+        response = self._get("/hosts/web1", HTTP_IF_MODIFIED_SINCE=last_modified)
+        self.assertEqual(response.status_code, 304)
+
+        response = self._get("/hosts/web1", HTTP_IF_MODIFIED_SINCE=http_date(0))
+        self.assertEqual(response.status_code, 200)
@@ -0,0 +1,61 @@
+"""Test streaming lists as newline delimited JSON."""
+import json
+
+from .base import HubuumAPITestCase
+
+
+class APIStreaming(HubuumAPITestCase):
+    """Test ?format=ndjson for list views."""
+
+    def setUp(self):
+        """Create two namespaces with hosts."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        other = self.assert_post("/namespaces/", {"name": "namespace2"})
+        for index in range(5):
+            self.assert_post(
+                "/hosts/",
+                {"namespace": self.namespace.data["id"], "name": f"web{index}"},
+            )
+        self.assert_post("/hosts/", {"namespace": other.data["id"], "name": "db1"})
+
+    def tearDown(self):
+        """Clean up the namespaces."""
+        self.client = self.get_superuser_client()
+        self.assert_delete("/namespaces/namespace1")
+        self.assert_delete("/namespaces/namespace2")
+
+    def _stream(self, path, **headers):
+        """Get a streamed list and return the decoded lines."""
+        response = self.client.get(self._create_path(path), **headers)
+        self._assert_status_and_debug(response, 200)
+        self.assertTrue(response.streaming)
+        self.assertTrue(response["Content-Type"].startswith("application/x-ndjson"))
+        content = b"".join(response.streaming_content).decode("utf-8")
+        self.assertTrue(content.endswith("\n"))
+        return [json.loads(line) for line in content.splitlines()]
+
+    def test_streaming(self):
+        """Test that every matching object is streamed, unpaginated."""
+        hosts = self._stream("/hosts/?format=ndjson")
+        self.assertEqual(len(hosts), 6)
+        self.assertEqual(hosts[0]["name"], "web0")
+
+        hosts = self._stream("/hosts/", HTTP_ACCEPT="application/x-ndjson")
+        self.assertEqual(len(hosts), 6)
+
+        hosts = self._stream("/hosts/?format=ndjson&name__startswith=web")
+        names = [host["name"] for host in hosts]
+        self.assertEqual(names, [f"web{index}" for index in range(5)])
+
+        namespaces = self._stream("/namespaces/?format=ndjson")
+        self.assertEqual(len(namespaces), 2)
+
+    def test_streaming_permissions(self):
+        """Test that only objects the user can read are streamed."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assertEqual(self._stream("/hosts/?format=ndjson"), [])
+
+        self.grant("tmpgroup", "namespace2", ["has_read"])
+        hosts = self._stream("/hosts/?format=ndjson")
+        self.assertEqual([host["name"] for host in hosts], ["db1"])
@@ -0,0 +1,34 @@
+"""Test the server information endpoint."""
+from django.conf import settings
+
+from hubuumsite.config import server_options
+
+from .base import HubuumAPITestCase
+
+
+class APIServerInfo(HubuumAPITestCase):
+    """Test /api/server-info/."""
+
+    def test_server_info(self):
+        """Test that admins get the effective settings and process statistics."""
+        response = self.assert_get("/api/server-info/")
+        self.assertEqual(response.data["server"], settings.SERVER)
+        self.assertEqual(response.data["database"]["vendor"], "postgresql")
+        self.assertGreater(response.data["process"]["pid"], 0)
+        self.assertGreaterEqual(response.data["process"]["uptime"], 0)
+        self.assertIn("django", response.data["versions"])
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_and_403("/api/server-info/")
+
+    def test_server_options(self):
+        """Test the defaults and overrides of the server tuning."""
+        options = server_options({})
+        self.assertGreaterEqual(options["workers"], 3)
+        self.assertEqual(options["keepalive"], 5)
+
+        options = server_options(
+            {"HUBUUM_SERVER_WORKERS": "2", "HUBUUM_SERVER_TIMEOUT": "120"}
+        )
+        self.assertEqual(options["workers"], 2)
+        self.assertEqual(options["timeout"], 120)
@@ -0,0 +1,45 @@
+"""Test the database maintenance endpoint."""
+from .base import HubuumAPITestCase
+
+
+class APIMaintenance(HubuumAPITestCase):
+    """Test /api/maintenance/."""
+
+    def test_table_statistics(self):
+        """Test that admins get statistics for the hot tables."""
+        response = self.assert_get("/api/maintenance/")
+        self.assertIsNone(response.data["running"])
+        table = response.data["tables"]["hubuum_host"]
+        self.assertGreaterEqual(table["size"], 0)
+        self.assertIn("dead_ratio", table)
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_and_403("/api/maintenance/")
+        self.assert_post_and_403("/api/maintenance/", {"operation": "analyze"})
+
+    def test_operations(self):
+        """Test running maintenance operations."""
+        response = self.assert_post_and_200(
+            "/api/maintenance/", {"operation": "analyze"}
+        )
+        tables = [result["table"] for result in response.data["results"]]
+        self.assertIn("hubuum_host", tables)
+
+        response = self.assert_post_and_200(
+            "/api/maintenance/", {"operation": "reindex", "tables": ["hubuum_host"]}
+        )
+        self.assertEqual(response.data["results"][0]["table"], "hubuum_host")
+
+        # Tests run within a transaction, where VACUUM can not.
+        self.assert_post_and_409("/api/maintenance/", {"operation": "vacuum"})
+
+    def test_invalid_input(self):
+        """Test that unknown operations and tables are rejected."""
+        self.assert_post_and_400("/api/maintenance/", {})
+        self.assert_post_and_400("/api/maintenance/", {"operation": "drop"})
+        self.assert_post_and_400(
+            "/api/maintenance/", {"operation": "analyze", "tables": ["auth_user"]}
+        )
+        self.assert_post_and_400(
+            "/api/maintenance/", {"operation": "analyze", "tables": "hubuum_host"}
+        )
@@ -0,0 +1,87 @@
+"""Test restricting access by the address of the client."""
+from django.test import override_settings
+
+from hubuum.models.auth import TokenMetadata
+from hubuum.models.namespace import ActivityEntry
+from hubuum.network import client_ip, parse_networks
+
+from .base import HubuumAPITestCase
+
+
+class APIIPFilter(HubuumAPITestCase):
+    """Test the IP allow and deny lists."""
+
+    def _get(self, path, address, forwarded_for=None):
+        """Get a path as if from an address, optionally through proxies."""
+        headers = {"REMOTE_ADDR": address}
+        if forwarded_for:
+            headers["HTTP_X_FORWARDED_FOR"] = forwarded_for
+        return self.client.get(path, **headers)
+
+    @override_settings(IP_ALLOW="10.0.0.0/8, 192.0.2.1", IP_DENY="10.0.66.0/24")
+    def test_allow_and_deny(self):
+        """Test that only allowed, and not denied, clients get through."""
+        self.assertEqual(self._get("/api/v1/namespaces/", "10.1.2.3").status_code, 200)
+        self.assertEqual(self._get("/api/v1/namespaces/", "192.0.2.1").status_code, 200)
+
+        response = self._get("/api/v1/namespaces/", "192.0.2.2")
+        self.assertEqual(response.status_code, 403)
+        self.assertEqual(response.json()["detail"], "Access denied from 192.0.2.2.")
+        self.assertEqual(self._get("/api/v1/namespaces/", "10.0.66.1").status_code, 403)
+
+    @override_settings(IP_ADMIN_ALLOW="10.0.0.0/8")
+    def test_admin_endpoints(self):
+        """Test that administrative endpoints are limited to their networks."""
+        self.assertEqual(self._get("/api/v1/namespaces/", "192.0.2.1").status_code, 200)
+        self.assertEqual(self._get("/api/server-info/", "192.0.2.1").status_code, 403)
+        self.assertEqual(self._get("/api/server-info/", "10.0.0.1").status_code, 200)
+        self.assertEqual(self._get("/api/server-info", "192.0.2.1").status_code, 403)
+        self.assertEqual(self._get("/admin/", "192.0.2.1").status_code, 403)
+
+    @override_settings(IP_ADMIN_ALLOW="10.0.0.0/8")
+    def test_admin_endpoints_elsewhere(self):
+        """Test that administrative views are not reached through other paths."""
+        for path in ("/api/v1/maintenance/", "/api/xdump/", "/api/dump/x"):
+            with self.subTest(path=path):
+                self.assertEqual(self._get(path, "192.0.2.1").status_code, 404)
+                self.assertEqual(self._get(path, "10.0.0.1").status_code, 404)
+        self.assertEqual(self._get("/api/maintenance/", "192.0.2.1").status_code, 403)
+
+    @override_settings(IP_ALLOW="192.0.2.0/24", IP_TRUSTED_PROXIES="10.0.0.1")
+    def test_proxies(self):
+        """Test that X-Forwarded-For is only trusted from proxies."""
+        response = self._get("/api/v1/namespaces/", "10.0.0.1", "192.0.2.1")
+        self.assertEqual(response.status_code, 200)
+
+        # Only the proxy is trusted, not the addresses the client claims.
+        response = self._get("/api/v1/namespaces/", "10.0.0.2", "192.0.2.1")
+        self.assertEqual(response.status_code, 403)
+        response = self._get("/api/v1/namespaces/", "10.0.0.1", "192.0.2.1, 10.0.0.3")
+        self.assertEqual(response.status_code, 403)
+
+    @override_settings(IP_TRUSTED_PROXIES="10.0.0.1")
+    def test_proxied_attribution(self):
+        """Test that writes and tokens are attributed to the client, not the proxy."""
+        headers = {"REMOTE_ADDR": "10.0.0.1", "HTTP_X_FORWARDED_FOR": "192.0.2.1"}
+        response = self.client.post(
+            "/api/v1/namespaces/", {"name": "proxied"}, **headers
+        )
+        self.assertEqual(response.status_code, 201)
+
+        entry = ActivityEntry.objects.get(namespace__name="proxied", action="created")
+        self.assertEqual(entry.actor["ip"], "192.0.2.1")
+        self.assertTrue(TokenMetadata.objects.filter(ip="192.0.2.1").exists())
+        self.assertFalse(TokenMetadata.objects.filter(ip="10.0.0.1").exists())
+
+    def test_client_ip(self):
+        """Test finding the client address through chains of proxies."""
+        proxies = parse_networks("10.0.0.0/24,2001:db8::/32")
+        self.assertEqual(client_ip("192.0.2.1", None, proxies), "192.0.2.1")
+        self.assertEqual(client_ip("192.0.2.1", "198.51.100.1", proxies), "192.0.2.1")
+        self.assertEqual(
+            client_ip("10.0.0.1", "198.51.100.1, 192.0.2.1, 10.0.0.2", proxies),
+            "192.0.2.1",
+        )
+        self.assertEqual(client_ip("2001:db8::1", "10.0.0.2", proxies), "10.0.0.2")
+        with self.assertRaises(ValueError):
+            parse_networks("10.0.0.0/33")
@@ -0,0 +1,22 @@
+"""Test applying seed data through the API."""
+from .base import HubuumAPITestCase
+
+
+class APISeed(HubuumAPITestCase):
+    """Test /api/seed/."""
+
+    def test_seed(self):
+        """Test that admins may apply seeds, and get a report."""
+        seed = {"groups": ["operators"], "namespaces": [{"name": "seeded"}]}
+        response = self.assert_post_and_200("/api/seed/", seed)
+        self.assertEqual(
+            response.data["created"], ["group operators", "namespace seeded"]
+        )
+        self.assert_get("/namespaces/seeded")
+
+        response = self.assert_post_and_200("/api/seed/", seed)
+        self.assertEqual(response.data["created"], [])
+        self.assert_post_and_400("/api/seed/", {"hosts": []})
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_post_and_403("/api/seed/", seed)
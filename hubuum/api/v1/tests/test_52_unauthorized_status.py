@@ -0,0 +1,56 @@
+"""Test the status for requests denied access to objects."""
+from django.contrib.auth.models import Group
+from django.test import override_settings
+
+from .base import HubuumAPITestCase
+
+
+class APIUnauthorizedStatus(HubuumAPITestCase):
+    """Test that denied access gives 403, or 404 to hide the object."""
+
+    def setUp(self):
+        """Create a namespace with a host and an extension."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+        data = {"namespace": self.namespace.data["id"]}
+        self.assert_post("/hosts/", {**data, "name": "web1"})
+        self.assert_post(
+            "/extensions/",
+            {
+                **data,
+                "name": "fleet",
+                "model": "host",
+                "url": "https://fleet.my.domain/api/v1/hosts/{name}",
+                "header": "Authorization: Bearer sh...==",
+            },
+        )
+        Group.objects.get_or_create(name="tmpgroup")
+        self.objects = ("/namespaces/namespace1", "/hosts/web1", "/extensions/fleet")
+
+    def tearDown(self):
+        """Clean up the namespace."""
+        self.client = self.get_superuser_client()
+        self.assert_delete("/namespaces/namespace1")
+
+    def test_forbidden(self):
+        """Test that denied access gives 403 by default."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        for path in self.objects:
+            self.assert_get_and_403(path)
+            self.assert_delete_and_403(path)
+
+    @override_settings(UNAUTHORIZED_STATUS=404)
+    def test_hidden(self):
+        """Test that objects the user can't read are hidden."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        for path in self.objects:
+            self.assert_get_and_404(path)
+            self.assert_delete_and_404(path)
+
+        # Objects the user can read are not hidden, denied changes give 403.
+        self.grant("tmpgroup", "namespace1", ["has_read"])
+        for path in self.objects:
+            self.assert_get(path)
+            self.assert_delete_and_403(path)
+
+        self.assert_get_and_404("/hosts/nosuchhost")
@@ -0,0 +1,86 @@
+"""Test the write limits of namespaces."""
+from unittest import mock
+
+from django.core.cache import cache
+
+from .test_30_extensions import HubuumExtensionTestCase
+
+
+class APINamespaceLimits(HubuumExtensionTestCase):
+    """Test limiting the rate of writes and the size of json_data in namespaces."""
+
+    def setUp(self):
+        """Create an extension, and forget earlier writes."""
+        super().setUp()
+        cache.clear()
+        self.extension = self.assert_post("/extensions/", self.extension_blob).data
+
+    def _host(self, name):
+        """Return the data for creating a host in the namespace."""
+        return {"namespace": self.namespace.id, "name": name}
+
+    def test_json_data_size(self):
+        """Test that json_data larger than the limit gets 413."""
+        self.assert_patch("/namespaces/test", {"max_json_data_size": 64})
+        self.assert_post(
+            "/extension_data/", self._extension_data_blob(self.extension["id"])
+        )
+
+        blob = self._extension_data_blob(self.extension["id"], value="x" * 64)
+        response = self.assert_post_and_413("/extension_data/", blob)
+        self.assertIn("at most 64 bytes", response.data["detail"])
+
+        self.assert_patch("/namespaces/test", {"max_json_data_size": None})
+        self.assert_post("/extension_data/", blob)
+
+    def test_write_rate(self):
+        """Test that writes over the rate limit get 429, until the next minute."""
+        self.assert_patch("/namespaces/test", {"write_rate_limit": 2})
+        with mock.patch("hubuum.models.namespace.time") as clock:
+            clock.time.return_value = 6000.0
+            self.assert_post("/hosts/", self._host("web1"))
+            self.assert_patch("/hosts/web1", {"fqdn": "web1.domain.tld"})
+            response = self.assert_post_and_429("/hosts/", self._host("web2"))
+            self.assertIn("at most 2 writes per minute", response.data["detail"])
+            self.assertEqual(response["Retry-After"], "60")
+
+            clock.time.return_value = 6060.0
+            self.assert_post("/hosts/", self._host("web2"))
+
+        # Writes to other namespaces are not limited.
+        other = self.assert_post("/namespaces/", {"name": "other"}).data
+        for name in ("web3", "web4", "web5"):
+            self.assert_post("/hosts/", {"namespace": other["id"], "name": name})
+        self.assert_delete("/namespaces/other")
+
+    def test_bulk_writes(self):
+        """Test that clones and schema migrations count every object written."""
+        self.assert_post("/hosts/", self._host("web1"))
+        self.assert_patch("/namespaces/test", {"write_rate_limit": 2})
+        with mock.patch("hubuum.models.namespace.time") as clock:
+            clock.time.return_value = 6000.0
+            self.assert_post("/hosts/web1/clone", {"name": "web2"})
+            self.assert_post("/hosts/web1/clone", {"name": "web3"})
+            self.assert_post_and_429("/hosts/web1/clone", {"name": "web4"})
+
+            clock.time.return_value = 6060.0
+            self.assert_post(
+                "/extension_data/", self._extension_data_blob(self.extension["id"])
+            )
+            migration = {"json_schema": {"type": "object"}, "dry_run": False}
+            path = f"/extensions/{self.extension['id']}/migrate_schema"
+            self.assert_post(path, migration)
+            self.assert_post_and_429(path, migration)
+
+            clock.time.return_value = 6120.0
+            self.assert_post_and_429(
+                "/namespaces/test/clone", {"name": "copy", "objects": True}
+            )
+            self.assert_get_and_404("/namespaces/copy")
+
+    def test_limits_require_admin(self):
+        """Test that only admins can set the limits of namespaces."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.grant("tmpgroup", "test", ["has_read", "has_update", "has_namespace"])
+        self.assert_patch_and_403("/namespaces/test", {"write_rate_limit": 10})
+        self.assert_patch("/namespaces/test", {"description": "Limited."})
@@ -0,0 +1,74 @@
+"""Test YAML request and response bodies."""
+import yaml
+
+from .base import HubuumAPITestCase
+
+
+class APIYAML(HubuumAPITestCase):
+    """Test that YAML is negotiated by Content-Type and Accept."""
+
+    def setUp(self):
+        """Create a namespace."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "namespace1"})
+
+    def tearDown(self):
+        """Clean up the namespace."""
+        self.assert_delete("/namespaces/namespace1")
+
+    def _post_yaml(self, path, body):
+        """Post a YAML body, returning the response."""
+        return self.client.post(
+            self._create_path(path), body, content_type="application/yaml"
+        )
+
+    def test_yaml_requests(self):
+        """Test that objects can be created from YAML."""
+        body = f"namespace: {self.namespace.data['id']}\nname: web1\nfqdn: web1.tld\n"
+        response = self._post_yaml("/hosts/", body)
+        self._assert_status_and_debug(response, 201)
+        self.assertEqual(self.assert_get("/hosts/web1").data["fqdn"], "web1.tld")
+
+        response = self._post_yaml("/hosts/", "name: [unclosed\n")
+        self._assert_status_and_debug(response, 400)
+        response = self._post_yaml("/hosts/", "!!python/object:os.system {}\n")
+        self._assert_status_and_debug(response, 400)
+
+    def test_yaml_timestamps(self):
+        """Test that timestamps are read as strings, as in JSON."""
+        body = f"namespace: {self.namespace.data['id']}\nname: web2\nfqdn: 2024-01-01\n"
+        response = self._post_yaml("/hosts/", body)
+        self._assert_status_and_debug(response, 201)
+        self.assertEqual(self.assert_get("/hosts/web2").data["fqdn"], "2024-01-01")
+
+    def test_yaml_aliases(self):
+        """Test that anchors and aliases are refused."""
+        namespace = self.namespace.data["id"]
+        body = f"namespace: &ns {namespace}\nname: web3\nfqdn: *ns\n"
+        response = self._post_yaml("/hosts/", body)
+        self._assert_status_and_debug(response, 400)
+        response = self._post_yaml("/hosts/", "a: &a [x, x]\nb: [*a, *a]\n")
+        self._assert_status_and_debug(response, 400)
+        self.assert_get_and_404("/hosts/web3")
+
+    def test_yaml_responses(self):
+        """Test that responses are rendered as YAML by Accept or ?format=yaml."""
+        namespace = self.namespace.data["id"]
+        self.assert_post("/hosts/", {"namespace": namespace, "name": "web1"})
+        response = self.client.get(
+            self._create_path("/hosts/web1"), HTTP_ACCEPT="application/yaml"
+        )
+        self._assert_status_and_debug(response, 200)
+        self.assertTrue(response["Content-Type"].startswith("application/yaml"))
+        host = yaml.safe_load(response.content)
+        self.assertEqual(host["name"], "web1")
+        self.assertEqual(host, self.assert_get("/hosts/web1").json())
+
+        response = self.client.get(self._create_path("/hosts/?format=yaml"))
+        self._assert_status_and_debug(response, 200)
+        hosts = yaml.safe_load(response.content)
+        self.assertEqual([host["name"] for host in hosts], ["web1"])
+
+        response = self.client.get(self._create_path("/hosts/nosuchhost?format=yaml"))
+        self._assert_status_and_debug(response, 404)
+        self.assertIn("detail", yaml.safe_load(response.content))
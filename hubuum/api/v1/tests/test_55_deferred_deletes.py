@@ -0,0 +1,91 @@
+"""Test deleting namespaces in the background."""
+from io import StringIO
+
+from django.core.management import call_command
+from django.core.management.base import CommandError
+
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host
+
+from .base import HubuumAPITestCase
+
+
+class APIDeferredDeletes(HubuumAPITestCase):
+    """Test that deferred deletes hide namespaces, and delete them in batches."""
+
+    def setUp(self):
+        """Create a namespace with rooms, and hosts in them."""
+        super().setUp()
+        self.namespace = self.assert_post("/namespaces/", {"name": "big"})
+        data = {"namespace": self.namespace.data["id"]}
+        for number in range(3):
+            room = self.assert_post("/rooms/", {**data, "room_id": f"BL14-{number}"})
+            self.assert_post(
+                "/hosts/", {**data, "name": f"host{number}", "room": room.data["id"]}
+            )
+
+    def tearDown(self):
+        """Clean up the namespaces."""
+        Namespace.objects.filter(name__in=["big", "other"]).delete()
+
+    def _delete_namespaces(self, *args):
+        """Run the worker deleting namespaces, and return its output."""
+        out = StringIO()
+        call_command("delete_namespaces", "--once", *args, stdout=out, stderr=out)
+        return out.getvalue()
+
+    def test_deferred_delete(self):
+        """Test that the namespace is hidden at once, and deleted by the worker."""
+        response = self.assert_delete_and_202("/namespaces/big?deferred=true")
+        self.assertEqual(response.data["total"], 6)
+        self.assertEqual(response.data["remaining"], 6)
+
+        self.assert_get_and_404("/namespaces/big")
+        self.assert_get_elements("/namespaces/", 0)
+        self.assert_get_elements("/hosts/", 0)
+        self.assert_get_and_404("/hosts/host1")
+        self.assert_post_and_409(
+            "/hosts/", {"namespace": self.namespace.data["id"], "name": "new"}
+        )
+        response = self.assert_get("/namespaces/big/deletion")
+        self.assertEqual(response.data["name"], "big")
+        self.assertIsNone(response.data["error"])
+
+        output = self._delete_namespaces("--batch-size", "4")
+        self.assertIn("2 of 6 object(s) remaining", output)
+        self.assertIn("Deleted big.", output)
+        self.assertFalse(Namespace.objects.filter(name="big").exists())
+        self.assert_get_and_404("/namespaces/big/deletion")
+
+    def test_restrict(self):
+        """Test that references from other namespaces with restrict are reported."""
+        other = self.assert_post("/namespaces/", {"name": "other"})
+        self.assert_patch("/hosts/host1", {"namespace": other.data["id"]})
+        response = self.assert_delete_and_409("/namespaces/big?deferred=true")
+        self.assertIn("host.room", response.data["detail"])
+        self.assert_get_and_404("/namespaces/big/deletion")
+        self.assertIsNone(Namespace.objects.get(name="big").deletion)
+
+        # References made after the deletion was requested fail the batch.
+        self.assert_patch("/hosts/host1", {"namespace": self.namespace.data["id"]})
+        self.assert_delete_and_202("/namespaces/big?deferred=true")
+        Host.objects.filter(name="host1").update(namespace=other.data["id"])
+        with self.assertRaises(CommandError):
+            self._delete_namespaces()
+        response = self.assert_get("/namespaces/big/deletion")
+        self.assertIn("host.room", response.data["error"])
+
+    def test_permissions(self):
+        """Test that deferred deletes require has_namespace."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.grant("tmpgroup", "big", ["has_read"])
+        self.assert_delete_and_403("/namespaces/big?deferred=true")
+        self.assert_get_and_404("/namespaces/big/deletion")
+
+        self.client = self.get_superuser_client()
+        path = "/namespaces/big/groups/tmpgroup"
+        self._assert_patch_and_status(path, 204, {"has_namespace": True})
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_delete_and_202("/namespaces/big?deferred=true")
+        self.assert_get("/namespaces/big/deletion")
+        self.client = self.get_superuser_client()
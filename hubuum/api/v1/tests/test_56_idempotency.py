@@ -0,0 +1,86 @@
+"""Test the Idempotency-Key header on create requests."""
+from datetime import timedelta
+from unittest import mock
+
+from django.core.cache import cache
+from django.utils import timezone
+
+from hubuum.models.auth import IdempotencyKey
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host
+
+from .base import HubuumAPITestCase
+
+
+class APIIdempotency(HubuumAPITestCase):
+    """Test that retried creates with an Idempotency-Key are not duplicated."""
+
+    def _post(self, path, data, key, status_code):
+        """Post with an Idempotency-Key, and assert the status."""
+        response = self.client.post(
+            self._create_path(path), data, HTTP_IDEMPOTENCY_KEY=key
+        )
+        self._assert_status_and_debug(response, status_code)
+        return response
+
+    def tearDown(self):
+        """Clean up the namespaces."""
+        Namespace.objects.filter(name__in=["retried", "other"]).delete()
+
+    def test_replay(self):
+        """Test that a retried request gets the stored response."""
+        first = self._post("/namespaces/", {"name": "retried"}, "key1", 201)
+        self.assertNotIn("Idempotent-Replayed", first)
+
+        retried = self._post("/namespaces/", {"name": "retried"}, "key1", 201)
+        self.assertEqual(retried["Idempotent-Replayed"], "true")
+        self.assertEqual(retried.data, first.data)
+        self.assertEqual(Namespace.objects.filter(name="retried").count(), 1)
+
+        # Errors are stored as well.
+        data = {"namespace": first.data["id"]}
+        self._post("/hosts/", data, "key2", 400)
+        retried = self._post("/hosts/", data, "key2", 400)
+        self.assertEqual(retried["Idempotent-Replayed"], "true")
+
+        # Keys are per user.
+        data = {"namespace": first.data["id"], "name": "host1"}
+        self.client = self.get_staff_client()
+        self._post("/hosts/", data, "key1", 201)
+        self.assertEqual(Host.objects.filter(name="host1").count(), 1)
+
+    def test_reuse(self):
+        """Test that keys can not be reused for different requests."""
+        self._post("/namespaces/", {"name": "retried"}, "key1", 201)
+        response = self._post("/namespaces/", {"name": "other"}, "key1", 422)
+        self.assertIn("different request", response.data["detail"])
+        self.assertFalse(Namespace.objects.filter(name="other").exists())
+
+        self._post("/namespaces/", {"name": "other"}, "", 400)
+        self._post("/namespaces/", {"name": "other"}, "k" * 256, 400)
+
+    def test_released(self):
+        """Test that responses that may differ on a retry release the key."""
+        cache.clear()
+        namespace = Namespace.objects.create(name="retried", write_rate_limit=1)
+        data = {"namespace": namespace.id, "name": "host2"}
+        with mock.patch("hubuum.models.namespace.time") as clock:
+            clock.time.return_value = 6000.0
+            self.assert_post("/hosts/", {"namespace": namespace.id, "name": "host1"})
+            self._post("/hosts/", data, "key1", 429)
+            self.assertFalse(IdempotencyKey.objects.filter(key="key1").exists())
+
+            clock.time.return_value = 6060.0
+            retried = self._post("/hosts/", data, "key1", 201)
+            self.assertNotIn("Idempotent-Replayed", retried)
+            self.assertEqual(IdempotencyKey.objects.get(key="key1").status, 201)
+
+    def test_window(self):
+        """Test that keys are forgotten after the window."""
+        self._post("/namespaces/", {"name": "retried"}, "key1", 201)
+        past = timezone.now() - timedelta(days=2)
+        IdempotencyKey.objects.update(created_at=past)
+
+        # The request is processed again, and the namespace exists.
+        self._post("/namespaces/", {"name": "retried"}, "key1", 400)
+        self.assertEqual(IdempotencyKey.objects.get(key="key1").status, 400)
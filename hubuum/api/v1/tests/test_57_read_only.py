@@ -0,0 +1,69 @@
+"""Test read-only (maintenance) modes of the server and of namespaces."""
+from django.core.cache import cache
+
+from hubuum.models.base import ReadOnlyMode
+
+from .base import HubuumAPITestCase
+
+
+class APIReadOnly(HubuumAPITestCase):
+    """Test /api/read-only/."""
+
+    def tearDown(self):
+        """Forget the cached modes, as the database is rolled back."""
+        cache.delete(ReadOnlyMode.CACHE_KEY)
+        return super().tearDown()
+
+    def test_read_only_server(self):
+        """Test that writes get 503 while the server is read-only."""
+        self.assert_post("/namespaces/", {"name": "yes"})
+        response = self.assert_put_and_201(
+            "/api/read-only/", {"reason": "Upgrading.", "retry_after": 60}
+        )
+        self.assertIsNone(response.data["namespace"])
+
+        response = self._assert_post_and_status("/namespaces/", 503, {"name": "no"})
+        self.assertEqual(response["Retry-After"], "60")
+        self.assertIn("Upgrading.", response.json()["detail"])
+        self.assert_get("/namespaces/yes")
+        self.assert_get_elements("/api/read-only/", 1)
+
+        self.assert_put_and_200("/api/read-only/", {"retry_after": 30})
+        self.assert_delete("/api/read-only/")
+        self.assert_post("/namespaces/", {"name": "no"})
+        self.assert_delete_and_404("/api/read-only/")
+
+    def test_read_only_namespace(self):
+        """Test that a read-only namespace, and its objects, get 503 on writes."""
+        yes = self.assert_post("/namespaces/", {"name": "yes"}).data
+        no = self.assert_post("/namespaces/", {"name": "no"}).data
+        self.assert_post("/hosts/", {"name": "one", "namespace": yes["id"]})
+        self.assert_put_and_201("/api/read-only/yes", {"reason": "Moving."})
+        self.assertEqual(self.assert_get("/api/read-only/yes").data["namespace"], "yes")
+        self.assert_get_and_404("/api/read-only/no")
+        self.assert_get_and_404("/api/read-only/nosuchnamespace")
+
+        response = self._assert_patch_and_status("/hosts/one", 503, {"name": "renamed"})
+        self.assertEqual(response["Retry-After"], "300")
+        self._assert_post_and_status(
+            "/hosts/", 503, {"name": "two", "namespace": yes["id"]}
+        )
+        self._assert_patch_and_status("/namespaces/yes", 503, {"description": "x"})
+        self._assert_delete_and_status("/namespaces/yes", 503)
+        self.assert_post("/hosts/", {"name": "two", "namespace": no["id"]})
+        self.assert_get("/hosts/one")
+
+        self.assert_delete("/api/read-only/yes")
+        self.assert_patch("/hosts/one", {"name": "renamed"})
+
+    def test_read_only_input_and_permissions(self):
+        """Test that only admins set modes, with valid input."""
+        self.assert_put_and_400("/api/read-only/", {"retry_after": -1})
+        self.assert_put_and_400("/api/read-only/", {"retry_after": "soon"})
+        self.assert_put_and_400("/api/read-only/", {"reason": 1})
+        self.assert_put_and_400("/api/read-only/", {"scope": "all"})
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_and_403("/api/read-only/")
+        self.assert_put_and_403("/api/read-only/", {})
+        self.assertFalse(ReadOnlyMode.objects.exists())
@@ -0,0 +1,60 @@
+"""Test the aggregated reporting of denied requests."""
+from rest_framework.test import APIClient
+
+from hubuum import denials
+from hubuum.models.namespace import Namespace
+
+from .base import HubuumAPITestCase
+
+
+class APIDenials(HubuumAPITestCase):
+    """Test /api/denials/."""
+
+    def setUp(self):
+        """Forget the denials of earlier tests."""
+        super().setUp()
+        denials.reset()
+
+    def test_denials(self):
+        """Test that denials are counted per user, route, namespace, and status."""
+        namespace = Namespace.objects.create(name="secret")
+        admin = self.client
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        for _ in range(2):
+            self._assert_post_and_status(
+                "/hosts/", 403, {"name": "denied", "namespace": namespace.id}
+            )
+        self.assert_get_and_403("/api/denials/")
+        self.client = APIClient()
+        self.assert_get_and_401("/hosts/")
+
+        self.client = admin
+        report = self.assert_get("/api/denials/").data
+        self.assertEqual(report["dropped"], 0)
+        first, *others = report["denials"]
+        self.assertEqual(
+            [first[key] for key in ("user", "method", "route", "namespace", "status")],
+            ["tmp", "POST", "api/v1/hosts/", str(namespace.id), 403],
+        )
+        self.assertEqual(first["count"], 2)
+        self.assertLessEqual(first["first_seen"], first["last_seen"])
+        self.assertIn(
+            ("tmp", "api/denials/", None),
+            [(row["user"], row["route"], row["namespace"]) for row in others],
+        )
+        self.assertIn((None, 401), [(row["user"], row["status"]) for row in others])
+
+        self.assert_delete("/api/denials/")
+        self.assertEqual(self.assert_get("/api/denials/").data["denials"], [])
+
+    def test_denial_tracking_size(self):
+        """Test that denials beyond the tracked combinations are only counted."""
+        self.client = APIClient()
+        with self.settings(DENIAL_TRACKING_SIZE=1):
+            self.assert_get_and_401("/hosts/")
+            self.assert_get_and_401("/rooms/")
+            self.assert_get_and_401("/hosts/")
+            report = denials.report()
+        self.assertEqual(len(report["denials"]), 1)
+        self.assertEqual(report["denials"][0]["count"], 2)
+        self.assertEqual(report["dropped"], 1)
@@ -0,0 +1,49 @@
+"""Test addressing objects by their namespace and name."""
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host, Room
+
+from .base import HubuumAPITestCase
+
+
+class APIByName(HubuumAPITestCase):
+    """Test /<model>/by-name/<namespace>/<name>, and ambiguous lookups."""
+
+    def setUp(self):
+        """Create hosts with the same name in two namespaces, and a room."""
+        super().setUp()
+        self.first = Namespace.objects.create(name="first")
+        self.second = Namespace.objects.create(name="second")
+        for namespace in (self.first, self.second):
+            Host.objects.create(name="web", namespace=namespace)
+        Host.objects.create(name="rack/slot", namespace=self.first)
+        Room.objects.create(room_id="BL01", namespace=self.first)
+
+    def test_by_name(self):
+        """Test getting and updating objects by name."""
+        response = self.assert_get("/hosts/by-name/first/web")
+        self.assertEqual(response.data["namespace"], self.first.id)
+        response = self.assert_get(f"/hosts/by-name/{self.second.id}/web")
+        self.assertEqual(response.data["namespace"], self.second.id)
+
+        self.assert_get("/hosts/by-name/first/rack/slot")
+        self.assert_get("/hosts/by-name/first/rack%2Fslot")
+        self.assert_patch("/rooms/by-name/first/BL01", {"floor": "2"})
+        self.assert_get_and_404("/rooms/by-name/second/BL01")
+        self.assert_get_and_404("/hosts/by-name/nosuchnamespace/web")
+        self.assert_get_and_404("/hosts/by-name/first/nosuchhost")
+
+    def test_ambiguous(self):
+        """Test that names matching several objects get 409."""
+        response = self._assert_get_and_status("/hosts/web", 409)
+        self.assertIn("several objects by name", response.data["detail"])
+
+        Host.objects.create(name="web", namespace=self.first)
+        self._assert_get_and_status("/hosts/by-name/first/web", 409)
+        self.assert_get("/hosts/by-name/second/web")
+
+    def test_by_name_permissions(self):
+        """Test that the permissions of the namespace apply."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_and_403("/hosts/by-name/first/web")
+        self.grant("tmpgroup", "first", ["has_read"])
+        self.assert_get("/hosts/by-name/first/web")
@@ -0,0 +1,59 @@
+"""Test taking and restoring logical dumps through the API."""
+import json
+
+from django.core.cache import cache
+
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host
+
+from .base import HubuumAPITestCase
+
+
+class APIDump(HubuumAPITestCase):
+    """Test /api/dump/."""
+
+    def test_dump_and_restore(self):
+        """Test that admins may stream a dump, and restore it."""
+        namespace = Namespace.objects.create(name="dumped")
+        Host.objects.create(name="web1", namespace=namespace)
+
+        response = self.client.get("/api/dump/")
+        self.assertEqual(response.status_code, 200)
+        self.assertTrue(response["Content-Type"].startswith("application/x-ndjson"))
+        self.assertIn("attachment; filename=", response["Content-Disposition"])
+        body = b"".join(response.streaming_content)
+        manifest = json.loads(body.splitlines()[0])["manifest"]
+        self.assertEqual(manifest["models"]["hubuum.host"], 1)
+
+        namespace.delete()
+        response = self.client.post(
+            "/api/dump/", body, content_type="application/x-ndjson"
+        )
+        self.assertEqual(response.status_code, 200)
+        self.assertEqual(response.data["restored"]["hubuum.namespace"], 1)
+        self.assert_get("/hosts/web1")
+
+        response = self.client.post(
+            "/api/dump/", b"not a dump", content_type="application/x-ndjson"
+        )
+        self.assertEqual(response.status_code, 400)
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_and_403("/api/dump/")
+        self.assert_post_and_403("/api/dump/", {})
+
+    def test_restore_limits(self):
+        """Test that nothing is restored if the write limits of a namespace are hit."""
+        cache.clear()
+        namespace = Namespace.objects.create(name="dumped", write_rate_limit=1)
+        Host.objects.create(name="web1", namespace=namespace)
+        Host.objects.create(name="web2", namespace=namespace)
+        body = b"".join(self.client.get("/api/dump/").streaming_content)
+
+        namespace.delete()
+        response = self.client.post(
+            "/api/dump/", body, content_type="application/x-ndjson"
+        )
+        self.assertEqual(response.status_code, 400)
+        self.assertIn("at most 1 writes per minute", response.data["detail"])
+        self.assertFalse(Namespace.objects.filter(name="dumped").exists())
@@ -0,0 +1,64 @@
+"""Test relating objects knowing only the id of the object related to."""
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host, Person, Room
+
+from .base import HubuumAPITestCase
+
+
+class APIRelate(HubuumAPITestCase):
+    """Test POST /<model>/<val>/relations."""
+
+    def setUp(self):
+        """Create objects with ids unique across models, and one that is not."""
+        super().setUp()
+        self.namespace = Namespace.objects.create(name="namespace1")
+        self.host = Host.objects.create(name="web", namespace=self.namespace)
+        self.room = Room.objects.create(
+            pk=9001, room_id="101", namespace=self.namespace
+        )
+        self.person = Person.objects.create(
+            pk=9002, username="alice", namespace=self.namespace
+        )
+        self.rack = Host.objects.create(pk=9003, name="rack", namespace=self.namespace)
+
+    def test_relate(self):
+        """Test setting foreign keys of the object, and of the object related to."""
+        response = self.assert_post_and_200("/hosts/web/relations", {"id": 9001})
+        self.assertEqual(response.data["relation"], "room")
+        self.assertEqual(response.data["direction"], "forward")
+        self.assertEqual(response.data["object"]["room"], self.room.id)
+        self.assertEqual(Host.objects.get(name="web").room, self.room)
+
+        response = self.assert_post_and_200("/rooms/9001/relations", {"id": 9002})
+        self.assertEqual(response.data["relation"], "person_set")
+        self.assertEqual(response.data["direction"], "reverse")
+        self.assertEqual(response.data["model"], "person")
+        self.assertEqual(Person.objects.get(pk=9002).room, self.room)
+
+    def test_ambiguous(self):
+        """Test that ids matching several relations get 409, unless one is chosen."""
+        response = self._assert_post_and_status(
+            "/hosts/web/relations", 409, {"id": 9003}
+        )
+        self.assertIn("children, parent", response.data["detail"])
+
+        data = {"id": 9003, "relation": "parent"}
+        self.assert_post_and_200("/hosts/web/relations", data)
+        self.assertEqual(Host.objects.get(name="web").parent, self.rack)
+
+        self.assert_post_and_404("/hosts/web/relations", {"id": 9001, "model": "jack"})
+        self.assert_post_and_404("/hosts/web/relations", {"id": 99999})
+        self.assert_post_and_400("/hosts/web/relations", {"id": "room"})
+
+    def test_relate_permissions(self):
+        """Test that the object changed must be updatable, the other readable."""
+        secret = Namespace.objects.create(name="secret")
+        Room.objects.create(pk=9004, room_id="vault", namespace=secret)
+        self.client = self.get_user_client(username="reader", groupname="readers")
+        self.grant("readers", "namespace1", ["has_read"])
+        self.assert_post_and_403("/hosts/web/relations", {"id": 9001})
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.grant("tmpgroup", "namespace1", ["has_read", "has_update"])
+        self.assert_post_and_200("/hosts/web/relations", {"id": 9001})
+        self.assert_post_and_404("/hosts/web/relations", {"id": 9004})
@@ -0,0 +1,32 @@
+"""Test the catalog of permissions."""
+from rest_framework.test import APIClient
+
+from hubuum.permissions import fully_qualified_operations
+
+from .base import HubuumAPITestCase
+
+
+class APIPermissionCatalog(HubuumAPITestCase):
+    """Test /meta/permissions."""
+
+    def test_catalog(self):
+        """Test that every permission is listed, with the models it applies to."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        catalog = {
+            permission["name"]: permission
+            for permission in self.assert_get("/meta/permissions").data
+        }
+        self.assertEqual(list(catalog), fully_qualified_operations())
+
+        self.assertEqual(catalog["has_read"]["operation"], "read")
+        self.assertIn("host", catalog["has_read"]["models"])
+        self.assertTrue(catalog["has_read"]["per_model"])
+        self.assertEqual(catalog["has_namespace"]["models"], ["namespace"])
+        self.assertFalse(catalog["has_namespace"]["per_model"])
+        self.assertEqual(catalog["has_read_sensitive"]["models"], ["extensiondata"])
+        for permission in catalog.values():
+            self.assertTrue(permission["description"])
+
+        self.assert_post_and_403("/meta/permissions", {})
+        self.client = APIClient()
+        self.assert_get_and_401("/meta/permissions")
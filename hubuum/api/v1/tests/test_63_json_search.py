@@ -0,0 +1,45 @@
+"""Test the budget for JSON searches."""
+from django.test import override_settings
+
+from hubuum import metrics, search
+
+from .base import HubuumAPITestCase
+
+SEARCH = "/extension_data/?json_data_has_key=fqdn"
+
+
+class APIJSONSearchBudget(HubuumAPITestCase):
+    """Test that JSON searches beyond the budget get 503."""
+
+    @override_settings(JSON_SEARCH_CONCURRENCY=1, JSON_SEARCH_QUEUE_TIMEOUT=0)
+    def test_busy(self):
+        """Test that searches without capacity get 503 with Retry-After."""
+        rejected = metrics.value("hubuum_json_searches", outcome="rejected")
+        with search.json_search({}):
+            response = self._assert_get_and_status(SEARCH, 503)
+            self.assertEqual(response["Retry-After"], "5")
+            self.assertEqual(response.data["detail"].code, "busy")
+            self.assert_get("/extension_data/")
+        self.assertEqual(
+            metrics.value("hubuum_json_searches", outcome="rejected"), rejected + 1
+        )
+        self.assert_get(SEARCH)
+
+    @override_settings(JSON_SEARCH_CONCURRENCY=0, JSON_SEARCH_QUEUE_TIMEOUT=0)
+    def test_unlimited(self):
+        """Test that searches are not limited with a concurrency of 0."""
+        with search.json_search({}):
+            self.assert_get(SEARCH)
+
+    @override_settings(JSON_SEARCH_SLOW_TIME=0)
+    def test_slow(self):
+        """Test that slow searches are counted."""
+        completed = metrics.value("hubuum_json_searches", outcome="completed")
+        slow = metrics.value("hubuum_json_searches", outcome="slow")
+        self.assert_get(SEARCH)
+        self.assertEqual(
+            metrics.value("hubuum_json_searches", outcome="completed"), completed + 1
+        )
+        self.assertEqual(
+            metrics.value("hubuum_json_searches", outcome="slow"), slow + 1
+        )
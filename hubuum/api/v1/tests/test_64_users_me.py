@@ -0,0 +1,74 @@
+"""Test users acting on themselves, ie through /users/me."""
+from django.contrib.auth.models import Group
+from knox.models import AuthToken
+from rest_framework.test import APIClient
+
+from hubuum.models.auth import User
+
+from .base import HubuumAPITestCase
+
+
+class APIUsersSelf(HubuumAPITestCase):
+    """Test /users/me, and what users may do to their own user."""
+
+    def setUp(self):
+        """Create another user, and a client for a normal user."""
+        super().setUp()
+        self.admin = self.client
+        self.other = User.objects.create(username="other")
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+
+    def test_me(self):
+        """Test reading and updating the user making the request."""
+        response = self.assert_get("/users/me")
+        self.assertEqual(response.data["username"], "tmp")
+        self.assert_patch("/users/me", {"first_name": "Temporary"})
+        self.assert_patch("/users/tmp", {"email": "tmp@example.com"})
+        user = User.objects.get(username="tmp")
+        self.assertEqual(user.first_name, "Temporary")
+        self.assertEqual(user.email, "tmp@example.com")
+
+        # Reading other users is allowed, changing them is not.
+        self.assert_get("/users/other")
+        self.assert_patch_and_403("/users/other", {"first_name": "Other"})
+
+        self.client = self.admin
+        self.assertEqual(self.assert_get("/users/me").data["username"], "superuser")
+        self.client = APIClient()
+        self.assert_get_and_401("/users/me")
+
+    def test_privilege_escalation(self):
+        """Test that users can not grant themselves privileges, or delete themselves."""
+        group = Group.objects.create(name="admins").id
+        for data in (
+            {"is_superuser": True},
+            {"is_staff": True},
+            {"is_active": True},
+            {"groups": [group]},
+            {"user_permissions": [1]},
+            {"username": "superuser2"},
+            {"password": "secret"},
+            {"first_name": "Sneaky", "is_staff": True},
+        ):
+            self.assert_patch_and_403("/users/me", data)
+        self.assert_put_and_403("/users/me", {"username": "tmp", "is_staff": True})
+        self.assert_delete_and_403("/users/me")
+        self.assert_post_and_403("/users/me/suspend")
+
+        user = User.objects.get(username="tmp")
+        self.assertFalse(user.is_staff or user.is_superuser)
+        self.assertEqual(user.first_name, "")
+
+    def test_own_tokens_and_logins(self):
+        """Test that users see and revoke their own tokens, but not those of others."""
+        AuthToken.objects.create(self.other)
+        self.assertEqual(len(self.assert_get("/users/me/tokens").data), 1)
+        self.assert_get("/users/me/logins")
+        self.assert_get_and_403("/users/other/tokens")
+        self.assert_delete_and_403("/users/other/tokens")
+        self.assert_get_and_403("/users/other/logins")
+        self.assertEqual(AuthToken.objects.filter(user=self.other).count(), 1)
+
+        response = self.assert_delete_and_200("/users/me/tokens")
+        self.assertEqual(response.data["revoked"], 1)
+        self.assert_get_and_401("/users/me")
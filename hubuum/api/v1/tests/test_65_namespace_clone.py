@@ -0,0 +1,155 @@
+"""Test cloning namespaces, with or without their objects and permissions."""
+from io import StringIO
+
+from django.contrib.auth.models import Group
+from django.core.management import call_command
+
+from hubuum.models.extensions import Extension, ExtensionData, SchemaDefinition
+from hubuum.models.namespace import ModelPermission, Namespace, Permission
+from hubuum.models.objects import Host, Room
+
+from .base import HubuumAPITestCase
+
+
+class APINamespaceClone(HubuumAPITestCase):
+    """Test /namespaces/<namespace>/clone and /namespaces/<namespace>/cloning."""
+
+    def setUp(self):
+        """Create a namespace with a schema, an extension, and related objects."""
+        super().setUp()
+        self.source = Namespace.objects.create(name="src", description="Source.")
+        SchemaDefinition.objects.create(
+            namespace=self.source, name="key", json_schema={"type": "string"}
+        )
+        extension = Extension.objects.create(
+            namespace=self.source,
+            name="fleet",
+            model="host",
+            url="https://fleet.my.domain/{name}",
+            header="Authorization: Bearer sh...==",
+            json_schema={"$ref": "hubuum:schemas/src/key"},
+        )
+        room = Room.objects.create(room_id="BL14", namespace=self.source)
+        parent = Host.objects.create(name="rack", namespace=self.source)
+        host = Host.objects.create(
+            name="web", namespace=self.source, room=room, parent=parent
+        )
+        host.add_tag("production")
+        ExtensionData.objects.create(
+            namespace=self.source,
+            extension=extension,
+            content_object=host,
+            json_data="fleet-key",
+        )
+        self.group = Group.objects.create(name="operators")
+        self.grant("operators", "src", ["has_read", "has_update"])
+
+    def test_clone_structure(self):
+        """Test that the schemas, extensions, and permissions are copied."""
+        response = self.assert_post("/namespaces/src/clone", {"name": "copy"})
+        self.assertEqual(response.data["description"], "Source.")
+        self.assertIsNone(response.data["cloning"])
+
+        copy = Namespace.objects.get(name="copy")
+        extension = Extension.objects.get(namespace=copy)
+        self.assertEqual(extension.name, "copy-fleet")
+        self.assertEqual(extension.json_schema, {"$ref": "hubuum:schemas/copy/key"})
+        self.assertTrue(SchemaDefinition.objects.filter(namespace=copy).exists())
+        self.assertFalse(Host.objects.filter(namespace=copy).exists())
+        grant = Permission.objects.get(namespace=copy, group=self.group)
+        self.assertTrue(grant.has_update)
+        self.assertFalse(grant.has_delete)
+
+        data = {"name": "fresh", "permissions": False}
+        self.assert_post("/namespaces/src/clone", data)
+        self.assertFalse(Permission.objects.filter(namespace__name="fresh").exists())
+        self.assert_post_and_400("/namespaces/src/clone", {"name": "copy"})
+        self.assert_post_and_400("/namespaces/src/clone", {"name": "x", "objects": 1})
+
+    def test_clone_objects(self):
+        """Test that relations between the objects refer to the copies."""
+        self.assert_post("/namespaces/src/clone", {"name": "copy", "objects": True})
+        host = Host.objects.get(namespace__name="copy", name="web")
+        self.assertEqual(host.room.namespace.name, "copy")
+        self.assertEqual(host.parent.namespace.name, "copy")
+        self.assertEqual(host.tags(), ["production"])
+        data = ExtensionData.objects.get(object_id=host.id)
+        self.assertEqual(data.extension.name, "copy-fleet")
+        self.assertEqual(data.json_data, "fleet-key")
+        self.assertEqual(Host.objects.filter(namespace=self.source).count(), 2)
+
+    def test_deferred_clone(self):
+        """Test that large namespaces are cloned by the worker."""
+        with self.settings(NAMESPACE_CLONE_SIZE=2):
+            response = self._assert_post_and_status(
+                "/namespaces/src/clone", 202, {"name": "copy", "objects": True}
+            )
+        self.assertEqual(response.data["cloning"]["total"], 3)
+        copy = response.data["id"]
+        response = self.assert_get("/namespaces/copy/cloning")
+        self.assertEqual(response.data["source"], self.source.id)
+        self.assertIsNone(response.data["error"])
+        self.assert_post_and_409("/hosts/", {"namespace": copy, "name": "new"})
+        self.assertFalse(Host.objects.filter(namespace__name="copy").exists())
+
+        out = StringIO()
+        call_command("clone_namespaces", "--once", stdout=out, stderr=out)
+        self.assertIn("Cloned 3 object(s) into copy.", out.getvalue())
+        self.assert_get_and_404("/namespaces/copy/cloning")
+        self.assertEqual(Host.objects.filter(namespace__name="copy").count(), 2)
+
+        path = "/namespaces/src/clone?deferred=true"
+        self._assert_post_and_status(path, 202, {"name": "later", "objects": True})
+
+    def test_permissions(self):
+        """Test that cloning requires has_read, and has_namespace for the parent."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.grant("tmpgroup", "src", ["has_read"])
+        self.assert_post_and_403("/namespaces/src/clone", {"name": "src.copy"})
+
+        self.client = self.get_superuser_client()
+        path = "/namespaces/src/groups/tmpgroup"
+        self._assert_patch_and_status(path, 204, {"has_namespace": True})
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_post("/namespaces/src/clone", {"name": "src.copy"})
+        grant = Permission.objects.get(
+            namespace__name="src.copy", group__name="tmpgroup"
+        )
+        self.assertTrue(grant.has_delete)
+
+    def test_sensitive_data(self):
+        """Test that cloning sensitive data requires has_read_sensitive."""
+        Extension.objects.filter(namespace=self.source).update(sensitive_paths=["*"])
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.grant("tmpgroup", "src", ["has_read", "has_namespace"])
+        data = {"name": "src.copy", "objects": True}
+        self.assert_post_and_403("/namespaces/src/clone", data)
+        self.assert_post("/namespaces/src/clone", {"name": "src.copy"})
+
+        self.client = self.get_superuser_client()
+        path = "/namespaces/src/groups/tmpgroup"
+        self._assert_patch_and_status(path, 204, {"has_read_sensitive": True})
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_post("/namespaces/src/clone", {**data, "name": "src.full"})
+        self.assertTrue(Host.objects.filter(namespace__name="src.full").exists())
+
+    def test_model_permissions(self):
+        """Test that cloning objects requires has_read for every model cloned."""
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.grant("tmpgroup", "src", ["has_read", "has_namespace"])
+        denied = ModelPermission.objects.create(
+            namespace=self.source,
+            group=Group.objects.get(name="tmpgroup"),
+            model="host",
+            has_read=False,
+        )
+        data = {"name": "src.copy", "objects": True}
+        response = self.assert_post_and_403("/namespaces/src/clone", data)
+        self.assertIn("host", str(response.data))
+        self.assertFalse(Namespace.objects.filter(name="src.copy").exists())
+        self.assert_post("/namespaces/src/clone", {"name": "src.copy"})
+
+        denied.has_read = True
+        denied.save()
+        self.assert_post("/namespaces/src/clone", {**data, "name": "src.full"})
+        self.assertTrue(Host.objects.filter(namespace__name="src.full").exists())
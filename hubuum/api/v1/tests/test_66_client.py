@@ -0,0 +1,84 @@
+"""Test the API client, talking to the test server."""
+import re
+from urllib.parse import urlencode, urlsplit
+
+from rest_framework.test import APIClient
+
+from hubuum.api.v1 import urls
+from hubuum.client import RESOURCES, Client, ClientError
+from hubuum.models.auth import User
+
+from .base import HubuumAPITestCase
+
+
+class ClientSession:
+    """Send the requests of a Client through an APIClient."""
+
+    def __init__(self, client):
+        """Use the given APIClient."""
+        self.client = client
+
+    def request(self, method, url, params=None, json=None, headers=None, **_):
+        """Send a request, as requests.Session.request does."""
+        extra = {
+            f"HTTP_{name.upper().replace('-', '_')}": value
+            for name, value in (headers or {}).items()
+        }
+        send = getattr(self.client, method.lower())
+        path = urlsplit(url).path
+        if method == "GET":
+            return send(path, params, **extra)
+        if params:
+            path = f"{path}?{urlencode(params)}"
+        return send(path, json, format="json", **extra)
+
+
+class APIClientSDK(HubuumAPITestCase):
+    """Test hubuum.client."""
+
+    def test_resources(self):
+        """Test that every collection of the v1 API is a resource of the client."""
+        collections = {
+            str(pattern.pattern)
+            for pattern in urls.urlpatterns
+            if re.fullmatch(r"[a-z_]+/", str(pattern.pattern))
+        }
+        self.assertEqual(collections, set(RESOURCES.values()))
+
+    def test_client(self):
+        """Test listing, getting, creating, updating, and deleting objects."""
+        client = Client("http://testserver", session=ClientSession(self.client))
+        namespace = client.namespaces.create(name="sdk")
+        for number in range(3):
+            client.hosts.create(name=f"web{number}", namespace=namespace["id"])
+
+        self.assertEqual(len(client.hosts.list(page_size=2)), 3)
+        hosts = client.hosts.list(name="web1")
+        self.assertEqual([host["name"] for host in hosts], ["web1"])
+        client.hosts.update("web1", fqdn="web1.example.com")
+        self.assertEqual(client.hosts.get("web1")["fqdn"], "web1.example.com")
+        copy = client.hosts.post("web1", "clone", name="web9")
+        self.assertEqual(copy["name"], "web9")
+
+        client.hosts.delete("web9")
+        with self.assertRaises(ClientError) as error:
+            client.hosts.get("web9")
+        self.assertEqual(error.exception.status, 404)
+        self.assertIn("versions", client.request("GET", "/api/server-info/"))
+
+    def test_login(self):
+        """Test logging in and out."""
+        user = User.objects.create(username="alice")
+        user.set_password("secret")
+        user.save()
+
+        client = Client("http://testserver", session=ClientSession(APIClient()))
+        self.assertTrue(client.login("alice", "secret"))
+        self.assertEqual(client.users.get("me")["username"], "alice")
+
+        token = client.token
+        client.logout()
+        client.token = token
+        with self.assertRaises(ClientError) as error:
+            client.users.get("me")
+        self.assertEqual(error.exception.status, 401)
@@ -0,0 +1,53 @@
+"""Test that the routes of the API work with and without a trailing slash."""
+import re
+
+from django.urls import URLPattern
+
+from hubuum.api.v1 import urls
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host
+
+from .base import HubuumAPITestCase
+
+
+class APITrailingSlashes(HubuumAPITestCase):
+    """Test hubuum.middleware.normalize_path."""
+
+    def setUp(self):
+        """Create a namespace with a host."""
+        super().setUp()
+        self.namespace = Namespace.objects.create(name="slashes")
+        Host.objects.create(name="rack/slot", namespace=self.namespace)
+        Host.objects.create(name="web", namespace=self.namespace)
+
+    def test_every_route(self):
+        """Test that every v1 route responds the same with and without the slash."""
+        self.client.raise_request_exception = False
+        for pattern in urls.urlpatterns:
+            if not isinstance(pattern, URLPattern):
+                continue
+            path = "/api/v1/" + re.sub(r"<[^>]+>", "1", str(pattern.pattern))
+            if path.endswith("/"):
+                path = path[:-1]
+            with self.subTest(path=path):
+                self.assertEqual(
+                    self.client.get(path).status_code,
+                    self.client.get(f"{path}/").status_code,
+                )
+
+    def test_both_forms(self):
+        """Test reading and writing through either form of a path."""
+        for path in ("/hosts", "/hosts/", "/hosts/web", "/hosts/web/"):
+            self.assert_get(path)
+        self.assert_get("/hosts/by-name/slashes/rack/slot/")
+        self.assert_get("/hosts/by-name/slashes/rack/slot")
+        self.assert_get("/api/server-info")
+        self.assert_get("/api/auth/logins")
+
+        self.assert_post("/hosts", {"name": "new", "namespace": self.namespace.id})
+        self.assert_patch("/hosts/new/", {"fqdn": "new.example.com"})
+        self.assert_post("/hosts/new/clone/", {"name": "newer"})
+        self.assert_delete("/hosts/newer/")
+        self.assert_get_and_404("/hosts/nosuchhost/")
+        self.assert_get_and_404("/nosuchroute/")
+        self.assert_get_and_404("/nosuchroute")
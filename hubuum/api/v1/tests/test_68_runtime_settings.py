@@ -0,0 +1,114 @@
+"""Test changing settings at runtime."""
+import logging
+
+from django.conf import settings
+from django.core.cache import cache
+
+from hubuum import runtime
+from hubuum.models.base import RuntimeSetting
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host
+
+from .base import HubuumAPITestCase
+
+
+class APIRuntimeSettings(HubuumAPITestCase):
+    """Test /api/runtime-settings/."""
+
+    def tearDown(self):
+        """Restore the configured settings, as the database is rolled back."""
+        cache.delete(RuntimeSetting.CACHE_KEY)
+        runtime.apply({})
+        return super().tearDown()
+
+    def test_settings(self):
+        """Test changing settings, and changing them back."""
+        namespace = Namespace.objects.create(name="runtime")
+        for number in range(3):
+            Host.objects.create(name=f"web{number}", namespace=namespace)
+        configured = settings.PAGE_SIZE
+
+        response = self.assert_patch("/api/runtime-settings/", {"page_size": 2})
+        self.assertEqual(
+            response.data["page_size"],
+            {"value": 2, "configured": configured, "overridden": True},
+        )
+        self.assert_get_elements("/hosts/", 2)
+
+        cache.delete(RuntimeSetting.CACHE_KEY)
+        runtime.apply({})
+        self.assert_get_elements("/hosts/", 2)
+
+        response = self.assert_patch("/api/runtime-settings/", {"page_size": None})
+        self.assertFalse(response.data["page_size"]["overridden"])
+        self.assertEqual(settings.PAGE_SIZE, configured)
+        self.assert_get_elements("/hosts/", 3)
+        self.assertFalse(RuntimeSetting.objects.exists())
+
+    def test_logging_levels(self):
+        """Test changing the logging level of a source, and of every source."""
+        logger = logging.getLogger("hubuum.database")
+        configured = logger.level
+
+        response = self.assert_patch(
+            "/api/runtime-settings/", {"logging_level_database": "debug"}
+        )
+        self.assertEqual(response.data["logging_level_database"]["value"], "DEBUG")
+        self.assertEqual(logger.level, logging.DEBUG)
+
+        self.assert_patch(
+            "/api/runtime-settings/",
+            {"logging_level": "info", "logging_level_database": None},
+        )
+        self.assertEqual(logger.level, logging.INFO)
+        self.assertEqual(logging.getLogger("hubuum.auth").level, logging.INFO)
+
+        response = self.assert_get("/api/runtime-settings/")
+        self.assertTrue(response.data["logging_level"]["overridden"])
+        self.assertFalse(response.data["logging_level_database"]["overridden"])
+
+        self.assert_patch("/api/runtime-settings/", {"logging_level": None})
+        self.assertEqual(logger.level, configured)
+
+    def test_page_sizes(self):
+        """Test that the page size can not be changed to above the maximum."""
+        maximum = settings.MAX_PAGE_SIZE
+        response = self.assert_patch_and_400(
+            "/api/runtime-settings/", {"page_size": maximum + 1}
+        )
+        self.assertIn("is above max_page_size", response.data["page_size"])
+        self.assert_patch_and_400("/api/runtime-settings/", {"max_page_size": 1})
+        self.assert_patch_and_400(
+            "/api/runtime-settings/", {"page_size": 20, "max_page_size": 10}
+        )
+        self.assertFalse(RuntimeSetting.objects.exists())
+
+        # Settings changed are checked against the overrides of the others.
+        self.assert_patch(
+            "/api/runtime-settings/", {"page_size": 20, "max_page_size": 20}
+        )
+        self.assert_patch_and_400("/api/runtime-settings/", {"page_size": 21})
+        self.assert_patch_and_400("/api/runtime-settings/", {"max_page_size": 19})
+        self.assert_patch(
+            "/api/runtime-settings/", {"page_size": None, "max_page_size": None}
+        )
+        self.assertFalse(RuntimeSetting.objects.exists())
+
+    def test_invalid(self):
+        """Test that invalid settings are refused, and that only admins change them."""
+        for data in (
+            {},
+            [],
+            {"database": "other"},
+            {"page_size": "many"},
+            {"page_size": True},
+            {"logging_level": "loud"},
+            {"page_size": 10, "explain_permissions": "nobody"},
+        ):
+            with self.subTest(data=data):
+                self.assert_patch_and_400("/api/runtime-settings/", data)
+        self.assertFalse(RuntimeSetting.objects.exists())
+
+        self.client = self.get_user_client(username="tmp", groupname="tmpgroup")
+        self.assert_get_and_403("/api/runtime-settings/")
+        self.assert_patch_and_403("/api/runtime-settings/", {"page_size": 10})
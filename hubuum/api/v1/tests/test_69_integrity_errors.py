@@ -0,0 +1,33 @@
+"""Test the responses to violations of the constraints of the database."""
+from unittest import mock
+
+from django.db import transaction
+
+from .base import HubuumAPITestCase
+
+
+class APIIntegrityErrors(HubuumAPITestCase):
+    """Test hubuum.integrity.exception_handler."""
+
+    def test_already_exists(self):
+        """Test that a name taken past validation, ie by a race, gets 409."""
+        self.assert_post("/namespaces/", {"name": "taken"})
+
+        # The request is rolled back to a savepoint, as the server is not within a
+        # transaction when the database refuses the write.
+        with transaction.atomic(), mock.patch(
+            "rest_framework.validators.UniqueValidator.__call__", return_value=None
+        ):
+            response = self.assert_post_and_409("/namespaces/", {"name": "taken"})
+
+        self.assertEqual(
+            response.data,
+            {
+                "detail": "A namespace with name taken already exists. Use another "
+                "name, or change the existing namespace.",
+                "code": "already_exists",
+                "model": "namespace",
+                "fields": {"name": "taken"},
+            },
+        )
+        self.assert_get_elements("/namespaces/?name=taken", 1)
@@ -14,13 +14,42 @@ urlpatterns = [
     path("", include(router.urls)),
     # Users and groups.
     path("users/", views.UserList.as_view()),
+    path(
+        "users/by-external-id/<source>/<path:external_id>",
+        views.UserByExternalID.as_view(),
+    ),
     path("users/<val>", views.UserDetail.as_view()),
+    path("users/<val>/suspend", views.UserSuspend.as_view()),
+    path("users/<val>/reactivate", views.UserReactivate.as_view()),
+    path("users/<val>/logins", views.UserLogins.as_view()),
+    path("users/<val>/tokens", views.UserTokens.as_view()),
+    path("users/<val>/external-ids/", views.UserExternalIDs.as_view()),
+    path("users/<val>/external-ids/<source>", views.UserExternalIDDetail.as_view()),
+    path("provisioning/", views.Provisioning.as_view()),
     path("groups/", views.GroupList.as_view()),
+    path(
+        "groups/by-external-id/<source>/<path:external_id>",
+        views.GroupByExternalID.as_view(),
+    ),
     path("groups/<val>", views.GroupDetail.as_view()),
     path("groups/<val>/members/", views.GroupMembers.as_view()),
     path("groups/<val>/members/<userid>", views.GroupMembersUser.as_view()),
+    path("groups/<val>/managers/", views.GroupManagers.as_view()),
+    path("groups/<val>/managers/<userid>", views.GroupManagersUser.as_view()),
+    path("groups/<val>/tokens", views.GroupTokens.as_view()),
+    path("groups/<val>/external-ids/", views.GroupExternalIDs.as_view()),
+    path("groups/<val>/external-ids/<source>", views.GroupExternalIDDetail.as_view()),
     # Permissions
     path("permissions/", views.PermissionList.as_view()),
+    path("permissions/export", views.PermissionExport.as_view()),
+    path("meta/permissions", views.PermissionCatalog.as_view()),
+    path("permissions/export/link", views.PermissionExportLink.as_view()),
+    path("downloads/<token>", views.SignedDownload.as_view()),
+    path("delete_policies/", views.DeletePolicyList.as_view()),
+    path("delete_policies/effective", views.DeletePolicyEffective.as_view()),
+    path("delete_policies/<val>", views.DeletePolicyDetail.as_view()),
+    path("lifecycles/", views.LifecycleList.as_view()),
+    path("lifecycles/<val>", views.LifecycleDetail.as_view()),
     path(
         "permissions/<val>",
         views.PermissionDetail.as_view(),
@@ -36,20 +65,87 @@ urlpatterns = [
         "namespaces/<val>/groups/<groupid>",
         views.NamespaceMembersGroup.as_view(),
     ),
+    path(
+        "namespaces/<val>/groups/<groupid>/models/",
+        views.NamespaceGroupModelPermissions.as_view(),
+    ),
+    path(
+        "namespaces/<val>/groups/<groupid>/models/<model>",
+        views.NamespaceGroupModelPermission.as_view(),
+    ),
+    path(
+        "namespaces/<val>/aliases/",
+        views.NamespaceAliasList.as_view(),
+    ),
+    path(
+        "namespaces/<val>/aliases/<alias>",
+        views.NamespaceAliasDetail.as_view(),
+    ),
+    path(
+        "namespaces/<val>/activity",
+        views.NamespaceActivityFeed.as_view(),
+    ),
+    path(
+        "namespaces/<val>/changes",
+        views.NamespaceChanges.as_view(),
+    ),
+    path(
+        "namespaces/<val>/graph",
+        views.NamespaceGraph.as_view(),
+    ),
+    path(
+        "namespaces/<val>/deletion",
+        views.NamespaceDeletion.as_view(),
+    ),
+    path(
+        "namespaces/<val>/clone",
+        views.NamespaceClone.as_view(),
+    ),
+    path(
+        "namespaces/<val>/cloning",
+        views.NamespaceCloning.as_view(),
+    ),
+    # Schema registry API.
+    path("schemas/", views.SchemaDefinitionList.as_view()),
+    path("schemas/dangling", views.SchemaDefinitionDangling.as_view()),
+    path("schemas/<val>", views.SchemaDefinitionDetail.as_view()),
     # Extension API.
     path("extensions/", views.ExtensionList.as_view()),
     path(
         "extensions/<val>",
         views.ExtensionDetail.as_view(),
     ),
+    path(
+        "extensions/<val>/migrate_schema",
+        views.ExtensionSchemaMigration.as_view(),
+    ),
+    path(
+        "extensions/<val>/validation",
+        views.ExtensionValidation.as_view(),
+    ),
+    path(
+        "extensions/<val>/aggregate",
+        views.ExtensionAggregation.as_view(),
+    ),
     path("extension_data/", views.ExtensionDataList.as_view()),
     path(
         "extension_data/<val>",
         views.ExtensionDataDetail.as_view(),
     ),
+    path(
+        "extension_data/<val>/revisions/",
+        views.ExtensionDataRevisions.as_view(),
+    ),
+    path(
+        "extension_data/<val>/diff",
+        views.ExtensionDataDiff.as_view(),
+    ),
     # Object models and their endpoints.
     path("hosts/", views.HostList.as_view()),
     path("hosts/<val>", views.HostDetail.as_view()),
+    path("hosts/<val>/children/", views.HostChildren.as_view()),
+    path("hosts/<val>/ancestors/", views.HostAncestors.as_view()),
+    path("hosts/<val>/clone", views.HostClone.as_view()),
     path("hosttypes/", views.HostTypeList.as_view()),
     path("hosttypes/<val>", views.HostTypeDetail.as_view()),
     path("rooms/", views.RoomList.as_view()),
@@ -71,3 +167,27 @@ urlpatterns = [
         views.PurchaseDocumentDetail.as_view(),
     ),
 ]
+
+# Addressing by name, tags, references, traversals, related models, and statistics
+# for the object models.
+for prefix, list_view, detail in (
+    ("hosts", views.HostList, views.HostDetail),
+    ("hosttypes", views.HostTypeList, views.HostTypeDetail),
+    ("rooms", views.RoomList, views.RoomDetail),
+    ("jacks", views.JackList, views.JackDetail),
+    ("persons", views.PersonList, views.PersonDetail),
+    ("vendors", views.VendorList, views.VendorDetail),
+    ("pos", views.PurchaseOrderList, views.PurchaseOrderDetail),
+    ("purchasedocuments", views.PurchaseDocumentList, views.PurchaseDocumentDetail),
+):
+    tag_list, tag_detail = views.tag_views(detail)
+    urlpatterns += [
+        path(f"{prefix}/by-name/<namespace>/<path:name>", views.by_name_view(detail)),
+        path(f"{prefix}/<val>/tags/", tag_list),
+        path(f"{prefix}/<val>/tags/<tag>", tag_detail),
+        path(f"{prefix}/<val>/references", views.references_view(detail)),
+        path(f"{prefix}/<val>/traverse", views.traverse_view(detail)),
+        path(f"{prefix}/<val>/relations", views.relate_view(detail)),
+        path(f"related/{prefix}", views.related_view(list_view)),
+        path(f"stats/{prefix}", views.statistics_view(list_view)),
+    ]
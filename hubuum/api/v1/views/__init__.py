@@ -0,0 +1,98 @@
+"""Versioned (v1) views for the hubuum models.
+
+The views are grouped by what they are for, see the modules of the package.
+"""
+from .admin import (
+    DOWNLOAD_SALT,
+    DeletePolicyDetail,
+    DeletePolicyEffective,
+    DeletePolicyList,
+    LifecycleDetail,
+    LifecycleList,
+    PermissionCatalog,
+    PermissionDetail,
+    PermissionExport,
+    PermissionExportLink,
+    PermissionList,
+    SignedDownload,
+)
+from .auth import (
+    GroupByExternalID,
+    GroupDetail,
+    GroupExternalIDDetail,
+    GroupExternalIDs,
+    GroupList,
+    GroupManagers,
+    GroupManagersUser,
+    GroupMembers,
+    GroupMembersUser,
+    GroupTokens,
+    Provisioning,
+    UserByExternalID,
+    UserDetail,
+    UserExternalIDDetail,
+    UserExternalIDs,
+    UserList,
+    UserLogins,
+    UserReactivate,
+    UserSuspend,
+    UserTokens,
+)
+from .extensions import (
+    ExtensionAggregation,
+    ExtensionDataDetail,
+    ExtensionDataDiff,
+    ExtensionDataList,
+    ExtensionDataRevisions,
+    ExtensionDetail,
+    ExtensionList,
+    ExtensionSchemaMigration,
+    ExtensionValidation,
+    SchemaDefinitionDangling,
+    SchemaDefinitionDetail,
+    SchemaDefinitionList,
+)
+from .namespaces import (
+    NamespaceActivityFeed,
+    NamespaceAliasDetail,
+    NamespaceAliasList,
+    NamespaceChanges,
+    NamespaceClone,
+    NamespaceCloning,
+    NamespaceDeletion,
+    NamespaceDetail,
+    NamespaceGraph,
+    NamespaceGroupModelPermission,
+    NamespaceGroupModelPermissions,
+    NamespaceList,
+    NamespaceMembers,
+    NamespaceMembersGroup,
+)
+from .objects import (
+    HostAncestors,
+    HostChildren,
+    HostClone,
+    HostDetail,
+    HostList,
+    HostTypeDetail,
+    HostTypeList,
+    JackDetail,
+    JackList,
+    PersonDetail,
+    PersonList,
+    PurchaseDocumentDetail,
+    PurchaseDocumentList,
+    PurchaseOrderDetail,
+    PurchaseOrderList,
+    RoomDetail,
+    RoomList,
+    VendorDetail,
+    VendorList,
+    by_name_view,
+    references_view,
+    relate_view,
+    related_view,
+    statistics_view,
+    tag_views,
+    traverse_view,
+)
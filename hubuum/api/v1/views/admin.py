@@ -0,0 +1,279 @@
+"""Views for delete policies, lifecycles, and permissions."""
+from django.conf import settings
+from django.contrib.auth.models import Group
+from django.core import signing
+from django.http import StreamingHttpResponse
+from rest_framework.exceptions import NotFound, ParseError, PermissionDenied
+from rest_framework.permissions import AllowAny
+from rest_framework.schemas.openapi import AutoSchema
+from rest_framework.settings import api_settings
+from rest_framework.views import APIView, Response
+
+from hubuum.filters import PermissionFilterSet
+from hubuum.models.auth import User
+from hubuum.models.base import DeletePolicy, Lifecycle, namespaced_models
+from hubuum.models.namespace import ModelPermission, Namespace, Permission
+from hubuum.permissions import (
+    OPERATION_MODELS,
+    OPERATIONS,
+    IsAuthenticatedAndReadOnly,
+    IsSuperOrAdminOrReadOnly,
+    NameSpace,
+    fully_qualified_operations,
+)
+from hubuum.renderers import CSVRenderer, csv_lines
+from hubuum.tools import get_object, sign_expiring, unsign_expiring
+
+from ..serializers import (
+    DeletePolicySerializer,
+    LifecycleSerializer,
+    PermissionSerializer,
+)
+from .base import HubuumDetail, HubuumList
+from .objects import readable
+
+
+# The salt for signing download links, see SignedDownload.
+DOWNLOAD_SALT = "hubuum.downloads"
+
+
+class DeletePolicyList(HubuumList):
+    """Get: List delete policies. Post: Add a delete policy (admins only)."""
+
+    queryset = DeletePolicy.objects.all()
+    serializer_class = DeletePolicySerializer
+    permission_classes = (IsSuperOrAdminOrReadOnly,)
+
+
+class DeletePolicyDetail(HubuumDetail):
+    """Get, Patch, or Destroy a delete policy (admins only).
+
+    Destroying a policy reverts the relation to its default policy.
+    """
+
+    queryset = DeletePolicy.objects.all()
+    serializer_class = DeletePolicySerializer
+    lookup_fields = ("id", "relation")
+    permission_classes = (IsSuperOrAdminOrReadOnly,)
+
+
+class LifecycleList(HubuumList):
+    """Get: List lifecycles. Post: Add the lifecycle of a model (admins only)."""
+
+    queryset = Lifecycle.objects.all()
+    serializer_class = LifecycleSerializer
+    permission_classes = (IsSuperOrAdminOrReadOnly,)
+
+
+class LifecycleDetail(HubuumDetail):
+    """Get, Patch, or Destroy the lifecycle of a model (admins only).
+
+    Destroying a lifecycle lets the objects of the model be in any state.
+    """
+
+    queryset = Lifecycle.objects.all()
+    serializer_class = LifecycleSerializer
+    lookup_fields = ("id", "model")
+    permission_classes = (IsSuperOrAdminOrReadOnly,)
+
+
+class DeletePolicyEffective(APIView):
+    """List the effective delete policy of every relation, including defaults."""
+
+    permission_classes = (IsAuthenticatedAndReadOnly,)
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Effective delete policies",
+        operation_id_base="DeletePolicyEffective",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get the policies, as a map of relations to policies."""
+        return Response(dict(sorted(DeletePolicy.policies().items())))
+
+
+class PermissionList(HubuumList):
+    """Get: List permissions. Post: Add permission."""
+
+    queryset = Permission.objects.all()
+    serializer_class = PermissionSerializer
+    filterset_class = PermissionFilterSet
+
+
+class PermissionDetail(HubuumDetail):
+    """Get, Patch, or Destroy a permission."""
+
+    queryset = Permission.objects.all()
+    serializer_class = PermissionSerializer
+
+
+class PermissionCatalog(APIView):
+    """The permissions groups may be granted for namespaces, and what they allow.
+
+    Every permission is listed with its operation, its description, the models it
+    applies to, and whether it may be refined per model (see ModelPermission).
+    """
+
+    permission_classes = (IsAuthenticatedAndReadOnly,)
+
+    def get(self, request, *args, **kwargs):
+        """Get the catalog of permissions."""
+        namespaced = sorted(model.__name__.lower() for model in namespaced_models())
+        return Response(
+            [
+                {
+                    "name": f"has_{operation}",
+                    "operation": operation,
+                    "description": description,
+                    "models": list(OPERATION_MODELS.get(operation, namespaced)),
+                    "per_model": f"has_{operation}" in ModelPermission.operations,
+                }
+                for operation, description in OPERATIONS.items()
+            ]
+        )
+
+
+class PermissionExport(APIView):
+    """Export the permission matrix, for every group and namespace."""
+
+    permission_classes = (NameSpace,)
+    renderer_classes = (*api_settings.DEFAULT_RENDERER_CLASSES, CSVRenderer)
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Permission export",
+        operation_id_base="PermissionExport",
+    )
+
+    def _scope(self, user, params):
+        """Get the namespaces and groups to export, from ?namespace= and ?group=.
+
+        Namespaces the user can't read are left out, unless the user is an admin.
+        """
+        namespaces = Namespace.objects.order_by("name")
+        groups = Group.objects.order_by("name")
+        if "namespace" in params:
+            namespace = get_object(Namespace, params["namespace"], ["id", "name"])
+            namespaces = namespaces.filter(pk=namespace.pk)
+        if "group" in params:
+            group = get_object(Group, params["group"], ["id", "name"])
+            groups = groups.filter(pk=group.pk)
+        if not user.is_admin():
+            readable = user.namespaces_where("has_read")
+            namespaces = namespaces.filter(id__in=readable)
+
+        return list(namespaces), groups
+
+    def _matrix(self, namespaces, groups):
+        """Yield the permissions of every group in every namespace, as rows."""
+        permissions = {
+            (permission.group_id, permission.namespace_id): permission
+            for permission in Permission.objects.filter(
+                namespace__in=namespaces, group__in=groups
+            )
+        }
+
+        for group in groups:
+            for namespace in namespaces:
+                permission = permissions.get((group.id, namespace.id))
+                row = {"group": group.name, "namespace": namespace.name}
+                for operation in fully_qualified_operations():
+                    row[operation] = bool(permission and getattr(permission, operation))
+                yield row
+
+    def export(self, user, params):
+        """Stream the permission matrix visible to user as CSV."""
+        fieldnames = ["group", "namespace", *fully_qualified_operations()]
+        response = StreamingHttpResponse(
+            csv_lines(fieldnames, self._matrix(*self._scope(user, params))),
+            content_type="text/csv; charset=utf-8",
+        )
+        response["Content-Disposition"] = 'attachment; filename="permissions.csv"'
+        return response
+
+    def get(self, request, *args, **kwargs):
+        """Get the permission matrix, optionally for a ?namespace= or a ?group=.
+
+        With ?format=csv, the matrix is streamed as CSV.
+        """
+        if request.accepted_renderer.format == "csv":
+            return self.export(request.user, request.query_params)
+
+        scope = self._scope(request.user, request.query_params)
+        return Response(list(self._matrix(*scope)))
+
+
+class PermissionExportLink(PermissionExport):
+    """Create a signed link to the CSV export of the permission matrix.
+
+    See SignedDownload.
+    """
+
+    export_name = "permissions"
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Permission export link",
+        operation_id_base="PermissionExportLink",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get a link to the CSV export, valid for ?expires_in=<seconds> (600).
+
+        The link exports what the user can see, with the same ?namespace= and
+        ?group= filters as the export itself.
+        """
+        expires_in = request.query_params.get("expires_in", "600")
+        max_age = settings.DOWNLOAD_LINK_MAX_AGE
+        if not expires_in.isdigit() or not 0 < int(expires_in) <= max_age:
+            raise ParseError(
+                detail=f"'expires_in' must be between 1 and {max_age} seconds."
+            )
+
+        params = {
+            key: request.query_params[key]
+            for key in ["namespace", "group"]
+            if key in request.query_params
+        }
+        # Fail early for filters that do not resolve.
+        self._scope(request.user, params)
+
+        payload = {
+            "export": self.export_name,
+            "user": request.user.id,
+            "params": params,
+        }
+        token, expires = sign_expiring(payload, int(expires_in), DOWNLOAD_SALT)
+        url = request.build_absolute_uri(f"/api/v1/downloads/{token}")
+        return Response({"url": url, "expires": expires})
+
+
+class SignedDownload(APIView):
+    """Download an export through a signed, expiring link.
+
+    The link itself is the credential, so no authentication is required. The
+    export is performed on behalf of the user who created the link, and the
+    link stops working if that user is suspended.
+    """
+
+    authentication_classes = ()
+    permission_classes = (AllowAny,)
+    exports = {"permissions": PermissionExport}
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Signed download",
+        operation_id_base="SignedDownload",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Verify the link and stream the export."""
+        try:
+            payload = unsign_expiring(kwargs["token"], DOWNLOAD_SALT)
+        except signing.SignatureExpired as exc:
+            raise PermissionDenied(detail="The link has expired.") from exc
+        except signing.BadSignature as exc:
+            raise NotFound() from exc
+
+        user = User.objects.filter(pk=payload["user"], is_active=True).first()
+        if user is None or payload["export"] not in self.exports:
+            raise NotFound()
+
+        return self.exports[payload["export"]]().export(user, payload["params"])
@@ -0,0 +1,581 @@
+"""Views for users, groups, their tokens and external identities."""
+from django.contrib.auth.models import Group
+from rest_framework import generics, status
+from rest_framework.exceptions import MethodNotAllowed, NotFound, ParseError
+from rest_framework.schemas.openapi import AutoSchema
+from rest_framework.views import APIView, Response
+
+from hubuum import provisioning
+from hubuum.exceptions import Conflict
+from hubuum.filters import GroupFilterSet, UserFilterSet
+from hubuum.models.auth import GroupManager, TokenMetadata, User, get_user
+from hubuum.permissions import (
+    GroupMembership,
+    IsSuperOrAdmin,
+    IsSuperOrAdminOrReadOnly,
+    IsSuperOrAdminOrSelf,
+)
+
+from ..serializers import (
+    GroupExternalIDSerializer,
+    GroupSerializer,
+    LoginEventSerializer,
+    UserExternalIDSerializer,
+    UserSerializer,
+)
+from .base import (
+    HubuumDetail,
+    HubuumList,
+    MultipleFieldLookupORMixin,
+    RelationListMixin,
+    UserLookupMixin,
+)
+
+
+class UserList(HubuumList):
+    """Get: List users. Post: Add user."""
+
+    queryset = User.objects.all()
+    serializer_class = UserSerializer
+    permission_classes = (IsSuperOrAdminOrReadOnly,)
+    filterset_class = UserFilterSet
+
+
+class UserDetail(UserLookupMixin, HubuumDetail):
+    """Get, Patch, or Destroy a user.
+
+    Every user may read every user. Users may change their own names and email,
+    ie with /users/me, everything else is for admins.
+    """
+
+    queryset = User.objects.all()
+    serializer_class = UserSerializer
+    lookup_fields = ("id", "username", "email")
+    permission_classes = (IsSuperOrAdminOrSelf,)
+    readable_by_all = True
+    self_methods = ("PATCH",)
+    self_fields = ("first_name", "last_name", "email")
+
+
+class UserSuspend(
+    UserLookupMixin,
+    generics.RetrieveAPIView,
+):
+    """Suspend a user, disabling logins and all existing tokens for the user."""
+
+    permission_classes = (IsSuperOrAdmin,)
+    lookup_fields = ("id", "username", "email")
+    serializer_class = UserSerializer
+    queryset = User.objects.all()
+    active = False
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="User suspension",
+        operation_id_base="UserSuspend",
+    )
+
+    def post(self, request, *args, **kwargs):
+        """Set the active status of the user."""
+        user = self.get_object()
+        if not self.active and user == request.user:
+            raise ParseError(detail="Users can not suspend themselves.")
+
+        user.set_active(self.active)
+        return Response(UserSerializer(user).data)
+
+
+class UserLogins(
+    UserLookupMixin,
+    generics.RetrieveAPIView,
+):
+    """List the logins of a user, newest first (admins, and the user).
+
+    Users find their own logins here (/users/me/logins), or at /api/auth/logins/.
+    """
+
+    permission_classes = (IsSuperOrAdminOrSelf,)
+    lookup_fields = ("id", "username", "email")
+    serializer_class = LoginEventSerializer
+    queryset = User.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="User logins",
+        operation_id_base="UserLogins",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get the login history of the user."""
+        user = self.get_object()
+        return Response(LoginEventSerializer(user.login_events.all(), many=True).data)
+
+
+class UserTokens(
+    UserLookupMixin,
+    generics.RetrieveAPIView,
+):
+    """List, or revoke, the active tokens of a user (admins, and the user).
+
+    Revoking the tokens logs the user out everywhere, ie when credentials leak.
+    Users manage their own tokens here (/users/me/tokens), or at /api/auth/tokens/.
+    """
+
+    permission_classes = (IsSuperOrAdminOrSelf,)
+    self_methods = ("GET", "HEAD", "OPTIONS", "DELETE")
+    lookup_fields = ("id", "username", "email")
+    queryset = User.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="User tokens",
+        operation_id_base="UserTokens",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get the active tokens of the user, without the tokens themselves."""
+        tokens = self.get_object().active_tokens()
+        return Response([TokenMetadata.describe(token) for token in tokens])
+
+    def delete(self, request, *args, **kwargs):
+        """Revoke every token of the user, returns the number revoked."""
+        return Response({"revoked": self.get_object().revoke_tokens()})
+
+
+class GroupTokens(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List, or revoke, the active tokens of the members of a group (admins only)."""
+
+    permission_classes = (IsSuperOrAdmin,)
+    lookup_fields = ("id", "name")
+    queryset = Group.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Group tokens",
+        operation_id_base="GroupTokens",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get the active tokens of the members, by username."""
+        members = User.objects.filter(groups=self.get_object()).order_by("username")
+        return Response(
+            {
+                member.username: [
+                    TokenMetadata.describe(token) for token in member.active_tokens()
+                ]
+                for member in members
+            }
+        )
+
+    def delete(self, request, *args, **kwargs):
+        """Revoke every token of the members, returns the number revoked by user."""
+        group = self.get_object()
+        members = User.objects.filter(groups=group).order_by("username")
+        return Response(
+            {
+                "revoked": {
+                    member.username: member.revoke_tokens(group=group)
+                    for member in members
+                }
+            }
+        )
+
+
+class UserReactivate(UserSuspend):
+    """Reactivate a suspended user."""
+
+    active = True
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="User reactivation",
+        operation_id_base="UserReactivate",
+    )
+
+
+class Provisioning(APIView):
+    """Create, update, and deactivate many users and groups at once."""
+
+    permission_classes = (IsSuperOrAdmin,)
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="User and group provisioning",
+        operation_id_base="Provisioning",
+    )
+
+    def post(self, request, *args, **kwargs):
+        """Apply the groups and users given, see hubuum.provisioning.
+
+        Every entry is applied on its own, and the result of every entry is
+        reported, including conflicts and invalid entries.
+        """
+        try:
+            return Response(provisioning.provision(request.data))
+        except ValueError as ex:
+            raise ParseError(detail=str(ex)) from ex
+
+
+class GroupList(HubuumList):
+    """Get: List groups. Post: Add group."""
+
+    queryset = Group.objects.all().order_by("id")
+    serializer_class = GroupSerializer
+    permission_classes = (IsSuperOrAdminOrReadOnly,)
+    filterset_class = GroupFilterSet
+
+
+class GroupDetail(HubuumDetail):
+    """Get, Patch, or Destroy a group."""
+
+    queryset = Group.objects.all()
+    serializer_class = GroupSerializer
+    lookup_fields = ("id", "name")
+    permission_classes = (IsSuperOrAdminOrReadOnly,)
+
+
+class GroupMembers(
+    RelationListMixin,
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List group members, paginated and sortable (see RelationListMixin)."""
+
+    permission_classes = (IsSuperOrAdminOrReadOnly,)
+    lookup_fields = ("id", "name")
+    ordering_fields = ("id", "username", "email", "date_joined", "last_login")
+    serializer_class = UserSerializer
+    queryset = Group.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Group memberships",
+        operation_id_base="Groupmemberships",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get all users in the group."""
+        group_object = self.get_object()
+        users = User.objects.filter(groups=group_object)
+
+        return self.relation_response(users, UserSerializer)
+
+
+class GroupMembersUser(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveUpdateDestroyAPIView,
+):
+    """Modify users in groups.
+
+    Admins, and the managers of the group, may add and remove members.
+    """
+
+    permission_classes = (GroupMembership,)
+    lookup_fields = ("id", "name")
+    serializer_class = UserSerializer
+    queryset = Group.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Group memberships users",
+        operation_id_base="Groupmembershipsusers",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get user in group."""
+        group = self.get_object()
+
+        user = get_user(kwargs["userid"])
+        if user:
+            if user.groups.filter(id=group.id).exists():
+                return Response(UserSerializer(user).data)
+
+        raise NotFound()
+
+    def patch(self, request, *args, **kwargs):
+        """Disallow patch."""
+        raise MethodNotAllowed(request.method)
+
+    def post(self, request, *args, **kwargs):
+        """Add a user to a group."""
+        group = self.get_object()
+        user = get_user(kwargs["userid"])
+
+        if user.groups.filter(id=group.id).exists():
+            return Response(
+                f"User {user.id} is already a member of group {group.id}",
+                status=status.HTTP_200_OK,
+            )
+
+        user.groups.add(group)
+        user.save()
+        return Response(UserSerializer(user).data, status=status.HTTP_201_CREATED)
+
+    def delete(self, request, *args, **kwargs):
+        """Delete a user from a group."""
+        group = self.get_object()
+        user = get_user(kwargs["userid"])
+
+        if user.groups.filter(id=group.id).exists():
+            user.groups.remove(group)
+            user.save()
+
+        return Response(status=status.HTTP_204_NO_CONTENT)
+
+
+class GroupManagers(
+    RelationListMixin,
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List the managers of a group, paginated and sortable (see GroupMembers)."""
+
+    permission_classes = (IsSuperOrAdminOrReadOnly,)
+    lookup_fields = ("id", "name")
+    ordering_fields = GroupMembers.ordering_fields
+    serializer_class = UserSerializer
+    queryset = Group.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Group managers",
+        operation_id_base="Groupmanagers",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get all users managing the group."""
+        group_object = self.get_object()
+        users = User.objects.filter(managed_groups__group=group_object)
+
+        return self.relation_response(users, UserSerializer)
+
+
+class GroupManagersUser(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveUpdateDestroyAPIView,
+):
+    """Appoint or dismiss managers of a group. Managers may modify its members."""
+
+    permission_classes = (IsSuperOrAdminOrReadOnly,)
+    lookup_fields = ("id", "name")
+    serializer_class = UserSerializer
+    queryset = Group.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Group managers users",
+        operation_id_base="Groupmanagersusers",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get a manager of the group."""
+        group = self.get_object()
+        user = get_user(kwargs["userid"])
+
+        if GroupManager.objects.filter(group=group, user=user).exists():
+            return Response(UserSerializer(user).data)
+
+        raise NotFound()
+
+    def patch(self, request, *args, **kwargs):
+        """Disallow patch."""
+        raise MethodNotAllowed(request.method)
+
+    def post(self, request, *args, **kwargs):
+        """Appoint a user as a manager of the group."""
+        group = self.get_object()
+        user = get_user(kwargs["userid"])
+
+        _, created = GroupManager.objects.get_or_create(group=group, user=user)
+        if not created:
+            return Response(
+                f"User {user.id} is already a manager of group {group.id}",
+                status=status.HTTP_200_OK,
+            )
+
+        return Response(UserSerializer(user).data, status=status.HTTP_201_CREATED)
+
+    def delete(self, request, *args, **kwargs):
+        """Dismiss a user as a manager of the group."""
+        group = self.get_object()
+        user = get_user(kwargs["userid"])
+        GroupManager.objects.filter(group=group, user=user).delete()
+
+        return Response(status=status.HTTP_204_NO_CONTENT)
+
+
+class ExternalIDList(MultipleFieldLookupORMixin, generics.RetrieveAPIView):
+    """List the identities of a user or group in external sources.
+
+    Subclasses set the queryset (of users or groups) and serializer_class (of
+    their identities).
+    """
+
+    permission_classes = (IsSuperOrAdminOrReadOnly,)
+
+    def get(self, request, *args, **kwargs):
+        """Get the identities, one per source."""
+        owner = self.get_object()
+        return Response(self.get_serializer(owner.external_ids.all(), many=True).data)
+
+
+class ExternalIDDetail(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveUpdateDestroyAPIView,
+):
+    """Set or remove the identity of a user or group in an external source.
+
+    Subclasses set owner_field (the name of the user or group field of the
+    identities), in addition to what ExternalIDList needs.
+    """
+
+    permission_classes = (IsSuperOrAdminOrReadOnly,)
+    owner_field = None
+
+    def _identity(self, owner):
+        """Return the identity of the owner in the source, if any."""
+        return owner.external_ids.filter(source=self.kwargs["source"]).first()
+
+    def get(self, request, *args, **kwargs):
+        """Get the identity in the source."""
+        identity = self._identity(self.get_object())
+        if identity is None:
+            raise NotFound()
+        return Response(self.get_serializer(identity).data)
+
+    def patch(self, request, *args, **kwargs):
+        """Disallow patch."""
+        raise MethodNotAllowed(request.method)
+
+    def put(self, request, *args, **kwargs):
+        """Set the identity in the source, {"external_id": <id>}.
+
+        raises: Conflict if the identity belongs to another user or group
+        """
+        owner = self.get_object()
+        if not isinstance(request.data, dict):
+            raise ParseError(detail='Expected {"external_id": <id>}.')
+
+        identity = self._identity(owner)
+        serializer = self.get_serializer(
+            identity, data={**request.data, "source": kwargs["source"]}
+        )
+        taken = serializer.Meta.model.objects.filter(
+            source=kwargs["source"], external_id=request.data.get("external_id")
+        ).exclude(**{self.owner_field: owner})
+        if taken.exists():
+            raise Conflict(
+                detail=f"The identity belongs to another {self.owner_field}."
+            )
+
+        serializer.is_valid(raise_exception=True)
+        serializer.save(**{self.owner_field: owner})
+        return Response(
+            serializer.data,
+            status=status.HTTP_200_OK if identity else status.HTTP_201_CREATED,
+        )
+
+    def delete(self, request, *args, **kwargs):
+        """Remove the identity in the source."""
+        owner = self.get_object()
+        owner.external_ids.filter(source=kwargs["source"]).delete()
+        return Response(status=status.HTTP_204_NO_CONTENT)
+
+
+class ByExternalID(generics.RetrieveAPIView):
+    """Get a user or group by its identity in an external source.
+
+    Subclasses set the queryset and serializer_class (of users or groups).
+    """
+
+    permission_classes = (IsSuperOrAdminOrReadOnly,)
+
+    def get_object(self):
+        """Find the object with the identity.
+
+        raises: 404 if not found.
+        """
+        obj = (
+            self.get_queryset()
+            .filter(
+                external_ids__source=self.kwargs["source"],
+                external_ids__external_id=self.kwargs["external_id"],
+            )
+            .first()
+        )
+        if obj is None:
+            raise NotFound()
+
+        self.check_object_permissions(self.request, obj)
+        return obj
+
+
+class UserExternalIDs(ExternalIDList):
+    """List the identities of a user in external sources."""
+
+    lookup_fields = ("id", "username", "email")
+    serializer_class = UserExternalIDSerializer
+    queryset = User.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="User external IDs",
+        operation_id_base="UserExternalIDs",
+    )
+
+
+class UserExternalIDDetail(ExternalIDDetail):
+    """Set or remove the identity of a user in an external source."""
+
+    lookup_fields = ("id", "username", "email")
+    serializer_class = UserExternalIDSerializer
+    queryset = User.objects.all()
+    owner_field = "user"
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="User external ID",
+        operation_id_base="UserExternalID",
+    )
+
+
+class UserByExternalID(ByExternalID):
+    """Get a user by its identity in an external source."""
+
+    serializer_class = UserSerializer
+    queryset = User.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="User by external ID",
+        operation_id_base="UserByExternalID",
+    )
+
+
+class GroupExternalIDs(ExternalIDList):
+    """List the identities of a group in external sources."""
+
+    lookup_fields = ("id", "name")
+    serializer_class = GroupExternalIDSerializer
+    queryset = Group.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Group external IDs",
+        operation_id_base="GroupExternalIDs",
+    )
+
+
+class GroupExternalIDDetail(ExternalIDDetail):
+    """Set or remove the identity of a group in an external source."""
+
+    lookup_fields = ("id", "name")
+    serializer_class = GroupExternalIDSerializer
+    queryset = Group.objects.all()
+    owner_field = "group"
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Group external ID",
+        operation_id_base="GroupExternalID",
+    )
+
+
+class GroupByExternalID(ByExternalID):
+    """Get a group by its identity in an external source."""
+
+    serializer_class = GroupSerializer
+    queryset = Group.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Group by external ID",
+        operation_id_base="GroupByExternalID",
+    )
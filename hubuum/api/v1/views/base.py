@@ -0,0 +1,521 @@
+"""Shared functionality for the views of the hubuum models."""
+import hashlib
+import json
+
+import structlog
+from django.conf import settings
+from django.core.exceptions import MultipleObjectsReturned
+from django.db import transaction
+from django.http import StreamingHttpResponse
+from django.utils.cache import get_conditional_response
+from django.utils.http import http_date, quote_etag
+from rest_framework import generics
+from rest_framework.exceptions import (
+    NotFound,
+    ParseError,
+    PermissionDenied,
+    ValidationError,
+)
+from rest_framework.settings import api_settings
+from rest_framework.utils.encoders import JSONEncoder
+from rest_framework.views import Response
+
+from hubuum.actor import get_actor
+from hubuum.exceptions import Ambiguous
+from hubuum.models.auth import IdempotencyKey, User
+from hubuum.models.base import NamespacedHubuumModel
+from hubuum.models.namespace import Namespace
+from hubuum.permissions import NameSpace
+from hubuum.renderers import NDJSONRenderer, ndjson_lines
+from hubuum.tools import get_object
+
+
+class LoggingMixin:
+    """Mixin to log object modifications (create, update, and delete).
+
+    Also logs the user who performed the action.
+    """
+
+    def _log(self, operation, model, user, instance):
+        """Write the log string."""
+        logger = structlog.get_logger("hubuum.api.object")
+        logger.info(
+            operation,
+            model=model,
+            user=str(user),
+            instance=instance.id,
+            actor=get_actor().as_dict(),
+        )
+
+    def perform_create(self, serializer):
+        """Log creates."""
+        super().perform_create(serializer)
+        instance = serializer.instance
+        if instance:
+            self._log(
+                "created", instance.__class__.__name__, self.request.user, instance
+            )
+
+    def perform_update(self, serializer):
+        """Log updates."""
+        super().perform_update(serializer)
+        instance = serializer.instance
+        if instance:
+            self._log(
+                "updated", instance.__class__.__name__, self.request.user, instance
+            )
+
+    def perform_destroy(self, instance):
+        """Log deletes.
+
+        Objects in namespaces, and namespaces, are deleted as the user, so the delete
+        policies only change objects the user may change, see apply_delete_policies.
+        """
+        self._log("deleted", instance.__class__.__name__, self.request.user, instance)
+        if isinstance(instance, (Namespace, NamespacedHubuumModel)):
+            instance.delete(user=self.request.user)
+        else:
+            super().perform_destroy(instance)
+
+
+class NamespaceLimitsMixin:
+    """Mixin to check creates and updates of objects against the namespace limits.
+
+    See Namespace.check_write_limits, writes over the limits get 413 or 429.
+    """
+
+    def _check_write_limits(self, serializer):
+        """Check the write against the limits of the namespace of the object."""
+        if not issubclass(serializer.Meta.model, NamespacedHubuumModel):
+            return
+
+        data = serializer.validated_data
+        namespace = data.get("namespace") or serializer.instance.namespace
+        namespace.check_write_limits(data.get("json_data"))
+
+    def perform_create(self, serializer):
+        """Check creates."""
+        self._check_write_limits(serializer)
+        super().perform_create(serializer)
+
+    def perform_update(self, serializer):
+        """Check updates."""
+        self._check_write_limits(serializer)
+        super().perform_update(serializer)
+
+
+class IdempotentReplay(Exception):
+    """Raised to answer a request with the stored response for its Idempotency-Key."""
+
+    def __init__(self, record):
+        """Initialize the exception with the key and its stored response."""
+        super().__init__(record.key)
+        self.record = record
+
+
+class IdempotencyMixin:
+    """Mixin to support the Idempotency-Key header on create (POST) requests.
+
+    The response to the first request with a key is stored, and requests retried
+    with the same key and data get it again, with Idempotent-Replayed set, rather
+    than creating the object again. Responses that may differ on a retry, ie 429,
+    release the key instead. See IdempotencyKey.complete.
+    """
+
+    idempotency_record = None
+
+    def initial(self, request, *args, **kwargs):
+        """Claim the Idempotency-Key given, once the request is authenticated."""
+        super().initial(request, *args, **kwargs)
+        key = request.headers.get("Idempotency-Key")
+        if request.method != "POST" or key is None:
+            return
+
+        if not key or len(key) > 255:
+            raise ParseError(detail="The Idempotency-Key must be 1 to 255 characters.")
+
+        content = json.dumps([request.path, request.data], sort_keys=True, default=str)
+        request_hash = hashlib.sha256(content.encode("utf-8")).hexdigest()
+        record, claimed = IdempotencyKey.claim(request.user, key, request_hash)
+        if not claimed:
+            raise IdempotentReplay(record)
+        self.idempotency_record = record
+
+    def handle_exception(self, exc):
+        """Replay stored responses, and release the key on unhandled errors."""
+        if isinstance(exc, IdempotentReplay):
+            headers = {"Idempotent-Replayed": "true"}
+            return Response(exc.record.response, exc.record.status, headers=headers)
+
+        try:
+            return super().handle_exception(exc)
+        except Exception:
+            if self.idempotency_record is not None:
+                self.idempotency_record.delete()
+            raise
+
+    def finalize_response(self, request, response, *args, **kwargs):
+        """Store the response for the Idempotency-Key claimed."""
+        if self.idempotency_record is not None:
+            self.idempotency_record.complete(response.status_code, response.data)
+            self.idempotency_record = None
+        return super().finalize_response(request, response, *args, **kwargs)
+
+
+class VisibleNamespacesMixin:  # pylint: disable=too-few-public-methods
+    """Mixin to hide namespaces being deleted, and the objects in them.
+
+    See Namespace.request_deletion, such namespaces and objects get 404.
+    """
+
+    def get_queryset(self):
+        """Leave out namespaces being deleted, and their objects."""
+        return Namespace.visible(super().get_queryset())
+
+
+class MultipleFieldLookupORMixin:  # pylint: disable=too-few-public-methods
+    """A mixin to allow us to look up objects beyond just the primary key.
+
+    Set lookup_fields in the class to select what fields, in the given order,
+    that are used for the lookup. The value is the parameter passed at all times.
+
+    Example: We are passed "foo" as the value to look up (using the key 'lookup_value'),
+    and the class has the following set:
+
+    lookup_fields = ("id", "username", "email")
+
+    Applying this mixin will make the class attempt to:
+      1. Try to find object where id=foo (the default behaviour)
+      2. If no match was found, try to find an object where username=foo
+      3. If still no match, try to find an object where email=foo
+
+    If no matches are found, return 404. If a field matches several objects, ie
+    hosts with the same name in different namespaces, return 409.
+    """
+
+    def get_object(self):
+        """Perform the actual lookup based on the model's lookup_fields.
+
+        raises: 404 if not found.
+        return: object
+        """
+        #        if self.request.user.is_anonymous:
+        #            raise NotAuthenticated()
+
+        queryset = self.get_queryset()
+        obj = None
+        value = self.kwargs["val"]
+        for field in self.lookup_fields:
+            try:
+                # https://stackoverflow.com/questions/9122169/calling-filter-with-a-variable-for-field-name
+                # No, just no.
+                obj = queryset.get(**{field: value})
+                if obj:
+                    break
+
+            except MultipleObjectsReturned as ex:
+                raise Ambiguous(
+                    detail=f"'{value}' matches several objects by {field}."
+                ) from ex
+            # If we didn't get a hit, or an error, keep trying.
+            # If we don't get a hit at all, we'll raise 404.
+            except Exception:  # nosec pylint: disable=broad-except
+                pass
+
+        if obj is None:
+            raise NotFound()
+
+        # As we overload get_object, we need to manually check permissions.
+        self.check_object_permissions(self.request, obj)
+        return obj
+
+    def check_object_permissions(self, request, obj):
+        """Check the permissions for the object, hiding it if so configured.
+
+        With UNAUTHORIZED_STATUS set to 404, users who are denied access to an
+        object they can't read get 404, as if it did not exist. Users who can read
+        the object get 403 as usual.
+
+        raises: PermissionDenied or NotFound
+        """
+        try:
+            super().check_object_permissions(request, obj)
+        except PermissionDenied:
+            if settings.UNAUTHORIZED_STATUS == 404 and not self._can_read(request, obj):
+                raise NotFound() from None
+            raise
+
+    def _can_read(self, request, obj):
+        """Check if the user can read the object, ie knows that it exists."""
+        if isinstance(obj, Namespace):
+            return request.user.namespaced_can("has_read", obj)
+        if isinstance(obj, NamespacedHubuumModel):
+            model = type(obj).__name__.lower()
+            return request.user.namespaced_can("has_read", obj.namespace, model)
+        return True
+
+
+class UserLookupMixin(MultipleFieldLookupORMixin):  # pylint: disable=R0903
+    """Look up users like MultipleFieldLookupORMixin, "me" being the user requesting.
+
+    "me" always refers to the user making the request, even if a user is named
+    "me" (who can still be found by id or email).
+    """
+
+    def get_object(self):
+        """Return the user making the request for "me", otherwise look it up."""
+        if self.kwargs["val"] != "me":
+            return super().get_object()
+
+        try:
+            obj = self.get_queryset().get(pk=self.request.user.pk)
+        except User.DoesNotExist as ex:
+            raise NotFound() from ex
+        self.check_object_permissions(self.request, obj)
+        return obj
+
+
+class RelationListMixin:  # pylint: disable=too-few-public-methods
+    """A mixin to paginate, and sort, the objects related to an object.
+
+    The objects are paginated like lists (?page=, ?page_size=), and may be sorted
+    with ?ordering=<field>, or -<field> for descending order, by one of the
+    ordering_fields of the view.
+    """
+
+    ordering_fields = ("id",)
+
+    def relation_response(self, queryset, serializer_class, **kwargs):
+        """Return the paginated response for the (sorted) queryset.
+
+        raises: ParseError if the ordering is not one of the ordering_fields
+        """
+        ordering = self.request.query_params.get("ordering")
+        if ordering is not None:
+            if ordering.lstrip("-") not in self.ordering_fields:
+                fields = ", ".join(self.ordering_fields)
+                raise ParseError(
+                    detail=f"'ordering' must be one of {fields}, optionally with -."
+                )
+            queryset = queryset.order_by(ordering, "id")
+        elif not queryset.ordered:
+            queryset = queryset.order_by("id")
+
+        page = self.paginate_queryset(queryset)
+        serializer = serializer_class(page, many=True, **kwargs)
+        return self.get_paginated_response(serializer.data)
+
+
+class ByNameMixin:  # pylint: disable=too-few-public-methods
+    """A mixin to address objects by their namespace and name, rather than by id.
+
+    /<model>/by-name/<namespace>/<name>, where the namespace is given by its name
+    or id, and the name is the name_field of the detail view of the model (ie
+    "room_id" for rooms). Names may hold slashes, and are URL-decoded. Names
+    given to several objects in the namespace get 409.
+    """
+
+    def get_object(self):
+        """Find the object with the name in the namespace.
+
+        raises: 404 if not found, 409 if the name is ambiguous.
+        """
+        namespace = get_object(
+            Namespace, self.kwargs["namespace"], lookup_fields=["id", "name"]
+        )
+        name = self.kwargs["name"]
+        queryset = self.get_queryset().filter(namespace=namespace)
+        matches = list(queryset.filter(**{self.name_field: name})[:2])
+        if not matches:
+            raise NotFound()
+        if len(matches) > 1:
+            raise Ambiguous(
+                detail=f"'{name}' matches several objects in {namespace.name}."
+            )
+
+        self.check_object_permissions(self.request, matches[0])
+        return matches[0]
+
+
+class HubuumList(
+    IdempotencyMixin,
+    VisibleNamespacesMixin,
+    NamespaceLimitsMixin,
+    LoggingMixin,
+    generics.ListCreateAPIView,
+):
+    """Get: List objects. Post: Add object."""
+
+    permission_classes = (NameSpace,)
+    renderer_classes = (*api_settings.DEFAULT_RENDERER_CLASSES, NDJSONRenderer)
+
+    # The number of objects fetched from the database at a time when streaming.
+    stream_chunk_size = 1000
+
+    def list(self, request, *args, **kwargs):
+        """List objects. With ?format=ndjson, every match is streamed unpaginated."""
+        if request.accepted_renderer.format == "ndjson":
+            return self.stream(self.filter_queryset(self.get_queryset()))
+
+        return super().list(request, *args, **kwargs)
+
+    def stream(self, queryset):
+        """Stream the objects of queryset as NDJSON, one object per line.
+
+        Objects are fetched and serialized in chunks as the response is written,
+        so memory use does not grow with the number of objects.
+        """
+        rows = (
+            self.get_serializer(instance).data
+            for instance in queryset.iterator(chunk_size=self.stream_chunk_size)
+        )
+        return StreamingHttpResponse(
+            ndjson_lines(rows), content_type="application/x-ndjson; charset=utf-8"
+        )
+
+
+class BulkDeleteMixin:
+    """Delete all the objects matching the filters of a list view.
+
+    DELETE /<objects>/?<filters>&dry_run=true reports what would be deleted.
+    DELETE /<objects>/?<filters>&confirm=true deletes it.
+
+    The filters are validated in strict mode, so unknown (ie misspelled) filters get
+    400 rather than being ignored, and at least one filter must have a value.
+    Objects the user can see but not delete, and objects in archived namespaces,
+    are skipped and reported. The deletion is performed in batches, within a
+    single transaction.
+    """
+
+    bulk_delete_batch_size = 500
+
+    def _flag(self, name):
+        """Check if the query parameter name is set to a true value."""
+        value = self.request.query_params.get(name, "").lower()
+        return value in ["1", "true", "yes"]
+
+    def delete(self, request, *args, **kwargs):
+        """Delete the objects matching the filters given."""
+        filterset = self.filterset_class(
+            request.query_params, queryset=self.get_queryset(), request=request
+        )
+        filterset.strict = True
+        if not filterset.is_valid():
+            raise ValidationError(filterset.errors)
+        if not any(request.query_params.get(name) for name in filterset.filters):
+            raise ParseError(detail="Bulk deletes require at least one filter.")
+
+        dry_run = self._flag("dry_run")
+        if not dry_run and not self._flag("confirm"):
+            raise ParseError(detail="Bulk deletes require dry_run or confirm.")
+
+        model = self.get_queryset().model
+        matched = list(self.filter_queryset(self.get_queryset()))
+        deletable = None
+        if not request.user.is_admin():
+            deletable = request.user.namespaces_where("has_delete", model)
+
+        archived = set(
+            Namespace.objects.filter(archived=True).values_list("id", flat=True)
+        )
+
+        delete = []
+        skipped = []
+        for obj in matched:
+            if obj.namespace_id in archived:
+                skipped.append({"id": obj.id, "reason": "The namespace is archived."})
+            elif deletable is None or obj.namespace_id in deletable:
+                delete.append(obj)
+            else:
+                skipped.append({"id": obj.id, "reason": "Permission denied."})
+
+        restricted = model.restricted(
+            model.objects.filter(pk__in=[obj.pk for obj in delete])
+        )
+        for obj in [obj for obj in delete if obj.pk in restricted]:
+            delete.remove(obj)
+            relations = ", ".join(restricted[obj.pk])
+            skipped.append(
+                {"id": obj.id, "reason": f"Referred to through {relations}."}
+            )
+
+        unwritable = model.unwritable(
+            model.objects.filter(pk__in=[obj.pk for obj in delete]), request.user
+        )
+        for obj in [obj for obj in delete if obj.pk in unwritable]:
+            delete.remove(obj)
+            relations = ", ".join(unwritable[obj.pk])
+            skipped.append(
+                {
+                    "id": obj.id,
+                    "reason": f"Referred to through {relations}, by objects you "
+                    "can not change.",
+                }
+            )
+
+        if not dry_run:
+            size = self.bulk_delete_batch_size
+            with transaction.atomic():
+                for start in range(0, len(delete), size):
+                    batch = delete[start : start + size]
+                    for obj in batch:
+                        self._log("deleted", model.__name__, request.user, obj)
+                    queryset = model.objects.filter(pk__in=[obj.pk for obj in batch])
+                    model.apply_delete_policies(queryset, user=request.user)
+                    queryset.delete()
+
+        return Response(
+            {
+                "dry_run": dry_run,
+                "matched": len(matched),
+                "deleted": len(delete),
+                "skipped": skipped,
+            }
+        )
+
+
+class ConditionalGetMixin:
+    """Mixin to support conditional GETs of single objects.
+
+    Responses carry an ETag (a hash of the representation) and, for objects with
+    an updated_at timestamp, a Last-Modified header. Requests with a matching
+    If-None-Match, or an If-Modified-Since no older than the object, get a 304.
+    """
+
+    def retrieve(self, request, *args, **kwargs):
+        """Get the object, or 304 if the client has the current version."""
+        instance = self.get_object()
+        data = self.get_serializer(instance).data
+
+        representation = json.dumps(
+            [request.accepted_media_type, data], cls=JSONEncoder, sort_keys=True
+        )
+        etag = quote_etag(hashlib.sha256(representation.encode("utf-8")).hexdigest())
+        updated_at = getattr(instance, "updated_at", None)
+        last_modified = int(updated_at.timestamp()) if updated_at else None
+
+        response = get_conditional_response(
+            request, etag=etag, last_modified=last_modified
+        )
+        if response is None:
+            response = Response(data)
+
+        response["ETag"] = etag
+        if last_modified is not None:
+            response["Last-Modified"] = http_date(last_modified)
+        return response
+
+
+# NOTE: Order for the inheritance here is vital.
+class HubuumDetail(
+    MultipleFieldLookupORMixin,
+    VisibleNamespacesMixin,
+    NamespaceLimitsMixin,
+    LoggingMixin,
+    ConditionalGetMixin,
+    generics.RetrieveUpdateDestroyAPIView,
+):
+    """Get, Patch, or Destroy an object."""
+
+    permission_classes = (NameSpace,)
+    lookup_fields = ("id",)
@@ -0,0 +1,511 @@
+"""Views for schema definitions, extensions, and extension data."""
+from datetime import datetime
+
+from django.utils import timezone
+from django.utils.dateparse import parse_date, parse_datetime
+from rest_framework import generics, status
+from rest_framework.exceptions import NotFound, ParseError, ValidationError
+from rest_framework.schemas.openapi import AutoSchema
+from rest_framework.views import APIView, Response
+
+from hubuum import search
+from hubuum.filters import (
+    ExtensionDataFilterSet,
+    ExtensionFilterSet,
+    SchemaDefinitionFilterSet,
+)
+from hubuum.models.extensions import Extension, ExtensionData, SchemaDefinition
+from hubuum.models.namespace import Namespace
+from hubuum.permissions import (
+    IsAuthenticatedAndReadOnly,
+    NameSpace,
+    NameSpaceSubresource,
+)
+from hubuum.tools import json_diff
+from hubuum.validators import validate_json_schema
+
+from ..serializers import (
+    ExtensionDataRevisionSerializer,
+    ExtensionDataSerializer,
+    ExtensionSerializer,
+    SchemaDefinitionSerializer,
+)
+from .base import BulkDeleteMixin, HubuumDetail, HubuumList, MultipleFieldLookupORMixin
+
+
+class SchemaDefinitionList(HubuumList):
+    """Get: List schema definitions. Post: Add a schema definition."""
+
+    queryset = SchemaDefinition.objects.all()
+    serializer_class = SchemaDefinitionSerializer
+    filterset_class = SchemaDefinitionFilterSet
+
+
+class SchemaDefinitionDetail(HubuumDetail):
+    """Get, Patch, or Destroy a schema definition."""
+
+    queryset = SchemaDefinition.objects.all()
+    serializer_class = SchemaDefinitionSerializer
+    lookup_fields = ("id", "name")
+
+
+class SchemaDefinitionDangling(APIView):
+    """List the schemas with references to the registry that do not resolve."""
+
+    permission_classes = (IsAuthenticatedAndReadOnly,)
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Dangling schema references",
+        operation_id_base="SchemaDefinitionDangling",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get the extensions and schema definitions with dangling references.
+
+        References dangle if the definition they refer to was removed or renamed.
+        Only objects the user can read are included.
+        """
+        user = request.user
+        dangling = []
+        for model in (Extension, SchemaDefinition):
+            model_name = model.__name__.lower()
+            queryset = model.objects.filter(json_schema__isnull=False).order_by("id")
+            if not user.is_admin():
+                namespaces = user.namespaces_where("has_read", model_name)
+                queryset = queryset.filter(namespace__in=namespaces)
+
+            for obj in queryset:
+                references = SchemaDefinition.dangling_references(obj.json_schema)
+                if references:
+                    dangling.append(
+                        {
+                            "model": model_name,
+                            "id": obj.id,
+                            "name": obj.name,
+                            "namespace": obj.namespace_id,
+                            "references": references,
+                        }
+                    )
+
+        return Response(dangling)
+
+
+class ExtensionList(BulkDeleteMixin, HubuumList):
+    """Get: List extensions. Post: Add extension."""
+
+    queryset = Extension.objects.all()
+    serializer_class = ExtensionSerializer
+    filterset_class = ExtensionFilterSet
+
+
+class ExtensionDetail(HubuumDetail):
+    """Get, Patch, or Destroy an extension."""
+
+    queryset = Extension.objects.all()
+    serializer_class = ExtensionSerializer
+    lookup_fields = ("id", "name")
+
+
+class ExtensionSchemaMigration(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """Migrate the existing data of an extension to a new json_schema."""
+
+    permission_classes = (NameSpaceSubresource,)
+    lookup_fields = ("id", "name")
+    serializer_class = ExtensionSerializer
+    queryset = Extension.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Extension schema migration",
+        operation_id_base="ExtensionSchemaMigration",
+    )
+
+    def post(self, request, *args, **kwargs):
+        """Check, and optionally apply, a new schema for the extension.
+
+        /extensions/<extensionid>/migrate_schema
+            {
+                json_schema = {...},
+                apply_defaults = false,
+                dry_run = true,
+            }
+
+        The response lists the data objects that are valid and invalid with
+        the new schema. Unless dry_run is false, nothing is changed.
+        """
+        extension = self.get_object()
+
+        if not isinstance(request.data, dict) or "json_schema" not in request.data:
+            raise ParseError(detail="API expected a dictionary with a json_schema.")
+
+        options = {"apply_defaults": False, "dry_run": True}
+        for option, default in options.items():
+            options[option] = request.data.get(option, default)
+            if not isinstance(options[option], bool):
+                raise ParseError(detail=f"'{option}' must be a boolean.")
+
+        json_schema = request.data["json_schema"]
+        validate_json_schema(json_schema)
+        SchemaDefinition.validate_references(json_schema)
+
+        report = extension.migrate_schema(json_schema, **options)
+        return Response({"dry_run": options["dry_run"], **report})
+
+
+class ExtensionValidation(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """Re-validate the data of an extension against its current json_schema."""
+
+    permission_classes = (NameSpaceSubresource,)
+    lookup_fields = ("id", "name")
+    serializer_class = ExtensionSerializer
+    queryset = Extension.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Extension data validation",
+        operation_id_base="ExtensionValidation",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get the validation status of every data object of the extension.
+
+        /extensions/<extensionid>/validation?invalid=true
+
+        The status recorded for the data objects is refreshed as well, which is
+        useful after the schema or the definitions it refers to are tightened.
+        Only data objects the user can read are included, and with invalid set,
+        only those that fail validation.
+        """
+        extension = self.get_object()
+
+        queryset = ExtensionData.objects.all()
+        if not request.user.is_admin():
+            namespaces = request.user.namespaces_where("has_read", ExtensionData)
+            queryset = queryset.filter(namespace__in=namespaces)
+
+        results = extension.validate_data(queryset)
+        summary = {
+            "valid": len([result for result in results if result["valid"]]),
+            "invalid": len([result for result in results if result["valid"] is False]),
+        }
+        if request.query_params.get("invalid", "").lower() in ["1", "true", "yes"]:
+            results = [result for result in results if result["valid"] is False]
+
+        return Response(
+            {
+                "schema": extension.json_schema is not None,
+                **summary,
+                "objects": results,
+            }
+        )
+
+
+class ExtensionAggregation(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """Aggregate the data of an extension, ie for dashboards."""
+
+    permission_classes = (NameSpaceSubresource,)
+    lookup_fields = ("id", "name")
+    serializer_class = ExtensionSerializer
+    queryset = Extension.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Extension data aggregation",
+        operation_id_base="ExtensionAggregation",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Aggregate the json_data of the data objects of the extension.
+
+        /extensions/<extension>/aggregate?group_by=json_data.location
+            &agg=count,sum:json_data.ram_gb
+
+        group_by and agg are comma separated. Paths are given as json_data.<key>,
+        with dots between nested keys. The functions are count, sum, avg, min, and
+        max, all but count taking a path. agg defaults to count, and without
+        group_by, all the data objects form a single group.
+
+        Only data objects the user can read are included. Users without
+        has_read_sensitive in a namespace may not aggregate the sensitive paths of
+        its data, as its data is then left out.
+        """
+        extension = self.get_object()
+        params = request.query_params
+        group_by = {
+            name: self._path(name)
+            for name in params.get("group_by", "").split(",")
+            if name
+        }
+        aggregations = {
+            name: self._aggregation(name)
+            for name in params.get("agg", "count").split(",")
+            if name
+        }
+
+        queryset = Namespace.visible(ExtensionData.objects.all())
+        if not request.user.is_admin():
+            namespaces = request.user.namespaces_where("has_read", ExtensionData)
+            paths = [*group_by.values()]
+            paths += [path for _, path in aggregations.values() if path is not None]
+            if any(extension.touches_sensitive_paths(path) for path in paths):
+                namespaces = namespaces & request.user.namespaces_where(
+                    "has_read_sensitive", ExtensionData
+                )
+            queryset = queryset.filter(namespace__in=namespaces)
+
+        groups = extension.aggregate_data(queryset, group_by, aggregations)
+        return Response(
+            {"group_by": list(group_by), "agg": list(aggregations), "groups": groups}
+        )
+
+    @staticmethod
+    def _path(value):
+        """Parse a path into json_data, ie json_data.location, into a list of keys.
+
+        raises: ParseError if the path is malformed
+        """
+        head, _, path = value.partition(".")
+        keys = path.split(".")
+        if head != "json_data" or "" in keys:
+            raise ParseError(
+                detail=f"Invalid path '{value}', expected json_data.<key>[.<key>]."
+            )
+        return keys
+
+    def _aggregation(self, value):
+        """Parse an aggregation, ie count or sum:json_data.ram_gb.
+
+        returns: (function, path), the path None for count
+        raises: ParseError if the aggregation is malformed
+        """
+        function, _, path = value.partition(":")
+        if function not in Extension.AGGREGATIONS:
+            functions = ", ".join(Extension.AGGREGATIONS)
+            raise ParseError(
+                detail=f"Unknown aggregation '{function}', expected {functions}."
+            )
+        if function == "count":
+            if path:
+                raise ParseError(detail="count takes no path.")
+            return function, None
+        if not path:
+            raise ParseError(
+                detail=f"{function} requires a path, ie {function}:json_data.<key>."
+            )
+        return function, self._path(path)
+
+
+class ExtensionDataList(BulkDeleteMixin, HubuumList):
+    """Get: List extensiondata. Post: Add extensiondata."""
+
+    queryset = ExtensionData.objects.all()
+    serializer_class = ExtensionDataSerializer
+    filterset_class = ExtensionDataFilterSet
+
+    def list(self, request, *args, **kwargs):
+        """List extensiondata, with ?explain_matches=true explaining the matches.
+
+        Every object then has "matched", the keys given to each of the json_data
+        filters that the json_data of the object satisfied, ie
+        {"json_data_has_any_key": ["fqdn"]} for ?json_data_has_any_key=fqdn,room_id.
+        Lists with json_data filters run within the budget of hubuum.search.
+        """
+        params = request.query_params
+        if not search.is_json_search(params):
+            return self._list(request, *args, **kwargs)
+        with search.json_search(params.dict()):
+            return self._list(request, *args, **kwargs)
+
+    def _list(self, request, *args, **kwargs):
+        """List extensiondata, see list."""
+        explain = request.query_params.get("explain_matches", "").lower()
+        if explain not in ["1", "true", "yes"]:
+            return super().list(request, *args, **kwargs)
+
+        queryset = self.filter_queryset(self.get_queryset())
+        page = self.paginate_queryset(queryset)
+        objects = list(queryset) if page is None else page
+
+        filterset = self.filterset_class(
+            request.query_params, queryset=queryset, request=request
+        )
+        filterset.is_valid()
+        matches = filterset.json_matches([obj.pk for obj in objects])
+
+        data = self.get_serializer(objects, many=True).data
+        for obj, row in zip(objects, data):
+            row["matched"] = matches[obj.pk]
+
+        if page is None:
+            return Response(data)
+        return self.get_paginated_response(data)
+
+    def post(self, request, *args, **kwargs):
+        """Handle posting duplicates as a patch.
+
+        Malformed data is left to the serializer, which reports errors by field.
+        """
+        data = request.data
+        existing_object_entry = None
+        if isinstance(data, dict) and "json_data" in data:
+            ids = (data.get("extension"), data.get("object_id"))
+            if all(str(value).isdigit() for value in ids):
+                existing_object_entry = ExtensionData.objects.filter(
+                    extension=data["extension"],
+                    object_id=data["object_id"],
+                    content_type__model=data.get("content_type"),
+                ).first()
+
+        if existing_object_entry:
+            namespace = existing_object_entry.namespace
+            namespace.check_write_limits(request.data["json_data"])
+            existing_object_entry.extension.check_schema(request.data["json_data"])
+
+            errors = existing_object_entry.extension.reference_errors(
+                request.data["json_data"], request.user
+            )
+            if errors:
+                raise ValidationError({"json_data": errors})
+
+            existing_object_entry.json_data = request.data["json_data"]
+            existing_object_entry.save()
+            return Response(
+                ExtensionDataSerializer(existing_object_entry).data,
+                status=status.HTTP_201_CREATED,
+            )
+
+        return super().post(request, *args, **kwargs)
+
+
+class ExtensionDataDetail(HubuumDetail):
+    """Get, Patch, or Destroy an extensiondata object."""
+
+    queryset = ExtensionData.objects.all()
+    serializer_class = ExtensionDataSerializer
+
+
+class ExtensionDataRevisions(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List the revisions of the json_data of an extensiondata object."""
+
+    permission_classes = (NameSpace,)
+    lookup_fields = ("id",)
+    serializer_class = ExtensionDataRevisionSerializer
+    queryset = ExtensionData.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Extension data revisions",
+        operation_id_base="ExtensionDataRevisions",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get all revisions for the extensiondata object."""
+        extension_data = self.get_object()
+        revisions = extension_data.revisions.all()
+
+        data = ExtensionDataRevisionSerializer(revisions, many=True).data
+        for revision in data:
+            revision["json_data"] = extension_data.redact_for(
+                request.user, revision["json_data"]
+            )
+        return Response(data)
+
+
+class ExtensionDataDiff(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """Show what changed in the json_data of an extensiondata object.
+
+    /extension_data/<id>/diff?from=<version|timestamp>&to=<version|timestamp>
+
+    Both parameters are optional. If "to" is omitted, the latest revision is used.
+    If "from" is omitted, the revision preceding "to" is used.
+    """
+
+    permission_classes = (NameSpace,)
+    lookup_fields = ("id",)
+    serializer_class = ExtensionDataRevisionSerializer
+    queryset = ExtensionData.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Extension data diff",
+        operation_id_base="ExtensionDataDiff",
+    )
+
+    def _get_revision(self, extension_data, param):
+        """Find the revision referred to by the query parameter param.
+
+        The value may either be a version number or an ISO 8601 timestamp.
+        A timestamp refers to the revision that was current at that point in time.
+
+        returns: revision object, or None if the parameter was not given.
+
+        raises: ParseError if the value is malformed, NotFound if there is no such revision.
+        """
+        value = self.request.query_params.get(param)
+        if value is None:
+            return None
+
+        if value.isdigit():
+            revision = extension_data.get_revision(version=int(value))
+        else:
+            timestamp = parse_datetime(value)
+            if timestamp is None:
+                date = parse_date(value)
+                if date is None:
+                    raise ParseError(
+                        detail=f"'{param}' must be a version or an ISO 8601 timestamp."
+                    )
+                timestamp = datetime.combine(date, datetime.min.time())
+
+            if timezone.is_naive(timestamp):
+                timestamp = timezone.make_aware(timestamp)
+
+            revision = extension_data.get_revision(timestamp=timestamp)
+
+        if revision is None:
+            raise NotFound(detail=f"No revision found for '{param}={value}'.")
+
+        return revision
+
+    def get(self, request, *args, **kwargs):
+        """Get the diff between two revisions."""
+        extension_data = self.get_object()
+
+        to_revision = self._get_revision(extension_data, "to")
+        if to_revision is None:
+            to_revision = extension_data.get_revision()
+            if to_revision is None:
+                raise NotFound(detail="The object has no revisions.")
+
+        from_revision = self._get_revision(extension_data, "from")
+        if from_revision is None:
+            from_revision = (
+                extension_data.revisions.filter(version__lt=to_revision.version)
+                .order_by("-version")
+                .first()
+            )
+
+        old = from_revision.json_data if from_revision else {}
+        diff = json_diff(
+            extension_data.redact_for(request.user, old),
+            extension_data.redact_for(request.user, to_revision.json_data),
+        )
+
+        return Response(
+            {
+                "id": extension_data.id,
+                "from": from_revision.version if from_revision else None,
+                "to": to_revision.version,
+                **diff,
+            }
+        )
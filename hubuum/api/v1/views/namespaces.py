@@ -0,0 +1,885 @@
+"""Views for namespaces, their members, and their contents."""
+from django.contrib.auth.models import Group
+from django.db import transaction
+from django.http import HttpResponse
+from rest_framework import generics, status
+from rest_framework.exceptions import (
+    MethodNotAllowed,
+    NotFound,
+    ParseError,
+    PermissionDenied,
+    ValidationError,
+)
+from rest_framework.schemas.openapi import AutoSchema
+from rest_framework.settings import api_settings
+from rest_framework.views import Response
+
+from hubuum.exceptions import Conflict
+from hubuum.filters import NamespaceFilterSet
+from hubuum.models.auth import get_group
+from hubuum.models.base import (
+    NamespacedHubuumModel,
+    NamespacedHubuumModelWithExtensions,
+    cloned_models,
+    namespaced_models,
+)
+from hubuum.models.extensions import ExtensionData
+from hubuum.models.namespace import (
+    ActivityEntry,
+    ModelPermission,
+    Namespace,
+    NamespaceAlias,
+    Permission,
+)
+from hubuum.permissions import (
+    NameSpace,
+    NameSpaceClone,
+    NameSpaceSubresource,
+    fully_qualified_operations,
+)
+from hubuum.renderers import DOTRenderer, GraphMLRenderer
+from hubuum.tools import get_model
+
+from ..serializers import (
+    ActivityEntrySerializer,
+    GroupSerializer,
+    ModelPermissionSerializer,
+    NamespaceAliasSerializer,
+    NamespaceSerializer,
+    PermissionSerializer,
+)
+from .base import HubuumDetail, HubuumList, LoggingMixin, MultipleFieldLookupORMixin
+
+
+def namespace_grantee(user, group_id=None, default=None):
+    """Find the group to grant all permissions for a new namespace.
+
+    param: group_id (the id of the group asked for by the user, if any)
+    param: default (the group to use if none is asked for, ie the owner group)
+
+    returns: the group, the only group of the user if there is no other default,
+             or None for admins without one
+    raises: ValidationError if the group is not found or the user is not a member,
+            or if a user who is not an admin has no group to grant permissions
+    """
+    group = None
+    if group_id is not None:
+        if str(group_id).isdigit():
+            group = Group.objects.filter(id=group_id).first()
+        if group is None:
+            raise ValidationError({"group": [f"No group with the id {group_id}."]})
+        if not user.is_member_of(group):
+            raise ValidationError(
+                {
+                    "group": [
+                        "The user is not a member of the group that was requested "
+                        "to have permissions for the created object."
+                    ]
+                }
+            )
+
+    if group is None:
+        group = default
+    if group is None and user.has_only_one_group():
+        group = user.groups.all().first()
+
+    if not user.is_admin() and group is None:
+        raise ValidationError(
+            {
+                "group": [
+                    "No group parameter provided, and no singular default "
+                    "available. All user-owned namespace-enabled objects are "
+                    "required to have an initial group with permissions to the "
+                    "object set upon creation."
+                ]
+            }
+        )
+
+    return group
+
+
+class NamespaceList(HubuumList):
+    """Get: List Namespaces. Post: Add Namespace."""
+
+    queryset = Namespace.objects.all()
+    serializer_class = NamespaceSerializer
+    permission_classes = (NameSpace,)
+    namespace_write_permission = "has_namespace"
+    filterset_class = NamespaceFilterSet
+
+    def get_queryset(self):
+        """Hide archived namespaces unless include_archived (or archived) is given."""
+        queryset = super().get_queryset()
+        params = self.request.query_params
+        include = params.get("include_archived", "").lower() in ["1", "true", "yes"]
+        if include or "archived" in params:
+            return queryset
+
+        return queryset.filter(archived=False)
+
+    def post(self, request, *args, **kwargs):
+        """Process creation of new namespaces."""
+        group_id = None
+        if isinstance(request.data, dict) and "group" in request.data:
+            # We want to pop the group since it's not part of the model.
+            # As such, validation will fail if it present.
+            group_id = request.data.pop("group")
+
+        serializer = self.get_serializer(data=request.data)
+        serializer.is_valid(raise_exception=True)
+        # The owner group of the namespace is the default grantee.
+        group = namespace_grantee(
+            request.user, group_id, serializer.validated_data.get("owner_group")
+        )
+        new_namespace = serializer.save()
+
+        if group is not None:
+            new_namespace.grant_all(group)
+
+        return Response(serializer.data, status=status.HTTP_201_CREATED)
+
+
+class NamespaceDetail(HubuumDetail):
+    """Get, Patch, or Destroy a namespace."""
+
+    queryset = Namespace.objects.all()
+    serializer_class = NamespaceSerializer
+    lookup_fields = ("id", "name", "aliases__name")
+    permission_classes = (NameSpace,)
+    namespace_write_permission = "has_namespace"
+    namespace_post = False
+
+    def delete(self, request, *args, **kwargs):
+        """Delete a namespace and its objects, in the background with ?deferred=true.
+
+        A deferred delete marks the namespace as being deleted, which hides it and
+        its objects at once, and returns 202 with the progress of the deletion.
+        The objects are then deleted in batches by `manage.py delete_namespaces`,
+        see /namespaces/<namespace>/deletion for the progress.
+        """
+        deferred = request.query_params.get("deferred", "").lower()
+        if deferred not in ["1", "true", "yes"]:
+            return super().delete(request, *args, **kwargs)
+
+        namespace = self.get_object()
+        progress = namespace.request_deletion(user=request.user)
+        self._log("deletion requested", "Namespace", request.user, namespace)
+        return Response(progress, status=status.HTTP_202_ACCEPTED)
+
+
+class NamespaceDeletion(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """Get the progress of the deferred delete of a namespace."""
+
+    permission_classes = (NameSpaceSubresource,)
+    lookup_fields = ("id", "name", "aliases__name")
+    queryset = Namespace.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Namespace deletion",
+        operation_id_base="NamespaceDeletion",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get the progress of the deletion of a namespace.
+
+        The progress has when the deletion was requested, the total number of
+        objects, the number remaining, and the error of the last batch (if it
+        failed). Namespaces not being deleted, or already deleted, get 404.
+        """
+        namespace = self.get_object()
+        if namespace.deletion is None:
+            raise NotFound(detail="The namespace is not being deleted.")
+
+        return Response({"name": namespace.name, **namespace.deletion})
+
+
+class NamespaceClone(
+    LoggingMixin,
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """Clone a namespace."""
+
+    permission_classes = (NameSpaceClone,)
+    lookup_fields = ("id", "name", "aliases__name")
+    serializer_class = NamespaceSerializer
+    queryset = Namespace.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Namespace clone",
+        operation_id_base="NamespaceClone",
+    )
+
+    def get_target_namespace(self, obj):
+        """Get the name of the copy, see NameSpaceClone."""
+        return self.request.data["name"]
+
+    def post(self, request, *args, **kwargs):
+        """Clone the namespace.
+
+        /namespaces/<namespace>/clone
+            {
+                name = <name of the copy>,
+                description = <description of the copy> (optional),
+                owner_group = <groupid> (optional),
+                group = <groupid> (optional, granted all permissions to the copy),
+                objects = false,
+                permissions = true,
+            }
+
+        The copy gets the schemas and extensions of the namespace, and its objects
+        with objects. With permissions, the groups with permissions for the
+        namespace get the same for the copy. The group gets all permissions for
+        the copy, as when creating namespaces. As the extension data is copied
+        unredacted, cloning objects with sensitive data requires has_read_sensitive
+        for the namespace.
+
+        Cloning namespaces with more than NAMESPACE_CLONE_SIZE objects, or with
+        ?deferred=true, returns 202 and leaves copying the objects to `manage.py
+        clone_namespaces`, see /namespaces/<copy>/cloning for the progress. The
+        objects of the copy are read-only until then.
+        """
+        if not isinstance(request.data, dict) or not request.data.get("name"):
+            raise ParseError(detail="API expected a dictionary with a name.")
+
+        options = {"objects": False, "permissions": True}
+        for option, default in options.items():
+            options[option] = request.data.get(option, default)
+            if not isinstance(options[option], bool):
+                raise ParseError(detail=f"'{option}' must be a boolean.")
+
+        namespace = self.get_object()
+        if options["objects"] and not request.user.is_admin():
+            for model in [*cloned_models(), ExtensionData]:
+                if not model.objects.filter(namespace=namespace).exists():
+                    continue
+                if not request.user.namespaced_can("has_read", namespace, model):
+                    name = model._meta.model_name  # pylint: disable=protected-access
+                    raise PermissionDenied(
+                        "Cloning the objects of the namespace requires has_read for "
+                        f"every model in it, including {name}."
+                    )
+
+            sensitive = ExtensionData.objects.filter(namespace=namespace).exclude(
+                extension__sensitive_paths=[]
+            )
+            if sensitive.exists() and not request.user.namespaced_can(
+                "has_read_sensitive", namespace
+            ):
+                raise PermissionDenied(
+                    "Cloning the objects of the namespace requires "
+                    "has_read_sensitive, as its extension data has sensitive paths."
+                )
+
+        fields = ("name", "description", "owner_group")
+        data = {field: request.data[field] for field in fields if field in request.data}
+        serializer = self.get_serializer(data=data)
+        serializer.is_valid(raise_exception=True)
+        group = namespace_grantee(
+            request.user,
+            request.data.get("group"),
+            serializer.validated_data.get("owner_group"),
+        )
+
+        deferred = request.query_params.get("deferred", "").lower()
+        with transaction.atomic():
+            copy = namespace.clone(
+                **serializer.validated_data,
+                **options,
+                deferred=True if deferred in ["1", "true", "yes"] else None,
+            )
+            if group is not None:
+                copy.grant_all(group)
+
+        self._log("cloned", "Namespace", request.user, copy)
+        code = status.HTTP_202_ACCEPTED if copy.cloning else status.HTTP_201_CREATED
+        return Response(self.get_serializer(copy).data, status=code)
+
+
+class NamespaceCloning(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """Get the progress of the deferred clone into a namespace."""
+
+    permission_classes = (NameSpaceSubresource,)
+    lookup_fields = ("id", "name", "aliases__name")
+    queryset = Namespace.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Namespace cloning",
+        operation_id_base="NamespaceCloning",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get the progress of the clone into a namespace.
+
+        The progress has the id of the namespace cloned, when the clone was
+        requested, the number of objects to copy, and the error of the last attempt
+        (if it failed). Namespaces not being cloned into get 404.
+        """
+        namespace = self.get_object()
+        if namespace.cloning is None:
+            raise NotFound(detail="The namespace is not being cloned into.")
+
+        return Response({"name": namespace.name, **namespace.cloning})
+
+
+class NamespaceMembers(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List groups that can access a namespace."""
+
+    permission_classes = (NameSpace,)
+    lookup_fields = ("id", "name", "aliases__name")
+    serializer_class = GroupSerializer
+    queryset = Namespace.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Namespace members",
+        operation_id_base="NamespaceMember",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get all groups that have access to a given namespace."""
+        namespace = self.get_object()
+
+        qs = Permission.objects.filter(namespace=namespace.id).values("group")
+        groups = Group.objects.filter(id__in=qs)
+
+        return Response(GroupSerializer(groups, many=True).data)
+
+
+class NamespaceMembersGroup(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveUpdateDestroyAPIView,
+):
+    """Modify groups that can access a namespace."""
+
+    permission_classes = (NameSpace,)
+    lookup_fields = ("id", "name", "aliases__name")
+    serializer_class = PermissionSerializer
+    queryset = Namespace.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Namespace group permissions",
+        operation_id_base="NamespaceMembersGroup",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get a group that has access to a namespace."""
+        namespace = self.get_object()
+        group = get_group(kwargs["groupid"])
+        permission = namespace.get_permissions_for_group(group)
+
+        return Response(PermissionSerializer(permission).data)
+
+    # TODO: Should be used to update a groups permissions for the namespace.
+    def patch(self, request, *args, **kwargs):
+        """Patch the permissions of an existing group for a namespace."""
+        namespace = self.get_object()
+        group = get_group(kwargs["groupid"])
+        instance = namespace.get_permissions_for_group(group)
+
+        serializer = self.get_serializer(instance, data=request.data, partial=True)
+        serializer.is_valid(raise_exception=True)
+        self.perform_update(serializer)
+        return HttpResponse(status=status.HTTP_204_NO_CONTENT)
+
+    def post(self, request, *args, **kwargs):
+        """Put associates a group with a namespace.
+
+        /namespace/<namespaceid>/groups/<groupid>
+            {
+                has_read = 1,
+                has_delete = 0,
+                has_create = 0,
+                has_update = 0,
+                has_namespace = 0,
+            }
+
+        Transparently creates a permission object.
+        """
+        namespace = self.get_object()
+        group = get_group(kwargs["groupid"])
+        instance = namespace.get_permissions_for_group(group, raise_exception=False)
+
+        if set(request.data.keys()).isdisjoint(fully_qualified_operations()):
+            raise ParseError(
+                detail=f"Missing at least one of '{fully_qualified_operations()}'"
+            )
+
+        if instance:
+            raise Conflict()
+
+        params = {"namespace": namespace.id, "group": group.id, **request.data}
+        serializer = self.get_serializer(data=params, partial=False)
+        serializer.is_valid(raise_exception=True)
+
+        create = serializer.data
+        create["namespace"] = namespace
+        create["group"] = group
+        create["has_read"] = True
+
+        Permission.objects.create(**create)
+        return HttpResponse(status=status.HTTP_204_NO_CONTENT)
+
+    def delete(self, request, *args, **kwargs):
+        """Delete disassociates a group with a namespace.
+
+        Transparently deletes the permission object.
+        """
+        namespace = self.get_object()
+        group = get_group(kwargs["groupid"])
+        permission = namespace.get_permissions_for_group(group)
+
+        permission.delete()
+        return HttpResponse(status=status.HTTP_204_NO_CONTENT)
+
+
+class NamespaceGroupModelPermissions(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List the permissions of a group for specific models in a namespace."""
+
+    permission_classes = (NameSpaceSubresource,)
+    lookup_fields = ("id", "name", "aliases__name")
+    serializer_class = ModelPermissionSerializer
+    queryset = Namespace.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Namespace group model permissions",
+        operation_id_base="NamespaceGroupModelPermissions",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get the model permissions of the group for the namespace."""
+        namespace = self.get_object()
+        group = get_group(kwargs["groupid"])
+        permissions = ModelPermission.objects.filter(namespace=namespace, group=group)
+        return Response(ModelPermissionSerializer(permissions, many=True).data)
+
+
+class NamespaceGroupModelPermission(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveUpdateDestroyAPIView,
+):
+    """Grant, modify, or revoke the permissions of a group for a model in a namespace.
+
+    For objects of the model, these permissions are used instead of the permissions
+    the group has for the namespace itself.
+    """
+
+    permission_classes = (NameSpaceSubresource,)
+    lookup_fields = ("id", "name", "aliases__name")
+    serializer_class = ModelPermissionSerializer
+    queryset = Namespace.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Namespace group model permission",
+        operation_id_base="NamespaceGroupModelPermission",
+    )
+
+    def _get_permission(self, raise_exception=True):
+        """Find the namespace, group, model name, and the permission object."""
+        namespace = self.get_object()
+        group = get_group(self.kwargs["groupid"])
+        model = get_model(self.kwargs["model"])
+        if model is None or not issubclass(model, NamespacedHubuumModel):
+            raise NotFound(detail=f"No namespaced model '{self.kwargs['model']}'.")
+
+        model = model.__name__.lower()
+        permission = ModelPermission.objects.filter(
+            namespace=namespace, group=group, model=model
+        ).first()
+        if permission is None and raise_exception:
+            raise NotFound()
+
+        return namespace, group, model, permission
+
+    def _validate_operations(self, data):
+        """Ensure that data sets at least one operation, and nothing else."""
+        operations = ModelPermission.operations
+        if not isinstance(data, dict) or set(data.keys()).isdisjoint(operations):
+            raise ParseError(detail=f"Missing at least one of '{operations}'")
+
+        extra_keys = set(data.keys()) - set(operations)
+        if extra_keys:
+            raise ParseError(detail=f"{extra_keys} can not be set.")
+
+    def get(self, request, *args, **kwargs):
+        """Get the permissions of the group for the model."""
+        _, _, _, permission = self._get_permission()
+        return Response(ModelPermissionSerializer(permission).data)
+
+    def post(self, request, *args, **kwargs):
+        """Grant the group permissions for the model in the namespace.
+
+        /namespaces/<namespaceid>/groups/<groupid>/models/<model>
+            {
+                has_read = true,
+                has_create = false,
+                has_update = false,
+                has_delete = false,
+            }
+
+        Operations not given are not granted.
+        """
+        namespace, group, model, permission = self._get_permission(
+            raise_exception=False
+        )
+        if permission:
+            raise Conflict()
+
+        self._validate_operations(request.data)
+        data = {
+            "namespace": namespace.id,
+            "group": group.id,
+            "model": model,
+            **request.data,
+        }
+        serializer = self.get_serializer(data=data)
+        serializer.is_valid(raise_exception=True)
+        serializer.save()
+        return Response(serializer.data, status=status.HTTP_201_CREATED)
+
+    def patch(self, request, *args, **kwargs):
+        """Modify the permissions of the group for the model."""
+        _, _, _, permission = self._get_permission()
+        self._validate_operations(request.data)
+        serializer = self.get_serializer(permission, data=request.data, partial=True)
+        serializer.is_valid(raise_exception=True)
+        serializer.save()
+        return Response(serializer.data)
+
+    def put(self, request, *args, **kwargs):
+        """Disallow PUT, use POST to grant and PATCH to modify."""
+        raise MethodNotAllowed(method=request.method)
+
+    def delete(self, request, *args, **kwargs):
+        """Revoke the permissions of the group for the model.
+
+        The permissions of the group for the namespace apply to the model again.
+        """
+        _, _, _, permission = self._get_permission()
+        permission.delete()
+        return HttpResponse(status=status.HTTP_204_NO_CONTENT)
+
+
+class NamespaceAliasList(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List or add aliases for a namespace."""
+
+    permission_classes = (NameSpaceSubresource,)
+    lookup_fields = ("id", "name", "aliases__name")
+    serializer_class = NamespaceAliasSerializer
+    queryset = Namespace.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Namespace aliases",
+        operation_id_base="NamespaceAliases",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get all aliases for a namespace."""
+        namespace = self.get_object()
+
+        return Response(
+            NamespaceAliasSerializer(namespace.aliases.all(), many=True).data
+        )
+
+    def post(self, request, *args, **kwargs):
+        """Add an alias to a namespace.
+
+        /namespaces/<namespaceid>/aliases/
+            {
+                name = "alias"
+            }
+        """
+        namespace = self.get_object()
+
+        if not isinstance(request.data, dict):
+            raise ParseError(detail="API expected a dictionary.")
+
+        serializer = self.get_serializer(
+            data={**request.data, "namespace": namespace.id}
+        )
+        serializer.is_valid(raise_exception=True)
+        serializer.save()
+        return Response(serializer.data, status=status.HTTP_201_CREATED)
+
+
+class NamespaceActivityFeed(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List the activity in a namespace, newest first."""
+
+    permission_classes = (NameSpaceSubresource,)
+    lookup_fields = ("id", "name", "aliases__name")
+    serializer_class = ActivityEntrySerializer
+    queryset = Namespace.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Namespace activity",
+        operation_id_base="NamespaceActivity",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get the activity in a namespace.
+
+        The feed may be limited to a model (?model=host) or an action
+        (?action=created|updated|deleted), and is paginated.
+        """
+        namespace = self.get_object()
+
+        entries = ActivityEntry.objects.filter(namespace=namespace)
+        if "model" in request.query_params:
+            entries = entries.filter(model__iexact=request.query_params["model"])
+        if "action" in request.query_params:
+            entries = entries.filter(action=request.query_params["action"])
+
+        page = self.paginate_queryset(entries)
+        serializer = ActivityEntrySerializer(page, many=True)
+        return self.get_paginated_response(serializer.data)
+
+
+class NamespaceGraph(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """Export the relations between the objects in a namespace as a graph."""
+
+    permission_classes = (NameSpaceSubresource,)
+    renderer_classes = (
+        *api_settings.DEFAULT_RENDERER_CLASSES,
+        DOTRenderer,
+        GraphMLRenderer,
+    )
+    lookup_fields = ("id", "name", "aliases__name")
+    queryset = Namespace.objects.all()
+    max_depth = 16
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Namespace graph",
+        operation_id_base="NamespaceGraph",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get the graph of the objects in a namespace, and the relations between them.
+
+        The graph is rendered as JSON, DOT (?format=dot), or GraphML (?format=graphml).
+        Only the models the user can read are included. The graph may be limited to
+        some of the models (?models=host,room), and to the objects within a number
+        of relations from an object (?root=host:<id>&depth=2), at most max_depth.
+        """
+        namespace = self.get_object()
+
+        nodes, edges = self._graph(namespace, self._models(request, namespace))
+
+        if "root" in request.query_params:
+            reached = self._reached(request, nodes, edges)
+            nodes = {node_id: nodes[node_id] for node_id in reached}
+            edges = [edge for edge in edges if edge["source"] in reached]
+            edges = [edge for edge in edges if edge["target"] in reached]
+
+        return Response(
+            {"name": namespace.name, "nodes": list(nodes.values()), "edges": edges}
+        )
+
+    def _models(self, request, namespace):
+        """Find the models to include, those requested that the user can read."""
+        models = {
+            model.__name__.lower(): model
+            for model in namespaced_models()
+            if issubclass(model, NamespacedHubuumModelWithExtensions)
+        }
+
+        if "models" in request.query_params:
+            requested = request.query_params["models"].lower().split(",")
+            unknown = [name for name in requested if name not in models]
+            if unknown:
+                raise ParseError(detail=f"Unknown model(s): {', '.join(unknown)}.")
+            models = {name: models[name] for name in requested}
+
+        user = request.user
+        return [
+            model
+            for name, model in models.items()
+            if user.is_admin() or user.namespaced_can("has_read", namespace, name)
+        ]
+
+    def _graph(self, namespace, models):
+        """Find the objects of the models in the namespace, and their relations.
+
+        Relations to objects outside of the graph are left out.
+
+        returns: (dict of node ids to nodes, list of edges)
+        """
+        nodes, edges = {}, []
+        for model in models:
+            name = model.__name__.lower()
+            fields = model._meta.get_fields()  # pylint: disable=protected-access
+            relations = [
+                field
+                for field in fields
+                if (field.many_to_one or field.many_to_many)
+                and not field.auto_created
+                and field.related_model in models
+            ]
+            many = [field.name for field in relations if field.many_to_many]
+            objects = model.objects.filter(namespace=namespace).prefetch_related(*many)
+            for obj in objects:
+                node_id = f"{name}:{obj.pk}"
+                nodes[node_id] = {"id": node_id, "model": name, "label": str(obj)}
+                for field in relations:
+                    if field.many_to_one:
+                        targets = [getattr(obj, field.attname)]
+                    else:
+                        related = getattr(obj, field.name).all()
+                        targets = [target.pk for target in related]
+                    target_model = field.related_model.__name__.lower()
+                    edges.extend(
+                        {
+                            "source": node_id,
+                            "target": f"{target_model}:{target}",
+                            "relation": f"{name}.{field.name}",
+                        }
+                        for target in targets
+                        if target is not None
+                    )
+
+        return nodes, [edge for edge in edges if edge["target"] in nodes]
+
+    def _reached(self, request, nodes, edges):
+        """Find the nodes within depth relations of the root, in either direction."""
+        root = request.query_params["root"].lower()
+        if root not in nodes:
+            raise ParseError(detail=f"No object '{root}' in the graph.")
+
+        try:
+            depth = int(request.query_params.get("depth", 1))
+        except ValueError as ex:
+            raise ParseError(detail="'depth' must be an integer.") from ex
+        if not 0 <= depth <= self.max_depth:
+            raise ParseError(detail=f"'depth' must be between 0 and {self.max_depth}.")
+
+        neighbours = {}
+        for edge in edges:
+            neighbours.setdefault(edge["source"], set()).add(edge["target"])
+            neighbours.setdefault(edge["target"], set()).add(edge["source"])
+
+        reached, frontier = {root}, {root}
+        for _ in range(depth):
+            frontier = {
+                neighbour
+                for node_id in frontier
+                for neighbour in neighbours.get(node_id, ())
+            } - reached
+            if not frontier:
+                break
+            reached |= frontier
+        return reached
+
+
+class NamespaceChanges(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List the changes in a namespace after a cursor, for incremental syncing."""
+
+    permission_classes = (NameSpaceSubresource,)
+    lookup_fields = ("id", "name", "aliases__name")
+    queryset = Namespace.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Namespace changes",
+        operation_id_base="NamespaceChanges",
+    )
+
+    # The default, and the largest, number of changes returned at a time.
+    default_limit = 500
+    max_limit = 5000
+
+    def get(self, request, *args, **kwargs):
+        """Get the changes in a namespace, oldest first.
+
+        /namespaces/<namespace>/changes?since=<cursor>&limit=<count>
+
+        Without since, the changes are listed from the start. The response holds
+        the cursor to pass as since to get the changes that follow, and whether
+        there are more changes already.
+        """
+        namespace = self.get_object()
+
+        try:
+            since = int(request.query_params.get("since", 0))
+            limit = int(request.query_params.get("limit", self.default_limit))
+        except ValueError as ex:
+            raise ParseError(detail="'since' and 'limit' must be integers.") from ex
+        if not 1 <= limit <= self.max_limit:
+            raise ParseError(detail=f"'limit' must be from 1 to {self.max_limit}.")
+
+        entries = ActivityEntry.objects.filter(namespace=namespace, id__gt=since)
+        entries = list(entries.order_by("id")[: limit + 1])
+        changes = [
+            {
+                "cursor": entry.id,
+                "action": entry.action,
+                "model": entry.model.lower(),
+                "object_id": entry.object_id,
+                "object_repr": entry.object_repr,
+                "timestamp": entry.timestamp,
+            }
+            for entry in entries[:limit]
+        ]
+
+        return Response(
+            {
+                "changes": changes,
+                "cursor": changes[-1]["cursor"] if changes else since,
+                "more": len(entries) > limit,
+            }
+        )
+
+
+class NamespaceAliasDetail(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveDestroyAPIView,
+):
+    """Get or remove an alias for a namespace."""
+
+    permission_classes = (NameSpaceSubresource,)
+    lookup_fields = ("id", "name", "aliases__name")
+    serializer_class = NamespaceAliasSerializer
+    queryset = Namespace.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Namespace alias",
+        operation_id_base="NamespaceAlias",
+    )
+
+    def _get_alias(self, namespace):
+        """Find the alias given in the URL for the namespace."""
+        try:
+            return namespace.aliases.get(name=self.kwargs["alias"])
+        except NamespaceAlias.DoesNotExist as exc:
+            raise NotFound() from exc
+
+    def get(self, request, *args, **kwargs):
+        """Get a specific alias of a namespace."""
+        alias = self._get_alias(self.get_object())
+        return Response(NamespaceAliasSerializer(alias).data)
+
+    def delete(self, request, *args, **kwargs):
+        """Remove an alias from a namespace."""
+        alias = self._get_alias(self.get_object())
+        alias.delete()
+        return HttpResponse(status=status.HTTP_204_NO_CONTENT)
@@ -0,0 +1,905 @@
+"""Views for hosts and the other objects, their tags, relations, and statistics."""
+import structlog
+from django.conf import settings
+from django.contrib.contenttypes.models import ContentType
+from django.core.cache import cache
+from django.db.models import Avg, Count, Max, Q, TextField
+from django.db.models.functions import Cast, Length
+from rest_framework import generics, status
+from rest_framework.exceptions import NotFound, ParseError
+from rest_framework.schemas.openapi import AutoSchema
+from rest_framework.views import Response
+
+from hubuum.actor import get_actor
+from hubuum.exceptions import Ambiguous
+from hubuum.filters import (
+    HostFilterSet,
+    HostTypeFilterSet,
+    JackFilterSet,
+    PersonFilterSet,
+    PurchaseDocumentsFilterSet,
+    PurchaseOrderFilterSet,
+    RoomFilterSet,
+    VendorFilterSet,
+)
+from hubuum.models.base import TaggedObject
+from hubuum.models.extensions import ExtensionData
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import (
+    Host,
+    HostType,
+    Jack,
+    Person,
+    PurchaseDocuments,
+    PurchaseOrder,
+    Room,
+    Vendor,
+)
+from hubuum.permissions import (
+    IsAuthenticatedAndReadOnly,
+    NameSpace,
+    NameSpaceClone,
+    NameSpaceRelations,
+    NameSpaceTags,
+)
+from hubuum.tools import get_object
+from hubuum.validators import validate_name
+
+from ..serializers import (
+    ExtensionDataSerializer,
+    HostSerializer,
+    HostTypeSerializer,
+    JackSerializer,
+    PersonSerializer,
+    PurchaseDocumentsSerializer,
+    PurchaseOrderSerializer,
+    RoomSerializer,
+    VendorSerializer,
+)
+from .base import (
+    BulkDeleteMixin,
+    ByNameMixin,
+    HubuumDetail,
+    HubuumList,
+    MultipleFieldLookupORMixin,
+    RelationListMixin,
+)
+
+
+class HostList(BulkDeleteMixin, HubuumList):
+    """Get: List hosts. Post: Add host."""
+
+    queryset = Host.objects.all().order_by("id")
+    serializer_class = HostSerializer
+    filterset_class = HostFilterSet
+
+
+class HostDetail(HubuumDetail):
+    """Get, Patch, or Destroy a host."""
+
+    queryset = Host.objects.all()
+    serializer_class = HostSerializer
+    lookup_fields = ("id", "name", "fqdn")
+    name_field = "name"
+
+
+class HostHierarchyMixin:
+    """Shared functionality for walking the containment hierarchy of hosts.
+
+    The depth of the walk is set with the query parameter "depth", and is
+    capped at max_depth.
+    """
+
+    max_depth = 32
+
+    def _get_depth(self, default):
+        """Get the requested depth, defaulting to default."""
+        value = self.request.query_params.get("depth")
+        if value is None:
+            return default
+
+        if not value.isdigit() or int(value) < 1:
+            raise ParseError(detail="'depth' must be a positive integer.")
+
+        return min(int(value), self.max_depth)
+
+    def _hierarchy_response(self, hosts, upwards=False):
+        """Serialize the (depth, host) pairs the user is allowed to read.
+
+        The depths count the readable hosts only, so hosts the user can not read
+        are not given away by gaps in the depths. Walking down, the depth of a
+        host is that of its parent, plus one if it is readable.
+        """
+        user = self.request.user
+        readable = None if user.is_admin() else user.namespaces_where("has_read")
+
+        data, depths, depth = [], {}, 0
+        for _, host in hosts:
+            visible = readable is None or host.namespace_id in readable
+            if not upwards:
+                depth = depths.get(host.parent_id, 0)
+            depth += visible
+            depths[host.id] = depth
+            if visible:
+                entry = HostSerializer(host).data
+                entry["depth"] = depth
+                data.append(entry)
+
+        return Response(data)
+
+
+class HostChildren(
+    HostHierarchyMixin,
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List (children by default, ?depth=n) or reorder the hosts contained in a host."""
+
+    permission_classes = (NameSpace,)
+    lookup_fields = ("id", "name", "fqdn")
+    serializer_class = HostSerializer
+    queryset = Host.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Host children",
+        operation_id_base="HostChildren",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get the hosts contained in the host."""
+        host = self.get_object()
+        return self._hierarchy_response(host.descendants(self._get_depth(1)))
+
+    def put(self, request, *args, **kwargs):
+        """Reorder the children of the host.
+
+        /hosts/<hostid>/children/
+            {
+                children = [<childid|childname>, ...]
+            }
+
+        Children not listed are placed after the listed ones.
+        """
+        host = self.get_object()
+
+        if not isinstance(request.data, dict) or not isinstance(
+            request.data.get("children"), list
+        ):
+            raise ParseError(detail="API expected a list of children.")
+
+        children = {child.id: child for _, child in host.descendants()}
+        by_name = {child.name: child for child in children.values()}
+        order = []
+        for identifier in request.data["children"]:
+            if str(identifier).isdigit() and int(identifier) in children:
+                child = children[int(identifier)]
+            elif identifier in by_name:
+                child = by_name[identifier]
+            else:
+                raise ParseError(detail=f"'{identifier}' is not a child of {host}.")
+
+            if child not in order:
+                order.append(child)
+
+        host.reorder_children(order)
+        return self._hierarchy_response(host.descendants())
+
+
+class HostAncestors(
+    HostHierarchyMixin,
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List the hosts containing a host (all levels by default, ?depth=n)."""
+
+    permission_classes = (NameSpace,)
+    lookup_fields = ("id", "name", "fqdn")
+    serializer_class = HostSerializer
+    queryset = Host.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Host ancestors",
+        operation_id_base="HostAncestors",
+    )
+
+    def get(self, request, *args, **kwargs):
+        """Get the hosts containing the host, closest first."""
+        host = self.get_object()
+        ancestors = host.ancestors(self._get_depth(self.max_depth))
+        return self._hierarchy_response(ancestors, upwards=True)
+
+
+class HostClone(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """Clone a host, optionally into another namespace."""
+
+    permission_classes = (NameSpaceClone,)
+    lookup_fields = ("id", "name", "fqdn")
+    serializer_class = HostSerializer
+    queryset = Host.objects.all()
+    schema = AutoSchema(
+        tags=["LISTVIEW"],
+        component_name="Host clone",
+        operation_id_base="HostClone",
+    )
+
+    def get_target_namespace(self, obj):
+        """Get the namespace the copy is to be placed in."""
+        if "namespace" not in self.request.data:
+            return obj.namespace
+
+        return get_object(
+            Namespace, self.request.data["namespace"], lookup_fields=["id", "name"]
+        )
+
+    def post(self, request, *args, **kwargs):
+        """Clone the host.
+
+        /hosts/<hostid>/clone
+            {
+                name = <name of the copy>,
+                fqdn = <fqdn of the copy> (optional),
+                namespace = <namespaceid|namespacename> (optional),
+                relations = true,
+                extension_data = false,
+            }
+
+        With relations, the copy refers to the same type, room, jack, purchase
+        order, person, and parent as the host. Children are never copied. The copy
+        and its extension data count as writes to the namespace of the copy.
+        """
+        if not isinstance(request.data, dict) or not request.data.get("name"):
+            raise ParseError(detail="API expected a dictionary with a name.")
+
+        options = {"relations": True, "extension_data": False}
+        for option, default in options.items():
+            options[option] = request.data.get(option, default)
+            if not isinstance(options[option], bool):
+                raise ParseError(detail=f"'{option}' must be a boolean.")
+
+        values = {"name": request.data["name"]}
+        if "fqdn" in request.data:
+            values["fqdn"] = request.data["fqdn"]
+
+        host = self.get_object()
+        namespace = self.get_target_namespace(host)
+        validate_name(values["name"], namespace.naming_rules)
+
+        documents = []
+        if options["extension_data"]:
+            documents = [data.json_data for data in host.extension_data_objects.all()]
+        namespace.check_write_limits(*documents, writes=1 + len(documents))
+
+        copy = host.clone(namespace=namespace, **options, **values)
+        return Response(HostSerializer(copy).data, status=status.HTTP_201_CREATED)
+
+
+class HostTypeList(BulkDeleteMixin, HubuumList):
+    """Get: List hosttypes. Post: Add hosttype."""
+
+    queryset = HostType.objects.all().order_by("name")
+    serializer_class = HostTypeSerializer
+    filterset_class = HostTypeFilterSet
+
+
+class HostTypeDetail(HubuumDetail):
+    """Get, Patch, or Destroy a hosttype."""
+
+    queryset = HostType.objects.all()
+    serializer_class = HostTypeSerializer
+    name_field = "name"
+
+
+class RoomList(BulkDeleteMixin, HubuumList):
+    """Get: List rooms. Post: Add room."""
+
+    queryset = Room.objects.all().order_by("id")
+    serializer_class = RoomSerializer
+    filterset_class = RoomFilterSet
+
+
+class RoomDetail(HubuumDetail):
+    """Get, Patch, or Destroy a room."""
+
+    queryset = Room.objects.all()
+    serializer_class = RoomSerializer
+    name_field = "room_id"
+
+
+class JackList(BulkDeleteMixin, HubuumList):
+    """Get: List jacks. Post: Add jack."""
+
+    queryset = Jack.objects.all().order_by("name")
+    serializer_class = JackSerializer
+    filterset_class = JackFilterSet
+
+
+class JackDetail(HubuumDetail):
+    """Get, Patch, or Destroy a jack."""
+
+    queryset = Jack.objects.all()
+    serializer_class = JackSerializer
+    name_field = "name"
+
+
+class PersonList(BulkDeleteMixin, HubuumList):
+    """Get: List persons. Post: Add person."""
+
+    queryset = Person.objects.all().order_by("id")
+    serializer_class = PersonSerializer
+    filterset_class = PersonFilterSet
+
+
+class PersonDetail(HubuumDetail):
+    """Get, Patch, or Destroy a person."""
+
+    queryset = Person.objects.all()
+    serializer_class = PersonSerializer
+    name_field = "username"
+
+
+class VendorList(BulkDeleteMixin, HubuumList):
+    """Get: List vendors. Post: Add vendor."""
+
+    queryset = Vendor.objects.all().order_by("vendor_name")
+    serializer_class = VendorSerializer
+    filterset_class = VendorFilterSet
+
+
+class VendorDetail(HubuumDetail):
+    """Get, Patch, or Destroy a vendor."""
+
+    queryset = Vendor.objects.all()
+    serializer_class = VendorSerializer
+    name_field = "vendor_name"
+
+
+class PurchaseOrderList(BulkDeleteMixin, HubuumList):
+    """Get: List purchaseorders. Post: Add purchaseorder."""
+
+    queryset = PurchaseOrder.objects.all().order_by("id")
+    serializer_class = PurchaseOrderSerializer
+    filterset_class = PurchaseOrderFilterSet
+
+
+class PurchaseOrderDetail(HubuumDetail):
+    """Get, Patch, or Destroy a purchaseorder."""
+
+    queryset = PurchaseOrder.objects.all()
+    serializer_class = PurchaseOrderSerializer
+    name_field = "po_number"
+
+
+class PurchaseDocumentList(BulkDeleteMixin, HubuumList):
+    """Get: List purchasedocuments. Post: Add purchasedocument."""
+
+    queryset = PurchaseDocuments.objects.all().order_by("id")
+    serializer_class = PurchaseDocumentsSerializer
+    filterset_class = PurchaseDocumentsFilterSet
+
+
+class PurchaseDocumentDetail(HubuumDetail):
+    """Get, Patch, or Destroy a purchasedocument."""
+
+    queryset = PurchaseDocuments.objects.all()
+    serializer_class = PurchaseDocumentsSerializer
+    name_field = "document_id"
+
+
+class ObjectTagList(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List the tags of an object."""
+
+    permission_classes = (NameSpaceTags,)
+
+    def get(self, request, *args, **kwargs):
+        """Get the tags of the object."""
+        return Response(self.get_object().tags())
+
+
+class ObjectTagDetail(
+    MultipleFieldLookupORMixin,
+    generics.GenericAPIView,
+):
+    """Add or remove a tag on an object."""
+
+    permission_classes = (NameSpaceTags,)
+
+    def put(self, request, *args, **kwargs):
+        """Tag the object, returns the tags of the object.
+
+        /<model>/<objectid>/tags/<tag>
+        """
+        obj = self.get_object()
+        created = obj.add_tag(kwargs["tag"])
+        if created:
+            self._log_tags("tagged", obj, kwargs["tag"])
+        code = status.HTTP_201_CREATED if created else status.HTTP_200_OK
+        return Response(obj.tags(), status=code)
+
+    def delete(self, request, *args, **kwargs):
+        """Remove the tag from the object."""
+        obj = self.get_object()
+        if not obj.remove_tag(kwargs["tag"]):
+            raise NotFound(detail=f"{obj} is not tagged '{kwargs['tag']}'.")
+
+        self._log_tags("untagged", obj, kwargs["tag"])
+        return Response(status=status.HTTP_204_NO_CONTENT)
+
+    def _log_tags(self, operation, obj, tag):
+        """Log changes to the tags of an object."""
+        structlog.get_logger("hubuum.api.object").info(
+            operation,
+            model=obj.__class__.__name__,
+            user=str(self.request.user),
+            instance=obj.id,
+            tag=tag,
+            actor=get_actor().as_dict(),
+        )
+
+
+def tag_views(detail):
+    """Create the views for the tags of the objects of a detail view.
+
+    param: detail (the detail view of the model, ie HostDetail)
+
+    returns: a tuple of the list view and the detail view for the tags
+    """
+    model = detail.queryset.model.__name__
+    tag_views_for_model = []
+    for view in (ObjectTagList, ObjectTagDetail):
+        name = view.__name__.replace("Object", model)
+        attributes = {
+            "__doc__": view.__doc__,
+            "queryset": detail.queryset,
+            "lookup_fields": detail.lookup_fields,
+            "serializer_class": detail.serializer_class,
+            "schema": AutoSchema(
+                tags=["LISTVIEW"],
+                component_name=f"{model} tags",
+                operation_id_base=name,
+            ),
+        }
+        tag_views_for_model.append(type(name, (view,), attributes).as_view())
+
+    return tuple(tag_views_for_model)
+
+
+def by_name_view(detail):
+    """Create the view addressing the objects of a detail view by name, see ByNameMixin.
+
+    param: detail (the detail view of the model, ie HostDetail)
+
+    returns: the by-name view, with the methods of the detail view
+    """
+    model = detail.queryset.model.__name__
+    name = f"{model}ByName"
+    attributes = {
+        "__doc__": ByNameMixin.__doc__,
+        "schema": AutoSchema(component_name=f"{model} by name", operation_id_base=name),
+    }
+    return type(name, (ByNameMixin, detail), attributes).as_view()
+
+
+class ObjectReferences(
+    RelationListMixin,
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List the extension data referring to an object.
+
+    References are declared by the reference_paths of extensions. Only data the
+    user can read is listed. The list is paginated, and may be sorted by id,
+    created_at, or updated_at (?ordering=).
+    """
+
+    permission_classes = (NameSpace,)
+    ordering_fields = ("id", "created_at", "updated_at")
+
+    def get(self, request, *args, **kwargs):
+        """Get the extension data with references to the object."""
+        queryset = Namespace.visible(ExtensionData.referencing(self.get_object()))
+        if not request.user.is_admin():
+            namespaces = request.user.namespaces_where("has_read", ExtensionData)
+            queryset = queryset.filter(namespace__in=namespaces)
+
+        return self.relation_response(
+            queryset.select_related("extension", "namespace"),
+            ExtensionDataSerializer,
+            context={"request": request},
+        )
+
+
+def references_view(detail):
+    """Create the view for the references to the objects of a detail view.
+
+    param: detail (the detail view of the model, ie HostDetail)
+
+    returns: the references view
+    """
+    model = detail.queryset.model.__name__
+    name = f"{model}References"
+    attributes = {
+        "__doc__": ObjectReferences.__doc__,
+        "queryset": detail.queryset,
+        "lookup_fields": detail.lookup_fields,
+        "serializer_class": ExtensionDataSerializer,
+        "schema": AutoSchema(
+            tags=["LISTVIEW"],
+            component_name=f"{model} references",
+            operation_id_base=name,
+        ),
+    }
+    return type(name, (ObjectReferences,), attributes).as_view()
+
+
+def readable(user, queryset):
+    """Restrict a queryset of namespaced objects to those the user can read."""
+    queryset = Namespace.visible(queryset)
+    if user.is_admin():
+        return queryset
+    namespaces = user.namespaces_where("has_read", queryset.model)
+    return queryset.filter(namespace__in=namespaces)
+
+
+class ObjectTraverse(
+    MultipleFieldLookupORMixin,
+    generics.RetrieveAPIView,
+):
+    """List the objects reached by following relations from an object.
+
+    /<model>/<val>/traverse?path=room.Person follows the relations given, each by
+    its name (ie "room") or by the name of the model related to (ie "Person"), at
+    most max_depth of them. For users other than admins, objects they can not
+    read are left out at every step.
+    """
+
+    permission_classes = (NameSpace,)
+    max_depth = 8
+    serializers = {
+        serializer.Meta.model: serializer
+        for serializer in (
+            HostSerializer,
+            HostTypeSerializer,
+            JackSerializer,
+            PersonSerializer,
+            PurchaseDocumentsSerializer,
+            PurchaseOrderSerializer,
+            RoomSerializer,
+            VendorSerializer,
+        )
+    }
+
+    def get(self, request, *args, **kwargs):
+        """Get the objects at the end of the path."""
+        path = request.query_params.get("path", "")
+        steps = path.split(".")
+        if not all(steps):
+            raise ParseError(detail="'path' must be relations separated by dots.")
+        if len(steps) > self.max_depth:
+            raise ParseError(detail=f"'path' may have at most {self.max_depth} steps.")
+
+        def restrict(queryset):
+            """Hide the objects the user can not read."""
+            return readable(request.user, queryset)
+
+        obj = self.get_object()
+        names, model = [], type(obj)
+        try:
+            for step in steps:
+                name, _, model = model.relation_for(step)
+                names.append(name)
+        except LookupError as ex:
+            raise ParseError(detail=str(ex)) from ex
+
+        objects = obj.traverse(names, restrict=restrict)
+        return Response(
+            {
+                "path": names,
+                "model": model._meta.model_name,  # pylint: disable=protected-access
+                "objects": self.serializers[model](objects, many=True).data,
+            }
+        )
+
+
+def traverse_view(detail):
+    """Create the view for traversing relations from the objects of a detail view.
+
+    param: detail (the detail view of the model, ie HostDetail)
+
+    returns: the traverse view
+    """
+    model = detail.queryset.model.__name__
+    name = f"{model}Traverse"
+    attributes = {
+        "__doc__": ObjectTraverse.__doc__,
+        "queryset": detail.queryset,
+        "lookup_fields": detail.lookup_fields,
+        "serializer_class": detail.serializer_class,
+        "schema": AutoSchema(
+            tags=["LISTVIEW"],
+            component_name=f"{model} traverse",
+            operation_id_base=name,
+        ),
+    }
+    return type(name, (ObjectTraverse,), attributes).as_view()
+
+
+class ObjectRelate(
+    MultipleFieldLookupORMixin,
+    generics.GenericAPIView,
+):
+    """Relate an object to another, knowing only the id of the other object.
+
+    POST /<model>/<val>/relations with {"id": <id>} finds the relations of the
+    model (see /related/<model>) to models with an object by that id the user can
+    read. The relation matching is set, on the object for its own foreign keys (ie
+    "room"), or on the other object for relations referring to it (ie "hosts").
+    If several relations match, the request gets 409, and "relation" (ie "parent")
+    or "model" (ie "room") must be given to choose one.
+    """
+
+    permission_classes = (NameSpaceRelations,)
+    _relation = None
+
+    def get_relation(self, obj):
+        """Find the relation requested, see NameSpaceRelations.
+
+        returns: {"relation", "direction", "related", "changed", "field", "value"}
+        raises: ParseError, NotFound, or Ambiguous unless exactly one relation matches
+        """
+        if self._relation is not None:
+            return self._relation
+
+        data = self.request.data if isinstance(self.request.data, dict) else {}
+        pk = data.get("id")
+        if isinstance(pk, bool) or not isinstance(pk, int):
+            raise ParseError(detail="'id' must be the id of the object to relate to.")
+        wanted = {key: data.get(key) for key in ("relation", "model")}
+        if wanted["model"] is not None:
+            wanted["model"] = str(wanted["model"]).lower()
+
+        matches = []
+        for name, (field, related) in sorted(type(obj).relations().items()):
+            model = related.__name__.lower()
+            if wanted["relation"] not in (None, name):
+                continue
+            if wanted["model"] not in (None, model):
+                continue
+            target = readable(self.request.user, related.objects.filter(pk=pk)).first()
+            if target is not None and target != obj:
+                matches.append((name, field, target))
+
+        if not matches:
+            raise NotFound(detail=f"{obj} has no relation to an object with id {pk}.")
+        if len(matches) > 1:
+            names = ", ".join(name for name, _, _ in matches)
+            raise Ambiguous(
+                detail=f"The id {pk} matches the relations {names}, give 'relation'."
+            )
+
+        name, field, target = matches[0]
+        if field.concrete:
+            relation = {"changed": obj, "field": field.name, "value": target}
+        else:
+            relation = {"changed": target, "field": field.field.name, "value": obj}
+        self._relation = {
+            "relation": name,
+            "direction": "forward" if field.concrete else "reverse",
+            "related": target,
+            **relation,
+        }
+        return self._relation
+
+    def post(self, request, *args, **kwargs):
+        """Set the relation, returns the object and the object related to."""
+        obj = self.get_object()
+        relation = self.get_relation(obj)
+        changed = relation["changed"]
+        setattr(changed, relation["field"], relation["value"])
+        changed.save()
+
+        related = relation["related"]
+        obj.refresh_from_db()
+        structlog.get_logger("hubuum.api.object").info(
+            "related",
+            model=obj.__class__.__name__,
+            user=str(request.user),
+            instance=obj.id,
+            relation=relation["relation"],
+            related=related.id,
+            actor=get_actor().as_dict(),
+        )
+        serializers = ObjectTraverse.serializers
+        return Response(
+            {
+                "relation": relation["relation"],
+                "direction": relation["direction"],
+                "model": related._meta.model_name,  # pylint: disable=protected-access
+                "object": serializers[type(obj)](obj).data,
+                "related": serializers[type(related)](related).data,
+            }
+        )
+
+
+def relate_view(detail):
+    """Create the view for relating the objects of a detail view to others.
+
+    param: detail (the detail view of the model, ie HostDetail)
+
+    returns: the relate view
+    """
+    model = detail.queryset.model.__name__
+    name = f"{model}Relate"
+    attributes = {
+        "__doc__": ObjectRelate.__doc__,
+        "queryset": detail.queryset,
+        "lookup_fields": detail.lookup_fields,
+        "serializer_class": detail.serializer_class,
+        "schema": AutoSchema(
+            tags=["LISTVIEW"],
+            component_name=f"{model} relate",
+            operation_id_base=name,
+        ),
+    }
+    return type(name, (ObjectRelate,), attributes).as_view()
+
+
+class ModelRelations(generics.GenericAPIView):
+    """The models reachable from a model by following relations, ie for navigation.
+
+    /related/<model> lists every model related to the model, directly or through
+    other models, by the shortest path. ?depth= limits how far relations are
+    followed. Every path may be given to /<model>/<val>/traverse.
+    """
+
+    permission_classes = (IsAuthenticatedAndReadOnly,)
+
+    def get(self, request, *args, **kwargs):
+        """Get the related models, with their depth, path, and direction."""
+        depth = request.query_params.get("depth")
+        if depth is not None:
+            if not depth.isdigit() or int(depth) < 1:
+                raise ParseError(detail="'depth' must be a positive integer.")
+            depth = int(depth)
+
+        model = self.get_queryset().model
+        return Response(
+            {
+                "model": model.__name__.lower(),
+                "related": model.related_models(max_depth=depth),
+            }
+        )
+
+
+def related_view(list_view):
+    """Create the view for the models related to the model of a list view.
+
+    param: list_view (the list view of the model, ie HostList)
+
+    returns: the related models view
+    """
+    model = list_view.queryset.model.__name__
+    name = f"{model}Relations"
+    attributes = {
+        "__doc__": ModelRelations.__doc__,
+        "queryset": list_view.queryset,
+        "schema": AutoSchema(
+            tags=["LISTVIEW"],
+            component_name=f"{model} relations",
+            operation_id_base=name,
+        ),
+    }
+    return type(name, (ModelRelations,), attributes).as_view()
+
+
+class ObjectStatistics(generics.GenericAPIView):
+    """Statistics for the objects of a model, ie for dashboards.
+
+    Only objects the user can read are counted, and the filters of the list view
+    of the model apply, ie /stats/hosts?namespace=1. The result is cached for
+    HUBUUM_STATISTICS_CACHE_TIME seconds.
+    """
+
+    permission_classes = (IsAuthenticatedAndReadOnly,)
+
+    def get(self, request, *args, **kwargs):
+        """Get the statistics for the objects the user can see.
+
+        Returns the number of objects, when an object was last modified, the
+        number of objects with ie a room and the number of related objects
+        (relations), the number of extension data objects and their average size
+        (in characters), and the number of tags on the objects.
+        """
+        model = self.get_queryset().model
+        key = ":".join(
+            [
+                "hubuum.stats",
+                model.__name__.lower(),
+                str(request.user.id),
+                request.GET.urlencode(),
+            ]
+        )
+        statistics = cache.get(key)
+        if statistics is None:
+            statistics = self._statistics(self.filter_queryset(self.get_queryset()))
+            cache.set(key, statistics, settings.STATISTICS_CACHE_TIME)
+
+        return Response(statistics)
+
+    def _statistics(self, queryset):
+        """Compute the statistics for a queryset, using aggregate queries.
+
+        The objects are counted distinctly, as filters on relations may join in
+        an object more than once.
+        """
+        model = queryset.model
+        fields = model._meta.get_fields()  # pylint: disable=protected-access
+        references = [
+            field.name
+            for field in fields
+            if field.many_to_one and field.concrete and field.name != "namespace"
+        ]
+        related = [
+            field.name for field in fields if field.one_to_many and field.auto_created
+        ]
+
+        aggregates = queryset.aggregate(
+            count=Count("id", distinct=True),
+            last_modified=Max("updated_at"),
+            **{
+                f"relation_{name}": Count(
+                    "id", distinct=True, filter=Q(**{f"{name}__isnull": False})
+                )
+                for name in references
+            },
+        )
+        relations = {name: aggregates.pop(f"relation_{name}") for name in references}
+        if related:
+            relations.update(
+                queryset.aggregate(
+                    **{name: Count(name, distinct=True) for name in related}
+                )
+            )
+
+        generic = {
+            "content_type": ContentType.objects.get_for_model(model),
+            "object_id__in": queryset.values("id"),
+        }
+        extension_data = ExtensionData.objects.filter(**generic).aggregate(
+            count=Count("id", distinct=True),
+            average_size=Avg(Length(Cast("json_data", output_field=TextField()))),
+        )
+
+        return {
+            **aggregates,
+            "relations": relations,
+            "extension_data": extension_data,
+            "tags": TaggedObject.objects.filter(**generic).count(),
+        }
+
+
+def statistics_view(list_view):
+    """Create the statistics view for the objects of a list view.
+
+    param: list_view (the list view of the model, ie HostList)
+
+    returns: the statistics view
+    """
+    model = list_view.queryset.model.__name__
+    name = f"{model}Statistics"
+    attributes = {
+        "__doc__": ObjectStatistics.__doc__,
+        "queryset": list_view.queryset,
+        "filterset_class": list_view.filterset_class,
+        "schema": AutoSchema(
+            tags=["LISTVIEW"],
+            component_name=f"{model} statistics",
+            operation_id_base=name,
+        ),
+    }
+    return type(name, (ObjectStatistics,), attributes).as_view()
@@ -1,7 +1,38 @@
 """Non-versioned views for hubuum."""
+import os
+import platform
+import resource
+import threading
 
+import django
+from django.apps import apps
+from django.conf import settings
+from django.db import connection, transaction
+from django.http import HttpResponse, StreamingHttpResponse
+from django.utils import timezone
+from django.utils.decorators import method_decorator
+from knox.models import AuthToken
 from knox.views import LoginView as KnoxLoginView
-from rest_framework.authentication import BasicAuthentication
+from knox.views import LogoutAllView as KnoxLogoutAllView
+from knox.views import LogoutView as KnoxLogoutView
+from rest_framework import status
+from rest_framework.exceptions import NotFound, ParseError
+from rest_framework.views import APIView, Response
+
+from hubuum import denials, dump, maintenance, metrics, runtime, seed
+from hubuum.api.authentication import (
+    ActorBasicAuthentication,
+    ActorTokenAuthentication,
+)
+from hubuum.api.v1.serializers import LoginEventSerializer
+from hubuum.exceptions import Conflict
+from hubuum.models.auth import TokenMetadata
+from hubuum.models.base import ReadOnlyMode, RuntimeSetting
+from hubuum.models.namespace import Namespace
+from hubuum.network import parse_networks
+from hubuum.permissions import IsSuperOrAdmin
+from hubuum.renderers import ndjson_lines
+from hubuum.tools import get_object
 
 
 # Allow basic auth to the Knox login view.
@@ -13,4 +44,386 @@ class LoginView(KnoxLoginView):
     https://james1345.github.io/django-rest-knox/auth/#global-usage-on-all-views
     """
 
-    authentication_classes = [BasicAuthentication]
+    authentication_classes = [ActorBasicAuthentication]
+
+    bind_cidr = ""
+
+    def post(self, request, *args, **kwargs):
+        """Log in, optionally binding the token to networks with bind_cidr.
+
+        bind_cidr is a comma separated list of networks or addresses, ie
+        "10.0.0.0/24,192.0.2.1". The token is only accepted from clients within them.
+        """
+        if not isinstance(request.data, dict):
+            raise ParseError(detail="API expected a dictionary, or no body.")
+
+        value = request.data.get("bind_cidr") or ""
+        try:
+            if not isinstance(value, str):
+                raise ValueError("expected a comma separated string")
+            networks = parse_networks(value)
+        except ValueError as ex:
+            raise ParseError(detail=f"Invalid bind_cidr: {ex}") from ex
+
+        self.bind_cidr = ",".join(str(network) for network in networks)
+        # pylint: disable=protected-access
+        field = TokenMetadata._meta.get_field("bind_cidr")
+        if len(self.bind_cidr) > field.max_length:
+            raise ParseError(detail="Invalid bind_cidr: too many networks.")
+
+        return super().post(request, *args, **kwargs)
+
+    def get_post_response_data(self, request, token, instance):
+        """Bind the token created, and include the binding in the response."""
+        data = super().get_post_response_data(request, token, instance)
+        if self.bind_cidr:
+            TokenMetadata.objects.create(token=instance, bind_cidr=self.bind_cidr)
+            data["bind_cidr"] = self.bind_cidr
+        return data
+
+
+class LogoutView(KnoxLogoutView):
+    """Log out the token used, attributing the deletion to the user."""
+
+    authentication_classes = [ActorTokenAuthentication]
+
+
+class LogoutAllView(KnoxLogoutAllView):
+    """Log out all the tokens of the user, attributing the deletions to the user."""
+
+    authentication_classes = [ActorTokenAuthentication]
+
+
+class MetricsView(APIView):
+    """Expose the hubuum counters in the OpenMetrics text format (admins only)."""
+
+    permission_classes = (IsSuperOrAdmin,)
+    schema = None
+
+    def get(self, request, *args, **kwargs):
+        """Render the counters."""
+        return HttpResponse(metrics.render(), content_type=metrics.CONTENT_TYPE)
+
+
+class DenialsView(APIView):
+    """Report the denied (401 and 403) requests, aggregated (admins only).
+
+    See hubuum.denials. The denials are those seen by the process (worker)
+    answering the request.
+    """
+
+    permission_classes = (IsSuperOrAdmin,)
+    schema = None
+
+    def get(self, request, *args, **kwargs):
+        """Get the denials, the most frequent first."""
+        return Response(denials.report())
+
+    def delete(self, request, *args, **kwargs):
+        """Forget the denials."""
+        denials.reset()
+        return Response(status=status.HTTP_204_NO_CONTENT)
+
+
+class ServerInfoView(APIView):
+    """Report the effective server settings and process statistics (admins only).
+
+    The statistics are for the process (worker) answering the request.
+    """
+
+    permission_classes = (IsSuperOrAdmin,)
+    schema = None
+
+    def get(self, request, *args, **kwargs):
+        """Get the server information."""
+        started_at = apps.get_app_config("hubuum").started_at
+        usage = resource.getrusage(resource.RUSAGE_SELF)
+        database = settings.DATABASES["default"]
+        return Response(
+            {
+                "versions": {
+                    "python": platform.python_version(),
+                    "django": django.get_version(),
+                },
+                "server": settings.SERVER,
+                "database": {
+                    "vendor": connection.vendor,
+                    "conn_max_age": database.get("CONN_MAX_AGE", 0),
+                    "atomic_requests": database.get("ATOMIC_REQUESTS", False),
+                },
+                "process": {
+                    "pid": os.getpid(),
+                    "started_at": started_at,
+                    "uptime": (timezone.now() - started_at).total_seconds(),
+                    "threads": threading.active_count(),
+                    "cpu_seconds": usage.ru_utime + usage.ru_stime,
+                    # Kilobytes on Linux, bytes on macOS.
+                    "max_rss": usage.ru_maxrss,
+                },
+            }
+        )
+
+
+@method_decorator(transaction.non_atomic_requests, name="dispatch")
+class MaintenanceView(APIView):
+    """Report table statistics, and run database maintenance (admins only).
+
+    See hubuum.maintenance. Requests are not wrapped in a transaction, to allow
+    VACUUM even with ATOMIC_REQUESTS.
+    """
+
+    permission_classes = (IsSuperOrAdmin,)
+    schema = None
+
+    def get(self, request, *args, **kwargs):
+        """Get the statistics of the hot tables, and the running operation."""
+        return Response(
+            {
+                "tables": maintenance.table_statistics(),
+                "running": maintenance.progress(),
+            }
+        )
+
+    def post(self, request, *args, **kwargs):
+        """Run a maintenance operation.
+
+        /api/maintenance/
+            {
+                operation = "analyze" | "reindex" | "vacuum",
+                tables = [...], (optional, defaults to all the hot tables)
+            }
+        """
+        if not isinstance(request.data, dict) or "operation" not in request.data:
+            raise ParseError(detail="API expected a dictionary with an operation.")
+
+        tables = request.data.get("tables")
+        if tables is not None and not isinstance(tables, list):
+            raise ParseError(detail="'tables' must be a list.")
+
+        operation = request.data["operation"]
+        try:
+            results = maintenance.run(operation, tables)
+        except ValueError as ex:
+            raise ParseError(detail=str(ex)) from ex
+        except maintenance.MaintenanceError as ex:
+            raise Conflict(detail=str(ex)) from ex
+
+        return Response({"operation": operation, "results": results})
+
+
+class SeedView(APIView):
+    """Apply declarative seed data (admins only), see hubuum.seed."""
+
+    permission_classes = (IsSuperOrAdmin,)
+    schema = None
+
+    def post(self, request, *args, **kwargs):
+        """Apply the seed in the request, report what was created and updated."""
+        try:
+            return Response(seed.apply(request.data))
+        except seed.SeedError as ex:
+            raise ParseError(detail=str(ex)) from ex
+
+
+class DumpView(APIView):
+    """Take and restore logical dumps (admins only), see hubuum.dump.
+
+    GET streams a dump as NDJSON, POST restores the NDJSON dump in the body.
+    """
+
+    permission_classes = (IsSuperOrAdmin,)
+    schema = None
+
+    def get(self, request, *args, **kwargs):
+        """Stream a dump of all the data."""
+        response = StreamingHttpResponse(
+            ndjson_lines(dump.dump()),
+            content_type="application/x-ndjson; charset=utf-8",
+        )
+        filename = timezone.now().strftime("hubuum-%Y%m%dT%H%M%SZ.ndjson")
+        response["Content-Disposition"] = f'attachment; filename="{filename}"'
+        return response
+
+    def post(self, request, *args, **kwargs):
+        """Restore the dump in the request, report the objects restored per model."""
+        if request.stream is None:
+            raise ParseError(detail="The request holds no dump.")
+        try:
+            return Response({"restored": dump.restore(request.stream)})
+        except dump.DumpError as ex:
+            raise ParseError(detail=str(ex)) from ex
+
+
+class ReadOnlyView(APIView):
+    """Put the server, or a namespace, in read-only (maintenance) mode (admins only).
+
+    /api/read-only/ lists the modes in effect, and is the mode of the server.
+    /api/read-only/<namespace> is the mode of a namespace. See ReadOnlyMode.
+    """
+
+    permission_classes = (IsSuperOrAdmin,)
+    schema = None
+
+    @staticmethod
+    def _as_dict(mode):
+        """Represent a mode."""
+        return {
+            "namespace": mode.namespace.name if mode.namespace_id else None,
+            "reason": mode.reason,
+            "retry_after": mode.retry_after,
+            "created_at": mode.created_at,
+        }
+
+    def _namespace(self):
+        """Get the namespace of the request, None for the server."""
+        if "namespace" not in self.kwargs:
+            return None
+        return get_object(
+            Namespace, self.kwargs["namespace"], ["id", "name", "aliases__name"]
+        )
+
+    def get(self, request, *args, **kwargs):
+        """Get the mode of a namespace, or every mode in effect for the server."""
+        namespace = self._namespace()
+        if namespace is None:
+            modes = ReadOnlyMode.objects.select_related("namespace")
+            return Response([self._as_dict(mode) for mode in modes])
+
+        mode = ReadOnlyMode.objects.filter(namespace=namespace).first()
+        if mode is None:
+            raise NotFound(detail=f"The namespace {namespace} is not read-only.")
+        return Response(self._as_dict(mode))
+
+    def put(self, request, *args, **kwargs):
+        """Make the server, or a namespace, read-only.
+
+        /api/read-only/[<namespace>]
+            {
+                reason = "...", (optional, given to clients)
+                retry_after = <seconds>, (optional, defaults to 300)
+            }
+        """
+        data = request.data if isinstance(request.data, dict) else None
+        if data is None or set(data) - {"reason", "retry_after"}:
+            raise ParseError(detail="API expected a dictionary of reason, retry_after.")
+
+        reason = data.get("reason", "")
+        retry_after = data.get("retry_after", 300)
+        if not isinstance(reason, str):
+            raise ParseError(detail="'reason' must be a string.")
+        valid = isinstance(retry_after, int) and not isinstance(retry_after, bool)
+        if not valid or retry_after < 0:
+            raise ParseError(detail="'retry_after' must be a number of seconds.")
+
+        mode, created = ReadOnlyMode.objects.update_or_create(
+            namespace=self._namespace(),
+            defaults={"reason": reason, "retry_after": retry_after},
+        )
+        code = status.HTTP_201_CREATED if created else status.HTTP_200_OK
+        return Response(self._as_dict(mode), status=code)
+
+    def delete(self, request, *args, **kwargs):
+        """Make the server, or a namespace, writable again."""
+        mode = ReadOnlyMode.objects.filter(namespace=self._namespace()).first()
+        if mode is None:
+            raise NotFound(detail="Not read-only.")
+        mode.delete()
+        return Response(status=status.HTTP_204_NO_CONTENT)
+
+
+class RuntimeSettingsView(APIView):
+    """Change the logging levels and other settings at runtime (admins only).
+
+    The settings are changed for every process, within
+    HUBUUM_RUNTIME_SETTINGS_CACHE_TIME seconds, and until they are changed back.
+    Other settings need a restart. See hubuum.runtime.
+
+    Settings are checked against each other, with the values in effect for those
+    not changed, ie page_size may not be changed to above max_page_size.
+    """
+
+    permission_classes = (IsSuperOrAdmin,)
+    schema = None
+
+    def get(self, request, *args, **kwargs):
+        """Get the settings that may be changed, with their values in effect."""
+        return Response(runtime.report())
+
+    def patch(self, request, *args, **kwargs):
+        """Change settings, or change them back to their configured values.
+
+        /api/runtime-settings/
+            {
+                "logging_level_database": "debug",
+                "page_size": 50,
+                "explain_permissions": null, (change back)
+            }
+        """
+        data = request.data if isinstance(request.data, dict) else None
+        if not data:
+            raise ParseError(detail="API expected a dictionary of settings.")
+
+        errors = {}
+        for name, value in data.items():
+            error = runtime.validate(name, value)
+            if error:
+                errors[name] = error
+        if not errors:
+            errors = runtime.conflicts(data, RuntimeSetting.overrides())
+        if errors:
+            raise ParseError(detail=errors)
+
+        with transaction.atomic():
+            for name, value in data.items():
+                if value is not None:
+                    RuntimeSetting.objects.update_or_create(
+                        name=name.upper(), defaults={"value": str(value)}
+                    )
+                    continue
+                for setting in RuntimeSetting.objects.filter(name=name.upper()):
+                    setting.delete()
+        runtime.apply(RuntimeSetting.overrides())
+        return Response(runtime.report())
+
+
+class TokenList(APIView):
+    """List the active tokens of the user."""
+
+    schema = None
+
+    def get(self, request, *args, **kwargs):
+        """List the active tokens, with their usage metadata."""
+        return Response(
+            [
+                {**TokenMetadata.describe(token), "current": token == request.auth}
+                for token in request.user.active_tokens()
+            ]
+        )
+
+
+class LoginList(APIView):
+    """List the logins of the user, newest first."""
+
+    schema = None
+
+    def get(self, request, *args, **kwargs):
+        """List the login history of the user, see LoginEvent."""
+        logins = request.user.login_events.all()
+        return Response(LoginEventSerializer(logins, many=True).data)
+
+
+class TokenDetail(APIView):
+    """Revoke one of the tokens of the user."""
+
+    schema = None
+
+    def delete(self, request, *args, **kwargs):
+        """Revoke the token, identified by its (public) token key."""
+        token = AuthToken.objects.filter(
+            user=request.user, token_key=kwargs["token_key"]
+        ).first()
+        if token is None:
+            raise NotFound()
+
+        token.delete()
+        return Response(status=status.HTTP_204_NO_CONTENT)
@@ -1,6 +1,8 @@
 """A basic app config for hubuum."""
 
 from django.apps import AppConfig
+from django.conf import settings
+from django.utils import timezone
 
 
 class HubuumApiConfig(AppConfig):
@@ -10,4 +12,12 @@ class HubuumApiConfig(AppConfig):
 
     def ready(self):
         """Initialize core services."""
+        # When the process started serving, reported by /api/server-info/.
+        self.started_at = timezone.now()
+        if settings.TRACING_OTLP_ENDPOINT:
+            from hubuum import tracing  # pylint: disable=import-outside-toplevel
+
+            tracing.configure_otlp(
+                settings.TRACING_OTLP_ENDPOINT, settings.TRACING_SERVICE_NAME
+            )
         import hubuum.signals  # noqa: F401 pylint: disable=unused-import,import-outside-toplevel
@@ -0,0 +1,212 @@
+"""A client for the hubuum API, for integrations and scripts.
+
+    from hubuum.client import Client
+
+    client = Client("https://hubuum.example.com")
+    client.login("alice", "secret")
+    for host in client.hosts.list(namespace__name="infra", name__startswith="web"):
+        client.hosts.update(host["id"], fqdn=f"{host['name']}.example.com")
+    client.hosts.post("web1", "clone", name="web2")
+    client.request("GET", "/api/server-info/")
+
+Every collection of the v1 API is a Resource of the client (see RESOURCES), with
+methods for listing, getting, creating, updating, and deleting objects, and for
+the routes below an object (ie hosts.get("web1", "tags/")). Objects are given and
+returned as dictionaries, as serialized by the API. Errors are raised as
+ClientError, with the status and the data of the response.
+
+Requests are sent with the requests package (pip install hubuum[client]), or with
+any session with the same request method, ie an adapter for a test client.
+
+This module must not depend on Django, or on the rest of hubuum.
+"""
+from base64 import b64encode
+from typing import Any, Dict, List, Optional, Union
+from urllib.parse import quote
+
+# The collections of the v1 API, by the name of their attribute in the client.
+RESOURCES = {
+    "users": "users/",
+    "groups": "groups/",
+    "provisioning": "provisioning/",
+    "permissions": "permissions/",
+    "delete_policies": "delete_policies/",
+    "lifecycles": "lifecycles/",
+    "namespaces": "namespaces/",
+    "schemas": "schemas/",
+    "extensions": "extensions/",
+    "extension_data": "extension_data/",
+    "hosts": "hosts/",
+    "hosttypes": "hosttypes/",
+    "rooms": "rooms/",
+    "jacks": "jacks/",
+    "persons": "persons/",
+    "vendors": "vendors/",
+    "purchase_orders": "pos/",
+    "purchase_documents": "purchasedocuments/",
+}
+
+
+class ClientError(Exception):
+    """An error response from the API."""
+
+    def __init__(self, status: int, data: Any):
+        """Keep the status and the data of the response."""
+        self.status = status
+        self.data = data
+        detail = data.get("detail", data) if isinstance(data, dict) else data
+        super().__init__(f"{status}: {detail}")
+
+
+class Resource:
+    """A collection of the API, ie hosts/."""
+
+    def __init__(self, client: "Client", path: str):
+        """Create the resource, for the path of the collection (ie "hosts/")."""
+        self.client = client
+        self.path = path
+
+    def _path(self, val: Union[int, str], *parts: str) -> str:
+        """Return the path of an object, or of a route below it."""
+        return "/".join([self.path.rstrip("/"), quote(str(val), safe=""), *parts])
+
+    def list(self, **filters: Any) -> List[Dict[str, Any]]:
+        """List the objects matching the filters, from every page."""
+        objects: List[Dict[str, Any]] = []
+        page = 1
+        while True:
+            params = {**filters, "envelope": True, "page": page}
+            data = self.client.request("GET", self.path, params=params)
+            if not isinstance(data, dict) or "meta" not in data:
+                return data
+
+            objects += data["data"]
+            if not data["data"] or len(objects) >= data["meta"]["count"]:
+                return objects
+            page += 1
+
+    def get(self, val: Union[int, str], *parts: str, **params: Any) -> Any:
+        """Get an object by id or name, or a route below it (ie "tags/")."""
+        return self.client.request("GET", self._path(val, *parts), params=params)
+
+    def create(self, **fields: Any) -> Dict[str, Any]:
+        """Create an object."""
+        return self.client.request("POST", self.path, json=fields)
+
+    def update(self, val: Union[int, str], **fields: Any) -> Dict[str, Any]:
+        """Update the given fields of an object."""
+        return self.client.request("PATCH", self._path(val), json=fields)
+
+    def delete(self, val: Union[int, str], *parts: str, **params: Any) -> Any:
+        """Delete an object, or a route below it (ie "tags/production")."""
+        return self.client.request("DELETE", self._path(val, *parts), params=params)
+
+    def post(self, val: Union[int, str], *parts: str, **data: Any) -> Any:
+        """Post to a route below an object, ie post("web1", "clone", name="web2")."""
+        return self.client.request("POST", self._path(val, *parts), json=data)
+
+    def put(self, val: Union[int, str], *parts: str, **data: Any) -> Any:
+        """Put to a route below an object, ie put("web1", "tags/production")."""
+        return self.client.request("PUT", self._path(val, *parts), json=data)
+
+
+class Client:
+    """A client for a hubuum server."""
+
+    def __init__(
+        self,
+        url: str,
+        token: Optional[str] = None,
+        session: Any = None,
+        timeout: Optional[float] = 30,
+    ):
+        """Create the client.
+
+        param: url (the URL of the server, ie "https://hubuum.example.com")
+        param: token (a token from logging in, see login)
+        param: session (sends the requests, a requests.Session by default)
+        param: timeout (the seconds to wait for responses, 30)
+        """
+        if session is None:
+            # pylint: disable=import-outside-toplevel
+            try:
+                import requests
+            except ImportError as ex:
+                raise ImportError(
+                    "The hubuum client requires the requests package to be "
+                    "installed, ie with pip install hubuum[client]."
+                ) from ex
+            session = requests.Session()
+
+        self.url = url.rstrip("/")
+        self.token = token
+        self.session = session
+        self.timeout = timeout
+        for name, path in RESOURCES.items():
+            setattr(self, name, Resource(self, path))
+
+    def login(self, username: str, password: str, bind_cidr: str = "") -> str:
+        """Log in, and use the token for later requests.
+
+        param: bind_cidr (networks the token is only accepted from, see the API)
+
+        returns: the token
+        """
+        credentials = b64encode(f"{username}:{password}".encode("utf-8"))
+        data = self.request(
+            "POST",
+            "/api/auth/login/",
+            json={"bind_cidr": bind_cidr} if bind_cidr else {},
+            headers={"Authorization": f"Basic {credentials.decode('ascii')}"},
+        )
+        self.token = data["token"]
+        return self.token
+
+    def logout(self):
+        """Log out, revoking the token."""
+        self.request("POST", "/api/auth/logout/")
+        self.token = None
+
+    def request(
+        self,
+        method: str,
+        path: str,
+        params: Optional[Dict[str, Any]] = None,
+        json: Any = None,
+        headers: Optional[Dict[str, str]] = None,
+    ) -> Any:
+        """Send a request to the API, and return the data of the response.
+
+        Paths starting with / are relative to the server, others to the v1 API.
+
+        returns: the data of the response, None if it has none
+        raises: ClientError if the response is an error
+        """
+        if not path.startswith("/"):
+            path = f"/api/v1/{path}"
+        params = {
+            key: str(value).lower() if isinstance(value, bool) else value
+            for key, value in (params or {}).items()
+        }
+        headers = {"Accept": "application/json", **(headers or {})}
+        if self.token and "Authorization" not in headers:
+            headers["Authorization"] = f"Token {self.token}"
+
+        response = self.session.request(
+            method,
+            f"{self.url}{path}",
+            params=params,
+            json=json,
+            headers=headers,
+            timeout=self.timeout,
+        )
+        data = None
+        if response.content:
+            try:
+                data = response.json()
+            except ValueError:
+                data = response.content.decode("utf-8", errors="replace")
+
+        if response.status_code >= 400:
+            raise ClientError(response.status_code, data)
+        return data
@@ -0,0 +1,65 @@
+"""Aggregated reporting of denied requests (401 and 403), for /api/denials/.
+
+Denials are counted per user, method, route (the URL pattern, ie "hosts/<val>"),
+namespace (if a namespace permission was missing), and status. This lets operators
+spot ie misconfigured automation hammering endpoints it can not use.
+
+Like the counters in hubuum.metrics, the denials are kept in memory, per process.
+At most DENIAL_TRACKING_SIZE combinations are tracked, denials of other
+combinations are only counted as dropped.
+"""
+import threading
+
+from django.conf import settings
+from django.utils import timezone
+
+_lock = threading.Lock()
+_denials = {}
+_dropped = 0
+
+
+def record(user, method, route, namespace, status):
+    """Count a denied request.
+
+    param: user (the username, None for anonymous requests)
+    param: namespace (the name or id of the namespace, None if not known)
+    """
+    global _dropped  # pylint: disable=global-statement
+    key = (user, method, route, None if namespace is None else str(namespace), status)
+    now = timezone.now()
+    with _lock:
+        if key in _denials:
+            _denials[key]["count"] += 1
+            _denials[key]["last_seen"] = now
+        elif len(_denials) < settings.DENIAL_TRACKING_SIZE:
+            _denials[key] = {"count": 1, "first_seen": now, "last_seen": now}
+        else:
+            _dropped += 1
+
+
+def report():
+    """Return the denials, the most frequent first, and the number dropped."""
+    with _lock:
+        denials = [
+            {
+                "user": user,
+                "method": method,
+                "route": route,
+                "namespace": namespace,
+                "status": status,
+                **counts,
+            }
+            for (user, method, route, namespace, status), counts in _denials.items()
+        ]
+        dropped = _dropped
+
+    denials.sort(key=lambda denial: (-denial["count"], str(denial["user"])))
+    return {"denials": denials, "dropped": dropped}
+
+
+def reset():
+    """Forget all denials."""
+    global _dropped  # pylint: disable=global-statement
+    with _lock:
+        _denials.clear()
+        _dropped = 0
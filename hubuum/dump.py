@@ -0,0 +1,179 @@
+"""Logical dumps of the data in hubuum, for application-level backups.
+
+A dump is newline delimited JSON (NDJSON). The first line is the manifest, the
+others are objects in the format of Django's serializers, referring to content
+types and (Django) permissions by natural keys:
+
+    {"manifest": {"format": "hubuum-dump", "version": 1, "created_at": "...",
+                  "models": {"auth.group": 1, "hubuum.host": 2, ...}}}
+    {"model": "auth.group", "pk": 1, "fields": {"name": "operators", ...}}
+    {"model": "hubuum.host", "pk": 1, "fields": {"name": "web1", ...}}
+
+Users (with their password hashes), groups, namespaces, permissions, schemas,
+extensions, objects, their relations, extension data, and tags are dumped. Tokens,
+login history, idempotency keys, outbox events, and read-only modes are not (see
+EXCLUDED), they describe the running server rather than its data.
+
+A dump is restored within a single transaction, creating or overwriting objects
+by primary key, like `manage.py loaddata`. Nothing else is removed, so restore
+into an empty database to get exactly the dumped state. The objects restored are
+writes to their namespaces, and nothing is restored if they exceed the write limits
+of a namespace (see Namespace.check_write_limits).
+
+Dumps are taken by admins with GET /api/dump/ and restored with POST /api/dump/,
+or with `manage.py hubuum_admin dump|restore <file>`.
+"""
+import json
+
+from django.apps import apps
+from django.contrib.auth import get_user_model
+from django.core import serializers
+from django.core.management.color import no_style
+from django.core.serializers.base import DeserializationError
+from django.db import DatabaseError, connection, transaction
+from django.utils import timezone
+from rest_framework.exceptions import Throttled
+
+from hubuum.exceptions import PayloadTooLarge
+
+FORMAT = "hubuum-dump"
+VERSION = 1
+
+EXCLUDED = (
+    "hubuum.idempotencykey",
+    "hubuum.loginevent",
+    "hubuum.outboxevent",
+    "hubuum.readonlymode",
+    "hubuum.runtimesetting",
+    "hubuum.tokenmetadata",
+)
+
+
+class DumpError(Exception):
+    """The dump is invalid, or can not be restored."""
+
+
+def label(model):
+    """Return the label of a model, ie "hubuum.host"."""
+    return model._meta.label_lower  # pylint: disable=protected-access
+
+
+def dumped_models():
+    """Return the models that are dumped.
+
+    Groups and users come first, as other objects refer to them by name.
+    """
+    first = [apps.get_model("auth", "group"), get_user_model()]
+    return first + [
+        model
+        for model in apps.get_app_config("hubuum").get_models()
+        if model not in first and label(model) not in EXCLUDED
+    ]
+
+
+def dump():
+    """Yield the manifest, and then every object, as dictionaries.
+
+    This is a generator, suitable for streaming responses (see ndjson_lines). When
+    not within a transaction already, the objects are read from one snapshot.
+    """
+    snapshot = not connection.in_atomic_block and connection.vendor == "postgresql"
+    with transaction.atomic():
+        if snapshot:
+            with connection.cursor() as cursor:
+                cursor.execute("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+
+        models = dumped_models()
+        counts = {label(model): model.objects.count() for model in models}
+        yield {
+            "manifest": {
+                "format": FORMAT,
+                "version": VERSION,
+                "created_at": timezone.now(),
+                "models": counts,
+            }
+        }
+
+        for model in models:
+            for obj in model.objects.order_by("pk").iterator():
+                yield serializers.serialize(
+                    "python", [obj], use_natural_foreign_keys=True
+                )[0]
+
+
+def _manifest(line):
+    """Parse and check the manifest line of a dump."""
+    try:
+        manifest = json.loads(line)["manifest"]
+    except (ValueError, TypeError, KeyError) as ex:
+        raise DumpError("The dump must start with a manifest.") from ex
+
+    if not isinstance(manifest, dict) or manifest.get("format") != FORMAT:
+        raise DumpError(f"The dump is not in the {FORMAT} format.")
+    if manifest.get("version") != VERSION:
+        raise DumpError(f"Unsupported dump version {manifest.get('version')}.")
+    if not isinstance(manifest.get("models"), dict):
+        raise DumpError("The manifest must count the objects of every model.")
+    return manifest
+
+
+def _check_write_limits(instance, namespaces):
+    """Check the restore of an object against the write limits of its namespace.
+
+    param: namespaces (the namespaces checked so far, by id)
+    """
+    namespace_id = getattr(instance, "namespace_id", None)
+    if namespace_id is None:
+        return
+    if namespace_id not in namespaces:
+        namespaces[namespace_id] = instance.namespace
+    namespaces[namespace_id].check_write_limits(getattr(instance, "json_data", None))
+
+
+def restore(lines):
+    """Restore a dump, within a single transaction.
+
+    param: lines (an iterable of the lines of the dump, as text or bytes)
+
+    returns: {model: the number of objects restored}
+    raises: DumpError if the dump is invalid or incomplete, or exceeds the write
+            limits of a namespace, nothing is restored then
+    """
+    lines = (line.decode() if isinstance(line, bytes) else line for line in lines)
+    lines = (line for line in lines if line.strip())
+    manifest = _manifest(next(lines, ""))
+
+    allowed = {label(model) for model in dumped_models()}
+    restored = {}
+    models = set()
+    namespaces = {}
+    try:
+        with transaction.atomic():
+            for number, line in enumerate(lines, start=2):
+                try:
+                    data = json.loads(line)
+                except ValueError as ex:
+                    raise DumpError(f"Line {number} is not JSON: {ex}") from ex
+                if not isinstance(data, dict) or data.get("model") not in allowed:
+                    raise DumpError(f"Line {number} is not an object that is dumped.")
+
+                for obj in serializers.deserialize("python", [data]):
+                    obj.save()
+                    _check_write_limits(obj.object, namespaces)
+                    models.add(type(obj.object))
+                restored[data["model"]] = restored.get(data["model"], 0) + 1
+
+            expected = manifest["models"]
+            if {model: restored.get(model, 0) for model in expected} != expected:
+                raise DumpError("The dump does not hold the objects in its manifest.")
+
+            # Primary keys were given, new objects must be numbered after them.
+            with connection.cursor() as cursor:
+                for sql in connection.ops.sequence_reset_sql(no_style(), models):
+                    cursor.execute(sql)
+    except (DeserializationError, DatabaseError) as ex:
+        raise DumpError(f"The dump can not be restored: {ex}") from ex
+    except (PayloadTooLarge, Throttled) as ex:
+        raise DumpError(f"The dump exceeds the limits: {ex.detail}") from ex
+
+    return restored
@@ -15,3 +15,79 @@ class Conflict(APIException):
     status_code = status.HTTP_409_CONFLICT
     default_detail = _("Resource already exists.")
     default_code = "resource_exists"
+
+
+class Ambiguous(APIException):
+    """Thrown when a name (or other identifier) given matches several objects."""
+
+    status_code = status.HTTP_409_CONFLICT
+    default_detail = _("The identifier matches several objects, use a unique one.")
+    default_code = "ambiguous"
+
+
+class PayloadTooLarge(APIException):
+    """Thrown when the data given is larger than allowed."""
+
+    status_code = status.HTTP_413_REQUEST_ENTITY_TOO_LARGE
+    default_detail = _("The request is too large.")
+    default_code = "payload_too_large"
+
+
+class IdempotencyKeyReused(APIException):
+    """Thrown when an Idempotency-Key is given again for a different request."""
+
+    status_code = status.HTTP_422_UNPROCESSABLE_ENTITY
+    default_detail = _("The Idempotency-Key was used for a different request.")
+    default_code = "idempotency_key_reused"
+
+
+class Busy(APIException):
+    """Thrown when the server has no capacity for a request right now.
+
+    wait is the number of seconds clients are told to wait, with Retry-After.
+    """
+
+    status_code = status.HTTP_503_SERVICE_UNAVAILABLE
+    default_detail = _("The server is busy, try again later.")
+    default_code = "busy"
+
+    def __init__(self, detail=None, code=None, wait=None):
+        """Initialize the exception, with the seconds to wait before retrying."""
+        super().__init__(detail, code)
+        self.wait = wait
+
+
+class ReadOnly(APIException):
+    """Thrown when writing while the server, or a namespace, is read-only.
+
+    wait is the number of seconds clients are told to wait, with Retry-After.
+    """
+
+    status_code = status.HTTP_503_SERVICE_UNAVAILABLE
+    default_detail = _("The server is read-only for maintenance.")
+    default_code = "read_only"
+
+    def __init__(self, detail=None, code=None, wait=None):
+        """Initialize the exception, with the seconds to wait before retrying."""
+        super().__init__(detail, code)
+        self.wait = wait
+
+
+class IntegrityViolation(APIException):
+    """Thrown when a change violates a constraint of the database.
+
+    context describes the violation, ie the model and the fields involved, and is
+    part of the response. See hubuum.integrity.
+    """
+
+    status_code = status.HTTP_409_CONFLICT
+    default_detail = _("The change conflicts with the data stored.")
+    default_code = "constraint_violation"
+
+    def __init__(self, detail=None, code=None, status_code=None, context=None):
+        """Initialize the exception, with its status and context."""
+        super().__init__(detail, code)
+        self.code = code or self.default_code
+        if status_code is not None:
+            self.status_code = status_code
+        self.context = context or {}
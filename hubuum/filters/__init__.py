@@ -0,0 +1,32 @@
+"""Filters for hubuum permissions.
+
+The filtersets are grouped by the models they are for, see the modules of the package.
+"""
+from .auth import GroupFilterSet, NamespaceFilterSet, PermissionFilterSet, UserFilterSet
+from .base import (
+    NON_FILTER_PARAMETERS,
+    DateBetweenFilter,
+    HubuumFilterSet,
+    JSONFieldContainsFilter,
+    JSONFieldKeyFilter,
+    JSONFieldLookupFilter,
+    NamespacePermissionFilter,
+    RelatedCountFilter,
+    SensitivePathsMixin,
+    TagFilter,
+)
+from .extensions import (
+    ExtensionDataFilterSet,
+    ExtensionFilterSet,
+    SchemaDefinitionFilterSet,
+)
+from .objects import (
+    HostFilterSet,
+    HostTypeFilterSet,
+    JackFilterSet,
+    PersonFilterSet,
+    PurchaseDocumentsFilterSet,
+    PurchaseOrderFilterSet,
+    RoomFilterSet,
+    VendorFilterSet,
+)
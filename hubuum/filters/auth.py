@@ -0,0 +1,136 @@
+"""Filtersets for namespaces, users, groups, and permissions."""
+from django.contrib.auth.models import Group
+from django_filters import rest_framework as filters
+from rest_framework.exceptions import ValidationError
+
+from hubuum.models.auth import User
+from hubuum.models.namespace import Namespace, Permission
+from hubuum.permissions import fully_qualified_operations
+
+from .base import (
+    HubuumFilterSet,
+    NamespacePermissionFilter,
+    _date_lookups,
+    _hubuum_fields,
+    _key_lookups,
+    _many_to_many_lookups,
+    _numeric_lookups,
+    _textual_lookups,
+)
+
+
+class NamespaceFilterSet(NamespacePermissionFilter):
+    """FilterSet class for Namespace."""
+
+    parameters = ("include_archived",)
+
+    owner = filters.CharFilter(field_name="owner_group__name")
+
+    class Meta:
+        """Metadata for the class."""
+
+        model = Namespace
+        fields = {
+            "name": _textual_lookups,
+            "description": _textual_lookups,
+            "archived": ["exact"],
+            "owner_group": _key_lookups,
+        }
+        fields.update(_hubuum_fields)
+
+
+class UserFilterSet(HubuumFilterSet):
+    """FilterSet class for User.
+
+    Users may be found by their groups (groups=, groups__in=, groups__not_in=), and
+    by the permissions their groups hold for a namespace, ie
+    ?can=has_update&namespace=infrastructure (see filter_can).
+    """
+
+    parameters = ("namespace",)
+
+    can = filters.CharFilter(method="filter_can")
+
+    def filter_can(self, queryset, name, value):
+        """Find the users that are granted a permission for the namespace.
+
+        The permission is given with or without its has_ prefix, and the namespace
+        (?namespace=) by id or name. Only the grants to the groups of the users
+        count, admins without such grants are not included.
+
+        raises: ValidationError for unknown permissions, or without a namespace
+        """
+        permission = value if value.startswith("has_") else f"has_{value}"
+        if permission not in fully_qualified_operations():
+            known = ", ".join(fully_qualified_operations())
+            raise ValidationError({name: f"Unknown permission, use one of {known}."})
+
+        namespace = self.data.get("namespace")
+        if not namespace:
+            raise ValidationError({"namespace": f"'{name}' requires a namespace."})
+        lookup = "id" if namespace.isdigit() else "name"
+
+        # One filter, so that both conditions apply to the same grant.
+        return queryset.filter(
+            **{
+                f"groups__p_group__namespace__{lookup}": namespace,
+                f"groups__p_group__{permission}": True,
+            }
+        ).distinct()
+
+    class Meta:
+        """Metadata for the class."""
+
+        model = User
+        fields = {
+            "id": _numeric_lookups,
+            "username": _textual_lookups,
+            "email": _textual_lookups,
+            "is_active": ["exact"],
+            "is_staff": ["exact"],
+            "is_superuser": ["exact"],
+            "last_login": _date_lookups,
+            "last_seen_at": _date_lookups,
+            "groups": _many_to_many_lookups,
+        }
+
+
+class GroupFilterSet(HubuumFilterSet):
+    """FilterSet class for Group.
+
+    Groups may be filtered on their number of members (user_count) and managers
+    (managers_count).
+    """
+
+    related_count_fields = ("user", "managers")
+
+    class Meta:
+        """Metadata for the class."""
+
+        model = Group
+        fields = {
+            "id": _numeric_lookups,
+            "name": _textual_lookups,
+            "user": _many_to_many_lookups,
+            "permissions": _many_to_many_lookups,
+        }
+
+
+class PermissionFilterSet(HubuumFilterSet):
+    """FilterSet class for Permission."""
+
+    class Meta:
+        """Metadata for the class."""
+
+        model = Permission
+        fields = {
+            "namespace": _key_lookups,
+            "group": _key_lookups,
+            "has_create": ["exact"],
+            "has_read": ["exact"],
+            "has_update": ["exact"],
+            "has_delete": ["exact"],
+            "has_namespace": ["exact"],
+            "has_read_sensitive": ["exact"],
+        }
+        fields.update(_hubuum_fields)
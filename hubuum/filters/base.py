@@ -0,0 +1,669 @@
+"""The filters, and the filtersets the others build on."""
+import copy
+import difflib
+import json
+from datetime import datetime, timedelta
+from functools import reduce
+from operator import and_, or_
+
+from django.conf import settings
+from django.core.exceptions import FieldDoesNotExist
+from django.db.models import Count, DateField, DateTimeField, Q
+from django.utils import timezone
+from django.utils.dateparse import parse_date, parse_datetime
+from django_filters import rest_framework as filters
+from rest_framework.exceptions import ValidationError
+
+from hubuum.models.base import TaggedModel, model_is_open
+from hubuum.models.extensions import Extension, ExtensionData
+from hubuum.tools import json_schema_type
+
+
+_key_lookups = ["exact", "in"]
+_many_to_many_lookups = _key_lookups
+_many_to_one_lookups = _key_lookups
+_textual_lookups = [
+    "contains",
+    "icontains",
+    "endswith",
+    "iendswith",
+    "startswith",
+    "istartswith",
+    "exact",
+    "iexact",
+    "in",
+]
+_numeric_lookups = ["exact", "gt", "gte", "lt", "lte", "range", "in"]
+_date_lookups = [
+    "day",
+    "month",
+    "quarter",
+    "year",
+    "exact",
+    "week_day",
+    "iso_week_day",
+    "iso_year",
+]
+_date_lookups.extend(_numeric_lookups)
+_count_lookups = ["exact", "gt", "gte", "lt", "lte"]
+
+_hubuum_fields = {
+    "id": _key_lookups,
+    "created_at": _date_lookups,
+    "updated_at": _date_lookups,
+}
+_namespace_fields = {"namespace": _key_lookups}
+_namespace_fields.update(_hubuum_fields)
+
+# Query parameters of list views that are not filters, see HubuumFilterSet.
+NON_FILTER_PARAMETERS = (
+    "format",
+    "page",
+    "page_size",
+    "envelope",
+    "strict",
+    "explain_permissions",
+    "explain_matches",
+    "dry_run",
+    "confirm",
+)
+
+
+def _json_value(value):
+    """Return a value to compare JSON elements against, as a number if possible."""
+    try:
+        return float(value)
+    except ValueError:
+        return value
+
+
+class SensitivePathsMixin:  # pylint: disable=too-few-public-methods
+    """Refuse JSON filters on sensitive paths for users who can not read them.
+
+    Filtering on a sensitive path (see Extension.sensitive_paths) tells what the
+    redacted value is, ie with secret__startswith=a. Filters touching the sensitive
+    paths of an extension get 400, unless the user has has_read_sensitive for every
+    namespace with data of the extension among the objects filtered.
+    """
+
+    def check_sensitive_paths(self, qs, paths):
+        """Ensure that the user may filter on the paths (lists of keys).
+
+        Raises:
+            ValidationError: If the paths touch sensitive paths the user can't read.
+        """
+        parent = getattr(self, "parent", None)
+        user = getattr(getattr(parent, "request", None), "user", None)
+        if user is None or not user.is_authenticated or user.is_admin():
+            return
+
+        extensions = Extension.objects.exclude(sensitive_paths=[])
+        given = parent.data.get("extension", "")
+        if str(given).isdigit():
+            extensions = extensions.filter(pk=given)
+        touched = [
+            extension
+            for extension in extensions
+            if any(extension.touches_sensitive_paths(path) for path in paths)
+        ]
+        if not touched:
+            return
+
+        readable = user.namespaces_where("has_read_sensitive", ExtensionData)
+        hidden = qs.filter(extension__in=touched).exclude(namespace__in=readable)
+        if hidden.exists():
+            names = ", ".join(sorted(extension.name for extension in touched))
+            raise ValidationError(
+                f"Filtering on the sensitive paths of {names} requires "
+                "has_read_sensitive."
+            )
+
+
+class JSONFieldLookupFilter(SensitivePathsMixin, filters.CharFilter):
+    """Class to allow filtering on JSON fields.
+
+    Keys referring to arrays may match any element of the array with the has
+    lookup, ie "tags__has=web". When filtering on a single extension (?extension=),
+    keys its json_schema declares as arrays match any element with exact as well,
+    ie "tags=web".
+
+    Args:
+        field_name (str): The field name to filter on. Must be a JSON field.
+    """
+
+    def filter(self, qs, value):
+        """Filter the queryset based on a JSON key, value, and optional lookup type.
+
+        Args:
+            qs (QuerySet): The queryset to filter.
+            value (str): The input value containing the key, value, and optional lookup type.
+
+        Returns:
+            QuerySet: The filtered queryset.
+
+        Raises:
+            ValidationError: If an invalid lookup type for the value is provided.
+        """
+        if not value:
+            return qs
+
+        ((key, lookup),) = self.key_lookups(value)
+        parts = key.split("__")
+        if parts[-1] in (*_numeric_lookups, *_textual_lookups, "has", "in"):
+            parts = parts[:-1]
+        self.check_sensitive_paths(qs, [parts])
+        return qs.filter(lookup)
+
+    def key_lookups(self, value):
+        """Return the key filtered on, and the lookup for it, see json_matches.
+
+        Returns:
+            list: [(key, Q)], with the key as given in the value.
+        """
+        try:
+            key, val = value.split("=")
+        except ValueError as ex:
+            raise ValidationError(
+                "Filtering requires both a key and a value, separated by '='"
+            ) from ex
+
+        # The values of in are comma separated, and may be numeric or text.
+        if key.endswith("__in"):
+            values = [_json_value(element) for element in val.split(",")]
+            return [(key, Q(**{f"{self.field_name}__{key}": values}))]
+
+        val = _json_value(val)
+
+        if isinstance(val, (float, int)):
+            val_type = "numeric"
+            allowed_lookups = _numeric_lookups
+        else:  # Assume string
+            val_type = "text"
+            allowed_lookups = _textual_lookups
+
+        given = key
+        parts = key.split("__")
+        if len(parts) > 1 and parts[-1] in _numeric_lookups + _textual_lookups:
+            lookup_type, path = parts[-1], parts[:-1]
+        elif len(parts) > 1 and parts[-1] == "has":
+            return [(given, self._element_lookup(parts[:-1], val))]
+        else:
+            lookup_type, path = "exact", parts
+            key = f"{key}__exact"
+
+        if lookup_type == "exact" and self._is_array(path):
+            return [(given, self._element_lookup(path, val))]
+
+        if lookup_type not in allowed_lookups:
+            valid_lookups = ", ".join(allowed_lookups)
+            allowed_string = f"Allowed types for {val_type} are {valid_lookups}."
+            raise ValidationError(
+                f"Invalid lookup type '{lookup_type}'. {allowed_string}"
+            )
+
+        return [(given, Q(**{f"{self.field_name}__{key}": val}))]
+
+    def _element_lookup(self, path, val):
+        """Return the lookup for arrays at the path with an element equal to val."""
+        return Q(**{f"{self.field_name}__{'__'.join(path)}__contains": [val]})
+
+    def _is_array(self, path):
+        """Check if the schema of the extension filtered on declares an array at path.
+
+        This only applies when filtering on a single extension, by its id.
+        """
+        parent = getattr(self, "parent", None)
+        extension = parent.data.get("extension", "") if parent is not None else ""
+        if not str(extension).isdigit():
+            return False
+
+        schema = (
+            Extension.objects.filter(pk=extension)
+            .values_list("json_schema", flat=True)
+            .first()
+        )
+        return json_schema_type(schema, path) == "array"
+
+
+class JSONFieldContainsFilter(SensitivePathsMixin, filters.CharFilter):
+    """Class to allow filtering on JSON fields containing a given JSON document.
+
+    The value is a JSON document, ie '{"dns": {"fqdn": "host.domain.tld"}}'.
+    Objects match if the given document is contained within the field, using
+    the same semantics as the PostgreSQL @> operator.
+
+    Args:
+        field_name (str): The field name to filter on. Must be a JSON field.
+    """
+
+    def filter(self, qs, value):
+        """Filter the queryset based on the contained document.
+
+        Args:
+            qs (QuerySet): The queryset to filter.
+            value (str): The JSON document to look for.
+
+        Returns:
+            QuerySet: The filtered queryset.
+
+        Raises:
+            ValidationError: If the value is not a JSON object or array.
+        """
+        if not value:
+            return qs
+
+        document = self._document(value)
+        self.check_sensitive_paths(qs, list(self._paths(document)))
+        return qs.filter(**{f"{self.field_name}__contains": document})
+
+    def _paths(self, document, path=()):
+        """Yield the paths matched by the document, ending at arrays and values.
+
+        Arrays contain a document at any index, so the paths end at the array.
+        """
+        if not isinstance(document, dict) or not document:
+            yield list(path)
+            return
+        for key, value in document.items():
+            yield from self._paths(value, (*path, key))
+
+    def _document(self, value):
+        """Parse the JSON document.
+
+        Raises:
+            ValidationError: If the value is not a JSON object or array.
+        """
+        try:
+            document = json.loads(value)
+        except ValueError as ex:
+            raise ValidationError(f"Invalid JSON document: {ex}") from ex
+
+        if not isinstance(document, (dict, list)):
+            raise ValidationError("The JSON document must be an object or an array.")
+        return document
+
+    def key_lookups(self, value):
+        """Return the keys of the document, and the lookups for them, see json_matches.
+
+        Returns:
+            list: [(key, Q)] for every top level key of an object, or [("", Q)]
+                  for an array.
+        """
+        document = self._document(value)
+        field = f"{self.field_name}__contains"
+        if isinstance(document, list):
+            return [("", Q(**{field: document}))]
+        return [(key, Q(**{field: {key: val}})) for key, val in document.items()]
+
+
+class JSONFieldKeyFilter(SensitivePathsMixin, filters.CharFilter):
+    """Class to allow filtering on the existence of keys in JSON fields.
+
+    The value is a comma separated list of keys. Keys may be scoped using
+    double underscores, ie "dns__fqdn" checks for the key "fqdn" within "dns".
+
+    Args:
+        field_name (str): The field name to filter on. Must be a JSON field.
+        require_all (bool): Require all keys to exist (the PostgreSQL ?& operator)
+                            rather than any of them (the ?| operator).
+    """
+
+    def __init__(self, *args, require_all=True, **kwargs):
+        """Initialize the filter."""
+        self.require_all = require_all
+        super().__init__(*args, **kwargs)
+
+    def filter(self, qs, value):
+        """Filter the queryset based on the existence of the given keys.
+
+        Args:
+            qs (QuerySet): The queryset to filter.
+            value (str): The comma separated list of keys.
+
+        Returns:
+            QuerySet: The filtered queryset.
+
+        Raises:
+            ValidationError: If a key is empty.
+        """
+        if not value:
+            return qs
+
+        pairs = self.key_lookups(value)
+        self.check_sensitive_paths(qs, [key.split("__") for key, _ in pairs])
+        lookups = [lookup for _, lookup in pairs]
+        return qs.filter(reduce(and_ if self.require_all else or_, lookups))
+
+    def key_lookups(self, value):
+        """Return the keys, and the lookups for them, see json_matches.
+
+        Returns:
+            list: [(key, Q)], with the keys as given in the value.
+        """
+        lookups = []
+        for path in value.split(","):
+            scope, _, key = path.strip().rpartition("__")
+            if not key:
+                raise ValidationError(f"Invalid key '{path}'.")
+            field = f"{self.field_name}__{scope}" if scope else self.field_name
+            lookups.append((path.strip(), Q(**{f"{field}__has_key": key})))
+        return lookups
+
+
+class RelatedCountFilter(filters.NumberFilter):
+    """Class to allow filtering on the number of related objects.
+
+    Args:
+        field_name (str): The relation to count, ie "hosts".
+    """
+
+    def filter(self, qs, value):
+        """Filter the queryset based on the number of related objects.
+
+        Args:
+            qs (QuerySet): The queryset to filter.
+            value (Decimal): The number of related objects to compare against.
+
+        Returns:
+            QuerySet: The filtered queryset.
+        """
+        if value is None:
+            return qs
+
+        annotation = f"{self.field_name}_count"
+        if annotation not in qs.query.annotations:
+            qs = qs.annotate(**{annotation: Count(self.field_name, distinct=True)})
+
+        return qs.filter(**{f"{annotation}__{self.lookup_expr}": value})
+
+
+class DateBetweenFilter(filters.CharFilter):
+    """Class to allow filtering on dates, or timestamps, between two bounds.
+
+    The value is two ISO 8601 dates or timestamps separated by "--", ie
+    2024-01-01--2024-02-01, and either bound may be left out. The bounds are
+    inclusive, unless marked as exclusive with "(" or ")", ie [2024-01-01--2024-02-01)
+    for January. A date as a bound for timestamps refers to the whole day.
+
+    Args:
+        field_name (str): The field name to filter on.
+        timestamps (bool): The field holds timestamps, rather than dates.
+    """
+
+    def __init__(self, *args, timestamps=False, **kwargs):
+        """Initialize the filter."""
+        self.timestamps = timestamps
+        super().__init__(*args, **kwargs)
+
+    def _bound(self, value, inclusive, upper):
+        """Parse a bound, return the lookup and the value to compare against.
+
+        raises: ValidationError if the bound is not a date or a timestamp
+        """
+        try:
+            bound = parse_datetime(value) or parse_date(value)
+        except ValueError:
+            bound = None
+        if bound is None:
+            raise ValidationError(f"'{value}' is not an ISO 8601 date or timestamp.")
+
+        if self.timestamps and not isinstance(bound, datetime):
+            # A date is the whole day, from its start until the start of the next.
+            bound = datetime.combine(bound, datetime.min.time())
+            if inclusive == upper:
+                bound += timedelta(days=1)
+            lookup = "lt" if upper else "gte"
+        else:
+            if not self.timestamps and isinstance(bound, datetime):
+                bound = bound.date()
+            lookup = "lt" if upper else "gt"
+            if inclusive:
+                lookup += "e"
+
+        if self.timestamps and timezone.is_naive(bound):
+            bound = timezone.make_aware(bound)
+        return lookup, bound
+
+    def filter(self, qs, value):
+        """Filter the queryset based on the bounds given.
+
+        Args:
+            qs (QuerySet): The queryset to filter.
+            value (str): The bounds, separated by "--".
+
+        Returns:
+            QuerySet: The filtered queryset.
+
+        Raises:
+            ValidationError: If the bounds are malformed, or the lower bound is
+                             after the upper bound.
+        """
+        if not value:
+            return qs
+
+        lower_inclusive = not value.startswith("(")
+        upper_inclusive = not value.endswith(")")
+        try:
+            lower, upper = value.lstrip("[(").rstrip("])").split("--")
+        except ValueError as ex:
+            raise ValidationError(
+                "Filtering between requires two bounds, separated by '--'."
+            ) from ex
+
+        lookups = {}
+        bounds = []
+        for bound, inclusive, is_upper in [
+            (lower, lower_inclusive, False),
+            (upper, upper_inclusive, True),
+        ]:
+            if bound:
+                lookup, bound = self._bound(bound, inclusive, is_upper)
+                lookups[f"{self.field_name}__{lookup}"] = bound
+                bounds.append(bound)
+
+        if len(bounds) == 2 and bounds[0] > bounds[1]:
+            raise ValidationError("The lower bound is after the upper bound.")
+
+        return qs.filter(**lookups)
+
+
+class TagFilter(filters.CharFilter):
+    """Class to allow filtering on the tags of objects.
+
+    The value is a comma separated list of tags, objects must match all of them.
+
+    Args:
+        field_name (str): The path to the tag names, ie "tagged_objects__tag__name".
+    """
+
+    def filter(self, qs, value):
+        """Filter the queryset based on the tags of the objects.
+
+        Args:
+            qs (QuerySet): The queryset to filter.
+            value (str): The comma separated list of tags.
+
+        Returns:
+            QuerySet: The filtered queryset.
+        """
+        if not value:
+            return qs
+
+        # Every filter joins the tags separately, so each tag must match on its own.
+        for tag in value.split(","):
+            lookup = f"{self.field_name}__{self.lookup_expr}"
+            qs = qs.filter(**{lookup: tag.strip()})
+
+        return qs.distinct()
+
+
+class HubuumFilterSet(filters.FilterSet):
+    """A FilterSet that adds the negation of every `in` filter, date ranges, and counts.
+
+    For a filter name__in=a,b, the filter name__not_in=a,b excludes the matches.
+    Date fields with range lookups get name__between, see DateBetweenFilter.
+    The relations in related_count_fields get count filters, see RelatedCountFilter.
+
+    Unknown filters (including lookups that do not apply to a field) and filters
+    without a value are ignored, unless in strict mode (HUBUUM_STRICT_FILTERS, or
+    ?strict=true for a request), where they get 400.
+    """
+
+    # Query parameters of the list views of the model that are not filters, in
+    # addition to NON_FILTER_PARAMETERS.
+    parameters = ()
+
+    # Relations that may be filtered on by the number of related objects, a relation
+    # named "hosts" gives the filters hosts_count, hosts_count__gt, etc.
+    related_count_fields = ()
+
+    # Strict mode regardless of the request and the configuration, when set, ie for
+    # bulk deletes.
+    strict = None
+
+    def is_valid(self):
+        """Validate the filters, and in strict mode, every parameter.
+
+        raises: ValidationError in strict mode, with the invalid parameters
+        """
+        if self.is_bound and self._strict():
+            known = {*self.filters, *NON_FILTER_PARAMETERS, *self.parameters}
+            errors = {}
+            for name, value in self.data.items():
+                if name not in known:
+                    errors[name] = self._unknown(name)
+                elif name in self.filters and not value:
+                    errors[name] = "The filter requires a value."
+            if errors:
+                raise ValidationError(errors)
+
+        return super().is_valid()
+
+    def json_matches(self, pks):
+        """Find the keys given to the JSON filters that the objects satisfy.
+
+        For filters of several keys, ie json_data_has_any_key, this tells which of
+        the keys an object matched. Call this once the filters are validated.
+
+        param: pks (the primary keys of the objects)
+        returns: {pk: {filter name: [keys]}}, for the JSON filters given
+        """
+        matches = {pk: {} for pk in pks}
+        for name, json_filter in self.filters.items():
+            value = self.form.cleaned_data.get(name)
+            if not value or not hasattr(json_filter, "key_lookups"):
+                continue
+
+            for pk in pks:
+                matches[pk][name] = []
+            for key, lookup in json_filter.key_lookups(value):
+                matching = self._meta.model.objects.filter(pk__in=pks).filter(lookup)
+                for pk in matching.values_list("pk", flat=True):
+                    matches[pk][name].append(key)
+
+        return matches
+
+    def _strict(self):
+        """Check if strict mode is set, asked for by the request, or configured."""
+        if self.strict is not None:
+            return self.strict
+        value = self.data.get("strict")
+        if value is None:
+            return settings.STRICT_FILTERS
+        return value.lower() in ["1", "true", "yes"]
+
+    def _unknown(self, name):
+        """Describe an unknown filter, with suggestions."""
+        model = self._meta.model.__name__.lower()
+        suggestions = difflib.get_close_matches(name, self.filters, n=3)
+        if suggestions:
+            return f"Unknown filter for {model}, did you mean {', '.join(suggestions)}?"
+        return f"Unknown filter for {model}."
+
+    @classmethod
+    def get_filters(cls):
+        """Add the negated, between, and count filters to the generated filters."""
+        generated = super().get_filters()
+        for relation in cls.related_count_fields:
+            for lookup in _count_lookups:
+                name = f"{relation}_count"
+                if lookup != "exact":
+                    name = f"{name}__{lookup}"
+                generated[name] = RelatedCountFilter(
+                    field_name=relation, lookup_expr=lookup
+                )
+
+        for name, generated_filter in list(generated.items()):
+            if name.endswith("__in") and generated_filter.lookup_expr == "in":
+                negated = copy.deepcopy(generated_filter)
+                negated.exclude = True
+                generated[f"{name[: -len('__in')]}__not_in"] = negated
+
+            field = cls._date_field(generated_filter.field_name)
+            if name.endswith("__range") and field is not None:
+                generated[f"{name[: -len('__range')]}__between"] = DateBetweenFilter(
+                    field_name=generated_filter.field_name,
+                    timestamps=isinstance(field, DateTimeField),
+                )
+
+        return generated
+
+    @classmethod
+    def _date_field(cls, field_name):
+        """Return the model field of a filter if it is a date (or timestamp) field."""
+        try:
+            field = cls._meta.model._meta.get_field(  # pylint: disable=protected-access
+                field_name
+            )
+        except FieldDoesNotExist:
+            return None
+        return field if isinstance(field, DateField) else None
+
+
+class NamespacePermissionFilter(HubuumFilterSet):
+    """Return viewable objects for a user.
+
+    This filter returns (request.)user-visible objects of a model in question.
+    """
+
+    @classmethod
+    def get_filters(cls):
+        """Add filters for tags to the generated filters."""
+        generated = super().get_filters()
+        if issubclass(cls._meta.model, TaggedModel):
+            for lookup in _textual_lookups:
+                # The tags are already a list, matching all of them.
+                if lookup == "in":
+                    continue
+                name = "tags" if lookup == "exact" else f"tags__{lookup}"
+                generated[name] = TagFilter(
+                    field_name="tagged_objects__tag__name", lookup_expr=lookup
+                )
+
+        return generated
+
+    def filter_queryset(self, queryset):
+        """Perform the filtering."""
+        queryset = super().filter_queryset(queryset)
+        user = self.request.user
+        # model = queryset.model._meta.model_name
+        #        permission = self.perm_format % {
+        #            "app_label": queryset.model._meta.app_label,
+        #            "model_name": queryset.model._meta.model_name,
+        #        }
+
+        #    Find all namespaces we can perform the given operation in.
+
+        #        print("List of {}".format(model))
+        model_name = queryset.model._meta.model_name  # pylint: disable=protected-access
+        if user.is_admin() or model_is_open(model_name):
+            return queryset
+
+        res = user.namespaces_where("has_read", model_name)
+        # print(res)
+        # print(queryset)
+        if model_name == "namespace":
+            filtered = queryset.filter(pk__in=res)
+        else:
+            filtered = queryset.filter(namespace__in=res)
+        return filtered
@@ -0,0 +1,76 @@
+"""Filtersets for schema definitions, extensions, and their data."""
+from django_filters import rest_framework as filters
+
+from hubuum.models.extensions import Extension, ExtensionData, SchemaDefinition
+
+from .base import (
+    JSONFieldContainsFilter,
+    JSONFieldKeyFilter,
+    JSONFieldLookupFilter,
+    NamespacePermissionFilter,
+    _namespace_fields,
+    _numeric_lookups,
+    _textual_lookups,
+)
+
+
+class SchemaDefinitionFilterSet(NamespacePermissionFilter):
+    """FilterSet class for SchemaDefinition."""
+
+    class Meta:
+        """Metadata for the class."""
+
+        model = SchemaDefinition
+        fields = {
+            "name": _textual_lookups,
+            "description": _textual_lookups,
+        }
+        fields.update(_namespace_fields)
+
+
+class ExtensionFilterSet(NamespacePermissionFilter):
+    """FilterSet class for Extension."""
+
+    class Meta:
+        """Metadata for the class."""
+
+        model = Extension
+        fields = {
+            "name": _textual_lookups,
+            "model": _textual_lookups,
+            "url": _textual_lookups,
+            "require_interpolation": ["exact"],
+            "header": _textual_lookups,
+            "cache_time": _numeric_lookups,
+        }
+        fields.update(_namespace_fields)
+
+
+class ExtensionDataFilterSet(NamespacePermissionFilter):
+    """FilterSet for the ExtensionData model with custom filters for json_data."""
+
+    json_data_lookup = JSONFieldLookupFilter(field_name="json_data")
+    json_data_contains = JSONFieldContainsFilter(field_name="json_data")
+    json_data_has_key = JSONFieldKeyFilter(field_name="json_data")
+    json_data_has_any_key = JSONFieldKeyFilter(
+        field_name="json_data", require_all=False
+    )
+    validation_status = filters.ChoiceFilter(
+        choices=[(status, status) for status in ("valid", "invalid", "unvalidated")],
+        method="filter_validation_status",
+    )
+
+    def filter_validation_status(self, queryset, name, value):
+        """Filter on the validation status against the schema of the extension.
+
+        Unvalidated data has no schema to validate against, or validation is off
+        for the extension. Invalid data was accepted with a schema that warns.
+        """
+        valid = {"valid": True, "invalid": False, "unvalidated": None}[value]
+        return queryset.filter(schema_valid=valid)
+
+    class Meta:
+        """Meta class for ExtensionDataFilterSet."""
+
+        model = ExtensionData
+        fields = ["extension", "content_type", "object_id"]
@@ -0,0 +1,175 @@
+"""Filtersets for hosts and the other objects in namespaces."""
+from hubuum.models.objects import (
+    Host,
+    HostType,
+    Jack,
+    Person,
+    PurchaseDocuments,
+    PurchaseOrder,
+    Room,
+    Vendor,
+)
+
+from .base import (
+    NamespacePermissionFilter,
+    _date_lookups,
+    _key_lookups,
+    _namespace_fields,
+    _numeric_lookups,
+    _textual_lookups,
+)
+
+
+class HostFilterSet(NamespacePermissionFilter):
+    """FilterSet class for Host."""
+
+    related_count_fields = ("children",)
+
+    class Meta:
+        """Metadata for the class."""
+
+        model = Host
+        fields = {
+            "name": _textual_lookups,
+            "fqdn": _textual_lookups,
+            "serial": _textual_lookups,
+            "registration_date": _date_lookups,
+            "room": _key_lookups,
+            "jack": _key_lookups,
+            "purchase_order": _key_lookups,
+            "person": _key_lookups,
+            "parent": _key_lookups,
+            "position": _numeric_lookups,
+            "state": _textual_lookups,
+        }
+
+
+class HostTypeFilterSet(NamespacePermissionFilter):
+    """FilterSet class for HostType."""
+
+    related_count_fields = ("hosts",)
+
+    class Meta:
+        """Metadata for the class."""
+
+        model = HostType
+        fields = {
+            "name": _textual_lookups,
+            "description": _textual_lookups,
+            "state": _textual_lookups,
+        }
+        fields.update(_namespace_fields)
+
+
+class JackFilterSet(NamespacePermissionFilter):
+    """FilterSet class for Jack."""
+
+    related_count_fields = ("hosts",)
+
+    class Meta:
+        """Metadata for the class."""
+
+        model = Jack
+        fields = {
+            "name": _textual_lookups,
+            "building": _textual_lookups,
+            "room": _key_lookups,
+            "state": _textual_lookups,
+        }
+        fields.update(_namespace_fields)
+
+
+class PersonFilterSet(NamespacePermissionFilter):
+    """FilterSet class for Person."""
+
+    related_count_fields = ("hosts",)
+
+    class Meta:
+        """Metadata for the class."""
+
+        model = Person
+        fields = {
+            "username": _textual_lookups,
+            "section": _textual_lookups,
+            "department": _textual_lookups,
+            "email": _textual_lookups,
+            "office_phone": _textual_lookups,
+            "mobile_phone": _textual_lookups,
+            "room": _key_lookups,
+            "state": _textual_lookups,
+        }
+        fields.update(_namespace_fields)
+
+
+class PurchaseDocumentsFilterSet(NamespacePermissionFilter):
+    """FilterSet class for PurchaseDocuments."""
+
+    class Meta:
+        """Metadata for the class."""
+
+        # It would be neat to have the binary field "document"
+        # be matchable to a hash...
+        model = PurchaseDocuments
+        fields = {
+            "document_id": _numeric_lookups,
+            "purchase_order": _key_lookups,
+            "state": _textual_lookups,
+        }
+        fields.update(_namespace_fields)
+
+
+class PurchaseOrderFilterSet(NamespacePermissionFilter):
+    """FilterSet class for PurchaseOrder."""
+
+    related_count_fields = ("hosts", "purchasedocuments")
+
+    class Meta:
+        """Metadata for the class."""
+
+        model = PurchaseOrder
+        fields = {
+            "vendor": _key_lookups,
+            "order_date": _date_lookups,
+            "po_number": _textual_lookups,
+            "state": _textual_lookups,
+        }
+        fields.update(_namespace_fields)
+
+
+class RoomFilterSet(NamespacePermissionFilter):
+    """FilterSet class for Room."""
+
+    related_count_fields = ("hosts", "jack", "person")
+
+    class Meta:
+        """Metadata for the class."""
+
+        model = Room
+        fields = {
+            "room_id": _textual_lookups,
+            "building": _textual_lookups,
+            "floor": _textual_lookups,
+            "state": _textual_lookups,
+        }
+        fields.update(_namespace_fields)
+
+
+class VendorFilterSet(NamespacePermissionFilter):
+    """FilterSet class for Vendor."""
+
+    related_count_fields = ("purchaseorder",)
+
+    class Meta:
+        """Metadata for the class."""
+
+        model = Vendor
+        fields = {
+            "vendor_name": _textual_lookups,
+            "vendor_url": _textual_lookups,
+            "vendor_credentials": _textual_lookups,
+            "contact_name": _textual_lookups,
+            "contact_email": _textual_lookups,
+            "contact_phone": _textual_lookups,
+            "state": _textual_lookups,
+        }
+        fields.update(_namespace_fields)
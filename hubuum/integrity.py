@@ -0,0 +1,208 @@
+"""Errors users can act on, for violations of the constraints of the database.
+
+Serializers validate uniqueness and references before writing, but the database
+has the final say, ie when two requests create the same name at once, or when a
+namespace or group is deleted while objects are created in it. The database then
+raises IntegrityError, which would otherwise be a 500. violation() maps it to an
+IntegrityViolation describing the model and the fields involved:
+
+ - already_exists (409): a unique name (or other fields) is taken.
+ - missing_reference (400): a related object (ie the namespace) does not exist.
+ - still_referenced (409): an object deleted is still referred to.
+ - required (400): a field without a value.
+ - constraint_violation (409): any other constraint.
+
+The API responds with {"detail": ..., "code": ..., "model": ..., "fields": ...},
+see exception_handler. The details of violations are read from PostgreSQL, other
+databases get constraint_violation.
+"""
+import re
+
+import structlog
+from django.apps import apps
+from django.db import IntegrityError
+from rest_framework import status
+from rest_framework.views import exception_handler as drf_exception_handler
+
+from hubuum.exceptions import IntegrityViolation
+
+logger = structlog.get_logger("hubuum.database")
+
+# The SQLSTATE codes of the violations, see the PostgreSQL documentation.
+NOT_NULL_VIOLATION = "23502"
+FOREIGN_KEY_VIOLATION = "23503"
+UNIQUE_VIOLATION = "23505"
+
+# The primary message and the detail of violations, ie
+#   insert or update on table "hubuum_host" violates foreign key constraint "..."
+#   Key (namespace_id)=(5) is not present in table "hubuum_namespace".
+_TABLE = re.compile(r'on table "(?P<table>[^"]+)"')
+_KEY = re.compile(
+    r"Key \((?P<columns>.*?)\)=\((?P<values>.*)\) "
+    r'(?:already exists|is (?:not present in|still referenced from) table '
+    r'"(?P<table>[^"]+)")'
+)
+
+
+def _models():
+    """Return the models, by their table."""
+    return {model._meta.db_table: model for model in apps.get_models()}
+
+
+def _field(model, column):
+    """Return the name of the field of a column, the column if there is none."""
+    for field in model._meta.concrete_fields:
+        if field.column == column:
+            return field.name
+    return column
+
+
+def _key(model, detail):
+    """Return {field: value} of the key in the detail, and the table it names."""
+    match = _KEY.search(detail or "")
+    if match is None:
+        return {}, None
+
+    columns = match.group("columns").split(", ")
+    values = match.group("values").split(", ", len(columns) - 1)
+    if len(values) != len(columns):
+        values = [match.group("values")] * len(columns)
+    fields = {_field(model, column): value for column, value in zip(columns, values)}
+    return fields, match.group("table")
+
+
+def _listed(words):
+    """Return the words in a list for a sentence, ie "namespace and name"."""
+    if len(words) == 1:
+        return words[0]
+    return f"{', '.join(words[:-1])} and {words[-1]}"
+
+
+def _already_exists(model, fields):
+    """Describe a unique violation."""
+    name = model._meta.verbose_name
+    key = _listed([f"{field} {value}" for field, value in fields.items()])
+    others = " or ".join(fields)
+    return IntegrityViolation(
+        detail=(
+            f"A {name} with {key} already exists. "
+            f"Use another {others}, or change the existing {name}."
+        ),
+        code="already_exists",
+        context={"model": model._meta.model_name, "fields": fields},
+    )
+
+
+def _missing_reference(model, fields, related):
+    """Describe a reference to an object that does not exist."""
+    ((field, value),) = fields.items()
+    name = related._meta.verbose_name
+    return IntegrityViolation(
+        detail=(
+            f"The {name} {value} given as {field} does not exist, it may have "
+            f"been deleted. Give an existing {name}."
+        ),
+        code="missing_reference",
+        status_code=status.HTTP_400_BAD_REQUEST,
+        context={
+            "model": model._meta.model_name,
+            "fields": fields,
+            "related_model": related._meta.model_name,
+        },
+    )
+
+
+def _still_referenced(model, fields, referrer):
+    """Describe deleting an object that is still referred to."""
+    relations = [
+        field.name
+        for field in referrer._meta.concrete_fields
+        if field.is_relation and field.related_model is model
+    ]
+    value = ", ".join(fields.values())
+    through = f" through {_listed(relations)}" if relations else ""
+    return IntegrityViolation(
+        detail=(
+            f"The {model._meta.verbose_name} {value} is still referred to by "
+            f"{referrer._meta.verbose_name_plural}{through}. Change or delete "
+            "them first."
+        ),
+        code="still_referenced",
+        context={
+            "model": model._meta.model_name,
+            "fields": fields,
+            "related_model": referrer._meta.model_name,
+        },
+    )
+
+
+def _required(model, column):
+    """Describe a field without a value."""
+    field = _field(model, column)
+    return IntegrityViolation(
+        detail=f"The {field} of a {model._meta.verbose_name} is required.",
+        code="required",
+        status_code=status.HTTP_400_BAD_REQUEST,
+        context={"model": model._meta.model_name, "fields": {field: None}},
+    )
+
+
+def violation(ex):
+    """Describe a violation of a constraint of the database.
+
+    param: ex (an IntegrityError)
+
+    returns: an IntegrityViolation, with the model and the fields involved
+    """
+    cause = ex.__cause__
+    diag = getattr(cause, "diag", None)
+    sqlstate = getattr(cause, "pgcode", None)
+    models = _models()
+
+    # The table of the constraint, for foreign keys the table referring.
+    model = models.get(getattr(diag, "table_name", None))
+    detail = getattr(diag, "message_detail", None)
+    if model is not None and sqlstate == UNIQUE_VIOLATION:
+        fields, _ = _key(model, detail)
+        if fields:
+            return _already_exists(model, fields)
+
+    if model is not None and sqlstate == FOREIGN_KEY_VIOLATION:
+        # The table written to, the one referring or the one referred to.
+        match = _TABLE.search(diag.message_primary or "")
+        written = models.get(match.group("table")) if match else None
+        if written is model:
+            fields, table = _key(model, detail)
+            if len(fields) == 1 and table in models:
+                return _missing_reference(model, fields, models[table])
+        elif written is not None:
+            fields, _ = _key(written, detail)
+            if fields:
+                return _still_referenced(written, fields, model)
+
+    column = getattr(diag, "column_name", None)
+    if model is not None and sqlstate == NOT_NULL_VIOLATION and column:
+        return _required(model, column)
+
+    context = {}
+    if model is not None:
+        context["model"] = model._meta.model_name
+    if getattr(diag, "constraint_name", None):
+        context["constraint"] = diag.constraint_name
+    return IntegrityViolation(context=context)
+
+
+def exception_handler(exc, context):
+    """Handle the exceptions of API views, describing violations of constraints.
+
+    See REST_FRAMEWORK["EXCEPTION_HANDLER"].
+    """
+    if isinstance(exc, IntegrityError):
+        error = str(exc).strip()
+        exc = violation(exc)
+        logger.info("integrity_violation", code=exc.code, error=error, **exc.context)
+
+    response = drf_exception_handler(exc, context)
+    if response is not None and isinstance(exc, IntegrityViolation):
+        response.data = {"detail": str(exc.detail), "code": exc.code, **exc.context}
+    return response
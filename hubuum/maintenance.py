@@ -0,0 +1,136 @@
+"""Database maintenance of the tables with the most writes, for PostgreSQL.
+
+Operations (ANALYZE, REINDEX, and VACUUM) are run synchronously, one table at a
+time. The progress of a running operation is kept in memory, per process, like
+the counters in hubuum.metrics, and only one operation runs at a time per process.
+"""
+import threading
+import time
+
+from django.apps import apps
+from django.db import connection
+from django.utils import timezone
+
+from hubuum import log
+
+# The models with the most writes, whose tables benefit the most from maintenance.
+HOT_MODELS = (
+    "Host",
+    "ExtensionData",
+    "ExtensionDataRevision",
+    "TaggedObject",
+    "ActivityEntry",
+    "OutboxEvent",
+)
+
+OPERATIONS = {
+    "analyze": "ANALYZE {table}",
+    "reindex": "REINDEX TABLE {table}",
+    "vacuum": "VACUUM (ANALYZE) {table}",
+}
+
+_lock = threading.Lock()
+_progress = {}
+
+
+class MaintenanceError(Exception):
+    """The maintenance operation can not be run."""
+
+
+def hot_tables():
+    """Return the names of the tables maintenance applies to."""
+    # pylint: disable=protected-access
+    return [apps.get_model("hubuum", model)._meta.db_table for model in HOT_MODELS]
+
+
+def table_statistics():
+    """Report the size and the dead (bloating) rows of the hot tables.
+
+    returns: {table: {live_rows, dead_rows, dead_ratio, size, last_analyze,
+              last_vacuum}}, size in bytes, automatic runs included
+    """
+    with connection.cursor() as cursor:
+        cursor.execute(
+            "SELECT relname, n_live_tup, n_dead_tup, pg_total_relation_size(relid),"
+            " GREATEST(last_analyze, last_autoanalyze),"
+            " GREATEST(last_vacuum, last_autovacuum)"
+            " FROM pg_stat_user_tables WHERE relname = ANY(%s) ORDER BY relname",
+            [hot_tables()],
+        )
+        rows = cursor.fetchall()
+
+    statistics = {}
+    for table, live, dead, size, last_analyze, last_vacuum in rows:
+        statistics[table] = {
+            "live_rows": live,
+            "dead_rows": dead,
+            "dead_ratio": round(dead / (live + dead), 4) if live + dead else 0.0,
+            "size": size,
+            "last_analyze": last_analyze,
+            "last_vacuum": last_vacuum,
+        }
+    return statistics
+
+
+def progress():
+    """Return the progress of the operation running in this process, if any."""
+    with _lock:
+        return dict(_progress) or None
+
+
+def run(operation, tables=None):
+    """Run a maintenance operation on some, or all, of the hot tables.
+
+    param: operation (one of OPERATIONS)
+    param: tables (the tables to run the operation on, defaults to all of them)
+
+    returns: [{"table": table, "seconds": duration}]
+    raises: ValueError if the operation or a table is unknown
+    raises: MaintenanceError if an operation is already running, or if VACUUM is
+            run within a transaction
+    """
+    if operation not in OPERATIONS:
+        raise ValueError(f"Unknown operation '{operation}'.")
+
+    known = hot_tables()
+    tables = known if tables is None else tables
+    unknown = [table for table in tables if table not in known]
+    if unknown:
+        raise ValueError(f"Unknown table(s) {', '.join(map(str, unknown))}.")
+
+    if operation == "vacuum" and connection.in_atomic_block:
+        raise MaintenanceError("VACUUM can not run within a transaction.")
+
+    with _lock:
+        if _progress:
+            raise MaintenanceError(f"Already running {_progress['operation']}.")
+        _progress.update(
+            operation=operation,
+            started_at=timezone.now(),
+            tables=len(tables),
+            done=0,
+            current=None,
+        )
+
+    results = []
+    try:
+        for table in tables:
+            with _lock:
+                _progress["current"] = table
+
+            start = time.monotonic()
+            with connection.cursor() as cursor:
+                cursor.execute(
+                    OPERATIONS[operation].format(table=connection.ops.quote_name(table))
+                )
+            seconds = round(time.monotonic() - start, 3)
+            log.info("maintenance", operation=operation, table=table, seconds=seconds)
+            results.append({"table": table, "seconds": seconds})
+
+            with _lock:
+                _progress["done"] += 1
+    finally:
+        with _lock:
+            _progress.clear()
+
+    return results
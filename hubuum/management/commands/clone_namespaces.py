@@ -0,0 +1,73 @@
+"""Copy the objects into namespaces being cloned into.
+
+    manage.py clone_namespaces [--once] [--interval <seconds>]
+
+Namespaces are cloned with POST /api/v1/namespaces/<namespace>/clone, and the
+objects of large namespaces (or with ?deferred=true) are left for this worker. Runs
+until interrupted, unless --once is given. The objects of a namespace are copied
+in a single transaction. If that fails the error is kept in the progress of the
+clone, and the namespace is retried after the interval. Several workers may run
+at once, each namespace is locked while its objects are copied.
+"""
+import time
+
+from django.core.management.base import BaseCommand, CommandError
+from django.db import transaction
+
+from hubuum.exceptions import Conflict
+from hubuum.models.namespace import Namespace
+
+
+class Command(BaseCommand):
+    """Copy the objects into namespaces being cloned into."""
+
+    help = "Copy the objects into namespaces being cloned into."
+
+    def add_arguments(self, parser):
+        """Add the arguments."""
+        parser.add_argument(
+            "--once", action="store_true", help="Clone pending namespaces and exit."
+        )
+        parser.add_argument(
+            "--interval",
+            type=float,
+            default=5.0,
+            help="Seconds to wait when there are no namespaces to clone.",
+        )
+
+    def handle(self, *args, **options):
+        """Clone namespaces until interrupted (or done, with --once)."""
+        while True:
+            failed = self.clone_namespaces()
+            if options["once"]:
+                if failed:
+                    raise CommandError(
+                        f"Cloning into {', '.join(failed)} failed, see the progress."
+                    )
+                return
+            time.sleep(options["interval"])
+
+    def clone_namespaces(self):
+        """Copy the objects into the namespaces being cloned into.
+
+        returns: the names of the namespaces where copying the objects failed
+        """
+        failed = []
+        pending = Namespace.objects.filter(cloning__isnull=False).order_by("id")
+        for pk in list(pending.values_list("id", flat=True)):
+            locked = pending.filter(pk=pk).select_for_update(skip_locked=True)
+            with transaction.atomic():
+                namespace = locked.first()
+                if namespace is None:
+                    continue
+
+                try:
+                    copied = namespace.clone_objects()
+                except Conflict as ex:
+                    self.stderr.write(str(ex))
+                    failed.append(namespace.name)
+                    continue
+
+            self.stdout.write(f"Cloned {copied} object(s) into {namespace.name}.")
+
+        return failed
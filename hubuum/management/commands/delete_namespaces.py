@@ -0,0 +1,84 @@
+"""Delete the contents of namespaces being deleted, in batches.
+
+    manage.py delete_namespaces [--once] [--batch-size <n>] [--interval <seconds>]
+
+Namespaces are marked as being deleted with DELETE /api/v1/namespaces/<namespace>
+?deferred=true. Runs until interrupted, unless --once is given. Every batch is
+deleted in a transaction of its own, so tables are only locked briefly, and the
+namespace is deleted once it is empty. If a batch fails, ie as a restrict policy
+protects objects in it, the error is kept in the progress of the deletion and the
+namespace is retried after the interval. Several workers may run at once, each
+namespace is locked while a batch of it is deleted.
+"""
+import time
+
+from django.core.management.base import BaseCommand, CommandError
+from django.db import transaction
+
+from hubuum.exceptions import Conflict
+from hubuum.models.namespace import Namespace
+
+
+class Command(BaseCommand):
+    """Delete namespaces being deleted."""
+
+    help = "Delete the contents of namespaces being deleted, in batches."
+
+    def add_arguments(self, parser):
+        """Add the arguments."""
+        parser.add_argument(
+            "--once", action="store_true", help="Delete pending namespaces and exit."
+        )
+        parser.add_argument(
+            "--batch-size", type=int, default=1000, help="Objects per batch."
+        )
+        parser.add_argument(
+            "--interval",
+            type=float,
+            default=5.0,
+            help="Seconds to wait when there are no namespaces to delete.",
+        )
+
+    def handle(self, *args, **options):
+        """Delete namespaces until interrupted (or done, with --once)."""
+        while True:
+            failed = self.delete_namespaces(options["batch_size"])
+            if options["once"]:
+                if failed:
+                    raise CommandError(
+                        f"Deleting {', '.join(failed)} failed, see the progress."
+                    )
+                return
+            time.sleep(options["interval"])
+
+    def delete_namespaces(self, batch_size):
+        """Delete the namespaces being deleted, batch by batch.
+
+        returns: the names of the namespaces where deleting a batch failed
+        """
+        failed = []
+        pending = Namespace.objects.filter(deletion__isnull=False).order_by("id")
+        for pk in list(pending.values_list("id", flat=True)):
+            locked = pending.filter(pk=pk).select_for_update(skip_locked=True)
+            while True:
+                with transaction.atomic():
+                    namespace = locked.first()
+                    if namespace is None:
+                        break
+
+                    try:
+                        progress = namespace.delete_batch(batch_size)
+                    except Conflict as ex:
+                        self.stderr.write(f"Deleting {namespace.name} failed: {ex}")
+                        failed.append(namespace.name)
+                        break
+
+                if progress is None:
+                    self.stdout.write(f"Deleted {namespace.name}.")
+                    break
+                self.stdout.write(
+                    f"Deleting {namespace.name}, {progress['remaining']} of "
+                    f"{progress['total']} object(s) remaining."
+                )
+
+        return failed
@@ -0,0 +1,219 @@
+"""Diagnose a hubuum deployment.
+
+Runs a set of checks against the configuration and the database and prints a
+pass/fail report suitable for pasting into a support ticket. The command exits
+with a non-zero status if any check fails, telling which check failed first, see
+EXIT_CODES.
+
+With --startup, only the checks needed to serve requests are run. The server runs
+these before starting, see HUBUUM_SERVER_SELF_CHECK in hubuumsite/gunicorn.conf.py.
+"""
+import os
+import socket
+import ssl
+from urllib.parse import urlsplit
+
+from django.conf import settings
+from django.core.exceptions import ImproperlyConfigured
+from django.core.management.base import BaseCommand, CommandError
+from django.db import DEFAULT_DB_ALIAS, connections
+from django.db.migrations.executor import MigrationExecutor
+from django.utils import timezone
+
+from hubuum.models.extensions import Extension
+from hubuum.validators import url_interpolation_regexp
+from hubuumsite.config import EXIT_CONFIGURATION, tls_options, validate
+
+PASS = "PASS"
+WARN = "WARN"
+FAIL = "FAIL"
+
+# The start of the SECRET_KEY shipped in settings.py, never to be used in production.
+DEFAULT_SECRET_KEY_PREFIX = "_1i4sc7w+h!i6fz"
+
+EXPLAIN_PERMISSIONS_VALUES = ("admin", "all", "none")
+
+# The exit codes by the first failed check, other failures exit with 1.
+EXIT_CODES = {
+    "configuration": EXIT_CONFIGURATION,
+    "database": 3,
+    "database schema": 3,
+    "migrations": 4,
+    "tls": 5,
+}
+
+
+class Command(BaseCommand):
+    """Print a diagnostic report for the deployment."""
+
+    help = "Check the database, migrations, configuration, and integrations."
+
+    def add_arguments(self, parser):
+        """Add the arguments for the command."""
+        parser.add_argument(
+            "--database",
+            default=DEFAULT_DB_ALIAS,
+            help="The database to check. Defaults to the 'default' database.",
+        )
+        parser.add_argument(
+            "--max-clock-skew",
+            type=float,
+            default=5.0,
+            help="Allowed difference in seconds between the local and database clocks.",
+        )
+        parser.add_argument(
+            "--check-integrations",
+            action="store_true",
+            help="Try to connect to the hosts of every configured extension.",
+        )
+        parser.add_argument(
+            "--startup",
+            action="store_true",
+            help="Only check the configuration, database, migrations, and TLS.",
+        )
+        parser.add_argument(
+            "--timeout",
+            type=float,
+            default=3.0,
+            help="Connection timeout in seconds for integration checks.",
+        )
+
+    def handle(self, *args, **options):
+        """Run the checks and print the report."""
+        connection = connections[options["database"]]
+        startup = options["startup"]
+
+        results = self.check_environment()
+        database = self.check_database(connection)
+        results += database
+        if database[0][1] != FAIL:
+            results += self.check_migrations(connection)
+            if not startup:
+                skew = options["max_clock_skew"]
+                results += self.check_clock_skew(connection, skew)
+        results += self.check_tls()
+        if not startup:
+            results += self.check_configuration()
+        if options["check_integrations"] and not startup:
+            results += self.check_integrations(options["timeout"])
+
+        styles = {
+            PASS: self.style.SUCCESS,
+            WARN: self.style.WARNING,
+            FAIL: self.style.ERROR,
+        }
+        for name, status, detail in results:
+            self.stdout.write(f"{styles[status](status)} {name}: {detail}")
+
+        failures = [result for result in results if result[1] == FAIL]
+        if failures:
+            raise CommandError(
+                f"{len(failures)} of {len(results)} checks failed.",
+                returncode=EXIT_CODES.get(failures[0][0], 1),
+            )
+
+    def check_environment(self):
+        """Check the HUBUUM_* variables, see hubuumsite.config."""
+        errors = validate(os.environ)
+        if errors:
+            return [("configuration", FAIL, "; ".join(errors))]
+        return [("configuration", PASS, "valid")]
+
+    def check_database(self, connection):
+        """Check that the database is reachable, and report its version."""
+        try:
+            with connection.cursor() as cursor:
+                cursor.execute("SELECT version()")
+                version = cursor.fetchone()[0]
+        except Exception as exc:  # pylint: disable=broad-except
+            return [("database", FAIL, f"unable to connect: {exc}")]
+
+        results = [("database", PASS, version)]
+        schema = getattr(settings, "DATABASE_SCHEMA", "")
+        if schema:
+            with connection.cursor() as cursor:
+                cursor.execute("SELECT current_schema()")
+                current = cursor.fetchone()[0]
+            status = PASS if current == schema else FAIL
+            results.append(("database schema", status, f"using '{current}'"))
+        return results
+
+    def check_migrations(self, connection):
+        """Check that all migrations are applied."""
+        executor = MigrationExecutor(connection)
+        plan = executor.migration_plan(executor.loader.graph.leaf_nodes())
+        if not plan:
+            return [("migrations", PASS, "all migrations applied")]
+
+        pending = ", ".join(f"{mig.app_label}.{mig.name}" for mig, _ in plan)
+        return [("migrations", FAIL, f"{len(plan)} unapplied: {pending}")]
+
+    def check_tls(self):
+        """Check that the TLS certificate and key of the server can be loaded."""
+        tls = tls_options()
+        if tls is None:
+            return [("tls", PASS, "not configured")]
+
+        context = ssl.create_default_context(ssl.Purpose.CLIENT_AUTH)
+        try:
+            passphrase = str(tls["passphrase"]) if tls["passphrase"] else None
+            context.load_cert_chain(tls["cert"], tls["key"], password=passphrase)
+        except (OSError, ImproperlyConfigured) as exc:
+            return [("tls", FAIL, f"unable to load {tls['cert']}: {exc}")]
+        return [("tls", PASS, f"loaded {tls['cert']}")]
+
+    def check_clock_skew(self, connection, max_skew):
+        """Check that the local clock agrees with the database clock."""
+        with connection.cursor() as cursor:
+            cursor.execute("SELECT now()")
+            database_now = cursor.fetchone()[0]
+        skew = abs((timezone.now() - database_now).total_seconds())
+        status = PASS if skew <= max_skew else FAIL
+        return [("clock skew", status, f"{skew:.3f}s (max {max_skew}s)")]
+
+    def check_configuration(self):
+        """Check the configuration for common mistakes."""
+        results = []
+        if settings.SECRET_KEY.startswith(DEFAULT_SECRET_KEY_PREFIX):
+            results.append(("secret key", WARN, "using the default SECRET_KEY"))
+        else:
+            results.append(("secret key", PASS, "set"))
+
+        if settings.DEBUG:
+            results.append(("debug", WARN, "DEBUG is enabled"))
+        else:
+            results.append(("debug", PASS, "DEBUG is disabled"))
+            if not settings.ALLOWED_HOSTS:
+                results.append(("allowed hosts", FAIL, "ALLOWED_HOSTS is empty"))
+
+        explain = getattr(settings, "EXPLAIN_PERMISSIONS", "admin")
+        if explain in EXPLAIN_PERMISSIONS_VALUES:
+            results.append(("explain permissions", PASS, explain))
+        else:
+            valid = ", ".join(EXPLAIN_PERMISSIONS_VALUES)
+            detail = f"'{explain}' is not one of {valid}"
+            results.append(("explain permissions", FAIL, detail))
+
+        return results
+
+    def check_integrations(self, timeout):
+        """Check that the hosts of every configured extension are reachable."""
+        results = []
+        for extension in Extension.objects.order_by("name"):
+            name = f"extension {extension.name}"
+            url = urlsplit(extension.url)
+            if url_interpolation_regexp.search(url.netloc):
+                detail = "host is interpolated per object, not checked"
+                results.append((name, WARN, detail))
+                continue
+
+            port = url.port or (443 if url.scheme == "https" else 80)
+            try:
+                with socket.create_connection((url.hostname, port), timeout=timeout):
+                    results.append((name, PASS, f"{url.hostname}:{port} reachable"))
+            except (OSError, ValueError) as exc:
+                results.append((name, FAIL, f"{url.hostname}:{port}: {exc}"))
+
+        if not results:
+            results.append(("integrations", PASS, "no extensions configured"))
+        return results
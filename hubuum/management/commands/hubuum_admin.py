@@ -0,0 +1,189 @@
+"""Administer a hubuum deployment without going through the API.
+
+Meant for bootstrapping and recovery, when there is no admin user (or no
+working token) to craft API calls with. Talks directly to the database:
+
+    manage.py hubuum_admin createadmin <username> [--password <password>]
+    manage.py hubuum_admin resetpassword <username> [--password <password>]
+    manage.py hubuum_admin namespaces
+    manage.py hubuum_admin migrate
+    manage.py hubuum_admin rotatetokens <username> [--revoke-only]
+    manage.py hubuum_admin seed <file>
+    manage.py hubuum_admin dump <file>
+    manage.py hubuum_admin restore <file>
+
+Passwords are prompted for unless given.
+"""
+import getpass
+
+from django.core.management import call_command
+from django.core.management.base import BaseCommand, CommandError
+from django.db import transaction
+from django.db.models import Count
+from knox.models import AuthToken
+
+from hubuum import dump, seed
+from hubuum.models.auth import User
+from hubuum.models.namespace import Namespace
+from hubuum.renderers import ndjson_lines
+
+
+class Command(BaseCommand):
+    """Bootstrap and recovery tasks for administrators."""
+
+    help = (
+        "Create admins, reset passwords, list namespaces, migrate, rotate tokens, "
+        "apply seed data, and dump and restore all data."
+    )
+
+    def add_arguments(self, parser):
+        """Add the subcommands and their arguments."""
+        actions = parser.add_subparsers(dest="action", required=True)
+
+        for action, description in (
+            ("createadmin", "Create a superuser, or promote an existing user."),
+            ("resetpassword", "Set the password of a user."),
+        ):
+            subparser = actions.add_parser(action, help=description)
+            subparser.add_argument("username")
+            subparser.add_argument(
+                "--password", help="The password to set. Prompted for if not given."
+            )
+
+        actions.add_parser("namespaces", help="List all namespaces.")
+        actions.add_parser("migrate", help="Apply all database migrations.")
+
+        subparser = actions.add_parser(
+            "rotatetokens", help="Revoke the tokens of a user and issue a new one."
+        )
+        subparser.add_argument("username")
+        subparser.add_argument(
+            "--revoke-only",
+            action="store_true",
+            help="Only revoke the tokens, do not issue a new one.",
+        )
+
+        subparser = actions.add_parser(
+            "seed", help="Apply declarative seed data from a file, see hubuum.seed."
+        )
+        subparser.add_argument("file")
+
+        for action, description in (
+            ("dump", "Write a logical dump of all data to a file, see hubuum.dump."),
+            ("restore", "Restore a logical dump from a file, see hubuum.dump."),
+        ):
+            subparser = actions.add_parser(action, help=description)
+            subparser.add_argument("file")
+
+    def handle(self, *args, **options):
+        """Run the requested action."""
+        getattr(self, f"handle_{options['action']}")(options)
+
+    def _get_user(self, username):
+        """Find a user by username."""
+        try:
+            return User.objects.get(username=username)
+        except User.DoesNotExist as ex:
+            raise CommandError(f"No such user '{username}'.") from ex
+
+    def _get_password(self, options):
+        """Get the password from the options, or prompt for it."""
+        if options["password"]:
+            return options["password"]
+
+        password = getpass.getpass("Password: ")
+        if not password or password != getpass.getpass("Password (again): "):
+            raise CommandError("The passwords are empty or do not match.")
+        return password
+
+    def handle_createadmin(self, options):
+        """Create a superuser, or promote an existing user to one."""
+        password = self._get_password(options)
+        user, created = User.objects.get_or_create(username=options["username"])
+        user.is_superuser = True
+        user.is_staff = True
+        user.is_active = True
+        user.set_password(password)
+        user.save()
+
+        action = "Created" if created else "Promoted"
+        self.stdout.write(self.style.SUCCESS(f"{action} admin user '{user}'."))
+
+    def handle_resetpassword(self, options):
+        """Set the password of a user."""
+        user = self._get_user(options["username"])
+        user.set_password(self._get_password(options))
+        user.save()
+        self.stdout.write(self.style.SUCCESS(f"Password set for '{user}'."))
+
+    def handle_namespaces(self, options):
+        """List all namespaces, with the number of hosts in them."""
+        namespaces = Namespace.objects.annotate(hosts=Count("host")).order_by("name")
+        for namespace in namespaces:
+            archived = " (archived)" if namespace.archived else ""
+            self.stdout.write(
+                f"{namespace.id}\t{namespace.name}\t{namespace.hosts} hosts{archived}"
+            )
+
+    def handle_migrate(self, options):
+        """Apply all database migrations."""
+        call_command("migrate", interactive=False, stdout=self.stdout)
+
+    def handle_rotatetokens(self, options):
+        """Revoke all the tokens of a user, and issue a new one to print."""
+        user = self._get_user(options["username"])
+        with transaction.atomic():
+            tokens = AuthToken.objects.filter(user=user)
+            revoked = tokens.count()
+            tokens.delete()
+            token = None
+            if not options["revoke_only"]:
+                _, token = AuthToken.objects.create(user)
+
+        self.stdout.write(f"Revoked {revoked} token(s) for '{user}'.")
+        if token:
+            self.stdout.write(token)
+
+    def handle_seed(self, options):
+        """Apply a seed file, and report what was created and updated."""
+        try:
+            report = seed.apply(seed.read_file(options["file"]))
+        except seed.SeedError as ex:
+            raise CommandError(str(ex)) from ex
+
+        for action in ("created", "updated"):
+            for description in report[action]:
+                self.stdout.write(f"{action.capitalize()} {description}.")
+        self.stdout.write(
+            self.style.SUCCESS(
+                f"Seed applied, {len(report['created'])} created"
+                f" and {len(report['updated'])} updated."
+            )
+        )
+
+    def handle_dump(self, options):
+        """Write a dump to a file."""
+        lines = 0
+        with open(options["file"], "w", encoding="utf-8") as file:
+            for line in ndjson_lines(dump.dump()):
+                file.write(line)
+                lines += 1
+        self.stdout.write(
+            self.style.SUCCESS(f"Dumped {lines - 1} objects to {options['file']}.")
+        )
+
+    def handle_restore(self, options):
+        """Restore a dump from a file, and report the objects restored per model."""
+        try:
+            with open(options["file"], encoding="utf-8") as file:
+                restored = dump.restore(file)
+        except OSError as ex:
+            raise CommandError(f"Unable to read {options['file']}: {ex}") from ex
+        except dump.DumpError as ex:
+            raise CommandError(str(ex)) from ex
+
+        for model, count in restored.items():
+            self.stdout.write(f"Restored {count} {model}.")
+        self.stdout.write(
+            self.style.SUCCESS(f"Restored {sum(restored.values())} objects.")
+        )
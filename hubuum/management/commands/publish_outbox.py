@@ -0,0 +1,103 @@
+"""Publish pending outbox events to the configured backend.
+
+    manage.py publish_outbox [--once] [--batch-size <n>] [--interval <seconds>]
+
+Runs until interrupted, unless --once is given. Events are published in the order
+they were recorded. If publishing an event fails, the rest of the batch is held
+back and retried after the interval, so the order is kept. Several publishers may
+be started, but only one publishes at a time (with PostgreSQL, through an
+advisory lock), the others wait for their turn.
+"""
+import time
+
+from django.core.exceptions import ImproperlyConfigured
+from django.core.management.base import BaseCommand, CommandError
+from django.db import connection, transaction
+from django.utils import timezone
+
+from hubuum.models.outbox import OutboxEvent
+from hubuum.outbox import get_backend
+
+# Held by the publisher publishing, so events are not published out of order.
+ADVISORY_LOCK = 0x6F757462  # "outb"
+
+
+class Command(BaseCommand):
+    """Publish outbox events."""
+
+    help = "Publish pending outbox events to the configured backend."
+
+    def add_arguments(self, parser):
+        """Add the arguments."""
+        parser.add_argument(
+            "--once", action="store_true", help="Publish pending events and exit."
+        )
+        parser.add_argument(
+            "--batch-size", type=int, default=100, help="Events per batch."
+        )
+        parser.add_argument(
+            "--interval",
+            type=float,
+            default=1.0,
+            help="Seconds to wait when there are no events to publish.",
+        )
+
+    def handle(self, *args, **options):
+        """Publish events until interrupted (or done, with --once)."""
+        try:
+            backend = get_backend()
+        except ImproperlyConfigured as ex:
+            raise CommandError(str(ex)) from ex
+
+        try:
+            while True:
+                published, failed = self.publish_batch(backend, options["batch_size"])
+                if published:
+                    self.stdout.write(f"Published {published} event(s).")
+                if options["once"]:
+                    if failed:
+                        raise CommandError("Publishing failed, see last_error.")
+                    return
+                if failed or published < options["batch_size"]:
+                    time.sleep(options["interval"])
+        finally:
+            backend.close()
+
+    def publish_batch(self, backend, batch_size):
+        """Publish a batch of pending events.
+
+        Nothing is published while another publisher holds the lock, as publishing
+        the events after those it has locked could publish them out of order.
+
+        returns: the number of events published, and whether publishing failed
+        """
+        published = 0
+        with transaction.atomic():
+            if connection.vendor == "postgresql":
+                with connection.cursor() as cursor:
+                    cursor.execute(
+                        "SELECT pg_try_advisory_xact_lock(%s)", [ADVISORY_LOCK]
+                    )
+                    if not cursor.fetchone()[0]:
+                        return published, False
+
+            events = (
+                OutboxEvent.objects.filter(published_at=None)
+                .select_for_update()
+                .order_by("id")[:batch_size]
+            )
+            for event in events:
+                try:
+                    backend.publish(event)
+                except Exception as ex:  # pylint: disable=broad-except
+                    event.attempts += 1
+                    event.last_error = str(ex)
+                    event.save(update_fields=["attempts", "last_error"])
+                    return published, True
+
+                event.published_at = timezone.now()
+                event.attempts += 1
+                event.save(update_fields=["published_at", "attempts"])
+                published += 1
+
+        return published, False
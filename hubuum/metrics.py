@@ -0,0 +1,75 @@
+"""Domain counters for hubuum, exposed in the OpenMetrics text format.
+
+Counters are kept in memory, per process. When running multiple workers, every
+worker reports its own counters and the scraper has to sum them up.
+
+Labels are restricted to values from small, known sets (model names, HTTP methods)
+to keep the cardinality of the metrics bounded.
+"""
+import threading
+
+CONTENT_TYPE = "application/openmetrics-text; version=1.0.0; charset=utf-8"
+
+# Apps whose models are labeled by name, everything else is labeled "other".
+LABELED_APPS = ("hubuum", "auth")
+
+COUNTERS = {
+    "hubuum_objects_created": ("Objects created, per model.", ("model",)),
+    "hubuum_objects_deleted": ("Objects deleted, per model.", ("model",)),
+    "hubuum_group_memberships": ("Users added to or removed from groups.", ("action",)),
+    "hubuum_permission_denials": (
+        "Requests denied due to missing permissions, per method.",
+        ("method",),
+    ),
+    "hubuum_json_searches": (
+        "JSON searches completed, rejected as busy, and slow, see hubuum.search.",
+        ("outcome",),
+    ),
+}
+
+_lock = threading.Lock()
+_values = {name: {} for name in COUNTERS}
+
+
+def model_label(model):
+    """Return the label to use for a model."""
+    meta = model._meta  # pylint: disable=protected-access
+    if meta.app_label in LABELED_APPS:
+        return meta.label_lower
+    return "other"
+
+
+def increment(name, value=1, **labels):
+    """Increment the counter name with the given labels."""
+    key = tuple(str(labels[label]) for label in COUNTERS[name][1])
+    with _lock:
+        _values[name][key] = _values[name].get(key, 0) + value
+
+
+def value(name, **labels):
+    """Return the current value of the counter name with the given labels."""
+    key = tuple(str(labels[label]) for label in COUNTERS[name][1])
+    with _lock:
+        return _values[name].get(key, 0)
+
+
+def render():
+    """Render all counters in the OpenMetrics text format."""
+    lines = []
+    with _lock:
+        for name, (description, label_names) in COUNTERS.items():
+            lines.append(f"# TYPE {name} counter")
+            lines.append(f"# HELP {name} {description}")
+            for key, count in sorted(_values[name].items()):
+                labels = ",".join(
+                    f'{label}="{_escape(label_value)}"'
+                    for label, label_value in zip(label_names, key)
+                )
+                lines.append(f"{name}_total{{{labels}}} {count}")
+    lines.append("# EOF")
+    return "\n".join(lines) + "\n"
+
+
+def _escape(label_value):
+    """Escape a label value."""
+    return label_value.replace("\\", "\\\\").replace('"', '\\"').replace("\n", "\\n")
@@ -0,0 +1,32 @@
+"""Middleware to set the actor for a request."""
+from hubuum.actor import Actor, reset_actor, set_actor
+
+
+class ActorMiddleware:
+    """
+    Middleware to attribute writes during a request to the source of the request.
+
+    The actor is updated with the user once the request is authenticated, see
+    hubuum.api.authentication.
+    """
+
+    def __init__(self, get_response):
+        """
+        Initialize the middleware.
+
+        :param get_response: A reference to the next middleware or view in the chain.
+        """
+        self.get_response = get_response
+
+    def __call__(self, request):
+        """
+        Process the request with the actor set.
+
+        :param request: The incoming request.
+        :return: A response object
+        """
+        token = set_actor(Actor.from_request(request))
+        try:
+            return self.get_response(request)
+        finally:
+            reset_actor(token)
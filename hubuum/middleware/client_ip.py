@@ -0,0 +1,36 @@
+"""Middleware to find the address of the client."""
+from django.conf import settings
+
+from hubuum.network import client_ip, parse_networks
+
+
+class ClientIPMiddleware:
+    """
+    Middleware to find the address of the client, behind trusted proxies.
+
+    The address is found by trusting X-Forwarded-For from the proxies in
+    IP_TRUSTED_PROXIES, and is stored as request.client_ip. It runs before the
+    middleware using it, ie ActorMiddleware and IPFilterMiddleware.
+    """
+
+    def __init__(self, get_response):
+        """
+        Initialize the middleware.
+
+        :param get_response: A reference to the next middleware or view in the chain.
+        """
+        self.get_response = get_response
+
+    def __call__(self, request):
+        """
+        Process the request with the address of the client set.
+
+        :param request: The incoming request.
+        :return: A response object
+        """
+        request.client_ip = client_ip(
+            request.META.get("REMOTE_ADDR", ""),
+            request.META.get("HTTP_X_FORWARDED_FOR"),
+            parse_networks(settings.IP_TRUSTED_PROXIES),
+        )
+        return self.get_response(request)
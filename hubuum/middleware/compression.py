@@ -0,0 +1,65 @@
+"""Middleware to accept compressed request bodies."""
+import io
+import zlib
+
+from django.conf import settings
+from django.http import JsonResponse
+
+
+class DecompressRequestMiddleware:
+    """
+    Middleware to decompress request bodies sent with a Content-Encoding.
+
+    Bodies encoded with gzip or deflate are decompressed before the views see
+    them. The decompressed body is limited by DATA_UPLOAD_MAX_MEMORY_SIZE, just
+    like an uncompressed body. Other encodings are rejected with 415.
+    """
+
+    encodings = {"gzip": 16 + zlib.MAX_WBITS, "deflate": zlib.MAX_WBITS}
+
+    def __init__(self, get_response):
+        """
+        Initialize the middleware.
+
+        :param get_response: A reference to the next middleware or view in the chain.
+        """
+        self.get_response = get_response
+
+    def __call__(self, request):
+        """
+        Process the request and decompress its body, if it is encoded.
+
+        :param request: The incoming request.
+        :return: A response object
+        """
+        encoding = request.META.get("HTTP_CONTENT_ENCODING", "").strip().lower()
+        if not encoding or encoding == "identity":
+            return self.get_response(request)
+
+        if encoding not in self.encodings:
+            return JsonResponse(
+                {"detail": f"Unsupported Content-Encoding '{encoding}'."}, status=415
+            )
+
+        limit = settings.DATA_UPLOAD_MAX_MEMORY_SIZE
+        decompressor = zlib.decompressobj(self.encodings[encoding])
+        try:
+            body = decompressor.decompress(request.body, limit + 1 if limit else 0)
+        except zlib.error:
+            body = None
+
+        if body is not None and limit and len(body) > limit:
+            return JsonResponse(
+                {"detail": "The decompressed request body is too large."}, status=413
+            )
+        if body is None or not decompressor.eof:
+            return JsonResponse(
+                {"detail": "The request body could not be decompressed."}, status=400
+            )
+
+        # Replace the body, as if it had been sent uncompressed.
+        request._body = body  # pylint: disable=protected-access
+        request._stream = io.BytesIO(body)  # pylint: disable=protected-access
+        request.META["CONTENT_LENGTH"] = str(len(body))
+        del request.META["HTTP_CONTENT_ENCODING"]
+        return self.get_response(request)
@@ -0,0 +1,60 @@
+"""Middleware to explain the permission checks performed for a request."""
+import json
+
+from django.conf import settings
+
+TRACE_HEADER = "X-Hubuum-Permission-Trace"
+
+
+class ExplainPermissionsMiddleware:
+    """
+    Middleware to report the permission checks made while handling a request.
+
+    If the query parameter "explain_permissions" is true, the permission classes
+    record every check they perform, with the groups that granted access. The
+    trace is returned as JSON in the X-Hubuum-Permission-Trace response header.
+
+    The setting EXPLAIN_PERMISSIONS decides who may ask for an explanation:
+     - "admin" (the default): only admin (staff or superuser) users.
+     - "all": all authenticated users.
+     - "none": nobody.
+    """
+
+    def __init__(self, get_response):
+        """
+        Initialize the middleware.
+
+        :param get_response: A reference to the next middleware or view in the chain.
+        """
+        self.get_response = get_response
+
+    def __call__(self, request):
+        """
+        Process the request and add the permission trace to the response.
+
+        :param request: The incoming request.
+        :return: A response object
+        """
+        explain = request.GET.get("explain_permissions", "").lower()
+        if explain in ["1", "true", "yes"]:
+            request.permission_trace = []
+
+        response = self.get_response(request)
+
+        trace = getattr(request, "permission_trace", None)
+        if trace is not None and self._may_explain(getattr(request, "user", None)):
+            response[TRACE_HEADER] = json.dumps(trace)
+
+        return response
+
+    @staticmethod
+    def _may_explain(user):
+        """Check if the (authenticated) user may see permission traces."""
+        policy = getattr(settings, "EXPLAIN_PERMISSIONS", "admin")
+        if user is None or not user.is_authenticated or policy == "none":
+            return False
+
+        if policy == "all":
+            return True
+
+        return user.is_staff or user.is_superuser
@@ -0,0 +1,78 @@
+"""Middleware to restrict access by the address of the client."""
+from django.conf import settings
+from django.http import JsonResponse
+
+from hubuum.network import in_networks, parse_networks
+
+
+class IPFilterMiddleware:
+    """
+    Middleware to allow or deny requests by the address of the client.
+
+    Requests from addresses in IP_DENY are denied. If IP_ALLOW is set, only
+    requests from addresses in it are allowed. Requests to the administrative
+    routes (IP_ADMIN_ROUTES, by the names of the routes resolved) must in addition
+    come from IP_ADMIN_ALLOW, if set. Denied requests get 403.
+
+    The address of the client is request.client_ip, see ClientIPMiddleware.
+    """
+
+    def __init__(self, get_response):
+        """
+        Initialize the middleware.
+
+        :param get_response: A reference to the next middleware or view in the chain.
+        """
+        self.get_response = get_response
+
+    def __call__(self, request):
+        """
+        Process the request, and deny it if the client is not allowed.
+
+        :param request: The incoming request.
+        :return: A response object
+        """
+        if not self._allowed(request):
+            return self._denied(request)
+
+        return self.get_response(request)
+
+    def process_view(self, request, view_func, view_args, view_kwargs):
+        """
+        Deny requests to the administrative routes from outside IP_ADMIN_ALLOW.
+
+        The route is known once the path is resolved, so this is checked here
+        rather than when the request comes in.
+
+        :param request: The incoming request.
+        :return: A response denying the request, or None to let it through
+        """
+        admin_allow = parse_networks(settings.IP_ADMIN_ALLOW)
+        if admin_allow and self._admin_route(request.resolver_match):
+            if not in_networks(request.client_ip, admin_allow):
+                return self._denied(request)
+        return None
+
+    @staticmethod
+    def _admin_route(match):
+        """Check if a resolved route is administrative, by its name or namespace."""
+        routes = settings.IP_ADMIN_ROUTES
+        return match.url_name in routes or any(
+            namespace in routes for namespace in match.namespaces
+        )
+
+    @staticmethod
+    def _denied(request):
+        """Deny the request."""
+        return JsonResponse(
+            {"detail": f"Access denied from {request.client_ip}."}, status=403
+        )
+
+    def _allowed(self, request):
+        """Check the address of the client against the configured networks."""
+        address = request.client_ip
+        if in_networks(address, parse_networks(settings.IP_DENY)):
+            return False
+
+        allow = parse_networks(settings.IP_ALLOW)
+        return not allow or in_networks(address, allow)
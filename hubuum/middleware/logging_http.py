@@ -33,6 +33,8 @@ class LogHttpResponseMiddleware:
         :return: A response object
         """
         start_time = time.time()
+        # The time spent so far is reported in enveloped responses, see pagination.
+        request.start_time = start_time
         response = self.get_response(request)
         end_time = time.time()
         status_code = response.status_code
@@ -0,0 +1,44 @@
+"""Middleware to collect request metrics."""
+from hubuum import denials, metrics
+
+
+class MetricsMiddleware:
+    """
+    Middleware to count requests that are denied due to missing permissions.
+
+    See hubuum.metrics for the exposed counters. Denials (401 and 403) are also
+    recorded per user, route, and namespace, see hubuum.denials.
+    """
+
+    def __init__(self, get_response):
+        """
+        Initialize the middleware.
+
+        :param get_response: A reference to the next middleware or view in the chain.
+        """
+        self.get_response = get_response
+
+    def __call__(self, request):
+        """
+        Process the request and count permission denials.
+
+        :param request: The incoming request.
+        :return: A response object
+        """
+        request.denied_namespaces = []
+        response = self.get_response(request)
+        if response.status_code == 403:
+            metrics.increment("hubuum_permission_denials", method=request.method)
+
+        if response.status_code in (401, 403):
+            user = getattr(request, "user", None)
+            match = request.resolver_match
+            denials.record(
+                user.username if user is not None and user.is_authenticated else None,
+                request.method,
+                match.route if match is not None else request.path,
+                request.denied_namespaces[-1] if request.denied_namespaces else None,
+                response.status_code,
+            )
+
+        return response
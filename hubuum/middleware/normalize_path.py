@@ -0,0 +1,61 @@
+"""Middleware to accept the paths of the API with and without a trailing slash."""
+from django.urls import Resolver404, resolve
+
+
+class NormalizePathMiddleware:
+    """
+    Middleware to accept the paths of the API with and without a trailing slash.
+
+    Routes are declared with (ie hosts/) or without (ie hosts/<val>) a trailing
+    slash. A path ending with a slash is resolved without it if that resolves,
+    which keeps the slash out of names (ie hosts/by-name/<namespace>/<path:name>).
+    A path that does not resolve is resolved with a trailing slash, if that does.
+    The request is handled as if the route was given, rather than redirected, so
+    writes work with either form. Only paths below /api/ are normalized, see
+    prefixes.
+    """
+
+    prefixes = ("/api/",)
+
+    def __init__(self, get_response):
+        """
+        Initialize the middleware.
+
+        :param get_response: A reference to the next middleware or view in the chain.
+        """
+        self.get_response = get_response
+
+    def __call__(self, request):
+        """
+        Process the request, normalizing the path to that of a route.
+
+        :param request: The incoming request.
+        :return: A response object
+        """
+        path = request.path_info
+        if path.startswith(self.prefixes):
+            normalized = self._normalize(path, getattr(request, "urlconf", None))
+            if normalized != path:
+                script_name = request.path[: len(request.path) - len(path)]
+                request.path = f"{script_name}{normalized}"
+                request.path_info = normalized
+                request.META["PATH_INFO"] = normalized
+
+        return self.get_response(request)
+
+    def _normalize(self, path, urlconf):
+        """Return the form of the path that resolves, the path if neither does."""
+        if path.endswith("/"):
+            return path[:-1] if self._resolves(path[:-1], urlconf) else path
+        if not self._resolves(path, urlconf) and self._resolves(f"{path}/", urlconf):
+            return f"{path}/"
+        return path
+
+    @staticmethod
+    def _resolves(path, urlconf):
+        """Check if the path resolves to a route."""
+        try:
+            resolve(path, urlconf)
+        except Resolver404:
+            return False
+        return True
@@ -0,0 +1,47 @@
+"""Middleware to deny writes while the server is read-only."""
+from django.http import JsonResponse
+
+from hubuum.exceptions import ReadOnly
+from hubuum.models.base import ReadOnlyMode
+
+
+class ReadOnlyMiddleware:
+    """
+    Middleware to deny writes while the server is in read-only (maintenance) mode.
+
+    Writes are requests with other methods than GET, HEAD, and OPTIONS. Logging in
+    and out, and turning the read-only mode off, are allowed (exempt_paths).
+    Denied requests get 503, with Retry-After. See ReadOnlyMode.
+    """
+
+    safe_methods = ("GET", "HEAD", "OPTIONS")
+    exempt_paths = ("/api/auth/", "/api/read-only/")
+
+    def __init__(self, get_response):
+        """
+        Initialize the middleware.
+
+        :param get_response: A reference to the next middleware or view in the chain.
+        """
+        self.get_response = get_response
+
+    def __call__(self, request):
+        """
+        Process the request, and deny it if it is a write to a read-only server.
+
+        :param request: The incoming request.
+        :return: A response object
+        """
+        if request.method in self.safe_methods or request.path.startswith(
+            self.exempt_paths
+        ):
+            return self.get_response(request)
+
+        try:
+            ReadOnlyMode.check([None])
+        except ReadOnly as ex:
+            response = JsonResponse({"detail": str(ex.detail)}, status=ex.status_code)
+            response["Retry-After"] = str(ex.wait)
+            return response
+
+        return self.get_response(request)
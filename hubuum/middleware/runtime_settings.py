@@ -0,0 +1,31 @@
+"""Middleware to apply the settings changed at runtime."""
+from hubuum import runtime
+from hubuum.models.base import RuntimeSetting
+
+
+class RuntimeSettingsMiddleware:
+    """
+    Middleware to apply the settings changed at runtime to this process.
+
+    The overrides are read from RuntimeSetting, cached for
+    HUBUUM_RUNTIME_SETTINGS_CACHE_TIME seconds, and applied before the request is
+    handled. See hubuum.runtime.
+    """
+
+    def __init__(self, get_response):
+        """
+        Initialize the middleware.
+
+        :param get_response: A reference to the next middleware or view in the chain.
+        """
+        self.get_response = get_response
+
+    def __call__(self, request):
+        """
+        Process the request, with the settings changed at runtime.
+
+        :param request: The incoming request.
+        :return: A response object
+        """
+        runtime.apply(RuntimeSetting.overrides())
+        return self.get_response(request)
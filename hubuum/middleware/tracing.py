@@ -0,0 +1,32 @@
+"""Middleware to trace requests and their database queries."""
+from hubuum import tracing
+
+
+class TracingMiddleware:
+    """
+    Middleware to trace requests, and the database queries made for them.
+
+    See hubuum.tracing for the logging and exporting of the traces.
+    """
+
+    def __init__(self, get_response):
+        """
+        Initialize the middleware.
+
+        :param get_response: A reference to the next middleware or view in the chain.
+        """
+        self.get_response = get_response
+
+    def __call__(self, request):
+        """
+        Process the request within a span.
+
+        :param request: The incoming request.
+        :return: A response object
+        """
+        with tracing.trace_request(request) as request_span:
+            response = self.get_response(request)
+            if request_span is not None:
+                request_span.set_attribute("http.status_code", response.status_code)
+
+        return response
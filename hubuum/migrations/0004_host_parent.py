@@ -0,0 +1,24 @@
+# Generated by Django 4.2 on 2026-10-14 11:05
+
+import django.db.models.deletion
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0003_namespacealias"),
+    ]
+
+    operations = [
+        migrations.AddField(
+            model_name="host",
+            name="parent",
+            field=models.ForeignKey(
+                blank=True,
+                null=True,
+                on_delete=django.db.models.deletion.SET_NULL,
+                related_name="children",
+                to="hubuum.host",
+            ),
+        ),
+    ]
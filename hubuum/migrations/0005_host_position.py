@@ -0,0 +1,17 @@
+# Generated by Django 4.2 on 2026-10-14 11:40
+
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0004_host_parent"),
+    ]
+
+    operations = [
+        migrations.AddField(
+            model_name="host",
+            name="position",
+            field=models.PositiveIntegerField(blank=True, null=True),
+        ),
+    ]
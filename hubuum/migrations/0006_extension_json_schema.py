@@ -0,0 +1,33 @@
+# Generated by Django 4.2 on 2026-10-14 12:25
+
+from django.db import migrations, models
+
+import hubuum.validators
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0005_host_position"),
+    ]
+
+    operations = [
+        migrations.AddField(
+            model_name="extension",
+            name="json_schema",
+            field=models.JSONField(
+                blank=True,
+                null=True,
+                validators=[hubuum.validators.validate_json_schema],
+            ),
+        ),
+        migrations.AddField(
+            model_name="extensiondata",
+            name="schema_errors",
+            field=models.JSONField(editable=False, null=True),
+        ),
+        migrations.AddField(
+            model_name="extensiondata",
+            name="schema_valid",
+            field=models.BooleanField(editable=False, null=True),
+        ),
+    ]
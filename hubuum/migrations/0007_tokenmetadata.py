@@ -0,0 +1,32 @@
+# Generated by Django 4.2 on 2026-10-14 14:10
+
+import django.db.models.deletion
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("knox", "0007_auto_20190111_0542"),
+        ("hubuum", "0006_extension_json_schema"),
+    ]
+
+    operations = [
+        migrations.CreateModel(
+            name="TokenMetadata",
+            fields=[
+                (
+                    "token",
+                    models.OneToOneField(
+                        on_delete=django.db.models.deletion.CASCADE,
+                        primary_key=True,
+                        related_name="metadata",
+                        serialize=False,
+                        to="knox.authtoken",
+                    ),
+                ),
+                ("last_used", models.DateTimeField(null=True)),
+                ("ip", models.GenericIPAddressField(null=True)),
+                ("user_agent", models.CharField(blank=True, max_length=255)),
+            ],
+        ),
+    ]
@@ -0,0 +1,23 @@
+# Generated by Django 4.2 on 2026-10-14 14:55
+
+from django.db import migrations, models
+
+import hubuum.validators
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0007_tokenmetadata"),
+    ]
+
+    operations = [
+        migrations.AddField(
+            model_name="namespace",
+            name="naming_rules",
+            field=models.JSONField(
+                blank=True,
+                null=True,
+                validators=[hubuum.validators.validate_naming_rules],
+            ),
+        ),
+    ]
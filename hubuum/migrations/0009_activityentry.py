@@ -0,0 +1,45 @@
+# Generated by Django 4.2 on 2026-10-14 15:30
+
+import django.db.models.deletion
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0008_namespace_naming_rules"),
+    ]
+
+    operations = [
+        migrations.CreateModel(
+            name="ActivityEntry",
+            fields=[
+                (
+                    "id",
+                    models.AutoField(
+                        auto_created=True,
+                        primary_key=True,
+                        serialize=False,
+                        verbose_name="ID",
+                    ),
+                ),
+                ("timestamp", models.DateTimeField(auto_now_add=True)),
+                ("action", models.CharField(max_length=16)),
+                ("model", models.CharField(max_length=255)),
+                ("object_id", models.PositiveIntegerField(null=True)),
+                ("object_repr", models.CharField(max_length=255)),
+                ("actor", models.JSONField(null=True)),
+                (
+                    "namespace",
+                    models.ForeignKey(
+                        db_constraint=False,
+                        on_delete=django.db.models.deletion.DO_NOTHING,
+                        related_name="activity",
+                        to="hubuum.namespace",
+                    ),
+                ),
+            ],
+            options={
+                "ordering": ["-timestamp", "-id"],
+            },
+        ),
+    ]
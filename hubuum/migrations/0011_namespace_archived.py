@@ -0,0 +1,17 @@
+# Generated by Django 4.2 on 2026-10-14 16:40
+
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0010_tags"),
+    ]
+
+    operations = [
+        migrations.AddField(
+            model_name="namespace",
+            name="archived",
+            field=models.BooleanField(default=False),
+        ),
+    ]
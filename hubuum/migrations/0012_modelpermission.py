@@ -0,0 +1,55 @@
+# Generated by Django 4.2 on 2026-10-14 17:05
+
+import django.db.models.deletion
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("auth", "0012_alter_user_first_name_max_length"),
+        ("hubuum", "0011_namespace_archived"),
+    ]
+
+    operations = [
+        migrations.CreateModel(
+            name="ModelPermission",
+            fields=[
+                (
+                    "id",
+                    models.AutoField(
+                        auto_created=True,
+                        primary_key=True,
+                        serialize=False,
+                        verbose_name="ID",
+                    ),
+                ),
+                ("created_at", models.DateTimeField(auto_now_add=True)),
+                ("updated_at", models.DateTimeField(auto_now=True)),
+                ("model", models.CharField(max_length=255)),
+                ("has_create", models.BooleanField(default=False)),
+                ("has_read", models.BooleanField(default=False)),
+                ("has_update", models.BooleanField(default=False)),
+                ("has_delete", models.BooleanField(default=False)),
+                (
+                    "group",
+                    models.ForeignKey(
+                        on_delete=django.db.models.deletion.CASCADE,
+                        related_name="model_permissions",
+                        to="auth.group",
+                    ),
+                ),
+                (
+                    "namespace",
+                    models.ForeignKey(
+                        on_delete=django.db.models.deletion.CASCADE,
+                        related_name="model_permissions",
+                        to="hubuum.namespace",
+                    ),
+                ),
+            ],
+            options={
+                "ordering": ["id"],
+                "unique_together": {("namespace", "group", "model")},
+            },
+        ),
+    ]
@@ -0,0 +1,43 @@
+# Generated by Django 4.2 on 2026-10-14 17:55
+
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0013_groupmanager"),
+    ]
+
+    operations = [
+        migrations.CreateModel(
+            name="OutboxEvent",
+            fields=[
+                (
+                    "id",
+                    models.AutoField(
+                        auto_created=True,
+                        primary_key=True,
+                        serialize=False,
+                        verbose_name="ID",
+                    ),
+                ),
+                ("created_at", models.DateTimeField(auto_now_add=True)),
+                ("event", models.CharField(max_length=16)),
+                ("model", models.CharField(max_length=255)),
+                ("object_id", models.PositiveIntegerField(null=True)),
+                ("payload", models.JSONField()),
+                ("published_at", models.DateTimeField(null=True)),
+                ("attempts", models.PositiveIntegerField(default=0)),
+                ("last_error", models.TextField(blank=True)),
+            ],
+            options={
+                "ordering": ["id"],
+                "indexes": [
+                    models.Index(
+                        fields=["published_at", "id"],
+                        name="hubuum_outbox_pending_idx",
+                    )
+                ],
+            },
+        ),
+    ]
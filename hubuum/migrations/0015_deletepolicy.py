@@ -0,0 +1,43 @@
+# Generated by Django 4.2 on 2026-10-14 18:20
+
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0014_outboxevent"),
+    ]
+
+    operations = [
+        migrations.CreateModel(
+            name="DeletePolicy",
+            fields=[
+                (
+                    "id",
+                    models.AutoField(
+                        auto_created=True,
+                        primary_key=True,
+                        serialize=False,
+                        verbose_name="ID",
+                    ),
+                ),
+                ("created_at", models.DateTimeField(auto_now_add=True)),
+                ("updated_at", models.DateTimeField(auto_now=True)),
+                ("relation", models.CharField(max_length=255, unique=True)),
+                (
+                    "policy",
+                    models.CharField(
+                        choices=[
+                            ("restrict", "restrict"),
+                            ("cascade", "cascade"),
+                            ("detach", "detach"),
+                        ],
+                        max_length=16,
+                    ),
+                ),
+            ],
+            options={
+                "ordering": ["relation"],
+            },
+        ),
+    ]
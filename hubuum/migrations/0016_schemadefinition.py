@@ -0,0 +1,50 @@
+# Generated by Django 4.2 on 2026-10-14 19:05
+
+import django.db.models.deletion
+from django.db import migrations, models
+
+import hubuum.validators
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0015_deletepolicy"),
+    ]
+
+    operations = [
+        migrations.CreateModel(
+            name="SchemaDefinition",
+            fields=[
+                (
+                    "id",
+                    models.AutoField(
+                        auto_created=True,
+                        primary_key=True,
+                        serialize=False,
+                        verbose_name="ID",
+                    ),
+                ),
+                ("created_at", models.DateTimeField(auto_now_add=True)),
+                ("updated_at", models.DateTimeField(auto_now=True)),
+                ("name", models.CharField(max_length=255)),
+                ("description", models.TextField(blank=True)),
+                (
+                    "json_schema",
+                    models.JSONField(
+                        validators=[hubuum.validators.validate_json_schema]
+                    ),
+                ),
+                (
+                    "namespace",
+                    models.ForeignKey(
+                        on_delete=django.db.models.deletion.CASCADE,
+                        to="hubuum.namespace",
+                    ),
+                ),
+            ],
+            options={
+                "ordering": ["id"],
+                "unique_together": {("namespace", "name")},
+            },
+        ),
+    ]
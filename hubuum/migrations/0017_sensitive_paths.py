@@ -0,0 +1,28 @@
+# Generated by Django 4.2 on 2026-10-14 20:10
+
+from django.db import migrations, models
+
+import hubuum.validators
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0016_schemadefinition"),
+    ]
+
+    operations = [
+        migrations.AddField(
+            model_name="extension",
+            name="sensitive_paths",
+            field=models.JSONField(
+                blank=True,
+                default=list,
+                validators=[hubuum.validators.validate_sensitive_paths],
+            ),
+        ),
+        migrations.AddField(
+            model_name="permission",
+            name="has_read_sensitive",
+            field=models.BooleanField(default=False),
+        ),
+    ]
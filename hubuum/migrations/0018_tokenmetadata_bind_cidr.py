@@ -0,0 +1,17 @@
+# Generated by Django 4.2 on 2026-10-14 20:40
+
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0017_sensitive_paths"),
+    ]
+
+    operations = [
+        migrations.AddField(
+            model_name="tokenmetadata",
+            name="bind_cidr",
+            field=models.CharField(blank=True, max_length=255),
+        ),
+    ]
@@ -0,0 +1,22 @@
+# Generated by Django 4.2 on 2026-10-14 21:10
+
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0018_tokenmetadata_bind_cidr"),
+    ]
+
+    operations = [
+        migrations.AddField(
+            model_name="namespace",
+            name="write_rate_limit",
+            field=models.PositiveIntegerField(blank=True, null=True),
+        ),
+        migrations.AddField(
+            model_name="namespace",
+            name="max_json_data_size",
+            field=models.PositiveIntegerField(blank=True, null=True),
+        ),
+    ]
@@ -0,0 +1,83 @@
+# Generated by Django 4.2 on 2026-10-14 21:30
+
+import django.core.validators
+import django.db.models.deletion
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("auth", "0012_alter_user_first_name_max_length"),
+        ("hubuum", "0019_namespace_limits"),
+    ]
+
+    operations = [
+        migrations.CreateModel(
+            name="UserExternalID",
+            fields=[
+                (
+                    "id",
+                    models.AutoField(
+                        auto_created=True,
+                        primary_key=True,
+                        serialize=False,
+                        verbose_name="ID",
+                    ),
+                ),
+                (
+                    "source",
+                    models.CharField(
+                        max_length=64,
+                        validators=[django.core.validators.validate_slug],
+                    ),
+                ),
+                ("external_id", models.CharField(max_length=255)),
+                (
+                    "user",
+                    models.ForeignKey(
+                        on_delete=django.db.models.deletion.CASCADE,
+                        related_name="external_ids",
+                        to="hubuum.user",
+                    ),
+                ),
+            ],
+            options={
+                "ordering": ["id"],
+                "unique_together": {("source", "external_id"), ("source", "user")},
+            },
+        ),
+        migrations.CreateModel(
+            name="GroupExternalID",
+            fields=[
+                (
+                    "id",
+                    models.AutoField(
+                        auto_created=True,
+                        primary_key=True,
+                        serialize=False,
+                        verbose_name="ID",
+                    ),
+                ),
+                (
+                    "source",
+                    models.CharField(
+                        max_length=64,
+                        validators=[django.core.validators.validate_slug],
+                    ),
+                ),
+                ("external_id", models.CharField(max_length=255)),
+                (
+                    "group",
+                    models.ForeignKey(
+                        on_delete=django.db.models.deletion.CASCADE,
+                        related_name="external_ids",
+                        to="auth.group",
+                    ),
+                ),
+            ],
+            options={
+                "ordering": ["id"],
+                "unique_together": {("source", "external_id"), ("source", "group")},
+            },
+        ),
+    ]
@@ -0,0 +1,83 @@
+# Generated by Django 4.2 on 2026-10-14 21:50
+
+from django.db import migrations, models
+
+import hubuum.validators
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0020_external_ids"),
+    ]
+
+    operations = [
+        migrations.CreateModel(
+            name="Lifecycle",
+            fields=[
+                (
+                    "id",
+                    models.AutoField(
+                        auto_created=True,
+                        primary_key=True,
+                        serialize=False,
+                        verbose_name="ID",
+                    ),
+                ),
+                ("created_at", models.DateTimeField(auto_now_add=True)),
+                ("updated_at", models.DateTimeField(auto_now=True)),
+                (
+                    "model",
+                    models.CharField(
+                        max_length=255,
+                        unique=True,
+                        validators=[hubuum.validators.validate_model],
+                    ),
+                ),
+                ("states", models.JSONField()),
+                ("transitions", models.JSONField(blank=True, default=dict)),
+            ],
+            options={
+                "ordering": ["model"],
+            },
+        ),
+        migrations.AddField(
+            model_name="host",
+            name="state",
+            field=models.CharField(blank=True, max_length=64),
+        ),
+        migrations.AddField(
+            model_name="hosttype",
+            name="state",
+            field=models.CharField(blank=True, max_length=64),
+        ),
+        migrations.AddField(
+            model_name="jack",
+            name="state",
+            field=models.CharField(blank=True, max_length=64),
+        ),
+        migrations.AddField(
+            model_name="person",
+            name="state",
+            field=models.CharField(blank=True, max_length=64),
+        ),
+        migrations.AddField(
+            model_name="purchasedocuments",
+            name="state",
+            field=models.CharField(blank=True, max_length=64),
+        ),
+        migrations.AddField(
+            model_name="purchaseorder",
+            name="state",
+            field=models.CharField(blank=True, max_length=64),
+        ),
+        migrations.AddField(
+            model_name="room",
+            name="state",
+            field=models.CharField(blank=True, max_length=64),
+        ),
+        migrations.AddField(
+            model_name="vendor",
+            name="state",
+            field=models.CharField(blank=True, max_length=64),
+        ),
+    ]
@@ -0,0 +1,17 @@
+# Generated by Django 4.2 on 2026-10-14 22:10
+
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0021_lifecycles"),
+    ]
+
+    operations = [
+        migrations.AddField(
+            model_name="namespace",
+            name="deletion",
+            field=models.JSONField(blank=True, editable=False, null=True),
+        ),
+    ]
@@ -0,0 +1,23 @@
+# Generated by Django 4.2 on 2026-10-14 23:05
+
+from django.db import migrations, models
+
+import hubuum.validators
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0024_idempotencykey"),
+    ]
+
+    operations = [
+        migrations.AddField(
+            model_name="extension",
+            name="reference_paths",
+            field=models.JSONField(
+                blank=True,
+                default=dict,
+                validators=[hubuum.validators.validate_reference_paths],
+            ),
+        ),
+    ]
@@ -0,0 +1,25 @@
+# Generated by Django 4.2 on 2026-10-14 23:20
+
+import django.db.models.deletion
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("auth", "0012_alter_user_first_name_max_length"),
+        ("hubuum", "0025_extension_reference_paths"),
+    ]
+
+    operations = [
+        migrations.AddField(
+            model_name="namespace",
+            name="owner_group",
+            field=models.ForeignKey(
+                blank=True,
+                null=True,
+                on_delete=django.db.models.deletion.SET_NULL,
+                related_name="owned_namespaces",
+                to="auth.group",
+            ),
+        ),
+    ]
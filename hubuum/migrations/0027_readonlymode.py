@@ -0,0 +1,44 @@
+# Generated by Django 4.2 on 2026-10-14 23:35
+
+import django.db.models.deletion
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0026_namespace_owner_group"),
+    ]
+
+    operations = [
+        migrations.CreateModel(
+            name="ReadOnlyMode",
+            fields=[
+                (
+                    "id",
+                    models.AutoField(
+                        auto_created=True,
+                        primary_key=True,
+                        serialize=False,
+                        verbose_name="ID",
+                    ),
+                ),
+                ("created_at", models.DateTimeField(auto_now_add=True)),
+                ("updated_at", models.DateTimeField(auto_now=True)),
+                ("reason", models.TextField(blank=True)),
+                ("retry_after", models.PositiveIntegerField(default=300)),
+                (
+                    "namespace",
+                    models.OneToOneField(
+                        blank=True,
+                        null=True,
+                        on_delete=django.db.models.deletion.CASCADE,
+                        related_name="read_only_mode",
+                        to="hubuum.namespace",
+                    ),
+                ),
+            ],
+            options={
+                "ordering": ["id"],
+            },
+        ),
+    ]
@@ -0,0 +1,21 @@
+# Generated by Django 4.2 on 2026-10-14 23:50
+
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0027_readonlymode"),
+    ]
+
+    operations = [
+        migrations.AddField(
+            model_name="extension",
+            name="schema_validation",
+            field=models.CharField(
+                choices=[("enforce", "enforce"), ("warn", "warn"), ("off", "off")],
+                default="enforce",
+                max_length=16,
+            ),
+        ),
+    ]
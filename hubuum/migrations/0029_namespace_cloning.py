@@ -0,0 +1,17 @@
+# Generated by Django 4.2 on 2026-10-15 00:20
+
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0028_extension_schema_validation"),
+    ]
+
+    operations = [
+        migrations.AddField(
+            model_name="namespace",
+            name="cloning",
+            field=models.JSONField(blank=True, editable=False, null=True),
+        ),
+    ]
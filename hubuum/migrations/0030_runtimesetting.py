@@ -0,0 +1,33 @@
+# Generated by Django 4.2 on 2026-10-15 00:45
+
+from django.db import migrations, models
+
+
+class Migration(migrations.Migration):
+    dependencies = [
+        ("hubuum", "0029_namespace_cloning"),
+    ]
+
+    operations = [
+        migrations.CreateModel(
+            name="RuntimeSetting",
+            fields=[
+                (
+                    "id",
+                    models.AutoField(
+                        auto_created=True,
+                        primary_key=True,
+                        serialize=False,
+                        verbose_name="ID",
+                    ),
+                ),
+                ("created_at", models.DateTimeField(auto_now_add=True)),
+                ("updated_at", models.DateTimeField(auto_now=True)),
+                ("name", models.CharField(max_length=255, unique=True)),
+                ("value", models.CharField(max_length=255)),
+            ],
+            options={
+                "ordering": ["name"],
+            },
+        ),
+    ]
@@ -5,3 +5,8 @@ Sadly the imports are required.
 """
 from .auth import *  # noqa
 from .base import *  # noqa
+from .extensions import *  # noqa
+from .namespace import *  # noqa
+from .objects import *  # noqa
+from .outbox import *  # noqa
+from .revisions import *  # noqa
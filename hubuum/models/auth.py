@@ -1,12 +1,21 @@
 """Authentication-related models for the hubuum project."""
 import re
+from datetime import timedelta
 
+import structlog
+from django.conf import settings
 from django.contrib.auth.models import AbstractUser, Group
+from django.core.serializers.json import DjangoJSONEncoder
+from django.core.validators import validate_slug
+from django.db import IntegrityError, models, transaction
+from django.utils import timezone
 from rest_framework.exceptions import NotFound
 
-from hubuum.exceptions import MissingParam
-from hubuum.models.base import Namespace, Permission
-from hubuum.permissions import operation_exists
+from hubuum.actor import get_actor
+from hubuum.exceptions import Conflict, IdempotencyKeyReused, MissingParam
+from hubuum.models.namespace import ModelPermission, Namespace, Permission
+from hubuum.network import in_networks, parse_networks
+from hubuum.permissions import fully_qualified_operations, operation_exists
 from hubuum.tools import get_model, get_object
 
 
@@ -43,7 +52,22 @@ class User(AbstractUser):
     )
     lookup_fields = ["id", "username", "email"]
 
+    # When the user was last authenticated, with a token or by logging in. As this
+    # is updated by requests, it is only written once per SEEN_INTERVAL.
+    last_seen_at = models.DateTimeField(null=True, blank=True, editable=False)
+    SEEN_INTERVAL = timedelta(minutes=1)
+
     _group_list = None
+    _namespace_permissions = None
+
+    # Bumped whenever permissions or group memberships change (see signals.py), which
+    # discards permissions cached on user objects for an older generation.
+    permission_generation = 0
+
+    @classmethod
+    def invalidate_permission_caches(cls):
+        """Invalidate the permissions cached on all user objects."""
+        cls.permission_generation += 1
 
     def is_admin(self):
         """Check if the user is any type of admin (staff/superadmin) (or in a similar group?)."""
@@ -61,6 +85,116 @@ class User(AbstractUser):
             self._group_list = list(self.groups.values_list("name", flat=True))
         return self._group_list
 
+    def _permission_cache(self):
+        """Fetch and cache the permissions of the groups of the user.
+
+        returns: dict with the namespace grants (keyed by group and namespace), the
+                 model grants (keyed by model, then group and namespace), and the
+                 namespaces resolved so far (keyed by permission and model)
+        """
+        generation, cache = self._namespace_permissions or (None, None)
+        if generation != User.permission_generation:
+            groups = self.groups.values("id")
+            operations = fully_qualified_operations()
+            cache = {"namespaces": {}, "grants": {}, "models": {}}
+            rows = Permission.objects.filter(group__in=groups).values(
+                "group", "namespace", *operations
+            )
+            for row in rows:
+                cache["grants"][(row["group"], row["namespace"])] = row
+
+            rows = ModelPermission.objects.filter(group__in=groups).values(
+                "group", "namespace", "model", *ModelPermission.operations
+            )
+            for row in rows:
+                grants = cache["models"].setdefault(row["model"], {})
+                grants[(row["group"], row["namespace"])] = row
+            self._namespace_permissions = (User.permission_generation, cache)
+
+        return cache
+
+    def namespaces_where(self, perm, model=None):
+        """Return the IDs of the namespaces where the user can perform perm.
+
+        The permissions for every namespace are fetched in a single query the first
+        time this is called, and cached on the user object. As the user object lives
+        for the duration of a request, repeated lookups within a request (ie, while
+        filtering or checking permissions for a list of objects) are cheap.
+
+        If a model is given, the permissions of a group for that model in a namespace
+        (see ModelPermission) take precedence over those for the namespace itself.
+
+        param: perm (permission string, 'has_[create|read|update|delete|namespace])
+        param: model (model name, class, or instance, optional)
+        return: set of namespace IDs
+        """
+        if not operation_exists(perm, fully_qualified=True):
+            raise MissingParam(
+                f"Unknown permission '{perm}' passed to namespaces_where."
+            )
+
+        if model is not None and not isinstance(model, str):
+            model = model._meta.model_name  # pylint: disable=protected-access
+
+        cache = self._permission_cache()
+        if (perm, model) not in cache["namespaces"]:
+            grants = cache["grants"]
+            if model in cache["models"] and perm in ModelPermission.operations:
+                grants = {**grants, **cache["models"][model]}
+            cache["namespaces"][(perm, model)] = {
+                namespace for (_, namespace), row in grants.items() if row[perm]
+            }
+
+        return cache["namespaces"][(perm, model)]
+
+    def set_active(self, active):
+        """Suspend (active=False) or reactivate (active=True) the user.
+
+        Suspended users can not log in, and their existing tokens are rejected.
+        """
+        self.is_active = active
+        self.save()
+
+        event = "reactivated" if active else "suspended"
+        structlog.get_logger("hubuum.auth").bind(
+            id=self.id, actor=get_actor().as_dict()
+        ).info(event)
+
+    def active_tokens(self):
+        """Return the tokens of the user that have not expired, oldest first."""
+        return (
+            self.auth_token_set.filter(
+                models.Q(expiry__isnull=True) | models.Q(expiry__gt=timezone.now())
+            )
+            .select_related("metadata")
+            .order_by("created")
+        )
+
+    def revoke_tokens(self, group=None):
+        """Revoke (delete) every token of the user, ie when credentials leak.
+
+        The revocation is logged, with the group it was made for, if any.
+
+        returns: the number of tokens revoked
+        """
+        tokens = self.auth_token_set.all()
+        count = tokens.count()
+        tokens.delete()
+
+        structlog.get_logger("hubuum.auth").bind(
+            id=self.id, actor=get_actor().as_dict()
+        ).info("tokens revoked", count=count, group=group.name if group else None)
+        return count
+
+    def record_seen(self):
+        """Record that the user was authenticated, see last_seen_at."""
+        now = timezone.now()
+        if self.last_seen_at and now - self.last_seen_at < self.SEEN_INTERVAL:
+            return
+
+        User.objects.filter(pk=self.pk).update(last_seen_at=now)
+        self.last_seen_at = now
+
     def group_count(self):
         """Return the number of groups the user is a member of."""
         return self.groups.count()
@@ -77,11 +211,21 @@ class User(AbstractUser):
         """Check to see if a user is a member of any of the groups in the list."""
         return bool([i for i in groups if i in self.groups.all()])
 
-    def namespaced_can(self, perm, namespace) -> bool:
+    def manages_group(self, group):
+        """Check if the user may add and remove members of the group.
+
+        Admins manage every group, other users only the groups they are managers of.
+        """
+        if self.is_admin():
+            return True
+        return GroupManager.objects.filter(group=group, user=self).exists()
+
+    def namespaced_can(self, perm, namespace, model=None) -> bool:
         """Check to see if the user can perform perm for namespace.
 
         param: perm (permission string, 'has_[create|read|update|delete|namespace])
         param: namespace (namespace object)
+        param: model (the model operated on, see namespaces_where)
         return True|False
         """
         if not operation_exists(perm, fully_qualified=True):
@@ -89,13 +233,13 @@ class User(AbstractUser):
 
         # We need to check if the user is a member of a group
         # that has the given permission the namespace.
-        groups = namespace.groups_that_can(perm)
-        return self.is_member_of_any(groups)
+        return namespace.id in self.namespaces_where(perm, model)
 
     def has_namespace(
         self,
         namespace,
         write_perm="has_namespace",
+        model=None,
     ):
         """Check if the user has namespace permissions for the given namespace.
 
@@ -109,7 +253,7 @@ class User(AbstractUser):
         if isinstance(namespace, int):
             try:
                 namespace_obj = Namespace.objects.get(pk=namespace)
-                return self.namespaced_can(write_perm, namespace_obj)
+                return self.namespaced_can(write_perm, namespace_obj, model)
             except Namespace.DoesNotExist as exc:
                 raise NotFound from exc
 
@@ -125,7 +269,7 @@ class User(AbstractUser):
         except Namespace.DoesNotExist as exc:
             raise NotFound from exc
 
-        return self.namespaced_can(write_perm, namespace_obj)
+        return self.namespaced_can(write_perm, namespace_obj, model)
 
     #        try:
     #            parent = Namespace.objects.get(name=scope[-1])
@@ -161,9 +305,7 @@ class User(AbstractUser):
 
         # We should always get an object to test against.
         if obj:
-            return Permission.objects.filter(
-                namespace=obj.namespace, **{field: True}, group__in=self.groups.all()
-            ).exists()
+            return obj.namespace_id in self.namespaces_where(field, model)
 
         return False
 
@@ -171,3 +313,242 @@ class User(AbstractUser):
         """Meta class for User."""
 
         ordering = ["id"]
+
+
+class GroupManager(models.Model):
+    """A user that may manage the members of a group, without being an admin.
+
+    Managers may add and remove members, but only admins may appoint managers.
+    """
+
+    group = models.ForeignKey(Group, on_delete=models.CASCADE, related_name="managers")
+    user = models.ForeignKey(
+        User, on_delete=models.CASCADE, related_name="managed_groups"
+    )
+
+    class Meta:
+        """Meta class for GroupManager."""
+
+        unique_together = ("group", "user")
+        ordering = ["id"]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return f"{self.user}:{self.group}"
+
+
+class ExternalID(models.Model):
+    """The identity of a user or group in an external source (ie LDAP, OIDC, SCIM).
+
+    Identities are unique per source, and users and groups have at most one
+    identity per source. Synchronizers use them to find the users and groups
+    they maintain, see UserExternalID and GroupExternalID.
+    """
+
+    source = models.CharField(max_length=64, validators=[validate_slug])
+    external_id = models.CharField(max_length=255)
+
+    class Meta:
+        """Meta class for ExternalID."""
+
+        abstract = True
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return f"{self.source}:{self.external_id}"
+
+
+class UserExternalID(ExternalID):
+    """The identity of a user in an external source."""
+
+    user = models.ForeignKey(
+        User, on_delete=models.CASCADE, related_name="external_ids"
+    )
+
+    class Meta:
+        """Meta class for UserExternalID."""
+
+        unique_together = (("source", "external_id"), ("source", "user"))
+        ordering = ["id"]
+
+
+class GroupExternalID(ExternalID):
+    """The identity of a group in an external source."""
+
+    group = models.ForeignKey(
+        Group, on_delete=models.CASCADE, related_name="external_ids"
+    )
+
+    class Meta:
+        """Meta class for GroupExternalID."""
+
+        unique_together = (("source", "external_id"), ("source", "group"))
+        ordering = ["id"]
+
+
+class LoginEvent(models.Model):
+    """A login of a user, kept as the login history of the user.
+
+    Only the latest LOGIN_HISTORY_SIZE logins of every user are kept.
+    """
+
+    user = models.ForeignKey(
+        "User", on_delete=models.CASCADE, related_name="login_events"
+    )
+    created_at = models.DateTimeField(auto_now_add=True)
+    ip = models.GenericIPAddressField(null=True)
+    user_agent = models.CharField(max_length=255, blank=True)
+    # How the user authenticated, ie "basic" (logging in for a token) or "session".
+    method = models.CharField(max_length=32)
+
+    @classmethod
+    def record(cls, user, request, method):
+        """Record a login of the user, and forget the oldest logins beyond the limit."""
+        cls.objects.create(
+            user=user,
+            ip=getattr(request, "client_ip", None) or request.META.get("REMOTE_ADDR"),
+            user_agent=request.META.get("HTTP_USER_AGENT", "")[:255],
+            method=method,
+        )
+        kept = cls.objects.filter(user=user).order_by("-created_at", "-id")
+        kept = kept.values("id")[: settings.LOGIN_HISTORY_SIZE]
+        cls.objects.filter(user=user).exclude(id__in=kept).delete()
+
+    class Meta:
+        """Meta class for LoginEvent."""
+
+        ordering = ["-created_at", "-id"]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return f"{self.user} {self.created_at.isoformat()}"
+
+
+class IdempotencyKey(models.Model):
+    """An Idempotency-Key given to a create (POST) request, and the response to it.
+
+    Requests retried with the same key within IDEMPOTENCY_WINDOW seconds get the
+    stored response, rather than creating the object again. Keys are per user, see
+    complete for the responses stored.
+    """
+
+    user = models.ForeignKey(
+        "User", on_delete=models.CASCADE, related_name="idempotency_keys"
+    )
+    key = models.CharField(max_length=255)
+    # The hash of the path and the data of the request the key was given for.
+    request_hash = models.CharField(max_length=64)
+    # The response, the status is null while the request is in progress.
+    status = models.PositiveSmallIntegerField(null=True)
+    response = models.JSONField(null=True, encoder=DjangoJSONEncoder)
+    created_at = models.DateTimeField(auto_now_add=True)
+
+    # The errors stored, those given for the data of the request.
+    STORED_ERRORS = (400, 422)
+
+    class Meta:
+        """Meta class for IdempotencyKey."""
+
+        unique_together = (("user", "key"),)
+
+    @classmethod
+    def claim(cls, user, key, request_hash):
+        """Claim a key for a request, or find the stored response for the key.
+
+        returns: (record, claimed), claimed if the request is to be processed
+        raises: Conflict if a request with the key is in progress
+        raises: IdempotencyKeyReused if the key was given for a different request
+        """
+        expired = timezone.now() - timedelta(seconds=settings.IDEMPOTENCY_WINDOW)
+        cls.objects.filter(user=user, created_at__lt=expired).delete()
+
+        try:
+            with transaction.atomic():
+                record = cls.objects.create(
+                    user=user, key=key, request_hash=request_hash
+                )
+                return record, True
+        except IntegrityError:
+            record = cls.objects.get(user=user, key=key)
+
+        if record.request_hash != request_hash:
+            raise IdempotencyKeyReused()
+        if record.status is None:
+            raise Conflict(detail="A request with the Idempotency-Key is in progress.")
+        return record, False
+
+    def complete(self, status, data):
+        """Store the response to the request, or release the key.
+
+        Only responses a retry would get again are stored, successes and validation
+        errors (400, 422). For others, ie throttling (429), conflicts (409), payloads
+        too large (413), and server errors, the key is released so it can be retried.
+        """
+        if not (200 <= status < 300 or status in self.STORED_ERRORS):
+            self.delete()
+            return
+
+        IdempotencyKey.objects.filter(pk=self.pk).update(status=status, response=data)
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return f"{self.user} {self.key}"
+
+
+class TokenMetadata(models.Model):
+    """Usage metadata for an authentication (knox) token.
+
+    This is bookkeeping updated on every authenticated request, and is therefore
+    written with queryset updates and bulk creates, which do not emit save signals.
+    Deletions (with the token) are not logged as object changes, see signals.py.
+    """
+
+    token = models.OneToOneField(
+        "knox.AuthToken",
+        on_delete=models.CASCADE,
+        primary_key=True,
+        related_name="metadata",
+    )
+    last_used = models.DateTimeField(null=True)
+    ip = models.GenericIPAddressField(null=True)
+    user_agent = models.CharField(max_length=255, blank=True)
+    # The networks (comma separated) the token is accepted from, empty for any.
+    bind_cidr = models.CharField(max_length=255, blank=True)
+
+    @classmethod
+    def record_use(cls, token, request):
+        """Record that the token was used for the given request, from the client."""
+        address = getattr(request, "client_ip", None) or request.META.get("REMOTE_ADDR")
+        usage = {
+            "last_used": timezone.now(),
+            "ip": address,
+            "user_agent": request.META.get("HTTP_USER_AGENT", "")[:255],
+        }
+        if not cls.objects.filter(token=token).update(**usage):
+            cls.objects.bulk_create([cls(token=token, **usage)], ignore_conflicts=True)
+
+    @staticmethod
+    def describe(token):
+        """Describe a token and its usage, without exposing the token itself."""
+        metadata = getattr(token, "metadata", None)
+        return {
+            "id": token.token_key,
+            "created": token.created,
+            "expiry": token.expiry,
+            "last_used": metadata.last_used if metadata else None,
+            "ip": metadata.ip if metadata else None,
+            "user_agent": metadata.user_agent if metadata else "",
+            "bind_cidr": metadata.bind_cidr if metadata else "",
+        }
+
+    @classmethod
+    def accepts(cls, token, address):
+        """Check if the token is accepted from the address, see bind_cidr."""
+        metadata = cls.objects.filter(token=token).first()
+        if metadata is None or not metadata.bind_cidr:
+            return True
+        return in_networks(address, parse_networks(metadata.bind_cidr))
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return self.token.token_key
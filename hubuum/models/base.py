@@ -1,16 +1,17 @@
 """Models for the hubuum project."""
 import re
 
-# from datetime import datetime
-from django.contrib.auth.models import Group
+from django.apps import apps
+from django.conf import settings
 from django.contrib.contenttypes.fields import GenericForeignKey, GenericRelation
 from django.contrib.contenttypes.models import ContentType
-from django.db import models
-from rest_framework.exceptions import NotFound
+from django.core.cache import cache
+from django.db import models, transaction
+from rest_framework.exceptions import PermissionDenied, ValidationError
 
-from hubuum.permissions import fully_qualified_operations
+from hubuum.exceptions import Conflict, ReadOnly
 from hubuum.tools import get_model
-from hubuum.validators import url_interpolation_regexp, validate_model, validate_url
+from hubuum.validators import url_interpolation_regexp, validate_model, validate_tag
 
 
 def model_is_open(model):
@@ -23,6 +24,46 @@ def models_that_are_open():
     return ("user", "group")
 
 
+def namespaced_models():
+    """Return the namespaced hubuum models."""
+    return [
+        model
+        for model in apps.get_app_config("hubuum").get_models()
+        if issubclass(model, NamespacedHubuumModel)
+    ]
+
+
+def cloned_models():
+    """Return the models of the objects copied when cloning a namespace."""
+    return [
+        model
+        for model in namespaced_models()
+        if issubclass(model, NamespacedHubuumModelWithExtensions)
+    ]
+
+
+def deletion_order():
+    """Return the namespaced models, with models referring to others before them.
+
+    Objects are deleted in this order when deleting the contents of a namespace in
+    batches, so no batch deletes objects still referred to from the namespace.
+    """
+    namespaced = namespaced_models()
+    ordered = []
+
+    def visit(model, path):
+        if model in ordered or model in path:
+            return
+        for _, referring, _ in model.referring_relations():
+            if referring is not model and referring in namespaced:
+                visit(referring, path | {model})
+        ordered.append(model)
+
+    for model in namespaced:
+        visit(model, set())
+    return ordered
+
+
 def model_supports_extensions(model):
     """Check if a model supports extensions."""
     if isinstance(model, str):
@@ -70,79 +111,248 @@ class NamespacedHubuumModel(HubuumModel):
 
         abstract = True
 
+    def assert_writable(self):
+        """Ensure that the object is not in, or moved out of, an archived namespace.
 
-class Extension(NamespacedHubuumModel):
-    """An extension to a specific model.
+        Namespaces being deleted, or cloned into, are read-only as well.
 
-    For now, it is implied that the extension uses REST.
-    """
+        raises: Conflict if it is
+        raises: ReadOnly if a namespace is in read-only mode, see ReadOnlyMode
+        """
+        namespaces = {self.namespace_id}
+        if self.pk:
+            stored = type(self).objects.filter(pk=self.pk)
+            namespaces.update(stored.values_list("namespace_id", flat=True))
+
+        ReadOnlyMode.check(namespaces)
+        states = get_model("namespace").objects.filter(pk__in=namespaces)
+        for archived, deletion, cloning in states.values_list(
+            "archived", "deletion", "cloning"
+        ):
+            if deletion is not None:
+                raise Conflict(detail="The namespace of the object is being deleted.")
+            if cloning is not None:
+                raise Conflict(detail="The namespace of the object is being cloned.")
+            if archived:
+                raise Conflict(detail="The namespace of the object is archived.")
+
+    def save(self, *args, **kwargs):
+        """Save the object, unless its namespace is archived."""
+        self.assert_writable()
+        super().save(*args, **kwargs)
+
+    def delete(self, *args, user=None, **kwargs):
+        """Delete the object, unless its namespace is archived.
+
+        The delete policies of the relations referring to the object are applied
+        first, see DeletePolicy.
+
+        param: user (the user deleting, see apply_delete_policies)
+
+        raises: Conflict if the namespace is archived, or a policy is restrict
+        raises: PermissionDenied if a policy changes objects the user can not
+        """
+        self.assert_writable()
+        with transaction.atomic():
+            type(self).apply_delete_policies(
+                type(self).objects.filter(pk=self.pk), user=user
+            )
+            return super().delete(*args, **kwargs)
 
-    name = models.CharField(max_length=255, null=False, unique=True)
-    model = models.CharField(max_length=255, null=False, validators=[validate_model])
-    url = models.CharField(max_length=255, null=False, validators=[validate_url])
-    require_interpolation = models.BooleanField(default=True, null=False)
-    header = models.CharField(max_length=512)
-    cache_time = models.PositiveSmallIntegerField(default=60)
+    @classmethod
+    def referring_relations(cls):
+        """List the relations through which other objects refer to objects of cls.
 
-    class Meta:
-        """Meta for the model."""
+        returns: list of (relation, model, field), where relation is the name used
+                 by DeletePolicy, ie "host.room", model is the referring model, and
+                 field is the name of the referring field
+        """
+        relations = []
+        for rel in cls._meta.get_fields():  # pylint: disable=protected-access
+            model = rel.related_model
+            if rel.one_to_many and rel.auto_created:
+                if issubclass(model, NamespacedHubuumModel):
+                    field = rel.field.name
+                    relation = f"{model.__name__.lower()}.{field}"
+                    relations.append((relation, model, field))
+        return relations
 
-        ordering = ["id"]
+    @classmethod
+    def _referring(cls, queryset, model, field, exclude=None):
+        """Find the objects of model referring to those in queryset through field.
 
-    def __str__(self):
-        """Stringify the object, used to represent the object towards users."""
-        return self.name
+        Objects that are in queryset themselves, or match exclude, are left out.
+        """
+        referring = model.objects.filter(**{f"{field}__in": queryset.values("pk")})
+        if model is cls:
+            referring = referring.exclude(pk__in=queryset.values("pk"))
+        if exclude is not None:
+            referring = referring.exclude(exclude)
+        return referring
 
+    @classmethod
+    def restricted(cls, queryset, exclude=None):
+        """Find the objects in queryset that restrict policies protect from deletion.
 
-class ExtensionData(NamespacedHubuumModel):
-    """A model for the extensions data for objects.
+        param: queryset (objects of cls that are to be deleted)
+        param: exclude (a Q for referring objects to disregard, ie as they are
+                        deleted as well)
 
-    Note that the object_id refers to an object of the appropriate model.
-    https://docs.djangoproject.com/en/4.1/ref/contrib/contenttypes/#generic-relations
-    """
+        returns: dict of the primary keys of protected objects to a sorted list
+                 of the relations protecting them
+        """
+        policies = DeletePolicy.policies()
+        restricted = {}
+        for relation, model, field in cls.referring_relations():
+            if policies.get(relation) == "restrict":
+                referring = cls._referring(queryset, model, field, exclude)
+                for pk in referring.values_list(field, flat=True).distinct():
+                    restricted.setdefault(pk, set()).add(relation)
 
-    extension = models.ForeignKey("Extension", on_delete=models.CASCADE, null=False)
+        return {pk: sorted(relations) for pk, relations in restricted.items()}
 
-    content_type = models.ForeignKey(ContentType, on_delete=models.CASCADE)
-    object_id = models.PositiveIntegerField()
-    content_object = GenericForeignKey("content_type", "object_id")
+    @classmethod
+    def unwritable(cls, queryset, user, exclude=None):
+        """Find the objects in queryset whose deletion changes objects the user can not.
 
-    json_data = models.JSONField(null=True)
+        Referring objects deleted by a cascade policy require has_delete, and those
+        detached require has_update, in their namespaces.
 
-    class Meta:
-        """Meta for the model."""
+        param: queryset (objects of cls that are to be deleted)
+        param: user (the user deleting, None for no check)
+        param: exclude (a Q for referring objects to disregard, see restricted)
 
-        unique_together = ("extension", "content_type", "object_id")
-        ordering = ["id"]
+        returns: dict of the primary keys of the objects to a sorted list of the
+                 relations through which objects the user can not change refer to them
+        """
+        if user is None or user.is_admin():
+            return {}
+
+        required = {"cascade": "has_delete", "detach": "has_update"}
+        policies = DeletePolicy.policies()
+        unwritable = {}
+        for relation, model, field in cls.referring_relations():
+            perm = required.get(policies.get(relation))
+            if perm is None:
+                continue
+            referring = cls._referring(queryset, model, field, exclude).exclude(
+                namespace__in=user.namespaces_where(perm, model)
+            )
+            for pk in referring.values_list(field, flat=True).distinct():
+                unwritable.setdefault(pk, set()).add(relation)
+
+        return {pk: sorted(relations) for pk, relations in unwritable.items()}
 
-    def __str__(self):
-        """Stringify the object, used to represent the object towards users."""
-        return str(self.id)
+    @classmethod
+    def apply_delete_policies(cls, queryset, exclude=None, user=None):
+        """Apply the delete policies to the objects referring to those in queryset.
+
+        Call this in a transaction, before deleting the objects in queryset.
+        Referring objects are deleted (cascade, with their own policies applied),
+        or their references are cleared and they are saved (detach). If a user is
+        given, the user must be allowed to do so, see unwritable.
+
+        param: queryset (objects of cls that are to be deleted)
+        param: exclude (a Q for referring objects to disregard, see restricted)
+        param: user (the user deleting, None for no check)
+
+        raises: Conflict if a restrict policy protects any of the objects
+        raises: PermissionDenied if a policy changes objects the user can not
+        """
+        restricted = cls.restricted(queryset, exclude)
+        if restricted:
+            relations = sorted(set().union(*restricted.values()))
+            raise Conflict(
+                detail=f"{cls.__name__} objects are referred to through "
+                f"{', '.join(relations)}, with the delete policy restrict."
+            )
+
+        unwritable = cls.unwritable(queryset, user, exclude)
+        if unwritable:
+            relations = sorted(set().union(*unwritable.values()))
+            raise PermissionDenied(
+                detail=f"{cls.__name__} objects are referred to through "
+                f"{', '.join(relations)}, by objects you can not change."
+            )
+
+        policies = DeletePolicy.policies()
+        for relation, model, field in cls.referring_relations():
+            referring = cls._referring(queryset, model, field, exclude)
+            if policies.get(relation) == "detach":
+                for obj in list(referring):
+                    setattr(obj, field, None)
+                    obj.save()
+            elif policies.get(relation) == "cascade":
+                for obj in list(referring):
+                    obj.delete(user=user)
+
+    def clone(self, namespace=None, relations=True, extension_data=False, **values):
+        """Create a copy of the object, optionally in another namespace.
+
+        Identifiers and automatically set fields (timestamps) are not copied.
+
+        param: namespace (the namespace of the copy, defaults to that of the object)
+        param: relations (copy references to other objects, True)
+        param: extension_data (copy the extension data of the object, False)
+        param: values (field values to set on the copy)
+
+        returns: the copy
+        """
+        automatic = ("auto_now", "auto_now_add")
+        copy = self.__class__(namespace=namespace or self.namespace)
+        for field in self._meta.concrete_fields:
+            if field.primary_key or field.name == "namespace":
+                continue
+            if any(getattr(field, auto, False) for auto in automatic):
+                continue
+            if field.is_relation and not relations:
+                continue
+            setattr(copy, field.attname, getattr(self, field.attname))
+
+        for field, value in values.items():
+            setattr(copy, field, value)
+
+        with transaction.atomic():
+            copy.save()
+            if extension_data and self.supports_extensions():
+                for data in self.extension_data_objects.all():
+                    type(data).objects.create(
+                        namespace=copy.namespace,
+                        extension=data.extension,
+                        content_object=copy,
+                        json_data=data.json_data,
+                    )
+
+        return copy
 
 
 class ExtensionsModel(models.Model):
     """A model that supports extensions."""
 
     extension_data_objects = GenericRelation(
-        ExtensionData, related_query_name="ext_objects"
+        "ExtensionData", related_query_name="ext_objects"
     )
 
     def extensions(self):
         """List all extensions registered for the object."""
         model = self.__class__.__name__.lower()
-        return Extension.objects.filter(model=model).order_by("name")
+        return get_model("extension").objects.filter(model=model).order_by("name")
+
+    def extension_data(self, user=None):
+        """Return the data for each extension the object has.
 
-    def extension_data(self):
-        """Return the data for each extension the object has."""
+        If a user is given, sensitive data is redacted as for that user.
+        """
         extension_data = {}
 
         for extension in self.extensions():
             extension_data[extension.name] = None
 
         for extension_data_obj in self.extension_data_objects.all():
-            extension_data[
-                extension_data_obj.extension.name
-            ] = extension_data_obj.json_data
+            json_data = extension_data_obj.json_data
+            if user is not None:
+                json_data = extension_data_obj.redact_for(user, json_data)
+            extension_data[extension_data_obj.extension.name] = json_data
 
         return extension_data
 
@@ -169,324 +379,492 @@ class ExtensionsModel(models.Model):
         abstract = True
 
 
-class NamespacedHubuumModelWithExtensions(NamespacedHubuumModel, ExtensionsModel):
-    """An abstract model that provides Namespaces and Extensions."""
-
-    class Meta:
-        """Meta data for the class."""
+class Tag(HubuumModel):
+    """A tag, a simple label for objects that is kept apart from their data."""
 
-        abstract = True
+    name = models.CharField(max_length=64, unique=True, validators=[validate_tag])
 
+    class Meta:
+        """Meta for the model."""
 
-class Namespace(HubuumModel):
-    """The namespace ('domain') of an object."""
-
-    name = models.CharField(max_length=255, unique=True)
-    description = models.TextField(blank=True)
+        ordering = ["name"]
 
-    def get_permissions_for_group(self, group: Group, raise_exception=True):
-        """Try to find a permission object for the given group.
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return self.name
 
-        param: group (Group instance)
-        param: raise_exception (True)
 
-        returns: permission object
+class TaggedObject(models.Model):
+    """A tag on an object, see TaggedModel."""
 
-        raises: NotFound if raise_exception is True and no object is found
-        """
-        try:
-            obj = Permission.objects.get(namespace=self, group=group)
-            return obj
-        except Permission.DoesNotExist as exc:
-            if raise_exception:
-                raise NotFound() from exc
-
-        return None
-
-    def grant_all(self, group):
-        """Grant all permissions to the namespace to the given group."""
-        create = {}
-        create["namespace"] = self
-        create["group"] = group
-        for perm in fully_qualified_operations():
-            create[perm] = True
-        Permission.objects.update_or_create(**create)
-        return True
-
-    def groups_that_can(self, perm):
-        """Fetch groups that can perform a specific permission.
-
-        param: perm (permission string, 'has_[read|create|update|delete|namespace])
-        return [group objects] (may be empty)
-        """
-        qs = Permission.objects.filter(namespace=self.id, **{perm: True}).values(
-            "group"
-        )
-        groups = Group.objects.filter(id__in=qs)
-        return groups
+    tag = models.ForeignKey(Tag, on_delete=models.CASCADE, related_name="tagged")
+    content_type = models.ForeignKey(ContentType, on_delete=models.CASCADE)
+    object_id = models.PositiveIntegerField()
+    content_object = GenericForeignKey("content_type", "object_id")
 
     class Meta:
         """Meta for the model."""
 
-        ordering = ["id"]
+        unique_together = ("tag", "content_type", "object_id")
 
     def __str__(self):
         """Stringify the object, used to represent the object towards users."""
-        return self.name
+        return f"{self.tag_id}:{self.content_type_id}:{self.object_id}"
+
 
+class TaggedModel(models.Model):
+    """A model that supports tags.
 
-class Permission(HubuumModel):
+    Only mixed into namespaced models, the tags of objects in archived namespaces
+    cannot be changed.
     """
-    Permissions in Hubuum.
 
-    - Permissions are set by group.
-    - Objects belong to a namespace.
-    - Every namespace has zero or more groups with permissions for the namespace.
+    tagged_objects = GenericRelation(TaggedObject)
 
-    The permission `has_namespace` allows for the group to create new namespaces scoped
-    under the current one.
+    def tags(self):
+        """List the names of the tags of the object."""
+        return sorted(tagged.tag.name for tagged in self.tagged_objects.all())
 
-    """
+    def add_tag(self, name):
+        """Tag the object, creating the tag if needed.
 
-    # If the namespace the permission points to goes away, clear the entry.
-    namespace = models.ForeignKey(
-        "Namespace", related_name="p_namespace", on_delete=models.CASCADE
-    )
-    # If the group the permission uses goes away, clear the entry.
-    group = models.ForeignKey(
-        "auth.Group", related_name="p_group", on_delete=models.CASCADE
-    )
+        returns: True if the object was tagged, False if it already had the tag.
+        """
+        validate_tag(name)
+        self.assert_writable()
+        tag, _ = Tag.objects.get_or_create(name=name)
+        _, created = self.tagged_objects.get_or_create(tag=tag)
+        return created
 
-    has_create = models.BooleanField(null=False, default=False)
-    has_read = models.BooleanField(null=False, default=False)
-    has_update = models.BooleanField(null=False, default=False)
-    has_delete = models.BooleanField(null=False, default=False)
-    has_namespace = models.BooleanField(null=False, default=False)
+    def remove_tag(self, name):
+        """Remove a tag from the object.
+
+        returns: True if the tag was removed, False if the object did not have it.
+        """
+        self.assert_writable()
+        deleted, _ = self.tagged_objects.filter(tag__name=name).delete()
+        return deleted > 0
 
     class Meta:
-        """Metadata permissions."""
+        """Meta data for the class."""
 
-        unique_together = ("namespace", "group")
-        ordering = ["id"]
+        abstract = True
 
-    def __str__(self):
-        """Stringify the object, used to represent the object towards users."""
-        return str(self.id)
 
+class NamespacedHubuumModelWithExtensions(
+    NamespacedHubuumModel, ExtensionsModel, TaggedModel
+):
+    """An abstract model that provides Namespaces, Extensions, Tags, and states."""
 
-class Host(NamespacedHubuumModelWithExtensions):
-    """Host model, a portal into hosts of any kind."""
+    # The state of the object in the lifecycle of its model, see Lifecycle.
+    state = models.CharField(max_length=64, blank=True)
 
-    name = models.CharField(max_length=255)
-    fqdn = models.CharField(max_length=255, blank=True)
-    type = models.ForeignKey(
-        "HostType",
-        on_delete=models.DO_NOTHING,
-        related_name="hosts",
-        blank=True,
-        null=True,
-    )
-    serial = models.CharField(max_length=255, blank=True)
-    registration_date = models.DateTimeField(auto_now_add=True)
-    room = models.ForeignKey(
-        "Room", on_delete=models.DO_NOTHING, related_name="hosts", blank=True, null=True
-    )
-    jack = models.ForeignKey(
-        "Jack", on_delete=models.DO_NOTHING, related_name="hosts", blank=True, null=True
-    )
-    purchase_order = models.ForeignKey(
-        "PurchaseOrder",
-        on_delete=models.DO_NOTHING,
-        related_name="hosts",
-        blank=True,
-        null=True,
-    )
+    class Meta:
+        """Meta data for the class."""
 
-    person = models.ForeignKey(
-        "Person",
-        on_delete=models.DO_NOTHING,
-        related_name="hosts",
-        blank=True,
-        null=True,
-    )
+        abstract = True
 
-    class Meta:
-        """Meta for the model."""
+    def save(self, *args, **kwargs):
+        """Save the object, if its state is allowed by the lifecycle of its model."""
+        Lifecycle.check_state(self)
+        super().save(*args, **kwargs)
 
-        ordering = ["id"]
+    @classmethod
+    def relations(cls):
+        """Return the relations to other objects, {name: (field, related model)}.
 
-    def __str__(self):
-        """Stringify the object, used to represent the object towards users."""
-        return self.name
+        Relations are the foreign keys of the model (ie "room") and those of other
+        models referring to it (ie "hosts").
+        """
+        relations = {}
+        for field in cls._meta.get_fields():
+            related = field.related_model
+            if not (field.many_to_one or field.one_to_many) or related is None:
+                continue
+            if not issubclass(related, NamespacedHubuumModelWithExtensions):
+                continue
+            name = field.name if field.concrete else field.get_accessor_name()
+            relations[name] = (field, related)
+        return relations
 
+    @classmethod
+    def relation_for(cls, step):
+        """Find the relation named by a step, by its name or that of the related model.
 
-class HostType(NamespacedHubuumModelWithExtensions):
-    """The type of hosts supported.
+        returns: (name, field, related model)
+        raises: LookupError unless the step names exactly one relation
+        """
+        relations = cls.relations()
+        if step in relations:
+            return (step, *relations[step])
+
+        found = [
+            (name, field, related)
+            for name, (field, related) in relations.items()
+            if related.__name__.lower() == step.lower()
+        ]
+        model = cls.__name__
+        if not found:
+            raise LookupError(f"{model} has no relation to '{step}'.")
+        if len(found) > 1:
+            names = ", ".join(name for name, _, _ in found)
+            raise LookupError(f"'{step}' is ambiguous for {model}, use one of {names}.")
+        return found[0]
 
-    These are a touple of a short name and a description, ie:
+    @classmethod
+    def related_models(cls, max_depth=None):
+        """Return the models reachable by following relations, by their shortest path.
 
-    name: mac_laptop
-    description: An Apple Laptop running MacOS
+        Every model is given with the depth at which it is first reached, and the
+        steps to it. A step has the relation (usable for traverse), the model
+        reached, and its direction, "forward" for foreign keys of the model and
+        "reverse" for those of other models referring to it.
 
-    or
+        param: max_depth (the largest depth to follow, None for no limit)
 
-    name: std_office_computer
-    description: A standard office computer running RHEL
+        returns: a list of {"model", "depth", "path", "steps"}, by depth and model
+        """
+        found = {}
+        queue = [(cls, [])]
+        while queue:
+            model, steps = queue.pop(0)
+            if max_depth is not None and len(steps) >= max_depth:
+                continue
+            for name, (field, related) in sorted(model.relations().items()):
+                related_name = related.__name__.lower()
+                # Relations back to cls are only given when they are direct.
+                if related_name in found or (related is cls and model is not cls):
+                    continue
+                step = {
+                    "relation": name,
+                    "model": related_name,
+                    "direction": "forward" if field.concrete else "reverse",
+                }
+                found[related_name] = steps + [step]
+                if related is not cls:
+                    queue.append((related, steps + [step]))
+
+        related = [
+            {
+                "model": model,
+                "depth": len(steps),
+                "path": ".".join(step["relation"] for step in steps),
+                "steps": steps,
+            }
+            for model, steps in found.items()
+        ]
+        return sorted(related, key=lambda entry: (entry["depth"], entry["model"]))
+
+    def traverse(self, steps, restrict=None):
+        """Follow relations from the object, one step at a time.
+
+        Every step names a relation of the model reached so far, see relation_for,
+        ie ["room", "Person"] for the persons in the room of a host. The objects
+        are found in a single query.
+
+        param: steps (a list of relations or model names)
+        param: restrict (a function applied to the queryset of every step, ie to
+               hide the objects the user can not read)
+
+        returns: the queryset of the objects reached
+        raises: LookupError if a step does not name exactly one relation
+        """
+        queryset = type(self).objects.filter(pk=self.pk)
+        for step in steps:
+            _, field, related = queryset.model.relation_for(step)
+            if field.concrete:
+                queryset = related.objects.filter(pk__in=queryset.values(field.name))
+            else:
+                queryset = related.objects.filter(
+                    **{f"{field.field.name}__in": queryset}
+                )
+            if restrict is not None:
+                queryset = restrict(queryset)
+        return queryset.order_by("id")
+
+
+class ReadOnlyMode(HubuumModel):
+    """A read-only (maintenance) mode of the whole server, or of a namespace.
+
+    While the server is read-only, writes to the API get 503 with Retry-After, see
+    hubuum.middleware.read_only. While a namespace is read-only, so are the
+    namespace and the objects in it. The modes in effect are cached for
+    HUBUUM_READ_ONLY_CACHE_TIME seconds.
     """
 
-    name = models.CharField(max_length=255)
-    description = models.TextField(blank=True)
+    CACHE_KEY = "hubuum.read_only"
+
+    # The namespace that is read-only, null for the whole server.
+    namespace = models.OneToOneField(
+        "Namespace",
+        on_delete=models.CASCADE,
+        null=True,
+        blank=True,
+        related_name="read_only_mode",
+    )
+    reason = models.TextField(blank=True)
+    # The seconds clients are told to wait before retrying their writes.
+    retry_after = models.PositiveIntegerField(default=300)
 
     class Meta:
         """Meta for the model."""
 
         ordering = ["id"]
 
-    def __str__(self):
-        """Stringify the object, used to represent the object towards users."""
-        return self.name
+    def save(self, *args, **kwargs):
+        """Save the mode, and forget the cached modes."""
+        super().save(*args, **kwargs)
+        cache.delete(self.CACHE_KEY)
 
+    def delete(self, *args, **kwargs):
+        """Delete the mode, and forget the cached modes."""
+        result = super().delete(*args, **kwargs)
+        cache.delete(self.CACHE_KEY)
+        return result
 
-class Jack(NamespacedHubuumModelWithExtensions):
-    """The wall end of a network jack.
+    @classmethod
+    def active(cls):
+        """Return the modes in effect, by the ID of the namespace (None for the server).
 
-    Like the marking of power outlets, there are standards for such things.
-    In Norway, the relevant standard is NS 3457-7.
-    https://www.standard.no/fagomrader/bygg-anlegg-og-eiendom/ns-3420-/klassifikasjon-av-byggverk---ns-3457/
+        returns: {namespace_id: {"reason": reason, "retry_after": seconds}}
+        """
+        modes = cache.get(cls.CACHE_KEY)
+        if modes is None:
+            modes = {
+                mode.namespace_id: {
+                    "reason": mode.reason,
+                    "retry_after": mode.retry_after,
+                }
+                for mode in cls.objects.all()
+            }
+            cache.set(cls.CACHE_KEY, modes, settings.READ_ONLY_CACHE_TIME)
+        return modes
 
-    Typically, a jack exists in a room. You an also set a building if your room
-    identifier by itself isn't unique.
-    """
+    @classmethod
+    def check(cls, namespace_ids):
+        """Ensure that none of the namespaces (None for the server) are read-only.
 
-    name = models.CharField(max_length=255)
-    room = models.ForeignKey(
-        "Room", models.CASCADE, db_column="room", blank=True, null=True
-    )
-    building = models.CharField(max_length=255, blank=True, null=True)
+        raises: ReadOnly if any of them are
+        """
+        modes = cls.active()
+        for namespace_id in namespace_ids:
+            mode = modes.get(namespace_id)
+            if mode is None:
+                continue
+
+            what = "The server" if namespace_id is None else "The namespace"
+            detail = f"{what} is read-only for maintenance."
+            if mode["reason"]:
+                detail = f"{detail} {mode['reason']}"
+            raise ReadOnly(detail=detail, wait=mode["retry_after"])
 
     def __str__(self):
         """Stringify the object, used to represent the object towards users."""
-        return self.name
+        return str(self.namespace) if self.namespace_id else "server"
 
-    class Meta:
-        """Meta for the model."""
-
-        ordering = ["id"]
 
+class RuntimeSetting(HubuumModel):
+    """A setting changed at runtime, overriding the configuration, see hubuum.runtime.
 
-class Person(NamespacedHubuumModelWithExtensions):
-    """A person.
-
-    Persons have rooms. Computers may have people. It's all very cozy.
+    The overrides are cached for HUBUUM_RUNTIME_SETTINGS_CACHE_TIME seconds, so
+    changes made through other processes apply within this time.
     """
 
-    username = models.CharField(max_length=255)
-    room = models.ForeignKey(
-        "Room", models.CASCADE, db_column="room", blank=True, null=True
-    )
-    section = models.IntegerField(blank=True, null=True)
-    department = models.CharField(max_length=255, blank=True, null=True)
-    email = models.EmailField(blank=True, null=True)
-    office_phone = models.CharField(max_length=255, blank=True, null=True)
-    mobile_phone = models.CharField(max_length=255, blank=True, null=True)
+    CACHE_KEY = "hubuum.runtime_settings"
 
-    def __str__(self):
-        """Stringify the object, used to represent the object towards users."""
-        return self.username
+    # The name of the setting, as in the configuration without the prefix, ie
+    # "PAGE_SIZE", and its value as a string.
+    name = models.CharField(max_length=255, unique=True)
+    value = models.CharField(max_length=255)
 
     class Meta:
         """Meta for the model."""
 
-        ordering = ["id"]
-
+        ordering = ["name"]
 
-class PurchaseDocuments(NamespacedHubuumModelWithExtensions):
-    """Accounting, the documents of an order.
+    def save(self, *args, **kwargs):
+        """Save the setting, and forget the cached settings."""
+        super().save(*args, **kwargs)
+        cache.delete(self.CACHE_KEY)
 
-    The documents that came with a given purchase order.
-    """
-
-    document_id = models.CharField(max_length=255)
-    purchase_order = models.ForeignKey(
-        "PurchaseOrder", models.CASCADE, blank=False, null=False
-    )
-    document = models.BinaryField(blank=False, null=False)
-
-    class Meta:
-        """Set permissions and other metadata."""
+    def delete(self, *args, **kwargs):
+        """Delete the setting, and forget the cached settings."""
+        result = super().delete(*args, **kwargs)
+        cache.delete(self.CACHE_KEY)
+        return result
 
-        verbose_name_plural = "purchase documents"
-        ordering = ["id"]
+    @classmethod
+    def overrides(cls):
+        """Return the settings overridden, {name: value}."""
+        overrides = cache.get(cls.CACHE_KEY)
+        if overrides is None:
+            overrides = dict(cls.objects.values_list("name", "value"))
+            cache.set(cls.CACHE_KEY, overrides, settings.RUNTIME_SETTINGS_CACHE_TIME)
+        return overrides
 
     def __str__(self):
         """Stringify the object, used to represent the object towards users."""
-        return self.document_id
+        return self.name
 
 
-class PurchaseOrder(NamespacedHubuumModelWithExtensions):
-    """Accounting, the order.
+class DeletePolicy(HubuumModel):
+    """What deleting an object does to the objects referring to it through a relation.
 
-    When something is bought there is typically some identifier for the purchase.
-    This may help you when it comes to service and maintenance.
-    Or disputes about money.
+    Relations are named by the referring model and field, ie "host.room" for the
+    room of hosts. The policies are:
+      - restrict: the object can not be deleted while objects refer to it.
+      - cascade: the objects referring to it are deleted as well.
+      - detach: the references are cleared, the referring objects remain.
+
+    Relations without a stored policy use DEFAULT_POLICIES.
     """
 
-    vendor = models.ForeignKey(
-        "Vendor", models.CASCADE, db_column="vendor", blank=True, null=True
+    POLICIES = ("restrict", "cascade", "detach")
+    DEFAULT_POLICIES = {
+        "host.type": "restrict",
+        "host.room": "restrict",
+        "host.jack": "restrict",
+        "host.purchase_order": "restrict",
+        "host.person": "restrict",
+        "host.parent": "detach",
+        "jack.room": "cascade",
+        "person.room": "cascade",
+        "purchasedocuments.purchase_order": "cascade",
+        "purchaseorder.vendor": "cascade",
+        "extensiondata.extension": "cascade",
+    }
+
+    relation = models.CharField(max_length=255, unique=True)
+    policy = models.CharField(
+        max_length=16, choices=[(policy, policy) for policy in POLICIES]
     )
-    order_date = models.DateTimeField(blank=True, null=True)
-    po_number = models.CharField(max_length=255, blank=False, null=False)
 
     class Meta:
         """Meta for the model."""
 
-        ordering = ["id"]
+        ordering = ["relation"]
+
+    @staticmethod
+    def relations():
+        """Return the relations policies apply to, mapped to the referring fields."""
+        relations = {}
+        for model in namespaced_models():
+            for relation, referring, field in model.referring_relations():
+                meta = referring._meta  # pylint: disable=protected-access
+                relations[relation] = meta.get_field(field)
+        return relations
+
+    @classmethod
+    def policies(cls):
+        """Return the effective policy of every relation."""
+        policies = {relation: "restrict" for relation in cls.relations()}
+        policies.update(cls.DEFAULT_POLICIES)
+        policies.update(cls.objects.values_list("relation", "policy"))
+        return policies
+
+    @classmethod
+    def validate_policy(cls, relation, policy):
+        """Validate a policy for a relation.
+
+        raises: ValidationError if the relation is unknown, or can't be detached
+        """
+        relations = cls.relations()
+        if relation not in relations:
+            known = ", ".join(sorted(relations))
+            raise ValidationError({"relation": f"Unknown relation, one of {known}."})
+        if policy == "detach" and not relations[relation].null:
+            raise ValidationError(
+                {"policy": f"{relation} is required, and can not be detached."}
+            )
 
     def __str__(self):
         """Stringify the object, used to represent the object towards users."""
-        return str(self.po_number)
+        return f"{self.relation}:{self.policy}"
 
 
-class Room(NamespacedHubuumModelWithExtensions):
-    """A room.
+class Lifecycle(HubuumModel):
+    """The states of the objects of a model, and the transitions between them.
 
-    Possibly with a view. If your room_id contains a floor or building identifier, feel free to
-    ignore the those fields. If your organization repeats room identifiers between buildings,
-    you have my sympathies. If they repeat the room identifier per floor, well, ouch.
+    A lifecycle for hosts may be the states ["planned", "active", "retired"], with
+    the transitions {"planned": ["active"], "active": ["retired"]}. New objects
+    start in the first state, unless given another, and objects may only change
+    state through the transitions. Objects in models without a lifecycle may be
+    in any state.
     """
 
-    room_id = models.CharField(max_length=255)
-    building = models.CharField(max_length=255, blank=True, null=True)
-    floor = models.CharField(max_length=255, blank=True, null=True)
+    model = models.CharField(max_length=255, unique=True, validators=[validate_model])
+    states = models.JSONField()
+    transitions = models.JSONField(default=dict, blank=True)
 
     class Meta:
         """Meta for the model."""
 
-        ordering = ["id"]
+        ordering = ["model"]
 
-    def __str__(self):
-        """Stringify the object, used to represent the object towards users."""
-        return self.building + "-" + self.floor.rjust(2, "0") + "-" + self.room_id
+    @staticmethod
+    def validate_definition(states, transitions):
+        """Validate the states and transitions of a lifecycle.
 
+        raises: ValidationError if the lifecycle is invalid
+        """
+        if (
+            not isinstance(states, list)
+            or not states
+            or not all(isinstance(state, str) and state for state in states)
+            or max(map(len, states)) > 64
+            or len(set(states)) != len(states)
+        ):
+            raise ValidationError({"states": "Expected a list of distinct states."})
+
+        if not isinstance(transitions, dict) or not all(
+            isinstance(targets, list) for targets in transitions.values()
+        ):
+            raise ValidationError(
+                {"transitions": "Expected a mapping of states to lists of states."}
+            )
+
+        used = set(transitions).union(*transitions.values())
+        unknown = sorted(map(str, used - set(states)))
+        if unknown:
+            raise ValidationError(
+                {"transitions": f"Unknown state(s) {', '.join(unknown)}."}
+            )
 
-class Vendor(NamespacedHubuumModelWithExtensions):
-    """A vendor, they sell you things.
-
-    Say thank you. Call your vendor today.
-    """
+    @classmethod
+    def check_state(cls, obj):
+        """Check the state of an object being saved against the lifecycle of its model.
 
-    vendor_name = models.CharField(max_length=255)
-    vendor_url = models.URLField()
-    vendor_credentials = models.CharField(max_length=255, blank=True, null=True)
-    contact_name = models.CharField(max_length=255, blank=True, null=True)
-    contact_email = models.EmailField()
-    contact_phone = models.CharField(max_length=255, blank=True, null=True)
+        New objects without a state get the first state of the lifecycle.
 
-    class Meta:
-        """Meta for the model."""
-
-        ordering = ["id"]
+        raises: ValidationError if the state, or the change of state, is not allowed
+        """
+        lifecycle = cls.objects.filter(model=obj._meta.model_name).first()
+        if lifecycle is None:
+            return
+
+        previous = None
+        if obj.pk:
+            stored = type(obj).objects.filter(pk=obj.pk)
+            previous = stored.values_list("state", flat=True).first()
+
+        if not obj.state and previous is None:
+            obj.state = lifecycle.states[0]
+
+        if obj.state not in lifecycle.states:
+            states = ", ".join(lifecycle.states)
+            raise ValidationError({"state": f"Unknown state, one of {states}."})
+
+        if previous in lifecycle.states and obj.state != previous:
+            allowed = lifecycle.transitions.get(previous, [])
+            if obj.state not in allowed:
+                targets = ", ".join(allowed) or "none"
+                raise ValidationError(
+                    {
+                        "state": f"Can not go from {previous} to {obj.state}, "
+                        f"allowed: {targets}."
+                    }
+                )
 
     def __str__(self):
         """Stringify the object, used to represent the object towards users."""
-        return self.vendor_name
+        return self.model
@@ -0,0 +1,478 @@
+"""Models for schema definitions, extensions, and their data."""
+from urllib.parse import urldefrag
+
+from django.contrib.contenttypes.fields import GenericForeignKey
+from django.contrib.contenttypes.models import ContentType
+from django.db import models, transaction
+from django.db.models.fields.json import KT
+from django.db.models.functions import Cast
+from rest_framework.exceptions import ValidationError
+
+from hubuum.tools import (
+    SCHEMA_REF_SCHEME,
+    get_model,
+    json_redact,
+    json_schema_defaults,
+    json_schema_errors,
+    json_schema_external_refs,
+    json_schema_refs,
+    json_values,
+)
+from hubuum.validators import (
+    validate_json_schema,
+    validate_model,
+    validate_reference_paths,
+    validate_sensitive_paths,
+    validate_url,
+)
+
+from .base import NamespacedHubuumModel
+from .revisions import ExtensionDataRevision
+
+
+class SchemaDefinition(NamespacedHubuumModel):
+    """A named JSON schema in the schema registry.
+
+    The json_schema of an extension, or of another definition, may refer to a
+    definition with {"$ref": "hubuum:schemas/<namespace>/<name>"}, optionally followed
+    by a fragment such as "#/definitions/address". References are resolved when data
+    is validated, former names of the namespace are accepted.
+    """
+
+    name = models.CharField(max_length=255)
+    description = models.TextField(blank=True)
+    json_schema = models.JSONField(validators=[validate_json_schema])
+
+    class Meta:
+        """Meta for the model."""
+
+        unique_together = ("namespace", "name")
+        ordering = ["id"]
+
+    @property
+    def ref(self):
+        """The reference to use for the definition."""
+        return f"{SCHEMA_REF_SCHEME}:schemas/{self.namespace.name}/{self.name}"
+
+    @classmethod
+    def lookup(cls, ref):
+        """Find the definition a reference refers to.
+
+        returns: the definition, or None if there is no such definition
+        """
+        prefix = f"{SCHEMA_REF_SCHEME}:schemas/"
+        ref = urldefrag(ref).url
+        if not ref.startswith(prefix) or "/" not in ref[len(prefix) :]:
+            return None
+
+        namespace, name = ref[len(prefix) :].rsplit("/", 1)
+        in_namespace = models.Q(namespace__name=namespace) | models.Q(
+            namespace__aliases__name=namespace
+        )
+        return cls.objects.filter(in_namespace, name=name).first()
+
+    @classmethod
+    def resolve(cls, ref):
+        """Return the schema a reference refers to, for json_schema_errors.
+
+        raises: LookupError if there is no such definition
+        """
+        definition = cls.lookup(ref)
+        if definition is None:
+            raise LookupError(f"No schema definition for '{ref}'.")
+        return definition.json_schema
+
+    @classmethod
+    def dangling_references(cls, json_schema):
+        """Find the references to the registry in a schema that do not resolve.
+
+        returns: a sorted list of the references
+        """
+        return sorted(
+            ref
+            for ref in json_schema_refs(json_schema)
+            if ref.startswith(f"{SCHEMA_REF_SCHEME}:") and cls.lookup(ref) is None
+        )
+
+    @classmethod
+    def validate_references(cls, json_schema):
+        """Validate that every reference to the registry in a schema resolves.
+
+        References to anything but the schema itself and the registry are refused.
+
+        raises: ValidationError if any reference is refused, or does not resolve
+        """
+        external = json_schema_external_refs(json_schema)
+        if external:
+            raise ValidationError(
+                {"json_schema": f"References not allowed: {', '.join(external)}."}
+            )
+
+        dangling = cls.dangling_references(json_schema)
+        if dangling:
+            raise ValidationError(
+                {"json_schema": f"Unresolved references: {', '.join(dangling)}."}
+            )
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return self.ref
+
+
+class Extension(NamespacedHubuumModel):
+    """An extension to a specific model.
+
+    For now, it is implied that the extension uses REST.
+
+    Data for the extension is validated against its json_schema (if any), and
+    schema_validation decides what failing the schema does:
+      - enforce: the data is rejected.
+      - warn: the data is accepted, flagged by schema_valid and schema_errors.
+      - off: the data is not validated.
+    """
+
+    SCHEMA_VALIDATIONS = ("enforce", "warn", "off")
+
+    name = models.CharField(max_length=255, null=False, unique=True)
+    model = models.CharField(max_length=255, null=False, validators=[validate_model])
+    url = models.CharField(max_length=255, null=False, validators=[validate_url])
+    require_interpolation = models.BooleanField(default=True, null=False)
+    header = models.CharField(max_length=512)
+    cache_time = models.PositiveSmallIntegerField(default=60)
+    json_schema = models.JSONField(
+        null=True, blank=True, validators=[validate_json_schema]
+    )
+    schema_validation = models.CharField(
+        max_length=16,
+        default="enforce",
+        choices=[(validation, validation) for validation in SCHEMA_VALIDATIONS],
+    )
+    # Paths in the data that are redacted for users without has_read_sensitive.
+    sensitive_paths = models.JSONField(
+        default=list, blank=True, validators=[validate_sensitive_paths]
+    )
+    # Paths in the data that refer to objects, by id, mapped to the model of the
+    # objects, ie {"room": "room", "hosts__*": "host"}.
+    reference_paths = models.JSONField(
+        default=dict, blank=True, validators=[validate_reference_paths]
+    )
+
+    class Meta:
+        """Meta for the model."""
+
+        ordering = ["id"]
+
+    @property
+    def validates_schema(self):
+        """Check if data for the extension is validated against a schema."""
+        return self.json_schema is not None and self.schema_validation != "off"
+
+    def check_schema(self, json_data):
+        """Check json_data against the schema, if the schema is enforced.
+
+        raises: ValidationError if the data fails an enforced schema
+        """
+        if self.json_schema is None or self.schema_validation != "enforce":
+            return
+        resolve = SchemaDefinition.resolve
+        errors = json_schema_errors(self.json_schema, json_data, resolve)
+        if errors:
+            raise ValidationError({"json_data": errors})
+
+    def migrate_schema(self, json_schema, apply_defaults=False, dry_run=True):
+        """Check the existing data for the extension against a new json_schema.
+
+        Unless dry_run is set, the schema is stored, defaults are applied to the data if
+        apply_defaults is set, and the validation status for every data object is
+        recorded. Every data object written is checked against the write limits of
+        its namespace, nothing is changed if one of them is exceeded.
+
+        param: json_schema (the new schema, None to remove the schema)
+        param: apply_defaults (apply default values declared in the schema)
+        param: dry_run (only report, do not change anything)
+
+        returns: {"valid": [ids], "invalid": [{"id": id, "errors": [errors]}]}
+        """
+        report = {"valid": [], "invalid": []}
+        with transaction.atomic():
+            if not dry_run:
+                self.json_schema = json_schema
+                self.save()
+
+            queryset = self.extensiondata_set.select_related("namespace")
+            for data in queryset.order_by("id"):
+                json_data = data.json_data
+                if apply_defaults:
+                    json_data = json_schema_defaults(json_schema, json_data)
+
+                errors = []
+                if json_schema is not None:
+                    errors = json_schema_errors(
+                        json_schema, json_data, SchemaDefinition.resolve
+                    )
+
+                if errors:
+                    report["invalid"].append({"id": data.id, "errors": errors})
+                else:
+                    report["valid"].append(data.id)
+
+                if not dry_run:
+                    data.namespace.check_write_limits(json_data)
+                    data.json_data = json_data
+                    data.save()
+
+        return report
+
+    def validate_data(self, queryset=None):
+        """Re-validate the data for the extension against its current json_schema.
+
+        The validation status recorded for the data objects is updated, without
+        recording new revisions of the data.
+
+        param: queryset (the data objects to validate, defaults to all of them)
+
+        returns: [{"id": id, "object_id": id, "valid": valid, "errors": [errors]}],
+                 valid being None if the extension has no schema, or it is off
+        """
+        if queryset is None:
+            queryset = self.extensiondata_set.all()
+
+        results = []
+        for data in queryset.filter(extension=self).order_by("id"):
+            valid, errors = None, None
+            if self.validates_schema:
+                errors = json_schema_errors(
+                    self.json_schema, data.json_data, SchemaDefinition.resolve
+                )
+                valid = not errors
+
+            if (valid, errors) != (data.schema_valid, data.schema_errors):
+                ExtensionData.objects.filter(pk=data.pk).update(
+                    schema_valid=valid, schema_errors=errors
+                )
+
+            results.append(
+                {
+                    "id": data.id,
+                    "object_id": data.object_id,
+                    "valid": valid,
+                    "errors": errors or [],
+                }
+            )
+
+        return results
+
+    def reference_errors(self, json_data, user):
+        """Check the references in json_data, see reference_paths.
+
+        Every reference must be the id of an existing object of the model of its
+        path, in a namespace where the user can read objects of that model. Null
+        references are allowed.
+
+        returns: a list of errors, empty if every reference resolves
+        """
+        errors = []
+        namespace = get_model("namespace")
+        for path, model_name in self.reference_paths.items():
+            model = get_model(model_name)
+            readable = user.namespaces_where("has_read", model)
+            for value in json_values(json_data, path):
+                if value is None:
+                    continue
+                if isinstance(value, bool) or not isinstance(value, int):
+                    errors.append(f"{path}: {value!r} is not the id of a {model_name}.")
+                    continue
+
+                obj = namespace.visible(model.objects.filter(pk=value)).first()
+                if obj is None or not (user.is_admin() or obj.namespace_id in readable):
+                    errors.append(f"{path}: no {model_name} with id {value}.")
+        return errors
+
+    # The functions of aggregate_data, all but count apply to numbers only.
+    AGGREGATIONS = {
+        "count": models.Count,
+        "sum": models.Sum,
+        "avg": models.Avg,
+        "min": models.Min,
+        "max": models.Max,
+    }
+
+    def touches_sensitive_paths(self, path):
+        """Check if a path (a list of keys) is within, or contains, a sensitive path."""
+        for sensitive in self.sensitive_paths:
+            keys = sensitive.split("__")
+            if all(key in ("*", part) for key, part in zip(keys, path)):
+                return True
+        return False
+
+    def aggregate_data(self, queryset, group_by, aggregations):
+        """Aggregate the json_data of the data objects of the extension in the database.
+
+        The data objects are grouped by the text of the values at the group_by paths
+        (None where missing), and for every group, the aggregations are computed.
+        Values that are not numbers are left out of sums, averages, minimums, and
+        maximums.
+
+        param: queryset (the data objects to aggregate)
+        param: group_by ({name: path}, the paths being lists of keys)
+        param: aggregations ({name: (function, path)}, the functions being those of
+               AGGREGATIONS, the path None for count)
+
+        returns: [{name: value}] for every group, ordered by the group_by values
+        """
+        queryset = queryset.filter(extension=self)
+
+        def field(path):
+            """Return the lookup of a path into json_data."""
+            return "__".join(["json_data", *path])
+
+        computed = {}
+        for index, (function, path) in enumerate(aggregations.values()):
+            if path is None:
+                computed[f"aggregate{index}"] = models.Count("id")
+                continue
+
+            kind = f"kind{index}"
+            queryset = queryset.alias(
+                **{
+                    kind: models.Func(
+                        models.F(field(path)),
+                        function="jsonb_typeof",
+                        output_field=models.CharField(),
+                    )
+                }
+            )
+            number = models.Case(
+                models.When(
+                    **{kind: "number"},
+                    then=Cast(KT(field(path)), output_field=models.FloatField()),
+                ),
+                output_field=models.FloatField(),
+            )
+            computed[f"aggregate{index}"] = self.AGGREGATIONS[function](number)
+
+        groups = {
+            f"group{index}": KT(field(path))
+            for index, path in enumerate(group_by.values())
+        }
+        if groups:
+            rows = queryset.values(**groups).annotate(**computed).order_by(*groups)
+        else:
+            rows = [queryset.aggregate(**computed)]
+
+        return [
+            {
+                **{name: row[f"group{i}"] for i, name in enumerate(group_by)},
+                **{name: row[f"aggregate{i}"] for i, name in enumerate(aggregations)},
+            }
+            for row in rows
+        ]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return self.name
+
+
+class ExtensionData(NamespacedHubuumModel):
+    """A model for the extensions data for objects.
+
+    Note that the object_id refers to an object of the appropriate model.
+    https://docs.djangoproject.com/en/4.1/ref/contrib/contenttypes/#generic-relations
+    """
+
+    extension = models.ForeignKey("Extension", on_delete=models.CASCADE, null=False)
+
+    content_type = models.ForeignKey(ContentType, on_delete=models.CASCADE)
+    object_id = models.PositiveIntegerField()
+    content_object = GenericForeignKey("content_type", "object_id")
+
+    json_data = models.JSONField(null=True)
+
+    # The result of validating json_data against the schema of the extension, if any.
+    schema_valid = models.BooleanField(null=True, editable=False)
+    schema_errors = models.JSONField(null=True, editable=False)
+
+    class Meta:
+        """Meta for the model."""
+
+        unique_together = ("extension", "content_type", "object_id")
+        ordering = ["id"]
+
+    def save(self, *args, **kwargs):
+        """Save the object, recording a new revision if json_data changed.
+
+        The validation status against the schema of the extension is updated as well,
+        unless validation is off for the extension (see Extension).
+        """
+        self.schema_valid = None
+        self.schema_errors = None
+        if self.extension.validates_schema:
+            self.schema_errors = json_schema_errors(
+                self.extension.json_schema, self.json_data, SchemaDefinition.resolve
+            )
+            self.schema_valid = not self.schema_errors
+
+        super().save(*args, **kwargs)
+
+        latest = self.revisions.order_by("-version").first()
+        if latest is None or latest.json_data != self.json_data:
+            ExtensionDataRevision.objects.create(
+                extension_data=self,
+                version=latest.version + 1 if latest else 1,
+                json_data=self.json_data,
+            )
+
+    @classmethod
+    def referencing(cls, obj):
+        """Find the data objects with references to obj, see Extension.reference_paths.
+
+        returns: a queryset of the data objects
+        """
+        model_name = obj._meta.model_name  # pylint: disable=protected-access
+        query = models.Q(pk__in=[])
+        for extension in Extension.objects.exclude(reference_paths={}):
+            for path, target in extension.reference_paths.items():
+                if target != model_name:
+                    continue
+
+                document = obj.pk
+                for key in reversed(path.split("__")):
+                    document = [document] if key == "*" else {key: document}
+                query |= models.Q(extension=extension, json_data__contains=document)
+
+        return cls.objects.filter(query)
+
+    def hides_sensitive_from(self, user):
+        """Check if the sensitive paths of the data are to be redacted for the user.
+
+        Admins, and users with has_read_sensitive in the namespace of the data, see
+        the data as is.
+        """
+        if not self.extension.sensitive_paths or user.is_admin():
+            return False
+        return not user.namespaced_can("has_read_sensitive", self.namespace)
+
+    def redact_for(self, user, json_data):
+        """Return the json_data, or a revision of it, as the user may see it."""
+        if self.hides_sensitive_from(user):
+            return json_redact(json_data, self.extension.sensitive_paths)
+        return json_data
+
+    def get_revision(self, version=None, timestamp=None):
+        """Find a specific revision of the json_data.
+
+        param: version (the revision number)
+        param: timestamp (datetime, the revision that was current at that point in time)
+
+        returns: revision object or None
+        """
+        revisions = self.revisions.order_by("-version")
+        if version is not None:
+            revisions = revisions.filter(version=version)
+        if timestamp is not None:
+            revisions = revisions.filter(created_at__lte=timestamp)
+        return revisions.first()
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return str(self.id)
@@ -0,0 +1,602 @@
+"""Models for namespaces, their aliases, permissions, and activity."""
+import json
+import time
+
+from django.conf import settings
+from django.contrib.auth.models import Group
+from django.core.cache import cache
+from django.db import DatabaseError, IntegrityError, models, transaction
+from django.utils import timezone
+from rest_framework.exceptions import (
+    NotFound,
+    PermissionDenied,
+    Throttled,
+    ValidationError,
+)
+
+from hubuum import integrity
+from hubuum.actor import get_actor
+from hubuum.exceptions import Conflict, PayloadTooLarge
+from hubuum.permissions import fully_qualified_operations
+from hubuum.tools import SCHEMA_REF_SCHEME
+from hubuum.validators import validate_naming_rules
+
+from .base import (
+    HubuumModel,
+    NamespacedHubuumModel,
+    ReadOnlyMode,
+    cloned_models,
+    deletion_order,
+    namespaced_models,
+)
+from .extensions import Extension, ExtensionData, SchemaDefinition
+
+
+class Namespace(HubuumModel):
+    """The namespace ('domain') of an object."""
+
+    name = models.CharField(max_length=255, unique=True)
+    description = models.TextField(blank=True)
+    # Rules for the names of objects in the namespace, and of namespaces below it.
+    # These apply in addition to the rules for the deployment.
+    naming_rules = models.JSONField(
+        null=True, blank=True, validators=[validate_naming_rules]
+    )
+    # Archived namespaces are read-only, only unarchiving them is allowed.
+    archived = models.BooleanField(default=False)
+    # Limits on writes to the objects in the namespace, set by admins. The rate is
+    # in writes per minute, and the size is of json_data in bytes. Null is unlimited.
+    write_rate_limit = models.PositiveIntegerField(null=True, blank=True)
+    max_json_data_size = models.PositiveIntegerField(null=True, blank=True)
+    # The progress of a deferred delete, null unless the namespace is being deleted.
+    # Namespaces being deleted, and their objects, are hidden and read-only.
+    deletion = models.JSONField(null=True, blank=True, editable=False)
+    # The progress of a deferred clone of the objects of another namespace into this
+    # one, null unless it is pending. The objects of the namespace are read-only then.
+    cloning = models.JSONField(null=True, blank=True, editable=False)
+    # The group owning the namespace, ie the team responsible for it. This grants
+    # nothing by itself, but the owner is the default grantee of new namespaces.
+    # Only admins may transfer the ownership of a namespace.
+    owner_group = models.ForeignKey(
+        Group,
+        on_delete=models.SET_NULL,
+        null=True,
+        blank=True,
+        related_name="owned_namespaces",
+    )
+
+    def save(self, *args, **kwargs):
+        """Save the namespace, keeping the former name as an alias if it was renamed.
+
+        raises: Conflict if the namespace is archived and stays archived
+        """
+        former_name, was_archived = None, False
+        if self.pk:
+            former_name, was_archived = (
+                Namespace.objects.filter(pk=self.pk)
+                .values_list("name", "archived")
+                .first()
+            ) or (None, False)
+
+        if was_archived and self.archived:
+            raise Conflict(detail="The namespace is archived, unarchive it first.")
+        if self.pk:
+            ReadOnlyMode.check([self.pk])
+
+        super().save(*args, **kwargs)
+
+        if former_name and former_name != self.name:
+            NamespaceAlias.objects.get_or_create(namespace=self, name=former_name)
+            # If we are renamed back to a former name, that name is no longer an alias.
+            NamespaceAlias.objects.filter(namespace=self, name=self.name).delete()
+
+    def delete(self, *args, user=None, **kwargs):
+        """Delete the namespace and its objects, unless it is archived.
+
+        The delete policies of relations from objects in other namespaces to the
+        objects in the namespace are applied first, see DeletePolicy.
+
+        param: user (the user deleting, see apply_delete_policies)
+
+        raises: Conflict if the namespace is archived, or a policy is restrict
+        raises: PermissionDenied if a policy changes objects the user can not
+        """
+        if self.archived:
+            raise Conflict(detail="The namespace is archived, unarchive it first.")
+        ReadOnlyMode.check([self.pk])
+
+        with transaction.atomic():
+            outside = models.Q(namespace=self)
+            for model in namespaced_models():
+                model.apply_delete_policies(
+                    model.objects.filter(namespace=self), exclude=outside, user=user
+                )
+            return super().delete(*args, **kwargs)
+
+    @staticmethod
+    def visible(queryset):
+        """Hide the namespaces being deleted, and the objects in them, from queryset."""
+        if queryset.model is Namespace:
+            return queryset.filter(deletion__isnull=True)
+        if issubclass(queryset.model, NamespacedHubuumModel):
+            return queryset.filter(namespace__deletion__isnull=True)
+        return queryset
+
+    def request_deletion(self, user=None):
+        """Mark the namespace as being deleted, see delete_batch.
+
+        Requesting the deletion of a namespace already being deleted changes nothing.
+        The delete policies are checked for the user here, as the batches are
+        deleted without one.
+
+        param: user (the user deleting, see NamespacedHubuumModel.unwritable)
+
+        returns: the progress of the deletion
+        raises: Conflict if the namespace is archived, or a policy is restrict
+        raises: PermissionDenied if a policy changes objects the user can not
+        """
+        if self.deletion is not None:
+            return self.deletion
+        if self.archived:
+            raise Conflict(detail="The namespace is archived, unarchive it first.")
+
+        total = 0
+        outside = models.Q(namespace=self)
+        for model in namespaced_models():
+            queryset = model.objects.filter(namespace=self)
+            restricted = model.restricted(queryset, exclude=outside)
+            if restricted:
+                relations = sorted(set().union(*restricted.values()))
+                raise Conflict(
+                    detail=f"{model.__name__} objects are referred to through "
+                    f"{', '.join(relations)}, with the delete policy restrict."
+                )
+            unwritable = model.unwritable(queryset, user, exclude=outside)
+            if unwritable:
+                relations = sorted(set().union(*unwritable.values()))
+                raise PermissionDenied(
+                    detail=f"{model.__name__} objects are referred to through "
+                    f"{', '.join(relations)}, by objects you can not change."
+                )
+            total += queryset.count()
+
+        self._set_deletion(
+            requested_at=timezone.now().isoformat(),
+            total=total,
+            remaining=total,
+            error=None,
+        )
+        return self.deletion
+
+    def delete_batch(self, batch_size=1000):
+        """Delete a batch of the objects in a namespace being deleted.
+
+        Every batch is deleted in a transaction of its own, applying the delete
+        policies of relations from objects in other namespaces, and the progress
+        is updated. The namespace itself is deleted once it is empty.
+
+        param: batch_size (the number of objects to delete, 1000)
+
+        returns: the progress of the deletion, None once the namespace is deleted
+        raises: Conflict if a policy is restrict, the error is kept in the progress
+        """
+        outside = models.Q(namespace=self)
+        try:
+            with transaction.atomic():
+                left = batch_size
+                for model in deletion_order():
+                    objects = model.objects.filter(namespace=self).order_by("pk")
+                    pks = list(objects.values_list("pk", flat=True)[:left])
+                    if pks:
+                        queryset = model.objects.filter(pk__in=pks)
+                        model.apply_delete_policies(queryset, exclude=outside)
+                        queryset.delete()
+                        left -= len(pks)
+                    if not left:
+                        break
+
+                remaining = sum(
+                    model.objects.filter(namespace=self).count()
+                    for model in namespaced_models()
+                )
+                if not remaining:
+                    super().delete()
+                    return None
+                self._set_deletion(remaining=remaining, error=None)
+        except Conflict as ex:
+            self._set_deletion(error=str(ex.detail))
+            raise
+
+        return self.deletion
+
+    def _set_deletion(self, **progress):
+        """Update the progress of the deletion of the namespace."""
+        self.deletion = {**(self.deletion or {}), **progress}
+        Namespace.objects.filter(pk=self.pk).update(deletion=self.deletion)
+
+    def clone(self, name, objects=False, permissions=True, deferred=None, **values):
+        """Create a copy of the namespace, with its schemas and extensions.
+
+        The copy gets the description, naming rules, and write limits of the
+        namespace. Copies of extensions are named "<copy>-<extension>", as the names
+        of extensions are unique, and references to the schemas of the namespace
+        refer to those of the copy.
+
+        param: name (the name of the copy)
+        param: objects (copy the objects as well, see clone_objects, False)
+        param: permissions (copy the permissions of the groups, True)
+        param: deferred (leave copying the objects to clone_objects, by default if
+               there are more than NAMESPACE_CLONE_SIZE objects)
+        param: values (field values to set on the copy)
+
+        returns: the copy
+        raises: Throttled if copying the objects at once exceeds the write rate limit
+        """
+        fields = ["description", "naming_rules"]
+        fields += ["write_rate_limit", "max_json_data_size"]
+        values = {**{field: getattr(self, field) for field in fields}, **values}
+        with transaction.atomic():
+            copy = Namespace.objects.create(name=name, **values)
+            for definition in SchemaDefinition.objects.filter(namespace=self):
+                definition.clone(
+                    namespace=copy,
+                    json_schema=self._clone_refs(definition.json_schema, copy),
+                )
+            for extension in Extension.objects.filter(namespace=self):
+                extension.clone(
+                    namespace=copy,
+                    name=f"{copy.name}-{extension.name}",
+                    json_schema=self._clone_refs(extension.json_schema, copy),
+                )
+
+            if permissions:
+                for model in (Permission, ModelPermission):
+                    kept = [f.name for f in model._meta.concrete_fields]
+                    kept = [f for f in kept if f.startswith("has_") or f == "model"]
+                    for grant in model.objects.filter(namespace=self):
+                        model.objects.create(
+                            namespace=copy,
+                            group=grant.group,
+                            **{field: getattr(grant, field) for field in kept},
+                        )
+
+            if objects:
+                total = sum(
+                    model.objects.filter(namespace=self).count()
+                    for model in cloned_models()
+                )
+                copy._set_cloning(
+                    source=self.pk,
+                    requested_at=timezone.now().isoformat(),
+                    total=total,
+                    error=None,
+                )
+                if deferred is None:
+                    deferred = total > settings.NAMESPACE_CLONE_SIZE
+                if not deferred:
+                    # The copy has the limits of the namespace, copying at once
+                    # writes every object to it.
+                    copy.check_write_limits(writes=total)
+                    copy.clone_objects()
+
+        return copy
+
+    def clone_objects(self):
+        """Copy the objects of the namespace being cloned into this one, see clone.
+
+        The objects are copied in a single transaction, with their tags and extension
+        data. Relations between the objects of the source refer to the copies, other
+        relations (and references in extension data) to the same objects.
+
+        returns: the number of objects copied
+        raises: Conflict if the objects can not be copied, the error is kept
+        """
+        source = Namespace.objects.filter(pk=self.cloning["source"]).first()
+        try:
+            if source is None:
+                raise Conflict(detail="The namespace cloned no longer exists.")
+
+            with transaction.atomic():
+                # The copies are written while the namespace is still read-only.
+                Namespace.objects.filter(pk=self.pk).update(cloning=None)
+                copies = {}
+                for model in cloned_models():
+                    for obj in model.objects.filter(namespace=source).order_by("pk"):
+                        copy = copies[(model, obj.pk)] = obj.clone(namespace=self)
+                        for tagged in obj.tagged_objects.all():
+                            copy.tagged_objects.create(tag=tagged.tag)
+
+                for (model, _), copy in copies.items():
+                    related = {}
+                    for field in model._meta.concrete_fields:
+                        if not field.is_relation or field.name == "namespace":
+                            continue
+                        target = (field.related_model, getattr(copy, field.attname))
+                        if target in copies:
+                            related[field.attname] = copies[target].pk
+                    if related:
+                        model.objects.filter(pk=copy.pk).update(**related)
+
+                extensions = Extension.objects.filter(namespace=self)
+                extensions = {extension.name: extension for extension in extensions}
+                for data in ExtensionData.objects.filter(namespace=source):
+                    target = (data.content_type.model_class(), data.object_id)
+                    if target not in copies:
+                        continue
+                    name = f"{self.name}-{data.extension.name}"
+                    ExtensionData.objects.create(
+                        namespace=self,
+                        extension=extensions.get(name, data.extension),
+                        content_object=copies[target],
+                        json_data=data.json_data,
+                    )
+        except (Conflict, ValidationError, DatabaseError) as ex:
+            error = str(ex)
+            if isinstance(ex, IntegrityError):
+                error = str(integrity.violation(ex).detail)
+            self._set_cloning(error=error)
+            raise Conflict(detail=f"Cloning into {self.name} failed: {error}") from ex
+
+        self.cloning = None
+        return len(copies)
+
+    def _set_cloning(self, **progress):
+        """Update the progress of the clone into the namespace."""
+        self.cloning = {**(self.cloning or {}), **progress}
+        Namespace.objects.filter(pk=self.pk).update(cloning=self.cloning)
+
+    def _clone_refs(self, json_schema, copy):
+        """Make references to the schemas of the namespace refer to those of copy."""
+        if json_schema is None:
+            return None
+        prefix = f"{SCHEMA_REF_SCHEME}:schemas/"
+        text = json.dumps(json_schema).replace(
+            f'"{prefix}{self.name}/', f'"{prefix}{copy.name}/'
+        )
+        return json.loads(text)
+
+    def check_write_limits(self, *documents, writes=1):
+        """Check writes to objects in the namespace against the limits.
+
+        Writes are counted in windows of a minute, and every write checked counts.
+        The counts are kept in the cache, see CACHES in the settings.
+
+        param: documents (the json_data written, if any)
+        param: writes (the number of objects written, 1)
+
+        raises: PayloadTooLarge if a document is larger than max_json_data_size
+        raises: Throttled if write_rate_limit writes are made this minute
+        """
+        for json_data in documents:
+            if self.max_json_data_size is None or json_data is None:
+                continue
+            size = len(json.dumps(json_data, separators=(",", ":")).encode("utf-8"))
+            if size > self.max_json_data_size:
+                raise PayloadTooLarge(
+                    detail=(
+                        f"The json_data is {size} bytes, the namespace {self.name} "
+                        f"allows at most {self.max_json_data_size} bytes."
+                    )
+                )
+
+        if self.write_rate_limit is not None and writes:
+            now = time.time()
+            key = f"hubuum.namespace.writes.{self.pk}.{int(now // 60)}"
+            cache.add(key, 0, 120)
+            if cache.incr(key, writes) > self.write_rate_limit:
+                raise Throttled(
+                    wait=60 - now % 60,
+                    detail=(
+                        f"The namespace {self.name} allows at most "
+                        f"{self.write_rate_limit} writes per minute."
+                    ),
+                )
+
+    def get_permissions_for_group(self, group: Group, raise_exception=True):
+        """Try to find a permission object for the given group.
+
+        param: group (Group instance)
+        param: raise_exception (True)
+
+        returns: permission object
+
+        raises: NotFound if raise_exception is True and no object is found
+        """
+        try:
+            obj = Permission.objects.get(namespace=self, group=group)
+            return obj
+        except Permission.DoesNotExist as exc:
+            if raise_exception:
+                raise NotFound() from exc
+
+        return None
+
+    def grant_all(self, group):
+        """Grant all permissions to the namespace to the given group."""
+        create = {}
+        create["namespace"] = self
+        create["group"] = group
+        for perm in fully_qualified_operations():
+            create[perm] = True
+        Permission.objects.update_or_create(**create)
+        return True
+
+    def groups_that_can(self, perm):
+        """Fetch groups that can perform a specific permission.
+
+        param: perm (permission string, 'has_[read|create|update|delete|namespace])
+        return [group objects] (may be empty)
+        """
+        qs = Permission.objects.filter(namespace=self.id, **{perm: True}).values(
+            "group"
+        )
+        groups = Group.objects.filter(id__in=qs)
+        return groups
+
+    class Meta:
+        """Meta for the model."""
+
+        ordering = ["id"]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return self.name
+
+
+class NamespaceAlias(HubuumModel):
+    """A former (or additional) name of a namespace.
+
+    When a namespace is renamed its old name is kept as an alias, so clients
+    referring to the namespace by the old name keep working.
+    """
+
+    namespace = models.ForeignKey(
+        "Namespace", on_delete=models.CASCADE, related_name="aliases"
+    )
+    name = models.CharField(max_length=255, unique=True)
+
+    class Meta:
+        """Meta for the model."""
+
+        ordering = ["id"]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return self.name
+
+
+class Permission(HubuumModel):
+    """
+    Permissions in Hubuum.
+
+    - Permissions are set by group.
+    - Objects belong to a namespace.
+    - Every namespace has zero or more groups with permissions for the namespace.
+
+    The permission `has_namespace` allows for the group to create new namespaces scoped
+    under the current one, and `has_read_sensitive` allows the group to read the
+    sensitive paths of extension data (see Extension.sensitive_paths).
+
+    """
+
+    # If the namespace the permission points to goes away, clear the entry.
+    namespace = models.ForeignKey(
+        "Namespace", related_name="p_namespace", on_delete=models.CASCADE
+    )
+    # If the group the permission uses goes away, clear the entry.
+    group = models.ForeignKey(
+        "auth.Group", related_name="p_group", on_delete=models.CASCADE
+    )
+
+    has_create = models.BooleanField(null=False, default=False)
+    has_read = models.BooleanField(null=False, default=False)
+    has_update = models.BooleanField(null=False, default=False)
+    has_delete = models.BooleanField(null=False, default=False)
+    has_namespace = models.BooleanField(null=False, default=False)
+    has_read_sensitive = models.BooleanField(null=False, default=False)
+
+    class Meta:
+        """Metadata permissions."""
+
+        unique_together = ("namespace", "group")
+        ordering = ["id"]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return str(self.id)
+
+
+class ModelPermission(HubuumModel):
+    """
+    Permissions for a group on the objects of a single model within a namespace.
+
+    These refine the namespace-wide permissions (see Permission) of the group: for
+    objects of the model, the flags here are used instead of those for the namespace.
+    This allows a group to ie read all objects in a namespace, but only update hosts.
+    """
+
+    namespace = models.ForeignKey(
+        "Namespace", related_name="model_permissions", on_delete=models.CASCADE
+    )
+    group = models.ForeignKey(
+        "auth.Group", related_name="model_permissions", on_delete=models.CASCADE
+    )
+    # The (lower case) name of a namespaced model, ie "host".
+    model = models.CharField(max_length=255)
+
+    # Creating namespaces is not tied to a model, so has_namespace is not refined.
+    operations = ("has_create", "has_read", "has_update", "has_delete")
+
+    has_create = models.BooleanField(null=False, default=False)
+    has_read = models.BooleanField(null=False, default=False)
+    has_update = models.BooleanField(null=False, default=False)
+    has_delete = models.BooleanField(null=False, default=False)
+
+    class Meta:
+        """Metadata permissions."""
+
+        unique_together = ("namespace", "group", "model")
+        ordering = ["id"]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return f"{self.model}:{self.id}"
+
+
+class ActivityEntry(models.Model):
+    """An entry in the activity feed of a namespace.
+
+    Entries are recorded for changes to namespaces, the objects within them, and
+    their permissions, see signals.py. The namespace is referenced without a
+    database constraint, as deleting a namespace deletes its objects (recording
+    their deletion) before the namespace itself. The entries of a namespace are
+    removed when the namespace is deleted.
+    """
+
+    namespace = models.ForeignKey(
+        "Namespace",
+        on_delete=models.DO_NOTHING,
+        db_constraint=False,
+        related_name="activity",
+    )
+    timestamp = models.DateTimeField(auto_now_add=True)
+    action = models.CharField(max_length=16)
+    model = models.CharField(max_length=255)
+    object_id = models.PositiveIntegerField(null=True)
+    object_repr = models.CharField(max_length=255)
+    actor = models.JSONField(null=True)
+
+    class Meta:
+        """Meta for the model."""
+
+        ordering = ["-timestamp", "-id"]
+
+    @classmethod
+    def record(cls, instance, action):
+        """Record an action (created, updated, deleted) on an instance.
+
+        Nothing is recorded for instances that do not belong to a namespace, or
+        for the deletion of a namespace itself.
+        """
+        if isinstance(instance, Namespace):
+            if action == "deleted":
+                return None
+            namespace_id = instance.id
+        else:
+            namespace_id = getattr(instance, "namespace_id", None)
+
+        if namespace_id is None:
+            return None
+
+        return cls.objects.create(
+            namespace_id=namespace_id,
+            action=action,
+            model=instance.__class__.__name__,
+            object_id=instance.pk,
+            object_repr=str(instance)[:255],
+            actor=get_actor().as_dict(),
+        )
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return f"{self.action} {self.model} {self.object_repr}"
@@ -0,0 +1,297 @@
+"""Models for hosts and the other objects in namespaces."""
+from django.db import models
+
+from .base import NamespacedHubuumModelWithExtensions
+
+
+class Host(NamespacedHubuumModelWithExtensions):
+    """Host model, a portal into hosts of any kind."""
+
+    name = models.CharField(max_length=255)
+    fqdn = models.CharField(max_length=255, blank=True)
+    type = models.ForeignKey(
+        "HostType",
+        on_delete=models.DO_NOTHING,
+        related_name="hosts",
+        blank=True,
+        null=True,
+    )
+    serial = models.CharField(max_length=255, blank=True)
+    registration_date = models.DateTimeField(auto_now_add=True)
+    room = models.ForeignKey(
+        "Room", on_delete=models.DO_NOTHING, related_name="hosts", blank=True, null=True
+    )
+    jack = models.ForeignKey(
+        "Jack", on_delete=models.DO_NOTHING, related_name="hosts", blank=True, null=True
+    )
+    purchase_order = models.ForeignKey(
+        "PurchaseOrder",
+        on_delete=models.DO_NOTHING,
+        related_name="hosts",
+        blank=True,
+        null=True,
+    )
+
+    person = models.ForeignKey(
+        "Person",
+        on_delete=models.DO_NOTHING,
+        related_name="hosts",
+        blank=True,
+        null=True,
+    )
+
+    # A host may be contained within another host, ie a blade in a chassis.
+    # If the containing host goes away, the contained hosts are left standing.
+    parent = models.ForeignKey(
+        "self",
+        on_delete=models.SET_NULL,
+        related_name="children",
+        blank=True,
+        null=True,
+    )
+    # The position of the host among its siblings, ie a port number or boot order.
+    # Hosts without a position are listed after those with one.
+    position = models.PositiveIntegerField(blank=True, null=True)
+
+    class Meta:
+        """Meta for the model."""
+
+        ordering = ["id"]
+
+    def ancestors(self, max_depth=None):
+        """Return the hosts containing this host, closest first.
+
+        param: max_depth (the number of levels to walk up, None for unlimited)
+
+        returns: [(depth, host)]
+        """
+        ancestors = []
+        seen = {self.id}
+        host = self.parent
+        depth = 1
+        while host and host.id not in seen:
+            if max_depth is not None and depth > max_depth:
+                break
+            ancestors.append((depth, host))
+            seen.add(host.id)
+            host = host.parent
+            depth += 1
+
+        return ancestors
+
+    def descendants(self, max_depth=1):
+        """Return the hosts contained in this host, level by level.
+
+        param: max_depth (the number of levels to walk down, None for unlimited)
+
+        returns: [(depth, host)]
+        """
+        descendants = []
+        seen = {self.id}
+        level = [self.id]
+        depth = 1
+        while level and (max_depth is None or depth <= max_depth):
+            children = (
+                Host.objects.filter(parent__in=level)
+                .exclude(id__in=seen)
+                .order_by(models.F("position").asc(nulls_last=True), "id")
+            )
+            level = []
+            for child in children:
+                descendants.append((depth, child))
+                seen.add(child.id)
+                level.append(child.id)
+            depth += 1
+
+        return descendants
+
+    def reorder_children(self, order):
+        """Set the position of the children of this host.
+
+        The children listed in order are given positions from 1 and up, any
+        remaining children are placed after them in their current order.
+
+        param: order (a list of child hosts)
+        """
+        listed = [child.id for child in order]
+        remaining = [child for _, child in self.descendants() if child.id not in listed]
+        for position, child in enumerate([*order, *remaining], start=1):
+            if child.position != position:
+                child.position = position
+                child.save()
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return self.name
+
+
+class HostType(NamespacedHubuumModelWithExtensions):
+    """The type of hosts supported.
+
+    These are a touple of a short name and a description, ie:
+
+    name: mac_laptop
+    description: An Apple Laptop running MacOS
+
+    or
+
+    name: std_office_computer
+    description: A standard office computer running RHEL
+    """
+
+    name = models.CharField(max_length=255)
+    description = models.TextField(blank=True)
+
+    class Meta:
+        """Meta for the model."""
+
+        ordering = ["id"]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return self.name
+
+
+class Jack(NamespacedHubuumModelWithExtensions):
+    """The wall end of a network jack.
+
+    Like the marking of power outlets, there are standards for such things.
+    In Norway, the relevant standard is NS 3457-7.
+    https://www.standard.no/fagomrader/bygg-anlegg-og-eiendom/ns-3420-/klassifikasjon-av-byggverk---ns-3457/
+
+    Typically, a jack exists in a room. You an also set a building if your room
+    identifier by itself isn't unique.
+    """
+
+    name = models.CharField(max_length=255)
+    room = models.ForeignKey(
+        "Room", models.CASCADE, db_column="room", blank=True, null=True
+    )
+    building = models.CharField(max_length=255, blank=True, null=True)
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return self.name
+
+    class Meta:
+        """Meta for the model."""
+
+        ordering = ["id"]
+
+
+class Person(NamespacedHubuumModelWithExtensions):
+    """A person.
+
+    Persons have rooms. Computers may have people. It's all very cozy.
+    """
+
+    username = models.CharField(max_length=255)
+    room = models.ForeignKey(
+        "Room", models.CASCADE, db_column="room", blank=True, null=True
+    )
+    section = models.IntegerField(blank=True, null=True)
+    department = models.CharField(max_length=255, blank=True, null=True)
+    email = models.EmailField(blank=True, null=True)
+    office_phone = models.CharField(max_length=255, blank=True, null=True)
+    mobile_phone = models.CharField(max_length=255, blank=True, null=True)
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return self.username
+
+    class Meta:
+        """Meta for the model."""
+
+        ordering = ["id"]
+
+
+class PurchaseDocuments(NamespacedHubuumModelWithExtensions):
+    """Accounting, the documents of an order.
+
+    The documents that came with a given purchase order.
+    """
+
+    document_id = models.CharField(max_length=255)
+    purchase_order = models.ForeignKey(
+        "PurchaseOrder", models.CASCADE, blank=False, null=False
+    )
+    document = models.BinaryField(blank=False, null=False)
+
+    class Meta:
+        """Set permissions and other metadata."""
+
+        verbose_name_plural = "purchase documents"
+        ordering = ["id"]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return self.document_id
+
+
+class PurchaseOrder(NamespacedHubuumModelWithExtensions):
+    """Accounting, the order.
+
+    When something is bought there is typically some identifier for the purchase.
+    This may help you when it comes to service and maintenance.
+    Or disputes about money.
+    """
+
+    vendor = models.ForeignKey(
+        "Vendor", models.CASCADE, db_column="vendor", blank=True, null=True
+    )
+    order_date = models.DateTimeField(blank=True, null=True)
+    po_number = models.CharField(max_length=255, blank=False, null=False)
+
+    class Meta:
+        """Meta for the model."""
+
+        ordering = ["id"]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return str(self.po_number)
+
+
+class Room(NamespacedHubuumModelWithExtensions):
+    """A room.
+
+    Possibly with a view. If your room_id contains a floor or building identifier, feel free to
+    ignore the those fields. If your organization repeats room identifiers between buildings,
+    you have my sympathies. If they repeat the room identifier per floor, well, ouch.
+    """
+
+    room_id = models.CharField(max_length=255)
+    building = models.CharField(max_length=255, blank=True, null=True)
+    floor = models.CharField(max_length=255, blank=True, null=True)
+
+    class Meta:
+        """Meta for the model."""
+
+        ordering = ["id"]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return self.building + "-" + self.floor.rjust(2, "0") + "-" + self.room_id
+
+
+class Vendor(NamespacedHubuumModelWithExtensions):
+    """A vendor, they sell you things.
+
+    Say thank you. Call your vendor today.
+    """
+
+    vendor_name = models.CharField(max_length=255)
+    vendor_url = models.URLField()
+    vendor_credentials = models.CharField(max_length=255, blank=True, null=True)
+    contact_name = models.CharField(max_length=255, blank=True, null=True)
+    contact_email = models.EmailField()
+    contact_phone = models.CharField(max_length=255, blank=True, null=True)
+
+    class Meta:
+        """Meta for the model."""
+
+        ordering = ["id"]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return self.vendor_name
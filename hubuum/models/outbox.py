@@ -0,0 +1,35 @@
+"""Models for the outbox of events published to external systems."""
+from django.db import models
+
+
+class OutboxEvent(models.Model):
+    """An event describing a change to an object, waiting to be published.
+
+    Events are written by signals (see signals.py) in the transaction of the change
+    itself, when an outbox backend is configured (HUBUUM_OUTBOX_BACKEND). If the
+    change is rolled back, so is the event. The publish_outbox command delivers
+    pending events to the backend in order, see hubuum.outbox.
+    """
+
+    created_at = models.DateTimeField(auto_now_add=True)
+    event = models.CharField(max_length=16)
+    model = models.CharField(max_length=255)
+    object_id = models.PositiveIntegerField(null=True)
+    payload = models.JSONField()
+    published_at = models.DateTimeField(null=True)
+    attempts = models.PositiveIntegerField(default=0)
+    last_error = models.TextField(blank=True)
+
+    class Meta:
+        """Meta for the model."""
+
+        ordering = ["id"]
+        indexes = [
+            models.Index(
+                fields=["published_at", "id"], name="hubuum_outbox_pending_idx"
+            )
+        ]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return f"{self.event} {self.model} {self.object_id}"
@@ -0,0 +1,28 @@
+"""Models for the revisions of extension data."""
+from django.db import models
+
+from .base import HubuumModel
+
+
+class ExtensionDataRevision(HubuumModel):
+    """A historical version of the json_data of an ExtensionData object.
+
+    A new revision is stored every time the json_data of its parent changes,
+    which allows us to see what changed on an object over time.
+    """
+
+    extension_data = models.ForeignKey(
+        "ExtensionData", on_delete=models.CASCADE, related_name="revisions"
+    )
+    version = models.PositiveIntegerField()
+    json_data = models.JSONField(null=True)
+
+    class Meta:
+        """Meta for the model."""
+
+        unique_together = ("extension_data", "version")
+        ordering = ["id"]
+
+    def __str__(self):
+        """Stringify the object, used to represent the object towards users."""
+        return f"{self.extension_data_id}:{self.version}"
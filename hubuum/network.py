@@ -0,0 +1,56 @@
+"""Client addresses and networks, for restricting access by IP.
+
+This module does not depend on Django, and is used when validating the
+configuration as well.
+"""
+import ipaddress
+from functools import lru_cache
+
+
+@lru_cache(maxsize=64)
+def parse_networks(value):
+    """Parse a comma separated list of addresses or networks (CIDR).
+
+    Addresses are single host networks, ie 10.0.0.1 is 10.0.0.1/32.
+
+    returns: a tuple of networks
+    raises: ValueError if an element is not an address or a network
+    """
+    return tuple(
+        ipaddress.ip_network(element.strip(), strict=False)
+        for element in value.split(",")
+        if element.strip()
+    )
+
+
+def in_networks(address, networks):
+    """Check if an address (a string) is within any of the networks."""
+    try:
+        address = ipaddress.ip_address(address)
+    except ValueError:
+        return False
+    return any(address in network for network in networks)
+
+
+def client_ip(remote_addr, forwarded_for, trusted_proxies):
+    """Find the address of the client, trusting X-Forwarded-For only from proxies.
+
+    The chain of addresses (those in X-Forwarded-For, then the peer address) is
+    walked from the end, skipping trusted proxies. The first address that is not a
+    trusted proxy is the client. Addresses added before it can be forged by the
+    client, and are ignored.
+
+    param: remote_addr (the address of the peer)
+    param: forwarded_for (the X-Forwarded-For header, or None)
+    param: trusted_proxies (networks, see parse_networks)
+
+    returns: the address of the client
+    """
+    chain = [remote_addr]
+    if forwarded_for:
+        chain = [hop.strip() for hop in forwarded_for.split(",")] + chain
+
+    for address in reversed(chain):
+        if not in_networks(address, trusted_proxies):
+            return address
+    return chain[0]
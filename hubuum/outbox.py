@@ -0,0 +1,149 @@
+"""Publishing of outbox events to external systems.
+
+Changes to objects are recorded as OutboxEvent objects, in the same transaction as
+the change, when HUBUUM_OUTBOX_BACKEND is set. The publish_outbox management
+command delivers them, in order, to the configured backend:
+
+    hubuum.outbox.LogBackend    - log the events (for testing and debugging)
+    hubuum.outbox.KafkaBackend  - Kafka, requires kafka-python (hubuum[kafka])
+    hubuum.outbox.AMQPBackend   - AMQP (ie RabbitMQ), requires pika (hubuum[amqp])
+
+Other backends (ie NATS) can be plugged in by giving the dotted path to a subclass
+of OutboxBackend. HUBUUM_OUTBOX_URL and HUBUUM_OUTBOX_TOPIC tell the backend where
+to deliver the events. An event is only marked as published once the backend
+accepted it, so events may be delivered more than once, but are never lost.
+"""
+import json
+
+from django.conf import settings
+from django.core.exceptions import ImproperlyConfigured
+from django.core.serializers.json import DjangoJSONEncoder
+from django.forms.models import model_to_dict
+from django.utils.module_loading import import_string
+
+from hubuum import log
+
+# Fields never included in event payloads.
+EXCLUDED_FIELDS = ("password",)
+
+
+def event_payload(instance, event):
+    """Describe an event on an instance as a JSON serializable dict."""
+    meta = instance._meta  # pylint: disable=protected-access
+    data = model_to_dict(instance, exclude=EXCLUDED_FIELDS)
+    return {
+        "event": event,
+        "model": meta.label_lower,
+        "id": instance.pk,
+        "namespace": getattr(instance, "namespace_id", None),
+        "data": json.loads(json.dumps(data, cls=DjangoJSONEncoder)),
+    }
+
+
+class OutboxBackend:
+    """Base class for outbox backends.
+
+    Subclasses implement publish, raising an exception if the event could not be
+    delivered. Publishing is retried on the next run of publish_outbox.
+    """
+
+    def __init__(self, url="", topic="hubuum"):
+        """Initialize the backend with where to deliver the events."""
+        self.url = url
+        self.topic = topic
+
+    def publish(self, event):
+        """Deliver an event (an OutboxEvent object)."""
+        raise NotImplementedError
+
+    def close(self):
+        """Release any resources held by the backend."""
+
+    @staticmethod
+    def encode(event):
+        """Encode an event for delivery."""
+        return json.dumps(
+            {"sequence": event.id, "created_at": event.created_at, **event.payload},
+            cls=DjangoJSONEncoder,
+        ).encode("utf-8")
+
+
+class LogBackend(OutboxBackend):
+    """Log the events, useful for testing and debugging."""
+
+    def publish(self, event):
+        """Log the event."""
+        log.info(
+            "published", topic=self.topic, sequence=event.id, payload=event.payload
+        )
+
+
+class KafkaBackend(OutboxBackend):
+    """Deliver the events to a Kafka topic. The url is the list of brokers."""
+
+    def __init__(self, url="", topic="hubuum"):
+        """Connect to the brokers."""
+        super().__init__(url, topic)
+        try:
+            from kafka import KafkaProducer  # pylint: disable=import-outside-toplevel
+        except ImportError as ex:
+            raise ImproperlyConfigured("KafkaBackend requires kafka-python.") from ex
+        self.producer = KafkaProducer(bootstrap_servers=url.split(","))
+
+    def publish(self, event):
+        """Send the event and wait for the brokers to acknowledge it."""
+        self.producer.send(self.topic, self.encode(event)).get(timeout=30)
+
+    def close(self):
+        """Close the producer."""
+        self.producer.close()
+
+
+class AMQPBackend(OutboxBackend):
+    """Deliver the events to an AMQP exchange. The url is the broker URL."""
+
+    def __init__(self, url="", topic="hubuum"):
+        """Connect to the broker."""
+        super().__init__(url, topic)
+        try:
+            import pika  # pylint: disable=import-outside-toplevel
+        except ImportError as ex:
+            raise ImproperlyConfigured("AMQPBackend requires pika.") from ex
+        self.connection = pika.BlockingConnection(pika.URLParameters(url))
+        self.channel = self.connection.channel()
+        self.channel.exchange_declare(
+            exchange=topic, exchange_type="topic", durable=True
+        )
+        self.channel.confirm_delivery()
+
+    def publish(self, event):
+        """Publish the event, routed by its model and event."""
+        routing_key = f"{event.model}.{event.event}"
+        self.channel.basic_publish(self.topic, routing_key, self.encode(event))
+
+    def close(self):
+        """Close the connection."""
+        self.connection.close()
+
+
+def get_backend():
+    """Create the configured outbox backend.
+
+    raises: ImproperlyConfigured if no (valid) backend is configured
+    """
+    if not settings.OUTBOX_BACKEND:
+        raise ImproperlyConfigured("No outbox backend, set HUBUUM_OUTBOX_BACKEND.")
+
+    try:
+        backend = import_string(settings.OUTBOX_BACKEND)
+    except ImportError as ex:
+        raise ImproperlyConfigured(
+            f"Unknown outbox backend '{settings.OUTBOX_BACKEND}'."
+        ) from ex
+
+    if not (isinstance(backend, type) and issubclass(backend, OutboxBackend)):
+        raise ImproperlyConfigured(
+            f"{settings.OUTBOX_BACKEND} is not an OutboxBackend."
+        )
+
+    return backend(url=settings.OUTBOX_URL, topic=settings.OUTBOX_TOPIC)
@@ -1,20 +1,50 @@
 """Pagination classes for hubuum."""
+import time
 
+from django.conf import settings
+from rest_framework.exceptions import ParseError
 from rest_framework.pagination import PageNumberPagination
 from rest_framework.response import Response
 
+ENVELOPE_HEADER = "X-Hubuum-Envelope"
+
 
 class HubuumFlexiblePagination(PageNumberPagination):
     """The default pagination class for hubuum.
 
     A custom pagination class that allows users to set their own pagination size
-    with a maximum limit and a default value.
+    with a maximum limit and a default value, see PAGE_SIZE and MAX_PAGE_SIZE in the
+    settings. Asking for more than the maximum gets 400. The applied page size and
+    the maximum are given in the X-Page-Size and X-Max-Page-Size headers.
+
+    Clients may opt in to an envelope with ?envelope=true, or the header
+    X-Hubuum-Envelope: true. The objects are then given as "data", next to "meta"
+    with the count, limit, and offset of the page, and the time spent (duration_ms).
     """
 
-    page_size = 100
-    max_page_size = 200
     page_size_query_param = "page_size"
 
+    def get_page_size(self, request):
+        """Return the page size asked for, or the default.
+
+        raises: ParseError if the page size is not a positive integer, or is above
+            the maximum
+        """
+        value = request.query_params.get(self.page_size_query_param)
+        if value is None:
+            return settings.PAGE_SIZE
+
+        if not value.isdigit() or int(value) < 1:
+            raise ParseError(
+                f"'{self.page_size_query_param}' must be a positive integer."
+            )
+        if int(value) > settings.MAX_PAGE_SIZE:
+            raise ParseError(
+                f"'{self.page_size_query_param}' may be at most "
+                f"{settings.MAX_PAGE_SIZE}, got {value}."
+            )
+        return int(value)
+
     def get_paginated_response(self, data):
         """
         Return a paginated response with count, next, and previous links as headers.
@@ -25,11 +55,34 @@ class HubuumFlexiblePagination(PageNumberPagination):
         Returns:
             rest_framework.response.Response: The paginated response.
         """
+        if self.wants_envelope():
+            data = {"data": data, "meta": self.get_meta()}
+
         response = Response(data)
         response["X-Total-Count"] = self.page.paginator.count
         response["Link"] = self.build_link_header()
+        response["X-Page-Size"] = self.page.paginator.per_page
+        response["X-Max-Page-Size"] = settings.MAX_PAGE_SIZE
         return response
 
+    def wants_envelope(self):
+        """Check if the client asked for the response to be enveloped."""
+        value = self.request.query_params.get("envelope")
+        if value is None:
+            value = self.request.headers.get(ENVELOPE_HEADER, "")
+        return value.lower() in ["1", "true", "yes"]
+
+    def get_meta(self):
+        """Return the metadata of the page, for the envelope."""
+        limit = self.page.paginator.per_page
+        started = getattr(self.request, "start_time", None)
+        return {
+            "count": self.page.paginator.count,
+            "limit": limit,
+            "offset": (self.page.number - 1) * limit,
+            "duration_ms": None if started is None else (time.time() - started) * 1000,
+        }
+
     def build_link_header(self):
         """
         Build the Link header value with next and previous links.
@@ -0,0 +1,45 @@
+"""Parsers for hubuum."""
+import yaml
+from rest_framework.exceptions import ParseError
+from rest_framework.parsers import BaseParser
+
+
+class PlainLoader(yaml.SafeLoader):  # pylint: disable=too-many-ancestors
+    """Load plain YAML, the values JSON has, without anchors and aliases.
+
+    Timestamps are left as strings, as in JSON, and anchors and aliases are
+    refused, as a few of them can expand to a huge document.
+    """
+
+    yaml_implicit_resolvers = {
+        first: [
+            (tag, regexp)
+            for tag, regexp in resolvers
+            if tag != "tag:yaml.org,2002:timestamp"
+        ]
+        for first, resolvers in yaml.SafeLoader.yaml_implicit_resolvers.items()
+    }
+
+    def compose_node(self, parent, index):
+        """Compose a node, refusing anchors and aliases."""
+        event = self.peek_event()
+        if isinstance(event, yaml.AliasEvent) or getattr(event, "anchor", None):
+            raise yaml.composer.ComposerError(
+                None, None, "anchors and aliases are not allowed", event.start_mark
+            )
+        return super().compose_node(parent, index)
+
+
+class YAMLParser(BaseParser):
+    """Parse YAML request bodies, with the same structure as the JSON ones."""
+
+    media_type = "application/yaml"
+
+    def parse(self, stream, media_type=None, parser_context=None):
+        """Parse the body, only plain YAML (no tags for Python objects) is allowed."""
+        parser_context = parser_context or {}
+        encoding = parser_context.get("encoding", "utf-8")
+        try:
+            return yaml.load(stream.read().decode(encoding), Loader=PlainLoader)
+        except (UnicodeDecodeError, yaml.YAMLError) as ex:
+            raise ParseError(f"YAML parse error - {ex}") from ex
@@ -22,9 +22,27 @@ class CustomObjectPermissions(DjangoObjectPermissions):
     }
 
 
+# The operations groups may be granted for namespaces, see Permission, and what they
+# allow. Listed by /meta/permissions, for clients that should not hardcode them.
+OPERATIONS = {
+    "create": "Create objects in the namespace.",
+    "read": "Read the namespace, and the objects in it.",
+    "update": "Update the objects in the namespace.",
+    "delete": "Delete the objects in the namespace.",
+    "namespace": "Create namespaces under the namespace, and manage its permissions.",
+    "read_sensitive": "Read the sensitive paths of extension data in the namespace.",
+}
+
+# The models operations apply to, if not every namespaced model.
+OPERATION_MODELS = {
+    "namespace": ("namespace",),
+    "read_sensitive": ("extensiondata",),
+}
+
+
 def operations():
     """Define the list of valid operations."""
-    return ("create", "read", "update", "delete", "namespace")
+    return tuple(OPERATIONS)
 
 
 def fully_qualified_operations():
@@ -45,6 +63,62 @@ def is_super_or_admin(user):
     return user.is_staff or user.is_superuser
 
 
+def trace_permission(request, check, granted, **details):
+    """Record the outcome of a permission check, if the request asked for it.
+
+    See hubuum.middleware.explain_permissions.
+
+    returns: granted
+    """
+    trace = getattr(request, "permission_trace", None)
+    if trace is not None:
+        trace.append({"check": check, "granted": granted, **details})
+    return granted
+
+
+def deny_namespace(request, namespace):
+    """Note the namespace a permission was missing in, see hubuum.denials.
+
+    returns: False
+    """
+    denied = getattr(request, "denied_namespaces", None)
+    if denied is not None:
+        denied.append(namespace)
+    return False
+
+
+def _model_name(obj):
+    """Return the model name of a namespaced object or model, or None for others."""
+    if hasattr(obj, "namespace_id"):
+        return obj._meta.model_name  # pylint: disable=protected-access
+    return None
+
+
+def namespaced_can(request, perm, namespace, model=None):
+    """Check if the user of the request can perform perm in namespace, and trace it.
+
+    param: model (the name of the model operated on, see User.namespaces_where)
+    """
+    granted = request.user.namespaced_can(perm, namespace, model)
+    if not granted:
+        deny_namespace(request, namespace.name)
+    if getattr(request, "permission_trace", None) is not None:
+        groups = namespace.groups_that_can(perm).filter(
+            name__in=request.user.group_list
+        )
+        details = {"model": model} if model else {}
+        trace_permission(
+            request,
+            "namespace",
+            granted,
+            permission=perm,
+            namespace=namespace.name,
+            groups=sorted(group.name for group in groups),
+            **details,
+        )
+    return granted
+
+
 class IsAuthenticatedAndReadOnly(IsAuthenticated):
     """Allow read-only access if authenticated."""
 
@@ -62,20 +136,83 @@ class IsAuthenticatedAndReadOnly(IsAuthenticated):
         return request.method in SAFE_METHODS
 
 
+class IsSuperOrAdmin(IsAuthenticated):
+    """Permit super or admin users only."""
+
+    def has_permission(self, request, view):
+        """Check if we're super/admin."""
+        granted = super().has_permission(request, view)
+        return trace_permission(
+            request, "admin", granted and is_super_or_admin(request.user)
+        )
+
+
 class IsSuperOrAdminOrReadOnly(IsAuthenticatedAndReadOnly):
     """Permit super or admin users, else read only."""
 
     def has_permission(self, request, view):
         """Check if we're super/admin otherwise authenticated readonly."""
         if is_super_or_admin(request.user):
-            return True
-        return super().has_permission(request, view)
+            return trace_permission(request, "admin", True)
+        return trace_permission(
+            request, "read_only", super().has_permission(request, view)
+        )
 
     def has_object_permission(self, request, view, obj):
         """Check if we're super/admin otherwise authenticated readonly."""
         if is_super_or_admin(request.user):
-            return True
-        return super().has_object_permission(request, view, obj)
+            return trace_permission(request, "admin", True)
+        return trace_permission(
+            request, "read_only", super().has_object_permission(request, view, obj)
+        )
+
+
+class IsSuperOrAdminOrSelf(IsAuthenticated):
+    """Permit super or admin users, and users acting on their own user.
+
+    Users acting on themselves (ie /users/me) may use the methods in
+    view.self_methods (read-only by default), and may only change the fields in
+    view.self_fields, so they can not grant themselves privileges or groups. Views
+    setting readable_by_all let every user read every user.
+    """
+
+    def has_object_permission(self, request, view, obj):
+        """Check if we're super/admin, otherwise if obj is the user and allowed."""
+        if is_super_or_admin(request.user):
+            return trace_permission(request, "admin", True)
+        if request.method in SAFE_METHODS and getattr(view, "readable_by_all", False):
+            return trace_permission(request, "read_only", True)
+        if obj != request.user:
+            return trace_permission(request, "self", False)
+
+        granted = request.method in getattr(view, "self_methods", SAFE_METHODS)
+        if granted and request.method in ("PUT", "PATCH"):
+            fields = set(request.data) if isinstance(request.data, dict) else set()
+            granted = fields <= set(getattr(view, "self_fields", ()))
+        return trace_permission(request, "self", granted)
+
+
+class GroupMembership(IsSuperOrAdminOrReadOnly):
+    """Permit admins, and the managers of a group to change its members.
+
+    See User.manages_group.
+    """
+
+    def has_permission(self, request, view):
+        """Defer writes by authenticated users to the object check."""
+        if request.method in SAFE_METHODS or is_super_or_admin(request.user):
+            return super().has_permission(request, view)
+        return trace_permission(
+            request, "authenticated", bool(request.user.is_authenticated)
+        )
+
+    def has_object_permission(self, request, view, obj):
+        """Check if the user manages the group."""
+        if request.method in SAFE_METHODS or is_super_or_admin(request.user):
+            return super().has_object_permission(request, view, obj)
+        return trace_permission(
+            request, "group_manager", request.user.manages_group(obj), group=obj.name
+        )
 
 
 # A thing here. Everyone can read all namespaces. For multi-tenant installations we probably need:
@@ -98,9 +235,12 @@ class NameSpace(IsSuperOrAdminOrReadOnly):
         """Check if superuser or admin by delegation, then check user, otherwise false."""
         # First check if we are superuser or asking for read-only (listing), if so, return true.
         if request.user.is_anonymous:
-            return False
+            return trace_permission(request, "authenticated", False)
+
+        if is_super_or_admin(request.user):
+            return trace_permission(request, "admin", True)
 
-        if super().has_permission(request, view):
+        if request.method in SAFE_METHODS:
             return True
 
         # POST needs special treatment as we don't have an object to work on.
@@ -136,7 +276,20 @@ class NameSpace(IsSuperOrAdminOrReadOnly):
             else:
                 name = request.data["namespace"]
 
-            return request.user.has_namespace(name, write_perm)
+            model = None
+            if write_perm == "has_create" and hasattr(view, "queryset"):
+                model = _model_name(view.queryset.model)
+
+            granted = request.user.has_namespace(name, write_perm, model)
+            if not granted:
+                deny_namespace(request, name)
+            return trace_permission(
+                request,
+                "namespace",
+                granted,
+                permission=write_perm,
+                namespace=name,
+            )
 
         return True
 
@@ -148,7 +301,7 @@ class NameSpace(IsSuperOrAdminOrReadOnly):
         #    return False
 
         if is_super_or_admin(request.user):
-            return True
+            return trace_permission(request, "admin", True)
 
         perms_map = {
             "GET": "has_read",
@@ -176,6 +329,134 @@ class NameSpace(IsSuperOrAdminOrReadOnly):
             perm = perms_map[request.method]
 
         if hasattr(obj, "namespace"):
-            return request.user.namespaced_can(perm, obj.namespace)
+            return namespaced_can(request, perm, obj.namespace, _model_name(obj))
+
+        return namespaced_can(request, perm, obj)
+
+
+class NameSpaceSubresource(IsSuperOrAdminOrReadOnly):
+    """
+    Access to resources belonging to a namespace, such as its aliases.
+
+    The namespace is always resolved from the URL, so unlike NameSpace we never
+    need to inspect the data sent to us. If the object resolved is a namespaced
+    object (ie, an extension), the permissions for its namespace apply.
+
+    Write access:
+        - super or admin users
+        - users in groups with has_namespace for the namespace
+
+    Read access:
+        - super or admin users
+        - users in groups with has_read for the namespace
+    """
+
+    def has_permission(self, request, view):
+        """Require authentication, the actual check is done against the namespace."""
+        return trace_permission(request, "authenticated", not request.user.is_anonymous)
+
+    def has_object_permission(self, request, view, obj):
+        """Check for has_read or has_namespace on the namespace."""
+        if is_super_or_admin(request.user):
+            return trace_permission(request, "admin", True)
+
+        namespace = getattr(obj, "namespace", obj)
+        if request.method in SAFE_METHODS:
+            return namespaced_can(request, "has_read", namespace)
+
+        return namespaced_can(request, "has_namespace", namespace)
+
+
+class NameSpaceClone(NameSpaceSubresource):
+    """
+    Cloning of namespaced objects, and of namespaces.
+
+    The target namespace is given by the view, through get_target_namespace(obj).
+    For namespaces, the target is the name of the copy.
+
+    Access:
+        - super or admin users
+        - users in groups with has_read for the namespace of the object, and
+          has_create for the target namespace
+        - for namespaces, users in groups with has_read for the namespace, and
+          has_namespace for the namespace the copy is created under
+    """
+
+    def has_object_permission(self, request, view, obj):
+        """Check for has_read on the object and has_create on the target."""
+        if is_super_or_admin(request.user):
+            return trace_permission(request, "admin", True)
+
+        if not hasattr(obj, "namespace"):
+            if not namespaced_can(request, "has_read", obj):
+                return False
+            name = view.get_target_namespace(obj)
+            granted = request.user.has_namespace(name, "has_namespace")
+            if not granted:
+                deny_namespace(request, name)
+            return trace_permission(
+                request,
+                "namespace",
+                granted,
+                permission="has_namespace",
+                namespace=name,
+            )
+
+        model = _model_name(obj)
+        if not namespaced_can(request, "has_read", obj.namespace, model):
+            return False
+
+        target = view.get_target_namespace(obj)
+        return namespaced_can(request, "has_create", target, model)
+
+
+class NameSpaceRelations(NameSpaceSubresource):
+    """
+    Relating namespaced objects to each other.
+
+    The relation is given by the view, through get_relation(obj), with the object
+    "changed" (the one holding the foreign key, obj or the object related to).
+
+    Access:
+        - super or admin users
+        - users in groups with has_read for the namespace of the object, and
+          has_update for the namespace of the object changed
+    """
+
+    def has_object_permission(self, request, view, obj):
+        """Check for has_read on the object and has_update on the object changed."""
+        if is_super_or_admin(request.user):
+            return trace_permission(request, "admin", True)
+
+        if not namespaced_can(request, "has_read", obj.namespace, _model_name(obj)):
+            return False
+
+        changed = view.get_relation(obj)["changed"]
+        return namespaced_can(
+            request, "has_update", changed.namespace, _model_name(changed)
+        )
+
+
+class NameSpaceTags(NameSpaceSubresource):
+    """
+    Access to the tags of namespaced objects.
+
+    Write access:
+        - super or admin users
+        - users in groups with has_update for the namespace of the object
+
+    Read access:
+        - super or admin users
+        - users in groups with has_read for the namespace of the object
+    """
+
+    def has_object_permission(self, request, view, obj):
+        """Check for has_read or has_update on the namespace of the object."""
+        if is_super_or_admin(request.user):
+            return trace_permission(request, "admin", True)
+
+        model = _model_name(obj)
+        if request.method in SAFE_METHODS:
+            return namespaced_can(request, "has_read", obj.namespace, model)
 
-        return request.user.namespaced_can(perm, obj)
+        return namespaced_can(request, "has_update", obj.namespace, model)
@@ -0,0 +1,186 @@
+"""Bulk provisioning of users and groups, ie from identity management systems.
+
+A request lists groups and users, and the groups are applied first:
+
+    {
+      "groups": [{"name": "operators"}, {"name": "contractors", "delete": true}],
+      "users": [
+        {"username": "alice", "email": "alice@example.com", "groups": ["operators"]},
+        {"username": "bob", "active": false}
+      ]
+    }
+
+Users and groups are found by username and name, created if missing, and updated
+to match the entry. The groups given for a user replace the groups the user is a
+member of, and a password is only set when the user is created. Users are
+deactivated (suspended) with "active": false, and groups are deleted with
+"delete": true.
+
+Every entry is applied in a transaction of its own. An entry that fails is rolled
+back and reported, without affecting the other entries.
+"""
+from django.contrib.auth.models import Group
+from django.core.exceptions import ValidationError
+from django.db import IntegrityError, transaction
+
+from hubuum import integrity, log
+from hubuum.models.auth import User
+
+SECTIONS = ("groups", "users")
+
+USER_FIELDS = ("email", "first_name", "last_name")
+USER_KEYS = ("username", "password", "active", "groups", *USER_FIELDS)
+GROUP_KEYS = ("name", "delete")
+
+
+class ProvisioningConflict(Exception):
+    """The entry conflicts with existing users or groups, or with another entry."""
+
+
+def provision(data):
+    """Apply the groups and users of a provisioning request.
+
+    returns: {"groups": [results], "users": [results], "summary": {status: count}},
+             the results having the name or username, the status (created, updated,
+             deactivated, deleted, unchanged, conflict, or invalid), and for
+             failures, a detail
+    raises: ValueError if the request is not a mapping of lists of entries
+    """
+    if not isinstance(data, dict):
+        raise ValueError("The request must be a mapping of groups and users.")
+
+    unknown = sorted(set(data) - set(SECTIONS))
+    if unknown:
+        raise ValueError(f"Unknown section(s): {', '.join(map(str, unknown))}.")
+
+    results = {}
+    sections = (("groups", "name", _group), ("users", "username", _user))
+    for section, key, apply in sections:
+        entries = data.get(section) or []
+        if not isinstance(entries, list):
+            raise ValueError(f"{section}: expected a list.")
+
+        seen = set()
+        results[section] = [_apply(apply, key, entry, seen) for entry in entries]
+
+    summary = {}
+    for result in results["groups"] + results["users"]:
+        summary[result["status"]] = summary.get(result["status"], 0) + 1
+    results["summary"] = summary
+
+    return results
+
+
+def _apply(apply, key, entry, seen):
+    """Apply an entry in a transaction of its own, and report the result."""
+    identifier = entry.get(key) if isinstance(entry, dict) else None
+    result = {key: identifier}
+    if not identifier or not isinstance(identifier, str):
+        return {**result, "status": "invalid", "detail": f"'{key}' is required."}
+
+    if identifier in seen:
+        detail = "Given more than once in the request."
+        return {**result, "status": "conflict", "detail": detail}
+    seen.add(identifier)
+
+    try:
+        with transaction.atomic():
+            result["status"] = apply(entry)
+    except ProvisioningConflict as ex:
+        result.update(status="conflict", detail=str(ex))
+    except IntegrityError as ex:
+        result.update(status="conflict", detail=str(integrity.violation(ex).detail))
+    except ValidationError as ex:
+        result.update(status="invalid", detail=" ".join(ex.messages))
+    except (LookupError, TypeError, ValueError) as ex:
+        result.update(status="invalid", detail=str(ex))
+
+    log.info("provisioned", key=key, identifier=identifier, status=result["status"])
+    return result
+
+
+def _check_keys(entry, keys):
+    """Check that an entry only has known keys.
+
+    raises: ValueError if there are unknown keys
+    """
+    unknown = sorted(set(entry) - set(keys))
+    if unknown:
+        raise ValueError(f"Unknown field(s): {', '.join(map(str, unknown))}.")
+
+
+def _group(entry):
+    """Create, or delete, a group.
+
+    returns: the status of the group
+    """
+    _check_keys(entry, GROUP_KEYS)
+    group = Group.objects.filter(name=entry["name"]).first()
+    if entry.get("delete"):
+        if group is None:
+            return "unchanged"
+        group.delete()
+        return "deleted"
+
+    if group is None:
+        group = Group(name=entry["name"])
+        group.full_clean()
+        group.save()
+        return "created"
+    return "unchanged"
+
+
+def _user(entry):
+    """Create or update a user, its group memberships, and whether it is active.
+
+    returns: the status of the user
+    raises: ProvisioningConflict if the email is that of another user
+    """
+    _check_keys(entry, USER_KEYS)
+    if not isinstance(entry.get("active", True), bool):
+        raise TypeError("'active' must be true or false.")
+    if not isinstance(entry.get("groups", []), list):
+        raise TypeError("'groups' must be a list of group names.")
+
+    username = entry["username"]
+    email = entry.get("email")
+    if email and User.objects.filter(email=email).exclude(username=username).exists():
+        raise ProvisioningConflict(f"The email {email} belongs to another user.")
+
+    user = User.objects.filter(username=username).first()
+    created = user is None
+    if created:
+        user = User(username=username, is_active=entry.get("active", True))
+        if entry.get("password"):
+            user.set_password(entry["password"])
+        else:
+            user.set_unusable_password()
+
+    changed = created
+    for field in USER_FIELDS:
+        if field in entry and getattr(user, field) != entry[field]:
+            setattr(user, field, entry[field])
+            changed = True
+
+    if changed:
+        user.full_clean()
+        user.save()
+
+    if "groups" in entry:
+        groups = []
+        for name in entry["groups"]:
+            group = Group.objects.filter(name=name).first()
+            if group is None:
+                raise LookupError(f"No group named '{name}'.")
+            groups.append(group)
+
+        if set(user.groups.all()) != set(groups):
+            user.groups.set(groups)
+            changed = True
+
+    status = "created" if created else "updated" if changed else "unchanged"
+    if not created and user.is_active != entry.get("active", user.is_active):
+        user.set_active(entry["active"])
+        status = "updated" if entry["active"] else "deactivated"
+
+    return status
@@ -0,0 +1,164 @@
+"""Renderers for hubuum."""
+import csv
+import json
+from xml.etree import ElementTree
+
+import yaml
+from rest_framework.renderers import BaseRenderer
+from rest_framework.utils.encoders import JSONEncoder
+
+
+class _Echo:  # pylint: disable=too-few-public-methods
+    """A file-like object that returns what is written to it, for csv.writer."""
+
+    def write(self, value):
+        """Return the value instead of storing it."""
+        return value
+
+
+def csv_lines(fieldnames, rows):
+    """Yield a list of dictionaries as lines of CSV, starting with a header.
+
+    This is a generator, suitable for streaming responses.
+    """
+    writer = csv.writer(_Echo())
+    yield writer.writerow(fieldnames)
+    for row in rows:
+        yield writer.writerow([row.get(field) for field in fieldnames])
+
+
+class CSVRenderer(BaseRenderer):
+    """Render a list of dictionaries as CSV, with the keys as the header.
+
+    Views selecting this renderer with ?format=csv may also stream their
+    response with csv_lines.
+    """
+
+    media_type = "text/csv"
+    format = "csv"
+    charset = "utf-8"
+
+    def render(self, data, accepted_media_type=None, renderer_context=None):
+        """Render the data, errors (dictionaries) are rendered as a single row."""
+        if isinstance(data, dict):
+            data = [data]
+
+        fieldnames = list(data[0].keys()) if data else []
+        return "".join(csv_lines(fieldnames, data))
+
+
+def ndjson_lines(rows):
+    """Yield each of the rows as a line of JSON.
+
+    This is a generator, suitable for streaming responses.
+    """
+    for row in rows:
+        yield json.dumps(row, cls=JSONEncoder, ensure_ascii=False) + "\n"
+
+
+class NDJSONRenderer(BaseRenderer):
+    """Render a list as newline delimited JSON, one element per line.
+
+    Views selecting this renderer with ?format=ndjson may also stream their
+    response with ndjson_lines.
+    """
+
+    media_type = "application/x-ndjson"
+    format = "ndjson"
+    charset = "utf-8"
+
+    def render(self, data, accepted_media_type=None, renderer_context=None):
+        """Render the data, errors (dictionaries) are rendered as a single line."""
+        if not isinstance(data, list):
+            data = [data]
+
+        return "".join(ndjson_lines(data))
+
+
+class YAMLRenderer(BaseRenderer):
+    """Render the data as YAML, with the same structure as the JSON responses."""
+
+    media_type = "application/yaml"
+    format = "yaml"
+    charset = "utf-8"
+
+    def render(self, data, accepted_media_type=None, renderer_context=None):
+        """Render the data, converted to plain types the way JSON responses are."""
+        if data is None:
+            return ""
+
+        data = json.loads(json.dumps(data, cls=JSONEncoder))
+        return yaml.safe_dump(data, allow_unicode=True, sort_keys=False)
+
+
+def _dot_quote(value):
+    """Quote a value as a DOT identifier."""
+    value = str(value).replace("\\", "\\\\").replace('"', '\\"')
+    return f'"{value}"'
+
+
+class DOTRenderer(BaseRenderer):
+    """Render a graph, {"name": name, "nodes": [...], "edges": [...]}, as DOT.
+
+    Nodes are dictionaries with an id, a model, and a label, and edges are
+    dictionaries with a source, a target, and a relation.
+    """
+
+    media_type = "text/vnd.graphviz"
+    format = "dot"
+    charset = "utf-8"
+
+    def render(self, data, accepted_media_type=None, renderer_context=None):
+        """Render the graph, errors are rendered as a comment in an empty graph."""
+        if "nodes" not in data:
+            return f"// {json.dumps(data, cls=JSONEncoder)}\ndigraph {{}}\n"
+
+        lines = [f"digraph {_dot_quote(data.get('name', 'hubuum'))} {{"]
+        for node in data["nodes"]:
+            label, model = _dot_quote(node["label"]), _dot_quote(node["model"])
+            lines.append(f"  {_dot_quote(node['id'])} [label={label}, model={model}];")
+        for edge in data["edges"]:
+            source, target = _dot_quote(edge["source"]), _dot_quote(edge["target"])
+            label = _dot_quote(edge["relation"])
+            lines.append(f"  {source} -> {target} [label={label}];")
+        lines.append("}")
+        return "\n".join(lines) + "\n"
+
+
+class GraphMLRenderer(BaseRenderer):
+    """Render a graph as GraphML, see DOTRenderer for the structure of the graph."""
+
+    media_type = "application/graphml+xml"
+    format = "graphml"
+    charset = "utf-8"
+
+    def render(self, data, accepted_media_type=None, renderer_context=None):
+        """Render the graph, errors are rendered as the description of an empty one."""
+        root = ElementTree.Element(
+            "graphml", xmlns="http://graphml.graphdrawing.org/xmlns"
+        )
+        for key, domain in (("label", "node"), ("model", "node"), ("relation", "edge")):
+            attributes = {"id": key, "for": domain, "attr.name": key}
+            ElementTree.SubElement(root, "key", attributes, **{"attr.type": "string"})
+
+        graph = ElementTree.SubElement(
+            root, "graph", id=str(data.get("name", "hubuum")), edgedefault="directed"
+        )
+        if "nodes" not in data:
+            description = ElementTree.SubElement(graph, "desc")
+            description.text = json.dumps(data, cls=JSONEncoder)
+            data = {"nodes": [], "edges": []}
+
+        for node in data["nodes"]:
+            element = ElementTree.SubElement(graph, "node", id=node["id"])
+            for key in ("label", "model"):
+                ElementTree.SubElement(element, "data", key=key).text = str(node[key])
+        for edge in data["edges"]:
+            element = ElementTree.SubElement(
+                graph, "edge", source=edge["source"], target=edge["target"]
+            )
+            ElementTree.SubElement(element, "data", key="relation").text = str(
+                edge["relation"]
+            )
+
+        return ElementTree.tostring(root, encoding="unicode", xml_declaration=True)
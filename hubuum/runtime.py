@@ -0,0 +1,169 @@
+"""Settings changed at runtime, without restarting the server.
+
+Admins override the logging levels and the non-structural settings in SETTINGS
+with PATCH /api/runtime-settings/, and remove overrides by setting them to null.
+The overrides are kept in the database (see RuntimeSetting), and every process
+applies them to its settings and loggers within RUNTIME_SETTINGS_CACHE_TIME
+seconds, see hubuum.middleware.runtime_settings. Removing an override restores the
+value the process was configured with.
+
+Values are given and validated as in the configuration (see hubuumsite.config),
+ie {"logging_level_database": "debug", "page_size": 50}. Other settings, such as
+the database or the networks allowed, are structural and need a restart.
+"""
+import logging
+import threading
+
+from django.conf import settings
+
+from hubuumsite.config import LOGGING_SOURCES, OPTIONS
+
+# The loggers of the sources of HUBUUM_LOGGING_LEVEL_<source>, see settings.LOGGING.
+LOGGERS = {
+    "DJANGO": "django_structlog",
+    "API": "hubuum.api.object",
+    "SIGNALS": "hubuum.signals.object",
+    "REQUEST": "hubuum.request",
+    "AUTH": "hubuum.auth",
+    "MANUAL": "hubuum.manual",
+    "DATABASE": "hubuum.database",
+}
+
+LEVELS = ("LOGGING_LEVEL", *(f"LOGGING_LEVEL_{source}" for source in LOGGING_SOURCES))
+
+# The other settings that may be overridden, and how their values are read.
+SETTINGS = {
+    "PAGE_SIZE": int,
+    "MAX_PAGE_SIZE": int,
+    "JSON_SEARCH_CONCURRENCY": int,
+    "JSON_SEARCH_QUEUE_TIMEOUT": int,
+    "JSON_SEARCH_RETRY_AFTER": int,
+    "JSON_SEARCH_SLOW_TIME": int,
+    "NAMESPACE_CLONE_SIZE": int,
+    "STATISTICS_CACHE_TIME": int,
+    "LOGIN_HISTORY_SIZE": int,
+    "DENIAL_TRACKING_SIZE": int,
+    "DOWNLOAD_LINK_MAX_AGE": int,
+    "IDEMPOTENCY_WINDOW": int,
+    "UNAUTHORIZED_STATUS": int,
+    "EXPLAIN_PERMISSIONS": str.lower,
+}
+
+_lock = threading.Lock()
+# The overrides applied in this process, and the configured values they replaced.
+_applied = {}
+_configured = {}
+
+
+def names():
+    """Return the names of the settings that may be overridden."""
+    return (*LEVELS, *SETTINGS)
+
+
+def validate(name, value):
+    """Check an override, as given to the API.
+
+    param: name (the name of the setting, ie "page_size")
+    param: value (a string or an integer, None to remove the override)
+
+    returns: an error message, None if the override is valid
+    """
+    if name.upper() not in names():
+        return "is not a setting that may be changed at runtime"
+    if value is None:
+        return None
+    if isinstance(value, bool) or not isinstance(value, (str, int)):
+        return "must be a string or an integer"
+    return OPTIONS[name.upper()](str(value))
+
+
+def conflicts(changes, overrides):
+    """Check the settings changed against the others, as they are once changed.
+
+    A change is checked against the value in effect for the other setting, ie a
+    PAGE_SIZE above the MAX_PAGE_SIZE it is used with is refused.
+
+    param: changes ({name: value}, as given to the API, None to remove an override)
+    param: overrides ({name: value}, as from RuntimeSetting.overrides)
+
+    returns: {name: error message} for the changes that conflict
+    """
+    overrides = dict(overrides)
+    for name, value in changes.items():
+        if value is None:
+            overrides.pop(name.upper(), None)
+        else:
+            overrides[name.upper()] = str(value)
+
+    def effective(name):
+        """Return the value of a setting in effect once changed."""
+        if name in overrides:
+            return SETTINGS[name](overrides[name])
+        with _lock:
+            return _configured.get(name, getattr(settings, name))
+
+    changed = {name.upper(): name for name in changes}
+    page_size, max_page_size = effective("PAGE_SIZE"), effective("MAX_PAGE_SIZE")
+    errors = {}
+    if page_size > max_page_size:
+        if "PAGE_SIZE" in changed:
+            errors[changed["PAGE_SIZE"]] = f"is above max_page_size ({max_page_size})"
+        if "MAX_PAGE_SIZE" in changed:
+            errors[changed["MAX_PAGE_SIZE"]] = f"is below page_size ({page_size})"
+    return errors
+
+
+def _level(source, overrides):
+    """Return the logging level of a source, with the overrides."""
+    level = overrides.get(f"LOGGING_LEVEL_{source}") or overrides.get("LOGGING_LEVEL")
+    return (level or settings.LOGGING_LEVEL_SOURCE[source]).upper()
+
+
+def apply(overrides):
+    """Apply the overrides to this process, restoring the settings not overridden.
+
+    param: overrides ({name: value}, as from RuntimeSetting.overrides)
+    """
+    with _lock:
+        if overrides == _applied:
+            return
+
+        for name, read in SETTINGS.items():
+            if name not in overrides and name not in _applied:
+                continue
+            configured = _configured.setdefault(name, getattr(settings, name))
+            value = read(overrides[name]) if name in overrides else configured
+            setattr(settings, name, value)
+
+        for source, logger in LOGGERS.items():
+            logging.getLogger(logger).setLevel(_level(source, overrides))
+
+        _applied.clear()
+        _applied.update(overrides)
+
+
+def report():
+    """Return the settings that may be overridden, and their values in effect.
+
+    returns: {name: {"value": ..., "configured": ..., "overridden": bool}}
+    """
+    with _lock:
+        applied = dict(_applied)
+
+    level = applied.get("LOGGING_LEVEL", settings.LOGGING_LEVEL)
+    values = {"LOGGING_LEVEL": (level.upper(), settings.LOGGING_LEVEL)}
+    for source in LOGGING_SOURCES:
+        configured = settings.LOGGING_LEVEL_SOURCE[source]
+        values[f"LOGGING_LEVEL_{source}"] = (_level(source, applied), configured)
+    for name in SETTINGS:
+        value = getattr(settings, name)
+        values[name] = (value, _configured.get(name, value))
+
+    return {
+        name.lower(): {
+            "value": value,
+            "configured": configured,
+            "overridden": name in applied,
+        }
+        for name, (value, configured) in values.items()
+    }
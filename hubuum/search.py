@@ -0,0 +1,68 @@
+"""A budget for expensive searches, the JSON filters on extension data.
+
+JSON filters (json_data_lookup, json_data_contains, etc) query into jsonb, and may
+be expensive. At most JSON_SEARCH_CONCURRENCY such searches run at once, per
+process. Searches beyond that wait up to JSON_SEARCH_QUEUE_TIMEOUT seconds for
+one to finish, and if none does, get 503 with Retry-After (JSON_SEARCH_RETRY_AFTER).
+
+Searches are counted in the hubuum_json_searches metric by outcome (completed,
+rejected, and slow), and searches slower than JSON_SEARCH_SLOW_TIME milliseconds
+are logged to the hubuum.database logger, with their filters.
+"""
+import contextlib
+import threading
+import time
+
+import structlog
+from django.conf import settings
+
+from hubuum import metrics
+from hubuum.exceptions import Busy
+
+logger = structlog.get_logger("hubuum.database")
+
+_lock = threading.Lock()
+_semaphores = {}
+
+
+def _semaphore(concurrency):
+    """Return the semaphore for the configured concurrency."""
+    with _lock:
+        if concurrency not in _semaphores:
+            _semaphores[concurrency] = threading.BoundedSemaphore(concurrency)
+        return _semaphores[concurrency]
+
+
+def is_json_search(params):
+    """Check if the query parameters of a request hold JSON filters."""
+    return any(name.startswith("json_data") for name in params)
+
+
+@contextlib.contextmanager
+def json_search(params):
+    """Run a JSON search within the budget.
+
+    param: params (the filters of the search, for logging slow searches)
+
+    raises: Busy if the search does not get to run in time
+    """
+    concurrency = settings.JSON_SEARCH_CONCURRENCY
+    if not concurrency:
+        yield
+        return
+
+    semaphore = _semaphore(concurrency)
+    if not semaphore.acquire(timeout=settings.JSON_SEARCH_QUEUE_TIMEOUT):
+        metrics.increment("hubuum_json_searches", outcome="rejected")
+        raise Busy(wait=settings.JSON_SEARCH_RETRY_AFTER)
+
+    start = time.monotonic()
+    try:
+        yield
+    finally:
+        semaphore.release()
+        elapsed = round((time.monotonic() - start) * 1000)
+        metrics.increment("hubuum_json_searches", outcome="completed")
+        if elapsed >= settings.JSON_SEARCH_SLOW_TIME:
+            metrics.increment("hubuum_json_searches", outcome="slow")
+            logger.warning("slow_json_search", duration_ms=elapsed, filters=params)
@@ -0,0 +1,223 @@
+"""Declarative seed data, applied idempotently.
+
+A seed describes groups, users, namespaces (with the permissions of groups in
+them), schema definitions, and extensions:
+
+    groups: [operators]
+    users:
+      - username: alice
+        password: secret  # Only set when the user is created.
+        groups: [operators]
+    namespaces:
+      - name: infrastructure
+        description: Servers and networks.
+        grants:
+          operators: [has_read, has_create, has_update]
+    schemas:
+      - namespace: infrastructure
+        name: address
+        json_schema: {type: string}
+    extensions:
+      - namespace: infrastructure
+        name: fleet
+        model: host
+        url: https://fleet.example.com/hosts/{name}
+        header: "Authorization: Bearer ..."
+        json_schema: {$ref: "hubuum:schemas/infrastructure/address"}
+
+Objects are found by name, created if missing, and updated to match the seed.
+Nothing is removed, and users keep the groups they are in. The grants given for
+a group in a namespace replace its existing permissions there.
+
+A seed may be applied with `manage.py hubuum_admin seed <file>`, by admins with
+POST /api/seed/, or at startup by naming the file in HUBUUM_SEED_FILE.
+"""
+import yaml
+from django.contrib.auth.models import Group
+from django.core.exceptions import ValidationError as DjangoValidationError
+from django.db import connection, transaction
+from rest_framework.exceptions import ValidationError
+
+from hubuum.models.auth import User
+from hubuum.models.extensions import Extension, SchemaDefinition
+from hubuum.models.namespace import Namespace, Permission
+from hubuum.permissions import fully_qualified_operations
+
+SECTIONS = ("groups", "users", "namespaces", "schemas", "extensions")
+
+# Serializes concurrent seeding, ie by several workers starting at once.
+ADVISORY_LOCK = 0x68756275  # "hubu"
+
+
+class SeedError(Exception):
+    """The seed is invalid."""
+
+
+def read_file(path):
+    """Read a seed from a YAML (or JSON) file.
+
+    raises: SeedError if the file can not be read
+    """
+    try:
+        with open(path, encoding="utf-8") as seed_file:
+            return yaml.safe_load(seed_file) or {}
+    except (OSError, yaml.YAMLError) as ex:
+        raise SeedError(f"{path}: {ex}") from ex
+
+
+def apply(seed):
+    """Apply a seed, within a single transaction.
+
+    returns: {"created": [descriptions], "updated": [descriptions]}
+    raises: SeedError if the seed is invalid, nothing is applied then
+    """
+    if not isinstance(seed, dict):
+        raise SeedError("The seed must be a mapping of sections.")
+
+    unknown = sorted(set(seed) - set(SECTIONS))
+    if unknown:
+        raise SeedError(f"Unknown section(s): {', '.join(unknown)}.")
+
+    report = {"created": [], "updated": []}
+    with transaction.atomic():
+        if connection.vendor == "postgresql":
+            with connection.cursor() as cursor:
+                cursor.execute("SELECT pg_advisory_xact_lock(%s)", [ADVISORY_LOCK])
+
+        for section in SECTIONS:
+            entries = seed.get(section) or []
+            if not isinstance(entries, list):
+                raise SeedError(f"{section}: expected a list.")
+            for index, entry in enumerate(entries):
+                try:
+                    _APPLY[section](entry, report)
+                except (KeyError, TypeError, AttributeError) as ex:
+                    message = f"{section}[{index}]: invalid entry ({ex})."
+                    raise SeedError(message) from ex
+                except (DjangoValidationError, ValidationError, LookupError) as ex:
+                    raise SeedError(f"{section}[{index}]: {ex}") from ex
+
+    return report
+
+
+def _sync(model, report, lookup, values, create_only=None):
+    """Find or create an object, and update it to match the values.
+
+    returns: the object
+    """
+    obj = model.objects.filter(**lookup).first()
+    created = obj is None
+    if created:
+        obj = model(**lookup)
+
+    changed = created
+    for field, value in values.items():
+        if created or getattr(obj, field) != value:
+            setattr(obj, field, value)
+            changed = True
+
+    if created and create_only:
+        create_only(obj)
+
+    description = f"{model.__name__.lower()} {' '.join(map(str, lookup.values()))}"
+    if changed:
+        obj.full_clean(validate_unique=False)
+        obj.save()
+        report["created" if created else "updated"].append(description)
+    return obj
+
+
+def _named(model, name):
+    """Find an object by name.
+
+    raises: LookupError if there is no such object
+    """
+    obj = model.objects.filter(name=name).first()
+    if obj is None:
+        raise LookupError(f"No {model.__name__.lower()} named '{name}'.")
+    return obj
+
+
+def _group(entry, report):
+    """Apply a group, given by name."""
+    _sync(Group, report, {"name": str(entry)}, {})
+
+
+def _user(entry, report):
+    """Apply a user, the password is only set when the user is created."""
+    values = {
+        field: entry[field]
+        for field in ("email", "first_name", "last_name", "is_staff", "is_superuser")
+        if field in entry
+    }
+
+    def set_password(user):
+        """Set the password of a new user, or make it unusable if not given."""
+        if entry.get("password"):
+            user.set_password(entry["password"])
+        else:
+            user.set_unusable_password()
+
+    user = _sync(User, report, {"username": entry["username"]}, values, set_password)
+    for name in entry.get("groups", []):
+        group = _named(Group, name)
+        if not user.groups.filter(pk=group.pk).exists():
+            user.groups.add(group)
+            report["updated"].append(f"user {user.username}")
+
+
+def _namespace(entry, report):
+    """Apply a namespace, and the permissions of groups in it."""
+    values = {"description": entry.get("description", "")}
+    namespace = _sync(Namespace, report, {"name": entry["name"]}, values)
+
+    operations = fully_qualified_operations()
+    for name, granted in entry.get("grants", {}).items():
+        unknown = [operation for operation in granted if operation not in operations]
+        if unknown:
+            raise LookupError(f"Unknown permission(s) {', '.join(unknown)}.")
+
+        group = _named(Group, name)
+        permission = Permission.objects.filter(namespace=namespace, group=group).first()
+        created = permission is None
+        if created:
+            permission = Permission(namespace=namespace, group=group)
+
+        wanted = {operation: operation in granted for operation in operations}
+        if created or any(getattr(permission, op) != wanted[op] for op in operations):
+            for operation, value in wanted.items():
+                setattr(permission, operation, value)
+            permission.save()
+            action = "created" if created else "updated"
+            report[action].append(f"permission {namespace.name} {group.name}")
+
+
+def _schema(entry, report):
+    """Apply a schema definition."""
+    lookup = {"namespace": _named(Namespace, entry["namespace"]), "name": entry["name"]}
+    values = {
+        "json_schema": entry["json_schema"],
+        "description": entry.get("description", ""),
+    }
+    SchemaDefinition.validate_references(entry["json_schema"])
+    _sync(SchemaDefinition, report, lookup, values)
+
+
+def _extension(entry, report):
+    """Apply an extension."""
+    fields = ("model", "url", "header", "require_interpolation", "json_schema")
+    values = {field: entry[field] for field in fields if field in entry}
+    values["namespace"] = _named(Namespace, entry["namespace"])
+    values["cache_time"] = entry.get("cache_time", 60)
+    if values.get("json_schema") is not None:
+        SchemaDefinition.validate_references(values["json_schema"])
+    _sync(Extension, report, {"name": entry["name"]}, values)
+
+
+_APPLY = {
+    "groups": _group,
+    "users": _user,
+    "namespaces": _namespace,
+    "schemas": _schema,
+    "extensions": _extension,
+}
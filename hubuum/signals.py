@@ -3,17 +3,42 @@
 import logging
 
 import structlog
+from django.conf import settings
 from django.contrib.auth.signals import (
     user_logged_in,
     user_logged_out,
     user_login_failed,
 )
-from django.db.models.signals import post_delete, post_save
+from django.db.models.signals import m2m_changed, post_delete, post_save
 from django.dispatch import receiver
 
+from hubuum import metrics
+from hubuum.actor import get_actor
+from hubuum.models.auth import LoginEvent, User
+from hubuum.models.namespace import (
+    ActivityEntry,
+    ModelPermission,
+    Namespace,
+    Permission,
+)
+from hubuum.models.outbox import OutboxEvent
+from hubuum.outbox import event_payload
+
 user_logger = structlog.getLogger("hubuum.auth")
 object_logger = structlog.getLogger("hubuum.signals.object")
 
+# Bookkeeping models whose changes are not logged as object changes.
+_unlogged_models = (
+    "ActivityEntry",
+    "IdempotencyKey",
+    "LoginEvent",
+    "OutboxEvent",
+    "TokenMetadata",
+)
+
+# Apps whose changes are recorded in the outbox.
+_outbox_apps = ("hubuum", "auth")
+
 
 def _log_user_event(
     sender, user, event, level=logging.INFO, **kwargs
@@ -36,17 +61,111 @@ def _identifier(instance):
 @receiver(post_save)
 def log_object_creation(sender, instance, created, **kwargs):
     """Log object creation."""
-    identifier = _identifier(instance)
+    if sender.__name__ in _unlogged_models:
+        return
+
+    logger = object_logger.bind(
+        model=sender.__name__, id=_identifier(instance), actor=get_actor().as_dict()
+    )
     if created:
-        object_logger.bind(model=sender.__name__, id=identifier).info("created")
+        logger.info("created")
     else:
-        object_logger.bind(model=sender.__name__, id=identifier).info("updated")
+        logger.info("updated")
 
 
 @receiver(post_delete)
 def log_object_deletion(sender, instance, **kwargs):
     """Log object deletion."""
-    object_logger.bind(model=sender.__name__, id=_identifier(instance)).info("deleted")
+    if sender.__name__ in _unlogged_models:
+        return
+
+    object_logger.bind(
+        model=sender.__name__, id=_identifier(instance), actor=get_actor().as_dict()
+    ).info("deleted")
+
+
+@receiver(post_save)
+def record_activity_on_save(sender, instance, created, raw=False, **kwargs):
+    """Record creation and updates in the activity feed of the namespace."""
+    if sender.__name__ in _unlogged_models or raw:
+        return
+
+    ActivityEntry.record(instance, "created" if created else "updated")
+
+
+@receiver(post_delete)
+def record_activity_on_delete(sender, instance, **kwargs):
+    """Record deletion in the activity feed of the namespace."""
+    if sender.__name__ in _unlogged_models:
+        return
+
+    ActivityEntry.record(instance, "deleted")
+
+
+@receiver(post_delete, sender=Namespace)
+def delete_namespace_activity(sender, instance, **kwargs):
+    """Remove the activity feed of a deleted namespace."""
+    ActivityEntry.objects.filter(namespace_id=instance.id).delete()
+
+
+def _record_outbox_event(sender, instance, event):
+    """Record an event in the outbox, if an outbox backend is configured."""
+    if not settings.OUTBOX_BACKEND or sender.__name__ in _unlogged_models:
+        return
+    if sender._meta.app_label not in _outbox_apps:  # pylint: disable=protected-access
+        return
+
+    OutboxEvent.objects.create(
+        event=event,
+        model=sender.__name__,
+        object_id=instance.pk,
+        payload={**event_payload(instance, event), "actor": get_actor().as_dict()},
+    )
+
+
+@receiver(post_save)
+def record_outbox_event_on_save(sender, instance, created, raw=False, **kwargs):
+    """Record creation and updates in the outbox."""
+    if not raw:
+        _record_outbox_event(sender, instance, "created" if created else "updated")
+
+
+@receiver(post_delete)
+def record_outbox_event_on_delete(sender, instance, **kwargs):
+    """Record deletion in the outbox."""
+    _record_outbox_event(sender, instance, "deleted")
+
+
+@receiver(post_save)
+def count_object_creation(sender, instance, created, **kwargs):
+    """Count object creation."""
+    if created:
+        metrics.increment("hubuum_objects_created", model=metrics.model_label(sender))
+
+
+@receiver(post_delete)
+def count_object_deletion(sender, instance, **kwargs):
+    """Count object deletion."""
+    metrics.increment("hubuum_objects_deleted", model=metrics.model_label(sender))
+
+
+@receiver(m2m_changed, sender=User.groups.through)
+def count_group_memberships(sender, action, pk_set, **kwargs):
+    """Count users being added to or removed from groups."""
+    if action in ["post_add", "post_remove"] and pk_set:
+        metrics.increment(
+            "hubuum_group_memberships", len(pk_set), action=action[len("post_") :]
+        )
+
+
+@receiver(post_save, sender=Permission)
+@receiver(post_delete, sender=Permission)
+@receiver(post_save, sender=ModelPermission)
+@receiver(post_delete, sender=ModelPermission)
+@receiver(m2m_changed, sender=User.groups.through)
+def invalidate_permission_caches(sender, **kwargs):
+    """Invalidate cached permissions when permissions or group memberships change."""
+    User.invalidate_permission_caches()
 
 
 @receiver(user_logged_in)
@@ -55,6 +174,20 @@ def log_user_login(sender, user, **kwargs):
     _log_user_event(sender, user, "login")
 
 
+@receiver(user_logged_in)
+def record_user_login(sender, user, request=None, **kwargs):
+    """Record logins in the login history of the user.
+
+    The method is that of the authenticator of API requests (see auth_method in
+    hubuum/api/authentication.py), and "session" for logins to the admin interface.
+    """
+    if request is None:
+        return
+
+    authenticator = getattr(request, "successful_authenticator", None)
+    LoginEvent.record(user, request, getattr(authenticator, "auth_method", "session"))
+
+
 @receiver(user_login_failed)
 def log_user_login_failed(sender, user=None, **kwargs):
     """Log user login failures."""
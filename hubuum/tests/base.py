@@ -4,15 +4,9 @@ from django.contrib.auth.models import Group
 from django.test import TestCase
 
 from hubuum.exceptions import MissingParam
-from hubuum.models.base import (  # Permissions,
-    Extension,
-    Namespace,
-    Person,
-    PurchaseDocuments,
-    PurchaseOrder,
-    Room,
-    Vendor,
-)
+from hubuum.models.extensions import Extension
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Person, PurchaseDocuments, PurchaseOrder, Room, Vendor
 
 
 class HubuumModelTestCase(TestCase):
@@ -1,12 +1,16 @@
 """Test module: Users and Groups."""
 import pytest
+from django.conf import settings
+from django.db import connection
 from rest_framework.exceptions import NotFound, ValidationError
 
 from hubuum.exceptions import MissingParam
 from hubuum.log import filter_sensitive_data
 from hubuum.models.auth import User
-from hubuum.models.base import Host, Namespace, model_supports_extensions
-from hubuum.tools import get_object
+from hubuum.models.base import model_supports_extensions
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host
+from hubuum.tools import REDACTED, get_object, json_diff, json_redact
 from hubuum.validators import validate_model
 
 from .base import HubuumModelTestCase
@@ -104,3 +108,58 @@ class InternalsTestCase(HubuumModelTestCase):
 
         for data, expected in test_data:
             self.assertEqual(filter_sensitive_data(None, None, data), expected)
+
+    def test_json_diff(self):
+        """Test that json_diff reports added, removed, and changed paths."""
+        old = {"a": 1, "b": {"c": 2, "d": [1, 2]}, "gone": True}
+        new = {"a": 2, "b": {"c": 2, "d": [1, 3, 4]}, "new": None}
+        self.assertEqual(
+            json_diff(old, new),
+            {
+                "added": {"b__d__2": 4, "new": None},
+                "removed": {"gone": True},
+                "changed": {
+                    "a": {"from": 1, "to": 2},
+                    "b__d__1": {"from": 2, "to": 3},
+                },
+            },
+        )
+        self.assertEqual(
+            json_diff([1], {"a": 1}),
+            {
+                "added": {},
+                "removed": {},
+                "changed": {"": {"from": [1], "to": {"a": 1}}},
+            },
+        )
+
+    def test_json_redact(self):
+        """Test that json_redact replaces the paths found, copying the document."""
+        data = {"a": {"b": 1, "c": 2}, "disks": [{"serial": "x"}, {"serial": "y"}]}
+        self.assertEqual(
+            json_redact(data, ["a__b", "disks__*__serial", "nosuch__path", "a__c__d"]),
+            {
+                "a": {"b": REDACTED, "c": 2},
+                "disks": [{"serial": REDACTED}, {"serial": REDACTED}],
+            },
+        )
+        self.assertEqual(json_redact(data, ["disks__1"])["disks"][1], REDACTED)
+        self.assertEqual(data["a"]["b"], 1)
+
+    def test_database_schema(self):
+        """Test that tables live in the configured schema (or the default)."""
+        with connection.cursor() as cursor:
+            cursor.execute("SHOW search_path")
+            search_path = cursor.fetchone()[0]
+            cursor.execute("SELECT current_schema()")
+            schema = cursor.fetchone()[0]
+            cursor.execute(
+                "SELECT table_schema FROM information_schema.tables"
+                " WHERE table_name = %s",
+                [Host._meta.db_table],  # pylint: disable=protected-access
+            )
+            table_schemas = [row[0] for row in cursor.fetchall()]
+
+        if settings.DATABASE_SCHEMA:
+            self.assertTrue(search_path.startswith(settings.DATABASE_SCHEMA))
+        self.assertIn(schema, table_schemas)
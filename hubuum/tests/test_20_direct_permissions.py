@@ -3,7 +3,8 @@ from django.contrib.auth.models import Group
 from django.test import TestCase
 
 from hubuum.models.auth import User
-from hubuum.models.base import Host, Namespace, Permission
+from hubuum.models.namespace import Namespace, Permission
+from hubuum.models.objects import Host
 
 
 class PermissionsTestCase(TestCase):
@@ -83,3 +84,18 @@ class PermissionsTestCase(TestCase):
             namespace=self.onehost.namespace, group=self.twogroup
         ).delete()
         self.assertFalse(self.two.has_perm(self.read_perm, self.onehost))
+
+    def test_permission_lookups_are_cached(self):
+        """Test that repeated permission lookups are served by the first queries."""
+        namespace = self.onenamespace
+        # One query for the grants for namespaces, one for those for models.
+        with self.assertNumQueries(2):
+            for _ in range(3):
+                self.assertTrue(self.one.has_perm(self.read_perm, self.onehost))
+                self.assertFalse(self.one.has_perm(self.read_perm, self.twohost))
+                self.assertTrue(self.one.namespaced_can("has_update", namespace))
+            self.assertEqual(self.one.namespaces_where("has_read"), {namespace.id})
+
+        # Changing group memberships invalidates the cache.
+        self.one.groups.add(self.twogroup)
+        self.assertTrue(self.one.has_perm(self.read_perm, self.twohost))
@@ -0,0 +1,88 @@
+"""Test the doctor management command."""
+import os
+from io import StringIO
+from unittest import mock
+
+import pytest
+from django.core.management import call_command
+from django.core.management.base import CommandError
+from django.test import override_settings
+
+from hubuum.models.extensions import Extension
+
+from .base import HubuumModelTestCase
+
+
+@override_settings(ALLOWED_HOSTS=["hubuum.example"])
+class DoctorTestCase(HubuumModelTestCase):
+    """Test the deployment diagnostics."""
+
+    def _doctor(self, *args):
+        """Run the doctor command and return its output."""
+        out = StringIO()
+        call_command("doctor", *args, stdout=out, no_color=True)
+        return out.getvalue()
+
+    def test_healthy_deployment(self):
+        """Test that a sane test deployment passes all checks."""
+        output = self._doctor()
+        self.assertIn("PASS database: PostgreSQL", output)
+        self.assertIn("PASS migrations: all migrations applied", output)
+        self.assertIn("PASS clock skew:", output)
+        self.assertIn("PASS explain permissions: admin", output)
+        self.assertIn("PASS configuration: valid", output)
+        self.assertIn("PASS tls: not configured", output)
+        self.assertNotIn("FAIL", output)
+        self.assertNotIn("integrations", output)
+
+    def test_startup(self):
+        """Test that --startup only runs the checks needed to serve requests."""
+        output = self._doctor("--startup")
+        self.assertIn("PASS configuration: valid", output)
+        self.assertIn("PASS migrations: all migrations applied", output)
+        self.assertNotIn("clock skew", output)
+        self.assertNotIn("explain permissions", output)
+
+        with override_settings(ALLOWED_HOSTS=[]):
+            self._doctor("--startup")
+
+    def test_exit_codes(self):
+        """Test that failures exit with the code of the first failed check."""
+        environ = {"HUBUUM_NAME_MAX_LENGTH": "0"}
+        with mock.patch.dict(os.environ, environ), pytest.raises(CommandError) as ex:
+            self._doctor("--startup")
+        self.assertEqual(ex.value.returncode, 2)
+
+        environ = {"HUBUUM_SERVER_TLS_CERT": "/nonexistent/tls.pem"}
+        with mock.patch.dict(os.environ, environ), pytest.raises(CommandError) as ex:
+            self._doctor("--startup")
+        self.assertEqual(ex.value.returncode, 5)
+
+        with override_settings(ALLOWED_HOSTS=[]), pytest.raises(CommandError) as ex:
+            self._doctor()
+        self.assertEqual(ex.value.returncode, 1)
+
+    def test_configuration_failures(self):
+        """Test that broken configuration fails the report."""
+        with override_settings(ALLOWED_HOSTS=[]), pytest.raises(CommandError):
+            self._doctor()
+        with override_settings(EXPLAIN_PERMISSIONS="sometimes"):
+            with pytest.raises(CommandError):
+                self._doctor()
+
+    def test_integrations(self):
+        """Test the connectivity checks for extensions."""
+        output = self._doctor("--check-integrations")
+        self.assertIn("PASS integrations: no extensions configured", output)
+
+        self._test_can_create_object(
+            model=Extension, name="perhost", url="https://{fqdn}/api/"
+        )
+        output = self._doctor("--check-integrations")
+        self.assertIn("WARN extension perhost: host is interpolated", output)
+
+        self._test_can_create_object(
+            model=Extension, name="closed", url="http://127.0.0.1:1/{fqdn}"
+        )
+        with pytest.raises(CommandError):
+            self._doctor("--check-integrations", "--timeout", "1")
@@ -0,0 +1,62 @@
+"""Test the hubuum_admin management command."""
+from io import StringIO
+
+import pytest
+from django.core.management import call_command
+from django.core.management.base import CommandError
+from knox.models import AuthToken
+
+from hubuum.models.auth import User
+from hubuum.models.objects import Host
+
+from .base import HubuumModelTestCase
+
+
+class AdminCommandTestCase(HubuumModelTestCase):
+    """Test the administrative bootstrap tasks."""
+
+    def _admin(self, *args):
+        """Run the hubuum_admin command and return its output."""
+        out = StringIO()
+        call_command("hubuum_admin", *args, stdout=out, no_color=True)
+        return out.getvalue()
+
+    def test_createadmin(self):
+        """Test creating and promoting admin users."""
+        output = self._admin("createadmin", "root", "--password", "secret")
+        self.assertIn("Created admin user 'root'.", output)
+        root = User.objects.get(username="root")
+        self.assertTrue(root.is_superuser)
+        self.assertTrue(root.check_password("secret"))
+
+        output = self._admin("createadmin", "test", "--password", "secret")
+        self.assertIn("Promoted admin user 'test'.", output)
+        self.assertTrue(User.objects.get(username="test").is_superuser)
+
+    def test_resetpassword(self):
+        """Test setting the password of a user."""
+        self._admin("resetpassword", "test", "--password", "newsecret")
+        self.assertTrue(User.objects.get(username="test").check_password("newsecret"))
+        with pytest.raises(CommandError):
+            self._admin("resetpassword", "nosuchuser", "--password", "newsecret")
+
+    def test_namespaces(self):
+        """Test listing namespaces."""
+        Host.objects.create(name="host1", namespace=self.namespace)
+        output = self._admin("namespaces")
+        self.assertIn(f"{self.namespace.id}\ttest\t1 hosts\n", output)
+
+    def test_rotatetokens(self):
+        """Test revoking and issuing tokens."""
+        AuthToken.objects.create(self.user)
+        AuthToken.objects.create(self.user)
+
+        output = self._admin("rotatetokens", "test")
+        self.assertIn("Revoked 2 token(s) for 'test'.", output)
+        self.assertEqual(AuthToken.objects.filter(user=self.user).count(), 1)
+        issued = AuthToken.objects.get(user=self.user)
+        self.assertTrue(output.splitlines()[-1].startswith(issued.token_key))
+
+        output = self._admin("rotatetokens", "test", "--revoke-only")
+        self.assertEqual(output, "Revoked 1 token(s) for 'test'.\n")
+        self.assertFalse(AuthToken.objects.filter(user=self.user).exists())
@@ -0,0 +1,174 @@
+"""Test the configuration file support and validation."""
+import os
+import sys
+import tempfile
+from io import StringIO
+from unittest import mock
+
+import pytest
+from django.core.exceptions import ImproperlyConfigured
+from django.test import SimpleTestCase
+
+from hubuumsite.config import (
+    ConfigurationError,
+    check,
+    load,
+    read_config_file,
+    secret,
+    tls_options,
+)
+from hubuumsite.secret_sources import Secret
+
+
+class ConfigTestCase(SimpleTestCase):
+    """Test reading, layering, and validating the configuration."""
+
+    def _config_file(self, content):
+        """Write a configuration file, returning its path."""
+        handle, path = tempfile.mkstemp(suffix=".yaml")
+        with os.fdopen(handle, "w", encoding="utf-8") as config_file:
+            config_file.write(content)
+        self.addCleanup(os.remove, path)
+        return path
+
+    def test_read_config_file(self):
+        """Test that sections are flattened and values converted."""
+        path = self._config_file(
+            "logging_level: info\n"
+            "compress_responses: true\n"
+            "reserved_names: [admin, root]\n"
+            "database:\n  host: db.example.com\n  port: 5433\n"
+        )
+        self.assertEqual(
+            read_config_file(path),
+            {
+                "LOGGING_LEVEL": "info",
+                "COMPRESS_RESPONSES": "true",
+                "RESERVED_NAMES": "admin,root",
+                "DATABASE_HOST": "db.example.com",
+                "DATABASE_PORT": "5433",
+            },
+        )
+        self.assertEqual(read_config_file(self._config_file("")), {})
+
+    def test_unknown_options(self):
+        """Test that unknown options are reported, with suggestions."""
+        path = self._config_file("database:\n  hots: db\nnosuchoption: 1\n")
+        with pytest.raises(ConfigurationError) as error:
+            read_config_file(path)
+        self.assertEqual(len(error.value.errors), 2)
+        self.assertIn("did you mean 'database_host'?", error.value.errors[0])
+        self.assertIn("unknown option 'nosuchoption'", error.value.errors[1])
+
+        with pytest.raises(ConfigurationError):
+            read_config_file(self._config_file("- a list"))
+        with pytest.raises(ConfigurationError):
+            read_config_file("/nonexistent/hubuum.yaml")
+
+    def test_layering(self):
+        """Test that the environment overrides the configuration file."""
+        path = self._config_file("database:\n  host: file\n  name: file\n")
+        environ = {"HUBUUM_CONFIG_FILE": path, "HUBUUM_DATABASE_HOST": "env"}
+        load(environ)
+        self.assertEqual(environ["HUBUUM_DATABASE_HOST"], "env")
+        self.assertEqual(environ["HUBUUM_DATABASE_NAME"], "file")
+
+    def test_validation(self):
+        """Test that invalid values and combinations are reported."""
+        environ = {
+            "HUBUUM_DATABASE_PORT": "many",
+            "HUBUUM_LOGGING_LEVEL": "loud",
+            "HUBUUM_NAME_PATTERN": "[unclosed",
+            "HUBUUM_SENTRY_LEVEL": "error",
+            "HUBUUM_DATABASE_BACKEND": "django.db.backends.sqlite3",
+            "HUBUUM_DATABASE_SCHEMA": "hubuum",
+            "HUBUUM_IP_ALLOW": "10.0.0.0/8, intranet",
+            "HUBUUM_PAGE_SIZE": "500",
+            "HUBUUM_MAX_PAGE_SIZE": "200",
+        }
+        with pytest.raises(ConfigurationError) as error:
+            load(environ)
+        self.assertEqual(len(error.value.errors), 7)
+
+        load({"HUBUUM_DATABASE_PORT": "5432", "HUBUUM_EXPLAIN_PERMISSIONS": "ALL"})
+
+    def test_check(self):
+        """Test the --check-config report."""
+        out = StringIO()
+        self.assertEqual(check({}, stdout=out), 0)
+        self.assertEqual(out.getvalue(), "Configuration OK\n")
+
+        out = StringIO()
+        self.assertEqual(check({"HUBUUM_NAME_MAX_LENGTH": "0"}, stdout=out), 2)
+        self.assertIn("HUBUUM_NAME_MAX_LENGTH (name_max_length)", out.getvalue())
+
+    def test_secret_sources(self):
+        """Test reading secrets inline, from files, and from other variables."""
+        path = self._config_file("from file\n")
+        self.assertEqual(secret("DATABASE_PASSWORD", {}), None)
+        environ = {"HUBUUM_DATABASE_PASSWORD": "inline"}
+        self.assertEqual(secret("DATABASE_PASSWORD", environ), "inline")
+
+        environ = {"HUBUUM_DATABASE_PASSWORD_SOURCE": f"file:{path}"}
+        password = secret("DATABASE_PASSWORD", environ)
+        self.assertEqual(str(password), "from file")
+        self.assertNotIn("from file", repr(password))
+
+        with mock.patch.dict(os.environ, {"DB_PASSWORD": "from env"}):
+            self.assertEqual(str(Secret("env:DB_PASSWORD")), "from env")
+
+        with pytest.raises(ImproperlyConfigured):
+            str(Secret("file:/nonexistent/secret"))
+        with pytest.raises(ImproperlyConfigured):
+            str(Secret("env:HUBUUM_NO_SUCH_VARIABLE"))
+        with mock.patch.dict(sys.modules, {"hvac": None}):
+            with pytest.raises(ImproperlyConfigured):
+                str(Secret("vault:secret/hubuum#password"))
+
+    def test_secret_refresh(self):
+        """Test that secrets are read again after the refresh interval."""
+        path = self._config_file("old")
+        with mock.patch("time.monotonic", return_value=1000) as clock:
+            once = Secret(f"file:{path}")
+            refreshed = Secret(f"file:{path}", refresh=60)
+            self.assertEqual(str(once), "old")
+            self.assertEqual(str(refreshed), "old")
+
+            with open(path, "w", encoding="utf-8") as secret_file:
+                secret_file.write("new")
+            clock.return_value = 1059
+            self.assertEqual(str(refreshed), "old")
+
+            clock.return_value = 1060
+            self.assertEqual(str(once), "old")
+            self.assertEqual(str(refreshed), "new")
+
+    def test_secret_validation(self):
+        """Test that malformed sources and conflicting secrets are reported."""
+        environ = {
+            "HUBUUM_DATABASE_PASSWORD": "inline",
+            "HUBUUM_DATABASE_PASSWORD_SOURCE": "file:/run/secrets/db",
+            "HUBUUM_SERVER_TLS_KEY_PASSPHRASE_SOURCE": "vault:secret#key",
+            "HUBUUM_SECRET_REFRESH": "-1",
+        }
+        with pytest.raises(ConfigurationError) as error:
+            load(environ)
+        self.assertEqual(len(error.value.errors), 4)
+
+        environ = {"HUBUUM_SERVER_TLS_CERT": "/etc/hubuum/tls.pem"}
+        load({**environ, "HUBUUM_SERVER_TLS_KEY_PASSPHRASE_SOURCE": "env:TLS_KEY"})
+        with pytest.raises(ConfigurationError):
+            load({**environ, "HUBUUM_SERVER_TLS_KEY_PASSPHRASE_SOURCE": "nosuch:value"})
+
+    def test_tls_options(self):
+        """Test that TLS is configured by the certificate, key, and passphrase."""
+        self.assertEqual(tls_options({}), None)
+        cert = "/etc/hubuum/tls.pem"
+        environ = {"HUBUUM_SERVER_TLS_CERT": cert}
+        self.assertEqual(
+            tls_options(environ), {"cert": cert, "key": cert, "passphrase": None}
+        )
+        environ["HUBUUM_SERVER_TLS_KEY"] = "/etc/hubuum/tls.key"
+        environ["HUBUUM_SERVER_TLS_KEY_PASSPHRASE"] = "secret"
+        self.assertEqual(tls_options(environ)["key"], "/etc/hubuum/tls.key")
+        self.assertEqual(tls_options(environ)["passphrase"], "secret")
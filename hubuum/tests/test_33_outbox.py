@@ -0,0 +1,143 @@
+"""Test the outbox and the publish_outbox management command."""
+from io import StringIO
+
+import pytest
+from django.core.management import call_command
+from django.core.management.base import CommandError
+from django.test import override_settings
+from structlog.testing import capture_logs
+
+from hubuum.models.auth import User
+from hubuum.models.objects import Host
+from hubuum.models.outbox import OutboxEvent
+from hubuum.outbox import OutboxBackend
+
+from .base import HubuumModelTestCase
+
+
+class CollectingBackend(OutboxBackend):
+    """Collect published events, optionally failing for some models."""
+
+    published = []
+    failing_models = ()
+
+    def publish(self, event):
+        """Collect the event, or fail."""
+        if event.model in self.failing_models:
+            raise ConnectionError(f"Can not publish {event.model}")
+        self.published.append(event.payload)
+
+
+BACKEND = "hubuum.tests.test_33_outbox.CollectingBackend"
+
+
+@override_settings(OUTBOX_BACKEND=BACKEND)
+class OutboxTestCase(HubuumModelTestCase):
+    """Test recording and publishing outbox events."""
+
+    def setUp(self):
+        """Start without events."""
+        super().setUp()
+        OutboxEvent.objects.all().delete()
+        CollectingBackend.published = []
+        CollectingBackend.failing_models = ()
+
+    def _publish(self):
+        """Run publish_outbox once and return its output."""
+        out = StringIO()
+        call_command("publish_outbox", "--once", stdout=out)
+        return out.getvalue()
+
+    def test_events_are_recorded(self):
+        """Test that changes are recorded as events."""
+        host = Host.objects.create(name="outbox", namespace=self.namespace)
+        host.fqdn = "outbox.domain.tld"
+        host.save()
+        host_id = host.id
+        host.delete()
+
+        events = OutboxEvent.objects.filter(model="Host")
+        self.assertEqual(
+            [event.event for event in events], ["created", "updated", "deleted"]
+        )
+        self.assertEqual(events[1].object_id, host_id)
+        self.assertEqual(events[1].payload["data"]["fqdn"], "outbox.domain.tld")
+        self.assertEqual(events[1].payload["namespace"], self.namespace.id)
+        self.assertTrue(all(event.published_at is None for event in events))
+
+    def test_no_secrets_in_events(self):
+        """Test that passwords are not included in events."""
+        user = User.objects.create(username="outboxuser")
+        user.set_password("secret")
+        user.save()
+        for event in OutboxEvent.objects.filter(model="User"):
+            self.assertNotIn("password", event.payload["data"])
+
+    @override_settings(OUTBOX_BACKEND="")
+    def test_disabled(self):
+        """Test that no events are recorded without a backend."""
+        Host.objects.create(name="outbox", namespace=self.namespace)
+        self.assertFalse(OutboxEvent.objects.exists())
+        with pytest.raises(CommandError):
+            self._publish()
+
+    def test_publishing(self):
+        """Test that events are published in order, once."""
+        Host.objects.create(name="outbox1", namespace=self.namespace)
+        Host.objects.create(name="outbox2", namespace=self.namespace)
+        total = OutboxEvent.objects.count()
+
+        self.assertIn(f"Published {total} event(s).", self._publish())
+        self.assertFalse(OutboxEvent.objects.filter(published_at=None).exists())
+        hosts = [
+            event["data"]["name"]
+            for event in CollectingBackend.published
+            if event["model"] == "hubuum.host"
+        ]
+        self.assertEqual(hosts, ["outbox1", "outbox2"])
+
+        self._publish()
+        self.assertEqual(len(CollectingBackend.published), total)
+
+    def test_failed_publishing_is_retried(self):
+        """Test that events that fail to publish are kept, and hold back the rest."""
+        Host.objects.create(name="outbox", namespace=self.namespace)
+        self.namespace.description = "Changed."
+        self.namespace.save()
+
+        CollectingBackend.failing_models = ("Host",)
+        with pytest.raises(CommandError):
+            self._publish()
+        failed = OutboxEvent.objects.get(model="Host")
+        self.assertIsNone(failed.published_at)
+        self.assertEqual(failed.attempts, 1)
+        self.assertIn("Can not publish Host", failed.last_error)
+        self.assertIsNone(OutboxEvent.objects.get(model="Namespace").published_at)
+
+        CollectingBackend.failing_models = ()
+        self._publish()
+        self.assertFalse(OutboxEvent.objects.filter(published_at=None).exists())
+        self.assertEqual(OutboxEvent.objects.get(model="Host").attempts, 2)
+
+    @override_settings(OUTBOX_BACKEND="hubuum.outbox.LogBackend")
+    def test_log_backend(self):
+        """Test that the log backend logs the events, with their payloads."""
+        Host.objects.create(name="outbox", namespace=self.namespace)
+        total = OutboxEvent.objects.count()
+        with capture_logs() as cap_logs:
+            self.assertIn(f"Published {total} event(s).", self._publish())
+
+        published = [entry for entry in cap_logs if entry["event"] == "published"]
+        self.assertEqual(len(published), total)
+        hosts = [
+            entry for entry in published if entry["payload"]["model"] == "hubuum.host"
+        ]
+        self.assertEqual(hosts[0]["payload"]["event"], "created")
+        self.assertEqual(hosts[0]["payload"]["data"]["name"], "outbox")
+        self.assertFalse(OutboxEvent.objects.filter(published_at=None).exists())
+
+    @override_settings(OUTBOX_BACKEND="hubuum.models.objects.Host")
+    def test_invalid_backend(self):
+        """Test that the backend must be an OutboxBackend."""
+        with pytest.raises(CommandError):
+            self._publish()
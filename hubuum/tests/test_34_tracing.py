@@ -0,0 +1,55 @@
+"""Test tracing of database queries."""
+import logging
+
+from django.db import connection
+from structlog.testing import capture_logs
+
+from hubuum import tracing
+from hubuum.models.objects import Host
+
+from .base import HubuumModelTestCase
+
+
+class TracingTestCase(HubuumModelTestCase):
+    """Test the logging of database queries."""
+
+    def test_query_kind_and_table(self):
+        """Test finding the kind and table of queries."""
+        for sql, kind, table in (
+            ('SELECT "hubuum_host"."id" FROM "hubuum_host"', "SELECT", "hubuum_host"),
+            ('INSERT INTO "hubuum_host" ("name") VALUES (%s)', "INSERT", "hubuum_host"),
+            ('UPDATE "hubuum_host" SET "name" = %s', "UPDATE", "hubuum_host"),
+            ("select 1", "SELECT", ""),
+            ("", "", ""),
+        ):
+            self.assertEqual(tracing.query_kind(sql), kind)
+            self.assertEqual(tracing.query_table(sql), table)
+
+    def test_queries_are_logged(self):
+        """Test that traced queries are logged with their row counts."""
+        Host.objects.create(name="traced1", namespace=self.namespace)
+        Host.objects.create(name="traced2", namespace=self.namespace)
+
+        logger = logging.getLogger("hubuum.database")
+        level = logger.level
+        self.addCleanup(logger.setLevel, level)
+        logger.setLevel(logging.DEBUG)
+        with capture_logs() as cap_logs:
+            with connection.execute_wrapper(tracing.trace_query):
+                list(Host.objects.filter(name__startswith="traced"))
+
+        self.assertEqual(len(cap_logs), 1)
+        self.assertEqual(cap_logs[0]["event"], "query")
+        self.assertEqual(cap_logs[0]["kind"], "SELECT")
+        self.assertEqual(cap_logs[0]["table"], "hubuum_host")
+        self.assertEqual(cap_logs[0]["rows"], 2)
+        self.assertGreaterEqual(cap_logs[0]["milliseconds"], 0)
+
+    def test_queries_are_not_logged_by_default(self):
+        """Test that queries are only logged with the logger enabled for debug."""
+        logger = logging.getLogger("hubuum.database")
+        self.assertFalse(logger.isEnabledFor(logging.DEBUG))
+        with capture_logs() as cap_logs:
+            with connection.execute_wrapper(tracing.trace_query):
+                list(Host.objects.all())
+        self.assertEqual(cap_logs, [])
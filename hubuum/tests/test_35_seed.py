@@ -0,0 +1,131 @@
+"""Test applying declarative seed data."""
+import os
+import tempfile
+from io import StringIO
+
+import pytest
+from django.contrib.auth.models import Group
+from django.core.management import call_command
+from django.core.management.base import CommandError
+
+from hubuum.models.auth import User
+from hubuum.models.extensions import Extension, SchemaDefinition
+from hubuum.models.namespace import Namespace, Permission
+from hubuum.seed import SeedError, apply
+
+from .base import HubuumModelTestCase
+
+SEED = {
+    "groups": ["operators"],
+    "users": [{"username": "alice", "password": "secret", "groups": ["operators"]}],
+    "namespaces": [
+        {
+            "name": "infrastructure",
+            "description": "Servers.",
+            "grants": {"operators": ["has_read", "has_create"]},
+        }
+    ],
+    "schemas": [
+        {
+            "namespace": "infrastructure",
+            "name": "address",
+            "json_schema": {"type": "string"},
+        }
+    ],
+    "extensions": [
+        {
+            "namespace": "infrastructure",
+            "name": "fleet",
+            "model": "host",
+            "url": "https://fleet.example.com/hosts/{name}",
+            "header": "Authorization: Bearer token",
+            "json_schema": {"$ref": "hubuum:schemas/infrastructure/address"},
+        }
+    ],
+}
+
+
+class SeedTestCase(HubuumModelTestCase):
+    """Test that seeds are applied, idempotently."""
+
+    def test_apply(self):
+        """Test that a seed creates what it describes."""
+        report = apply(SEED)
+        self.assertIn("user alice", report["created"])
+        self.assertIn("permission infrastructure operators", report["created"])
+        self.assertEqual(report["updated"], [])
+
+        alice = User.objects.get(username="alice")
+        self.assertTrue(alice.check_password("secret"))
+        self.assertTrue(alice.groups.filter(name="operators").exists())
+        namespace = Namespace.objects.get(name="infrastructure")
+        permission = Permission.objects.get(namespace=namespace)
+        self.assertTrue(permission.has_create)
+        self.assertFalse(permission.has_delete)
+        self.assertTrue(SchemaDefinition.objects.filter(name="address").exists())
+        self.assertEqual(Extension.objects.get(name="fleet").namespace, namespace)
+
+    def test_idempotency(self):
+        """Test that reapplying a seed changes nothing, and changes are applied."""
+        apply(SEED)
+        alice = User.objects.get(username="alice")
+        alice.set_password("changed")
+        alice.save()
+
+        self.assertEqual(apply(SEED), {"created": [], "updated": []})
+        self.assertTrue(User.objects.get(username="alice").check_password("changed"))
+
+        seed = {
+            "namespaces": [
+                {
+                    "name": "infrastructure",
+                    "description": "Servers and networks.",
+                    "grants": {"operators": ["has_read"]},
+                }
+            ]
+        }
+        report = apply(seed)
+        self.assertEqual(
+            report["updated"],
+            ["namespace infrastructure", "permission infrastructure operators"],
+        )
+        self.assertFalse(Permission.objects.get(group__name="operators").has_create)
+
+    def test_invalid_seeds(self):
+        """Test that invalid seeds are rejected, without applying anything."""
+        grants = {"new": ["has_read", "x"]}
+        for seed, message in (
+            (["groups"], "must be a mapping"),
+            ({"hosts": []}, "Unknown section(s): hosts."),
+            ({"groups": "operators"}, "groups: expected a list."),
+            ({"users": [{"name": "bob"}]}, "users[0]: invalid entry"),
+            ({"users": [{"username": "bob", "groups": ["no"]}]}, "No group named"),
+            (
+                {"groups": ["new"], "namespaces": [{"name": "n", "grants": grants}]},
+                "namespaces[0]: Unknown permission(s) x.",
+            ),
+            (
+                {"extensions": [{**SEED["extensions"][0], "namespace": "test"}]},
+                "Unresolved references",
+            ),
+        ):
+            with pytest.raises(SeedError) as error:
+                apply(seed)
+            self.assertIn(message, str(error.value))
+
+        self.assertFalse(Group.objects.filter(name="new").exists())
+
+    def test_command(self):
+        """Test applying a seed file with hubuum_admin."""
+        handle, path = tempfile.mkstemp(suffix=".yaml")
+        with os.fdopen(handle, "w", encoding="utf-8") as seed_file:
+            seed_file.write("groups: [operators]\nnamespaces:\n  - name: lab\n")
+        self.addCleanup(os.remove, path)
+
+        out = StringIO()
+        call_command("hubuum_admin", "seed", path, stdout=out, no_color=True)
+        self.assertIn("Created namespace lab.", out.getvalue())
+        self.assertIn("Seed applied, 2 created and 0 updated.", out.getvalue())
+
+        with pytest.raises(CommandError):
+            call_command("hubuum_admin", "seed", f"{path}.missing")
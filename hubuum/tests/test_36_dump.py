@@ -0,0 +1,87 @@
+"""Test logical dumps, and restoring them."""
+import json
+import os
+import tempfile
+from io import StringIO
+
+import pytest
+from django.core.management import call_command
+from django.core.management.base import CommandError
+
+from hubuum.dump import FORMAT, DumpError, dump, restore
+from hubuum.models.auth import User
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host
+from hubuum.renderers import ndjson_lines
+
+from .base import HubuumModelTestCase
+
+
+class DumpTestCase(HubuumModelTestCase):
+    """Test that dumps hold all data, and restore it."""
+
+    def setUp(self):
+        """Create a host to dump."""
+        super().setUp()
+        self.host = Host.objects.create(name="web1", namespace=self.namespace)
+
+    def test_dump(self):
+        """Test the manifest, and that objects refer to groups by name."""
+        lines = list(ndjson_lines(dump()))
+        manifest = json.loads(lines[0])["manifest"]
+        self.assertEqual(manifest["format"], FORMAT)
+        self.assertEqual(manifest["models"]["hubuum.host"], 1)
+        self.assertEqual(manifest["models"]["auth.group"], 1)
+        self.assertNotIn("hubuum.tokenmetadata", manifest["models"])
+        self.assertEqual(len(lines) - 1, sum(manifest["models"].values()))
+
+        objects = [json.loads(line) for line in lines[1:]]
+        self.assertEqual(objects[0]["model"], "auth.group")
+        host = [obj for obj in objects if obj["model"] == "hubuum.host"][0]
+        self.assertEqual(host["pk"], self.host.id)
+        self.assertEqual(host["fields"]["namespace"], self.namespace.id)
+
+    def test_restore(self):
+        """Test that a restore brings back deleted objects, and numbers new ones."""
+        lines = list(ndjson_lines(dump()))
+        self.namespace.delete()
+        User.objects.filter(username="test").delete()
+
+        restored = restore(lines)
+        self.assertEqual(restored["hubuum.host"], 1)
+        self.assertEqual(Host.objects.get(name="web1").namespace.name, "test")
+        self.assertTrue(User.objects.filter(username="test").exists())
+        namespace = Namespace.objects.create(name="new")
+        self.assertGreater(namespace.id, self.namespace.id)
+
+    def test_invalid(self):
+        """Test that invalid or incomplete dumps are rejected, restoring nothing."""
+        lines = list(ndjson_lines(dump()))
+        self.host.delete()
+        for invalid in (
+            [],
+            lines[1:],
+            ['{"manifest": {"format": "other", "version": 1, "models": {}}}'],
+            [lines[0], "not json"],
+            [lines[0], '{"model": "hubuum.tokenmetadata", "pk": 1, "fields": {}}'],
+            [line for line in lines if '"hubuum.host"' not in line],
+        ):
+            with pytest.raises(DumpError):
+                restore(invalid)
+        self.assertFalse(Host.objects.exists())
+
+    def test_command(self):
+        """Test dumping to and restoring from files with hubuum_admin."""
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "dump.ndjson")
+            out = StringIO()
+            call_command("hubuum_admin", "dump", path, stdout=out, no_color=True)
+            self.assertIn(f"objects to {path}.", out.getvalue())
+
+            self.host.delete()
+            call_command("hubuum_admin", "restore", path, stdout=out, no_color=True)
+            self.assertIn("Restored 1 hubuum.host.", out.getvalue())
+            self.assertTrue(Host.objects.filter(name="web1").exists())
+
+            with pytest.raises(CommandError):
+                call_command("hubuum_admin", "restore", os.path.join(directory, "no"))
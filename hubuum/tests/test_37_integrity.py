@@ -0,0 +1,87 @@
+"""Test describing violations of the constraints of the database."""
+from django.db import IntegrityError, connection, transaction
+
+from hubuum.integrity import violation
+from hubuum.models.extensions import SchemaDefinition
+from hubuum.models.namespace import Namespace
+from hubuum.models.objects import Host, Room
+
+from .base import HubuumModelTestCase
+
+
+class IntegrityTestCase(HubuumModelTestCase):
+    """Test hubuum.integrity."""
+
+    def _violation(self, write):
+        """Return the violation of a write, checking the deferred constraints."""
+        with self.assertRaises(IntegrityError) as error, transaction.atomic():
+            write()
+            connection.check_constraints()
+        return violation(error.exception)
+
+    def test_already_exists(self):
+        """Test taking a unique name, alone and within a namespace."""
+        error = self._violation(lambda: Namespace.objects.create(name="test"))
+        self.assertEqual(error.status_code, 409)
+        self.assertEqual(error.code, "already_exists")
+        self.assertEqual(
+            error.context, {"model": "namespace", "fields": {"name": "test"}}
+        )
+        self.assertIn("A namespace with name test already exists.", error.detail)
+
+        SchemaDefinition.objects.create(
+            namespace=self.namespace, name="key", json_schema={}
+        )
+        error = self._violation(
+            lambda: SchemaDefinition.objects.create(
+                namespace=self.namespace, name="key", json_schema={}
+            )
+        )
+        self.assertEqual(
+            error.context["fields"],
+            {"namespace": str(self.namespace.id), "name": "key"},
+        )
+        self.assertIn("Use another namespace or name", error.detail)
+
+    def test_missing_reference(self):
+        """Test referring to a namespace that does not exist."""
+        missing = self.namespace.id + 1000
+        error = self._violation(
+            lambda: Host.objects.bulk_create(
+                [Host(name="orphan", namespace_id=missing)]
+            )
+        )
+        self.assertEqual(error.status_code, 400)
+        self.assertEqual(error.code, "missing_reference")
+        self.assertEqual(
+            error.context,
+            {
+                "model": "host",
+                "fields": {"namespace": str(missing)},
+                "related_model": "namespace",
+            },
+        )
+        self.assertIn(f"The namespace {missing} given as namespace", error.detail)
+
+    def test_still_referenced(self):
+        """Test deleting a room that hosts still refer to."""
+        room = Room.objects.create(room_id="101", namespace=self.namespace)
+        Host.objects.create(name="web1", namespace=self.namespace, room=room)
+        error = self._violation(lambda: Room.objects.filter(pk=room.pk).delete())
+        self.assertEqual(error.status_code, 409)
+        self.assertEqual(error.code, "still_referenced")
+        self.assertEqual(error.context["related_model"], "host")
+        self.assertIn("still referred to by hosts through room", error.detail)
+
+    def test_required(self):
+        """Test leaving out a required field, and other violations."""
+        namespaces = Namespace.objects.filter(pk=self.namespace.pk)
+        error = self._violation(lambda: namespaces.update(name=None))
+        self.assertEqual(error.status_code, 400)
+        self.assertEqual(error.code, "required")
+        self.assertEqual(error.context["fields"], {"name": None})
+
+        error = violation(IntegrityError("unknown"))
+        self.assertEqual(error.status_code, 409)
+        self.assertEqual(error.code, "constraint_violation")
+        self.assertEqual(error.context, {})
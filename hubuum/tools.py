@@ -3,10 +3,22 @@
 This package is NOT allowed to import anything from internally in hubuum.
 """
 
+import copy
+from datetime import timedelta
+from urllib.parse import urlsplit
 
+import jsonschema
 from django.apps import apps
+from django.core import signing
+from django.utils import timezone
 from rest_framework.exceptions import NotFound
 
+# The URI scheme of references to the schema registry, see SchemaDefinition.
+SCHEMA_REF_SCHEME = "hubuum"
+
+# What redacted elements of JSON documents are replaced with, see json_redact.
+REDACTED = "[redacted]"
+
 
 def get_model(model):
     """Return the model from a string. Returns None if it fails.."""
@@ -51,3 +63,252 @@ def get_object(cls, lookup_value, lookup_fields=None, raise_exception=True):
         raise NotFound()
 
     return None
+
+
+def json_diff(old, new):
+    """Compute a structured diff between two JSON documents.
+
+    Paths into the documents are joined with double underscores, the same way
+    json_data_lookup expects them, ie "dns__fqdn" or "list__0".
+
+    param: old (the original document)
+    param: new (the new document)
+
+    return: dict with the keys "added", "removed", and "changed".
+    """
+    diff = {"added": {}, "removed": {}, "changed": {}}
+    _json_diff(old, new, "", diff)
+    return diff
+
+
+def _json_path(path, key):
+    """Extend a JSON path with a key or index."""
+    if path:
+        return f"{path}__{key}"
+    return str(key)
+
+
+def _json_diff(old, new, path, diff):
+    """Recursively populate the diff between two JSON elements."""
+    if isinstance(old, dict) and isinstance(new, dict):
+        for key in sorted(old.keys() - new.keys()):
+            diff["removed"][_json_path(path, key)] = old[key]
+        for key in sorted(new.keys() - old.keys()):
+            diff["added"][_json_path(path, key)] = new[key]
+        for key in sorted(old.keys() & new.keys()):
+            _json_diff(old[key], new[key], _json_path(path, key), diff)
+    elif isinstance(old, list) and isinstance(new, list):
+        for index in range(max(len(old), len(new))):
+            if index >= len(new):
+                diff["removed"][_json_path(path, index)] = old[index]
+            elif index >= len(old):
+                diff["added"][_json_path(path, index)] = new[index]
+            else:
+                _json_diff(old[index], new[index], _json_path(path, index), diff)
+    elif old != new:
+        diff["changed"][path] = {"from": old, "to": new}
+
+
+def json_redact(data, paths, replacement=REDACTED):
+    """Redact the elements of a JSON document at the given paths.
+
+    Paths are joined with double underscores, as for json_diff, and "*" matches
+    every key of an object or index of a list. Paths that are not found are
+    ignored. The document passed is not modified.
+
+    returns: a copy of the document, with the elements found replaced
+    """
+    data = copy.deepcopy(data)
+    for path in paths:
+        data = _json_redact(data, path.split("__"), replacement)
+    return data
+
+
+def _json_redact(data, elements, replacement):
+    """Replace the elements at the path (as a list) within data."""
+    if not elements:
+        return replacement
+
+    head, rest = elements[0], elements[1:]
+    keys = []
+    if isinstance(data, dict):
+        keys = list(data) if head == "*" else [head] if head in data else []
+    elif isinstance(data, list):
+        if head == "*":
+            keys = range(len(data))
+        elif head.isdigit() and int(head) < len(data):
+            keys = [int(head)]
+
+    for key in keys:
+        data[key] = _json_redact(data[key], rest, replacement)
+    return data
+
+
+def json_values(data, path):
+    """Find the elements of a JSON document at a path.
+
+    The path is joined with double underscores, and "*" matches every index of a
+    list, as for json_redact. Paths that are not found give no elements.
+
+    returns: a list of the elements found
+    """
+    values = [data]
+    for head in path.split("__"):
+        found = []
+        for value in values:
+            if isinstance(value, dict) and head in value:
+                found.append(value[head])
+            elif isinstance(value, list) and head == "*":
+                found.extend(value)
+        values = found
+    return values
+
+
+def json_schema_refs(schema):
+    """Find every $ref in a JSON schema, recursively.
+
+    returns: a set of the references
+    """
+    refs = set()
+    if isinstance(schema, dict):
+        if isinstance(schema.get("$ref"), str):
+            refs.add(schema["$ref"])
+        for value in schema.values():
+            refs.update(json_schema_refs(value))
+    elif isinstance(schema, list):
+        for value in schema:
+            refs.update(json_schema_refs(value))
+
+    return refs
+
+
+def json_schema_external_refs(schema):
+    """Find the references in a JSON schema that are neither local nor to the registry.
+
+    Only fragments of the schema itself ("#/definitions/...") and references to the
+    schema registry ("hubuum:...") may be used, anything else would have the server
+    read files or fetch URLs.
+
+    returns: a sorted list of the references
+    """
+    return sorted(
+        ref
+        for ref in json_schema_refs(schema)
+        if not ref.startswith(("#", f"{SCHEMA_REF_SCHEME}:"))
+    )
+
+
+class RegistryRefResolver(jsonschema.RefResolver):
+    """A resolver of references that only resolves them through its handlers.
+
+    The default resolver fetches references with any other scheme itself, from files
+    or the network. Note that the resolution scope of a schema ("$id") may turn a
+    local reference into a remote one, so this is checked when resolving as well.
+    """
+
+    def resolve_remote(self, uri):
+        """Resolve a reference through the handler for its scheme.
+
+        raises: RefResolutionError if there is no handler for the scheme
+        """
+        if urlsplit(uri).scheme not in self.handlers:
+            raise jsonschema.exceptions.RefResolutionError(
+                f"References to '{uri}' are not allowed."
+            )
+        return super().resolve_remote(uri)
+
+
+def json_schema_errors(schema, data, resolve=None):
+    """Validate a JSON document against a JSON schema.
+
+    Paths to the failing elements are joined with double underscores, as for json_diff.
+    A reference that can not be resolved is reported as an error for the document.
+
+    References other than to the schema itself, or through resolve, are not resolved.
+
+    param: resolve (a callable returning the schema for a hubuum: reference)
+
+    returns: [{"path": path, "message": message}] (empty if the document is valid)
+    """
+    handlers = {SCHEMA_REF_SCHEME: resolve} if resolve is not None else {}
+    resolver = RegistryRefResolver.from_schema(schema, handlers=handlers)
+
+    validator = jsonschema.validators.validator_for(schema)(schema, resolver=resolver)
+    errors = []
+    try:
+        for error in validator.iter_errors(data):
+            path = "__".join(str(element) for element in error.absolute_path)
+            errors.append({"path": path, "message": error.message})
+    except jsonschema.exceptions.RefResolutionError as ex:
+        errors.append({"path": "", "message": f"Unresolvable reference: {ex}"})
+
+    return sorted(errors, key=lambda error: error["path"])
+
+
+def json_schema_defaults(schema, data):
+    """Apply the default values declared in a JSON schema to a JSON document.
+
+    Defaults are applied to missing properties of objects, recursively. The document
+    passed is not modified.
+
+    returns: a copy of the document with defaults applied
+    """
+    if not isinstance(schema, dict) or not isinstance(data, dict):
+        return data
+
+    data = dict(data)
+    for key, subschema in schema.get("properties", {}).items():
+        if not isinstance(subschema, dict):
+            continue
+
+        if key not in data and "default" in subschema:
+            data[key] = copy.deepcopy(subschema["default"])
+
+        if key in data:
+            data[key] = json_schema_defaults(subschema, data[key])
+
+    return data
+
+
+def json_schema_type(schema, path):
+    """Return the type a JSON schema declares for the value at a path.
+
+    The path is a list of keys of nested objects, found through "properties", or
+    indices of arrays, found through "items". References are not followed.
+
+    returns: the declared type, ie "array", or None if it is not declared
+    """
+    for key in path:
+        if not isinstance(schema, dict):
+            return None
+        if key.isdigit() and isinstance(schema.get("items"), dict):
+            schema = schema["items"]
+        else:
+            schema = schema.get("properties", {}).get(key)
+
+    if not isinstance(schema, dict):
+        return None
+    return schema.get("type")
+
+
+def sign_expiring(payload, expires_in, salt):
+    """Sign a JSON serializable payload, to be valid for expires_in seconds.
+
+    returns: (the signed value, the time it expires)
+    """
+    expires = timezone.now() + timedelta(seconds=expires_in)
+    data = {"payload": payload, "expires": expires.timestamp()}
+    return signing.dumps(data, salt=salt), expires
+
+
+def unsign_expiring(value, salt):
+    """Verify a value signed with sign_expiring, and return its payload.
+
+    raises: signing.SignatureExpired if the value has expired,
+            signing.BadSignature if the value is not validly signed.
+    """
+    data = signing.loads(value, salt=salt)
+    if data["expires"] < timezone.now().timestamp():
+        raise signing.SignatureExpired("The signed value has expired.")
+
+    return data["payload"]
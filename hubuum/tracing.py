@@ -0,0 +1,121 @@
+"""Tracing of requests and database queries.
+
+When the hubuum.database logger is enabled for debug, every query is logged to it
+with its kind, table, duration, and row count. With HUBUUM_TRACING_OTLP_ENDPOINT
+set, requests and queries are also exported as OpenTelemetry spans over OTLP, for
+backends such as Jaeger or Tempo. Exporting requires the opentelemetry-sdk and
+opentelemetry-exporter-otlp packages (hubuum[opentelemetry]). Queries are not
+wrapped at all when neither is on.
+"""
+import logging
+import re
+import time
+from contextlib import contextmanager, nullcontext
+
+import structlog
+from django.core.exceptions import ImproperlyConfigured
+from django.db import connection
+
+logger = structlog.get_logger("hubuum.database")
+
+# The OpenTelemetry tracer, set by configure_otlp.
+_tracer = None
+
+_table_regexp = re.compile(
+    r'\b(?:FROM|INTO|UPDATE|JOIN)\s+"?(?P<table>[\w.]+)"?', re.IGNORECASE
+)
+
+
+def configure_otlp(endpoint, service_name="hubuum"):
+    """Export spans to an OTLP (gRPC) endpoint.
+
+    raises: ImproperlyConfigured if the OpenTelemetry packages are not installed
+    """
+    global _tracer  # pylint: disable=global-statement
+    # pylint: disable=import-outside-toplevel
+    try:
+        from opentelemetry import trace
+        from opentelemetry.exporter.otlp.proto.grpc.trace_exporter import (
+            OTLPSpanExporter,
+        )
+        from opentelemetry.sdk.resources import SERVICE_NAME, Resource
+        from opentelemetry.sdk.trace import TracerProvider
+        from opentelemetry.sdk.trace.export import BatchSpanProcessor
+    except ImportError as ex:
+        raise ImproperlyConfigured(
+            "HUBUUM_TRACING_OTLP_ENDPOINT requires opentelemetry-sdk and "
+            "opentelemetry-exporter-otlp to be installed."
+        ) from ex
+
+    provider = TracerProvider(resource=Resource.create({SERVICE_NAME: service_name}))
+    provider.add_span_processor(BatchSpanProcessor(OTLPSpanExporter(endpoint=endpoint)))
+    trace.set_tracer_provider(provider)
+    _tracer = trace.get_tracer("hubuum")
+
+
+def span(name, **attributes):
+    """Return a context manager for a span, which does nothing unless exporting.
+
+    The context manager gives the span, or None.
+    """
+    if _tracer is None:
+        return nullcontext()
+    return _tracer.start_as_current_span(name, attributes=attributes)
+
+
+def query_kind(sql):
+    """Return the kind of a query, its first keyword (SELECT, INSERT, ...)."""
+    words = sql.split(None, 1)
+    return words[0].upper() if words else ""
+
+
+def query_table(sql):
+    """Return the (first) table a query reads from or writes to, if any."""
+    match = _table_regexp.search(sql)
+    return match.group("table") if match else ""
+
+
+def _logging_queries():
+    """Check if the hubuum.database logger is enabled for debug."""
+    return logging.getLogger("hubuum.database").isEnabledFor(logging.DEBUG)
+
+
+def trace_query(execute, sql, params, many, context):
+    """Trace a database query, see connection.execute_wrapper."""
+    kind, table = query_kind(sql), query_table(sql)
+    attributes = {"db.operation": kind, "db.sql.table": table}
+    with span(f"db.{kind.lower()}", **attributes) as query_span:
+        start = time.monotonic()
+        try:
+            result = execute(sql, params, many, context)
+        finally:
+            rows = getattr(context["cursor"], "rowcount", -1)
+            milliseconds = round((time.monotonic() - start) * 1000, 3)
+            if query_span is not None:
+                query_span.set_attribute("db.rows", rows)
+            if _logging_queries():
+                logger.debug(
+                    "query",
+                    kind=kind,
+                    table=table,
+                    rows=rows,
+                    milliseconds=milliseconds,
+                    many=many,
+                )
+
+    return result
+
+
+@contextmanager
+def trace_request(request):
+    """Trace a request, and the database queries made while handling it.
+
+    The context manager gives the span of the request, or None.
+    """
+    attributes = {"http.method": request.method, "http.target": request.path}
+    with span(f"HTTP {request.method}", **attributes) as request_span:
+        if _tracer is None and not _logging_queries():
+            yield request_span
+            return
+        with connection.execute_wrapper(trace_query):
+            yield request_span
@@ -5,12 +5,23 @@ This package is NOT allowed to import anything from internally in hubuum, except
 
 import re
 
+try:
+    from re import _parser as sre_parse
+except ImportError:  # pragma: no cover, before Python 3.11
+    import sre_parse  # pylint: disable=deprecated-module
+
+import jsonschema
 import validators
+from django.conf import settings
 from rest_framework.exceptions import ValidationError
 
-from hubuum.tools import get_model
+from hubuum.tools import get_model, json_schema_external_refs
 
 url_interpolation_regexp = re.compile("{(.*?)}")
+tag_regexp = re.compile(r"[\w.:-]+")
+
+# The longest pattern a namespace may have as a naming rule.
+NAMING_PATTERN_MAX_LENGTH = 256
 
 
 def url_interpolation_fields(url):
@@ -51,3 +62,222 @@ def validate_url(url):
         raise ValidationError({"url": f"{url} is malformed."})
 
     return True
+
+
+def validate_json_schema(schema):
+    """Validate that a JSON schema is itself valid.
+
+    Requirements:
+     - Is a dictionary (or None, for no schema).
+     - Is valid according to the draft it declares (or the latest draft).
+     - Only refers to itself, or to the schema registry.
+    """
+    if schema is None:
+        return True
+
+    if not isinstance(schema, dict):
+        raise ValidationError({"json_schema": "The schema must be a dictionary."})
+
+    try:
+        jsonschema.validators.validator_for(schema).check_schema(schema)
+    except jsonschema.exceptions.SchemaError as ex:
+        raise ValidationError({"json_schema": f"Invalid schema: {ex.message}"}) from ex
+
+    external = json_schema_external_refs(schema)
+    if external:
+        raise ValidationError(
+            {"json_schema": f"References not allowed: {', '.join(external)}."}
+        )
+
+    return True
+
+
+def validate_sensitive_paths(paths):
+    """Validate the sensitive paths of an extension.
+
+    Requirements:
+     - Is a list of non-empty strings, paths joined by double underscores.
+    """
+    if not isinstance(paths, list) or not all(
+        isinstance(path, str) and path for path in paths
+    ):
+        raise ValidationError(
+            {"sensitive_paths": "The paths must be a list of non-empty strings."}
+        )
+
+    return True
+
+
+def validate_reference_paths(paths):
+    """Validate the reference paths of an extension.
+
+    Requirements:
+     - Is a mapping of non-empty strings, paths joined by double underscores, to
+       the names of models that support extensions.
+    """
+    if not isinstance(paths, dict) or not all(
+        isinstance(path, str) and path for path in paths
+    ):
+        raise ValidationError(
+            {"reference_paths": "The paths must be a mapping of non-empty strings."}
+        )
+
+    for path, model_name in paths.items():
+        model = get_model(model_name) if isinstance(model_name, str) else None
+        if not model or not model.supports_extensions():
+            raise ValidationError(
+                {"reference_paths": f"{path}: no such model '{model_name}'."}
+            )
+
+    return True
+
+
+def deployment_naming_rules():
+    """Return the naming rules for the deployment, as set in the settings."""
+    return {
+        "pattern": settings.NAME_PATTERN,
+        "reserved": settings.RESERVED_NAMES,
+        "max_length": settings.NAME_MAX_LENGTH,
+    }
+
+
+def _ambiguous_repeats(parsed, repeated=False):
+    """Return True if a repeated part of a parsed pattern can match in several ways.
+
+    Within a repetition, another repetition, an alternation, or an optional part
+    lets the same text be matched in several ways, ie "(a+)+", "(a|a)*", or
+    "(a?)*". Failing to match then tries all of them, which takes exponential time.
+    """
+    for op, args in parsed:
+        if op in (sre_parse.MAX_REPEAT, sre_parse.MIN_REPEAT):
+            min_repeats, max_repeats, item = args
+            if repeated and (max_repeats > 1 or min_repeats != max_repeats):
+                return True
+            if _ambiguous_repeats(item, repeated or max_repeats > 1):
+                return True
+        elif op is sre_parse.SUBPATTERN:
+            if _ambiguous_repeats(args[-1], repeated):
+                return True
+        elif op in (sre_parse.ASSERT, sre_parse.ASSERT_NOT):
+            if _ambiguous_repeats(args[-1], repeated):
+                return True
+        elif op in (sre_parse.BRANCH, sre_parse.GROUPREF_EXISTS):
+            if repeated:
+                return True
+            branches = args[1] if op is sre_parse.BRANCH else args[1:]
+            if any(_ambiguous_repeats(b, repeated) for b in branches if b):
+                return True
+    return False
+
+
+def validate_naming_rules(rules):
+    """Validate a set of naming rules.
+
+    Requirements:
+     - Is a dictionary (or None, for no rules).
+     - Only has the keys pattern (a regular expression), reserved (a list of
+       strings), and max_length (a positive integer).
+     - The pattern is at most NAMING_PATTERN_MAX_LENGTH characters, and has no
+       repetitions, alternations, or optional parts within a repetition (ie
+       "(a+)+" or "(a|b)*"), as those can take exponential time to match.
+    """
+    if rules is None:
+        return True
+
+    if not isinstance(rules, dict):
+        raise ValidationError({"naming_rules": "The rules must be a dictionary."})
+
+    unknown = set(rules.keys()) - {"pattern", "reserved", "max_length"}
+    if unknown:
+        raise ValidationError({"naming_rules": f"Unknown rules: {sorted(unknown)}."})
+
+    pattern = rules.get("pattern", "")
+    try:
+        re.compile(pattern)
+    except (re.error, TypeError) as ex:
+        raise ValidationError({"naming_rules": f"Invalid pattern: {ex}"}) from ex
+
+    if len(pattern) > NAMING_PATTERN_MAX_LENGTH:
+        raise ValidationError(
+            {
+                "naming_rules": "The pattern may be at most "
+                f"{NAMING_PATTERN_MAX_LENGTH} characters."
+            }
+        )
+    if _ambiguous_repeats(sre_parse.parse(pattern)):
+        raise ValidationError(
+            {
+                "naming_rules": "The pattern may not have repetitions, alternations, "
+                "or optional parts within a repetition."
+            }
+        )
+
+    reserved = rules.get("reserved", [])
+    if not isinstance(reserved, list) or not all(isinstance(n, str) for n in reserved):
+        raise ValidationError({"naming_rules": "reserved must be a list of strings."})
+
+    max_length = rules.get("max_length", 1)
+    is_integer = isinstance(max_length, int) and not isinstance(max_length, bool)
+    if not is_integer or max_length < 1:
+        raise ValidationError(
+            {"naming_rules": "max_length must be a positive integer."}
+        )
+
+    return True
+
+
+def validate_name(name, namespace_rules=None, local_name=None):
+    """Validate a name against the naming rules.
+
+    The rules for the deployment always apply, the rules of a namespace apply in
+    addition to those. Every broken rule is reported, as a list of dictionaries
+    with the scope (deployment or namespace), the rule, and a message.
+
+    param: name (the name to validate)
+    param: namespace_rules (the rules of the namespace, None)
+    param: local_name (the name checked against the namespace rules, defaults to name)
+    """
+    errors = []
+    scopes = [("deployment", deployment_naming_rules(), name)]
+    if namespace_rules:
+        scopes.append(("namespace", namespace_rules, local_name or name))
+
+    for scope, rules, checked in scopes:
+        pattern = rules.get("pattern")
+        if pattern and not re.fullmatch(pattern, checked):
+            message = f"'{checked}' does not match the pattern '{pattern}'."
+            errors.append({"scope": scope, "rule": "pattern", "message": message})
+
+        reserved = [reserved.lower() for reserved in rules.get("reserved", [])]
+        if checked.lower() in reserved:
+            message = f"'{checked}' is a reserved name."
+            errors.append({"scope": scope, "rule": "reserved", "message": message})
+
+        max_length = rules.get("max_length")
+        if max_length and len(checked) > max_length:
+            message = f"'{checked}' is longer than {max_length} characters."
+            errors.append({"scope": scope, "rule": "max_length", "message": message})
+
+    if errors:
+        raise ValidationError({"name": errors})
+
+    return True
+
+
+def validate_tag(tag):
+    """Validate that a tag is valid.
+
+    Requirements:
+     - Is a string.
+     - Consists of letters, digits, and the characters "_.:-".
+     - Is at most 64 characters long.
+    """
+    if not isinstance(tag, str) or not re.fullmatch(tag_regexp, tag):
+        raise ValidationError(
+            {"tag": f"'{tag}' is not a valid tag, use letters, digits, and _.:-"}
+        )
+
+    if len(tag) > 64:
+        raise ValidationError({"tag": f"'{tag}' is longer than 64 characters."})
+
+    return True
@@ -0,0 +1,378 @@
+"""Configuration file support and validation for hubuum.
+
+Settings are read from HUBUUM_* environment variables, see settings.py. They may
+also be given in a YAML file named by HUBUUM_CONFIG_FILE, using the same names
+without the prefix, in lower case. Nested sections are joined with underscores,
+so the following sets HUBUUM_LOGGING_LEVEL and HUBUUM_DATABASE_HOST:
+
+    logging_level: info
+    database:
+      host: db.example.com
+
+Environment variables take precedence over the file. The combined configuration
+is validated when the settings are loaded. To only validate it, run
+`manage.py --check-config`.
+
+This module is imported by settings.py, and must not depend on Django settings.
+"""
+
+import difflib
+import os
+import re
+import sys
+
+import yaml
+from django.core.exceptions import ImproperlyConfigured
+
+from hubuum.network import parse_networks
+from hubuumsite import secret_sources
+
+PREFIX = "HUBUUM_"
+CONFIG_FILE_VARIABLE = f"{PREFIX}CONFIG_FILE"
+
+LOGGING_LEVELS = ("critical", "error", "warning", "info", "debug")
+LOGGING_SOURCES = (
+    "DJANGO",
+    "API",
+    "SIGNALS",
+    "REQUEST",
+    "MANUAL",
+    "AUTH",
+    "DATABASE",
+)
+BOOLEANS = ("", "1", "true", "yes", "0", "false", "no")
+
+# The exit code for an invalid configuration, from --check-config, the doctor
+# command, and the startup of the server.
+EXIT_CONFIGURATION = 2
+
+# Options holding secrets, which may instead be read from a source given by the
+# option <name>_SOURCE, see hubuumsite.secret_sources.
+SECRET_OPTIONS = ("DATABASE_PASSWORD", "SERVER_TLS_KEY_PASSPHRASE")
+
+
+class ConfigurationError(ImproperlyConfigured):
+    """The configuration is invalid, errors holds every problem found."""
+
+    def __init__(self, errors):
+        """Initialize the exception with a list of error messages."""
+        self.errors = errors
+        super().__init__("Invalid configuration:\n  " + "\n  ".join(errors))
+
+
+def _one_of(*choices):
+    """Validate that the value is one of the choices, ignoring case."""
+
+    def validate(value):
+        """Return an error message if the value is not a choice."""
+        if value.lower() not in choices:
+            return f"must be one of {', '.join(repr(c) for c in choices if c)}"
+        return None
+
+    return validate
+
+
+def _integer(minimum, maximum=None):
+    """Validate that the value is an integer within the given range."""
+
+    def validate(value):
+        """Return an error message if the value is not an integer in range."""
+        try:
+            number = int(value)
+        except ValueError:
+            return "must be an integer"
+        if number < minimum or (maximum is not None and number > maximum):
+            upper = f" and at most {maximum}" if maximum is not None else ""
+            return f"must be at least {minimum}{upper}"
+        return None
+
+    return validate
+
+
+def _regexp(value):
+    """Validate that the value is a regular expression."""
+    try:
+        re.compile(value)
+    except re.error as ex:
+        return f"must be a regular expression ({ex})"
+    return None
+
+
+def _networks(value):
+    """Validate that the value is a comma separated list of addresses or networks."""
+    try:
+        parse_networks(value)
+    except ValueError as ex:
+        return f"must be addresses or networks ({ex})"
+    return None
+
+
+def _secret_source(value):
+    """Validate that the value is the source of a secret."""
+    try:
+        secret_sources.parse(value)
+    except ValueError as ex:
+        return f"must be the source of a secret ({ex})"
+    return None
+
+
+def _text(value):  # pylint: disable=unused-argument
+    """Accept any value."""
+    return None
+
+
+_logging_level = _one_of(*LOGGING_LEVELS)
+
+# The known options, without the prefix, and the validation of their values.
+OPTIONS = {
+    "LOGGING_LEVEL": _logging_level,
+    **{f"LOGGING_LEVEL_{source}": _logging_level for source in LOGGING_SOURCES},
+    "LOGGING_PRODUCTION": _text,
+    "SENTRY_DSN": _text,
+    "SENTRY_LEVEL": _logging_level,
+    "TRACING_OTLP_ENDPOINT": _text,
+    "TRACING_SERVICE_NAME": _text,
+    "COMPRESS_RESPONSES": _one_of(*BOOLEANS),
+    "STRICT_FILTERS": _one_of(*BOOLEANS),
+    "EXPLAIN_PERMISSIONS": _one_of("admin", "all", "none"),
+    "NAME_PATTERN": _regexp,
+    "RESERVED_NAMES": _text,
+    "NAME_MAX_LENGTH": _integer(1),
+    "DOWNLOAD_LINK_MAX_AGE": _integer(1),
+    "STATISTICS_CACHE_TIME": _integer(0),
+    "LOGIN_HISTORY_SIZE": _integer(1),
+    "IDEMPOTENCY_WINDOW": _integer(1),
+    "READ_ONLY_CACHE_TIME": _integer(0),
+    "RUNTIME_SETTINGS_CACHE_TIME": _integer(0),
+    "DENIAL_TRACKING_SIZE": _integer(1),
+    "JSON_SEARCH_CONCURRENCY": _integer(0),
+    "JSON_SEARCH_QUEUE_TIMEOUT": _integer(0),
+    "JSON_SEARCH_RETRY_AFTER": _integer(1),
+    "JSON_SEARCH_SLOW_TIME": _integer(0),
+    "NAMESPACE_CLONE_SIZE": _integer(0),
+    "PAGE_SIZE": _integer(1),
+    "MAX_PAGE_SIZE": _integer(1),
+    "OUTBOX_BACKEND": _text,
+    "OUTBOX_URL": _text,
+    "OUTBOX_TOPIC": _text,
+    "DATABASE_BACKEND": _text,
+    "DATABASE_NAME": _text,
+    "DATABASE_USER": _text,
+    "DATABASE_PASSWORD": _text,
+    "DATABASE_PASSWORD_SOURCE": _secret_source,
+    "DATABASE_HOST": _text,
+    "DATABASE_PORT": _integer(1, 65535),
+    "DATABASE_SCHEMA": _text,
+    "CACHE_BACKEND": _text,
+    "CACHE_LOCATION": _text,
+    "SERVER_WORKERS": _integer(0),
+    "SERVER_KEEPALIVE": _integer(0),
+    "SERVER_TIMEOUT": _integer(0),
+    "SERVER_MAX_CONNECTIONS": _integer(1),
+    "SERVER_SELF_CHECK": _one_of(*BOOLEANS),
+    "SERVER_TLS_CERT": _text,
+    "SERVER_TLS_KEY": _text,
+    "SERVER_TLS_KEY_PASSPHRASE": _text,
+    "SERVER_TLS_KEY_PASSPHRASE_SOURCE": _secret_source,
+    "SECRET_REFRESH": _integer(0),
+    "IP_ALLOW": _networks,
+    "IP_DENY": _networks,
+    "IP_ADMIN_ALLOW": _networks,
+    "IP_ADMIN_ROUTES": _text,
+    "IP_TRUSTED_PROXIES": _networks,
+    "SEED_FILE": _text,
+    "UNAUTHORIZED_STATUS": _one_of("403", "404"),
+}
+
+
+def _flatten(data, path=""):
+    """Flatten nested sections into option names and string values.
+
+    Booleans become "true" or "", lists become comma separated strings, and null
+    values are dropped.
+    """
+    values = {}
+    for key, value in data.items():
+        name = f"{path}_{key}" if path else str(key)
+        if isinstance(value, dict):
+            values.update(_flatten(value, name))
+        elif isinstance(value, bool):
+            values[name.upper()] = "true" if value else ""
+        elif isinstance(value, list):
+            values[name.upper()] = ",".join(str(item) for item in value)
+        elif value is not None:
+            values[name.upper()] = str(value)
+    return values
+
+
+def read_config_file(path):
+    """Read a configuration file, returning the options it sets.
+
+    param: path (the path to the YAML file)
+
+    returns: dict of option names (without the prefix) to string values
+    raises: ConfigurationError if the file can't be read or has unknown options
+    """
+    try:
+        with open(path, encoding="utf-8") as config_file:
+            data = yaml.safe_load(config_file)
+    except (OSError, yaml.YAMLError) as ex:
+        raise ConfigurationError([f"{path}: {ex}"]) from ex
+
+    if data is None:
+        return {}
+    if not isinstance(data, dict):
+        raise ConfigurationError([f"{path}: expected a mapping of options"])
+
+    values = _flatten(data)
+    errors = []
+    for name in values:
+        if name not in OPTIONS:
+            error = f"{path}: unknown option '{name.lower()}'"
+            close = difflib.get_close_matches(name, OPTIONS, n=1)
+            if close:
+                error += f", did you mean '{close[0].lower()}'?"
+            errors.append(error)
+
+    if errors:
+        raise ConfigurationError(errors)
+
+    return values
+
+
+def validate(environ):
+    """Validate the HUBUUM_* variables of an environment.
+
+    returns: a list of error messages, empty if the configuration is valid
+    """
+    errors = []
+    for name, validator in OPTIONS.items():
+        value = environ.get(f"{PREFIX}{name}")
+        if value is None:
+            continue
+        error = validator(value)
+        if error:
+            errors.append(f"{PREFIX}{name} ({name.lower()}) {error}, got '{value}'")
+
+    if environ.get(f"{PREFIX}SENTRY_LEVEL") and not environ.get(f"{PREFIX}SENTRY_DSN"):
+        errors.append(f"{PREFIX}SENTRY_LEVEL requires {PREFIX}SENTRY_DSN to be set")
+
+    for name in SECRET_OPTIONS:
+        if environ.get(f"{PREFIX}{name}") and environ.get(f"{PREFIX}{name}_SOURCE"):
+            errors.append(f"{PREFIX}{name} and {PREFIX}{name}_SOURCE are both set")
+
+    tls = ("KEY", "KEY_PASSPHRASE", "KEY_PASSPHRASE_SOURCE")
+    if any(environ.get(f"{PREFIX}SERVER_TLS_{name}") for name in tls):
+        if not environ.get(f"{PREFIX}SERVER_TLS_CERT"):
+            errors.append(f"{PREFIX}SERVER_TLS_KEY* requires {PREFIX}SERVER_TLS_CERT")
+
+    sizes = [environ.get(f"{PREFIX}{name}") for name in ("PAGE_SIZE", "MAX_PAGE_SIZE")]
+    if all(size and size.isdigit() for size in sizes):
+        if int(sizes[0]) > int(sizes[1]):
+            errors.append(f"{PREFIX}PAGE_SIZE is above {PREFIX}MAX_PAGE_SIZE")
+
+    backend = environ.get(f"{PREFIX}DATABASE_BACKEND", "django.db.backends.postgresql")
+    if environ.get(f"{PREFIX}DATABASE_SCHEMA") and "postgresql" not in backend:
+        errors.append(f"{PREFIX}DATABASE_SCHEMA requires a PostgreSQL database backend")
+
+    return errors
+
+
+def load(environ=None):
+    """Merge the configuration file (if any) into the environment and validate it.
+
+    param: environ (the environment to use, defaults to os.environ)
+
+    raises: ConfigurationError if the configuration is invalid
+    """
+    if environ is None:
+        environ = os.environ
+
+    path = environ.get(CONFIG_FILE_VARIABLE)
+    if path:
+        for name, value in read_config_file(path).items():
+            environ.setdefault(f"{PREFIX}{name}", value)
+
+    errors = validate(environ)
+    if errors:
+        raise ConfigurationError(errors)
+
+
+def server_options(environ=None):
+    """Return the effective tuning of the application server.
+
+    Used by settings.py (as SERVER) and hubuumsite/gunicorn.conf.py. Zero workers
+    (the default) means two per CPU, plus one.
+
+    returns: dict with workers, keepalive, timeout (seconds), max_connections, and
+             self_check (run manage.py doctor --startup before serving, on by
+             default)
+    """
+    if environ is None:
+        environ = os.environ
+
+    workers = int(environ.get(f"{PREFIX}SERVER_WORKERS", 0))
+    self_check = environ.get(f"{PREFIX}SERVER_SELF_CHECK", "true")
+    return {
+        "workers": workers or (os.cpu_count() or 1) * 2 + 1,
+        "keepalive": int(environ.get(f"{PREFIX}SERVER_KEEPALIVE", 5)),
+        "timeout": int(environ.get(f"{PREFIX}SERVER_TIMEOUT", 30)),
+        "max_connections": int(environ.get(f"{PREFIX}SERVER_MAX_CONNECTIONS", 1000)),
+        "self_check": self_check.lower() in ("1", "true", "yes"),
+    }
+
+
+def secret(name, environ=None):
+    """Return the value of a secret option, given inline or by its source.
+
+    param: name (one of SECRET_OPTIONS)
+    param: environ (the environment to use, defaults to os.environ)
+
+    returns: the value, a secret_sources.Secret (refreshed every
+             HUBUUM_SECRET_REFRESH seconds), or None if not set
+    """
+    if environ is None:
+        environ = os.environ
+
+    source = environ.get(f"{PREFIX}{name}_SOURCE")
+    if source:
+        refresh = int(environ.get(f"{PREFIX}SECRET_REFRESH", 0))
+        return secret_sources.Secret(source, refresh=refresh)
+    return environ.get(f"{PREFIX}{name}")
+
+
+def tls_options(environ=None):
+    """Return the TLS configuration of the application server.
+
+    Used by hubuumsite/gunicorn.conf.py. TLS is enabled if a certificate is given,
+    the key defaults to being in the certificate file.
+
+    returns: dict with cert, key (paths), and passphrase (see secret), or None
+    """
+    if environ is None:
+        environ = os.environ
+
+    cert = environ.get(f"{PREFIX}SERVER_TLS_CERT")
+    if not cert:
+        return None
+    return {
+        "cert": cert,
+        "key": environ.get(f"{PREFIX}SERVER_TLS_KEY") or cert,
+        "passphrase": secret("SERVER_TLS_KEY_PASSPHRASE", environ),
+    }
+
+
+def check(environ=None, stdout=sys.stdout):
+    """Validate the configuration and report the result, for --check-config.
+
+    returns: the exit code, 0 if the configuration is valid, else EXIT_CONFIGURATION
+    """
+    try:
+        # Validate a copy, checking does not change the environment.
+        load(dict(os.environ if environ is None else environ))
+    except ConfigurationError as ex:
+        stdout.write(f"{ex}\n")
+        return EXIT_CONFIGURATION
+
+    stdout.write("Configuration OK\n")
+    return 0
@@ -0,0 +1,86 @@
+"""Configuration for serving hubuum with gunicorn.
+
+    gunicorn -c hubuumsite/gunicorn.conf.py
+
+The tuning is read from the same HUBUUM_* variables (or configuration file) as the
+settings, see hubuumsite.config.server_options:
+
+  - HUBUUM_SERVER_WORKERS: worker processes, defaults to two per CPU, plus one.
+    Every worker holds its own database connection(s) and in-memory caches (ie the
+    metrics and statistics), so more workers means more connections to PostgreSQL.
+  - HUBUUM_SERVER_TIMEOUT: seconds a request may take before its worker is
+    restarted. Raise it when streaming large lists (?format=ndjson).
+  - HUBUUM_SERVER_KEEPALIVE: seconds to keep idle client connections open.
+  - HUBUUM_SERVER_MAX_CONNECTIONS: concurrent clients per worker.
+
+The default (sync) worker class serves one request at a time per worker, and does
+not keep connections alive. Keep-alive and max connections apply to the async
+worker classes (ie gevent), selected with --worker-class.
+
+TLS is served directly when HUBUUM_SERVER_TLS_CERT is set, see
+hubuumsite.config.tls_options:
+
+  - HUBUUM_SERVER_TLS_CERT: path to the certificate (chain).
+  - HUBUUM_SERVER_TLS_KEY: path to the key, defaults to the certificate file.
+  - HUBUUM_SERVER_TLS_KEY_PASSPHRASE: passphrase of an encrypted key, or read from
+    the source in HUBUUM_SERVER_TLS_KEY_PASSPHRASE_SOURCE (file:<path>, env:<name>,
+    or vault:<mount>/<path>#<key>), see hubuumsite/secret_sources.py.
+
+Before serving, the configuration, database, migrations, and TLS material are
+checked (manage.py doctor --startup), unless HUBUUM_SERVER_SELF_CHECK is false. A
+failed check is reported, and the server exits with the code of the check, see
+hubuum/management/commands/doctor.py. An invalid configuration exits with 2.
+"""
+import os
+import ssl
+import sys
+
+import hubuumsite.config
+
+os.environ.setdefault("DJANGO_SETTINGS_MODULE", "hubuumsite.settings")
+try:
+    hubuumsite.config.load()
+except hubuumsite.config.ConfigurationError as _ex:
+    sys.stderr.write(f"{_ex}\n")
+    sys.exit(hubuumsite.config.EXIT_CONFIGURATION)
+_options = hubuumsite.config.server_options()
+
+wsgi_app = "hubuumsite.wsgi:application"
+workers = _options["workers"]
+timeout = _options["timeout"]
+keepalive = _options["keepalive"]
+worker_connections = _options["max_connections"]
+
+_tls = hubuumsite.config.tls_options()
+if _tls:
+    certfile = _tls["cert"]
+    keyfile = _tls["key"]
+
+if _tls and _tls["passphrase"]:
+
+    # pylint: disable-next=unused-argument
+    def ssl_context(conf, default_ssl_context_factory):
+        """Load the certificate with an encrypted key, read when (re)loaded."""
+        context = ssl.create_default_context(ssl.Purpose.CLIENT_AUTH)
+        context.load_cert_chain(
+            conf.certfile, conf.keyfile, password=lambda: str(_tls["passphrase"])
+        )
+        return context
+
+
+def on_starting(server):
+    """Run the startup self-check before serving, exiting if a check fails."""
+    if not _options["self_check"]:
+        return
+
+    # pylint: disable=import-outside-toplevel
+    import django
+    from django.core.management import call_command
+    from django.core.management.base import CommandError
+
+    django.setup()
+    try:
+        call_command("doctor", "--startup")
+    except CommandError as ex:
+        server.log.error("Startup self-check failed: %s", ex)
+        sys.exit(ex.returncode)
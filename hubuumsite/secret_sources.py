@@ -0,0 +1,138 @@
+"""Secrets read from external sources, rather than given inline in the environment.
+
+A source is given as <kind>:<reference>:
+
+  - file:<path>, the contents of a file (ie a Docker or Kubernetes secret), without
+    trailing newlines.
+  - env:<name>, the value of another environment variable.
+  - vault:<mount>/<path>#<key>, a key of a secret in a HashiCorp Vault KV (version 2)
+    secrets engine, ie vault:secret/hubuum/database#password. This requires the
+    hvac package (pip install hubuum[vault]), which finds the Vault server and
+    token in VAULT_ADDR and VAULT_TOKEN.
+
+Secrets are read when first used. With a refresh interval, they are read again
+when used after the interval has passed, so rotated secrets are picked up without
+restarting, ie when opening new database connections.
+
+This module is imported by hubuumsite.config, and must not depend on Django settings.
+"""
+import os
+import time
+
+from django.core.exceptions import ImproperlyConfigured
+
+KINDS = ("file", "env", "vault")
+
+
+def parse(source):
+    """Split a source into its kind and reference.
+
+    returns: (kind, reference)
+    raises: ValueError if the source is malformed
+    """
+    kind, separator, reference = source.partition(":")
+    if not separator or kind not in KINDS or not reference:
+        raise ValueError(f"expected one of {', '.join(f'{k}:...' for k in KINDS)}")
+
+    if kind == "vault":
+        location, _, key = reference.partition("#")
+        mount, _, path = location.partition("/")
+        if not (mount and path and key):
+            raise ValueError("expected vault:<mount>/<path>#<key>")
+
+    return kind, reference
+
+
+def read(source):
+    """Read a secret from its source.
+
+    raises: ValueError if the source is malformed
+    raises: ImproperlyConfigured if the secret can not be read
+    """
+    kind, reference = parse(source)
+    return _READERS[kind](reference)
+
+
+def _read_file(path):
+    """Read a secret from a file."""
+    try:
+        with open(path, encoding="utf-8") as secret_file:
+            return secret_file.read().rstrip("\r\n")
+    except OSError as ex:
+        raise ImproperlyConfigured(f"Can not read the secret in {path}: {ex}") from ex
+
+
+def _read_env(name):
+    """Read a secret from an environment variable."""
+    value = os.environ.get(name)
+    if value is None:
+        raise ImproperlyConfigured(f"The secret variable {name} is not set.")
+    return value
+
+
+def _read_vault(reference):
+    """Read a secret from a Vault KV (version 2) secrets engine."""
+    # pylint: disable=import-outside-toplevel
+    try:
+        import hvac
+    except ImportError as ex:
+        raise ImproperlyConfigured(
+            "Secrets in Vault require the hvac package to be installed."
+        ) from ex
+
+    location, _, key = reference.partition("#")
+    mount, _, path = location.partition("/")
+    try:
+        response = hvac.Client().secrets.kv.v2.read_secret_version(
+            path=path, mount_point=mount
+        )
+        return response["data"]["data"][key]
+    except (hvac.exceptions.VaultError, KeyError, OSError) as ex:
+        raise ImproperlyConfigured(
+            f"Can not read the secret {reference} from Vault: {ex!r}"
+        ) from ex
+
+
+_READERS = {"file": _read_file, "env": _read_env, "vault": _read_vault}
+
+
+class Secret:
+    """A secret read from a source, and read again after the refresh interval.
+
+    str() gives the value of the secret, so it may be used where a string is
+    expected. Database drivers convert the password with str() for every new
+    connection, so a refreshed password is used for new connections.
+    """
+
+    def __init__(self, source, refresh=0):
+        """Initialize the secret, nothing is read until it is used.
+
+        param: source (see the module documentation)
+        param: refresh (seconds before reading the secret again, 0 for never)
+        """
+        self.source = source
+        self.refresh = refresh
+        self._value = None
+        self._read_at = None
+
+    def value(self):
+        """Return the value of the secret, reading it if due."""
+        now = time.monotonic()
+        if self._read_at is None or (
+            self.refresh and now - self._read_at >= self.refresh
+        ):
+            self._value = read(self.source)
+            self._read_at = now
+        return self._value
+
+    def __str__(self):
+        """Return the value of the secret."""
+        return self.value()
+
+    def __bool__(self):
+        """Secrets are set, even if their value is not read yet."""
+        return True
+
+    def __repr__(self):
+        """Represent the secret by its source, never by its value."""
+        return f"Secret({self.source!r})"
@@ -20,11 +20,16 @@ import structlog
 from structlog_sentry import SentryProcessor
 
 import hubuum.log
+import hubuumsite.config
+
+# Settings are read from HUBUUM_* environment variables, optionally backed by the YAML
+# file named by HUBUUM_CONFIG_FILE. The result is validated, see hubuumsite/config.py.
+hubuumsite.config.load()
 
 LOGGING_LEVEL = os.environ.get("HUBUUM_LOGGING_LEVEL", "critical").upper()
 LOGGING_LEVEL_SOURCE = {}
 
-for source in ["DJANGO", "API", "SIGNALS", "REQUEST", "MANUAL", "AUTH"]:
+for source in hubuumsite.config.LOGGING_SOURCES:
     LOGGING_LEVEL_SOURCE[source] = os.environ.get(
         f"HUBUUM_LOGGING_LEVEL_{source}", LOGGING_LEVEL
     ).upper()
@@ -42,6 +47,12 @@ if SENTRY_DSN:
         traces_sample_rate=1.0,
     )
 
+# Export traces of requests and database queries to an OTLP endpoint, such as
+# Jaeger or Tempo, see hubuum/tracing.py. Queries are always logged to the
+# hubuum.database logger, at debug level (see HUBUUM_LOGGING_LEVEL_DATABASE).
+TRACING_OTLP_ENDPOINT = os.environ.get("HUBUUM_TRACING_OTLP_ENDPOINT", "")
+TRACING_SERVICE_NAME = os.environ.get("HUBUUM_TRACING_SERVICE_NAME", "hubuum")
+
 # from rest_framework.settings import api_settings
 
 # Build paths inside the project like this: BASE_DIR / 'subdir'.
@@ -81,7 +92,15 @@ AUTH_USER_MODEL = "hubuum.User"
 
 MIDDLEWARE = [
     "django_structlog.middlewares.RequestMiddleware",
+    "hubuum.middleware.normalize_path.NormalizePathMiddleware",
+    "hubuum.middleware.runtime_settings.RuntimeSettingsMiddleware",
+    "hubuum.middleware.tracing.TracingMiddleware",
+    "hubuum.middleware.client_ip.ClientIPMiddleware",
+    "hubuum.middleware.actor.ActorMiddleware",
     "hubuum.middleware.logging_http.LogHttpResponseMiddleware",
+    "hubuum.middleware.ip_filter.IPFilterMiddleware",
+    "hubuum.middleware.compression.DecompressRequestMiddleware",
+    "hubuum.middleware.read_only.ReadOnlyMiddleware",
     "django.middleware.security.SecurityMiddleware",
     "django.contrib.sessions.middleware.SessionMiddleware",
     "django.middleware.common.CommonMiddleware",
@@ -89,8 +108,131 @@ MIDDLEWARE = [
     "django.contrib.auth.middleware.AuthenticationMiddleware",
     "django.contrib.messages.middleware.MessageMiddleware",
     "django.middleware.clickjacking.XFrameOptionsMiddleware",
+    "hubuum.middleware.explain_permissions.ExplainPermissionsMiddleware",
+    "hubuum.middleware.metrics.MetricsMiddleware",
+]
+
+# Compress responses with gzip for clients that accept it. This is off by default,
+# as compressing responses that contain secrets (tokens) opens for BREACH-style attacks.
+# Compressed (gzip or deflate) request bodies are always accepted.
+COMPRESS_RESPONSES = os.environ.get("HUBUUM_COMPRESS_RESPONSES", "").lower() in [
+    "1",
+    "true",
+    "yes",
+]
+if COMPRESS_RESPONSES:
+    # Outermost, so everything else sees the uncompressed response.
+    MIDDLEWARE.insert(0, "django.middleware.gzip.GZipMiddleware")
+
+# Who may ask for an explanation of permission checks with ?explain_permissions=true.
+# One of "admin" (staff and superusers), "all" (all authenticated users), or "none".
+EXPLAIN_PERMISSIONS = os.environ.get("HUBUUM_EXPLAIN_PERMISSIONS", "admin").lower()
+
+# Rules for the names of namespaces and namespaced objects, for the whole deployment.
+# Namespaces may add their own rules, see Namespace.naming_rules.
+#  - HUBUUM_NAME_PATTERN: a regular expression names must match in full.
+#  - HUBUUM_RESERVED_NAMES: a comma separated list of names that can't be used.
+#  - HUBUUM_NAME_MAX_LENGTH: the maximum length of a name.
+NAME_PATTERN = os.environ.get("HUBUUM_NAME_PATTERN", "")
+RESERVED_NAMES = [
+    name.strip()
+    for name in os.environ.get("HUBUUM_RESERVED_NAMES", "").split(",")
+    if name.strip()
+]
+NAME_MAX_LENGTH = int(os.environ.get("HUBUUM_NAME_MAX_LENGTH", 255))
+
+# The longest time, in seconds, a signed download link may be valid for.
+DOWNLOAD_LINK_MAX_AGE = int(os.environ.get("HUBUUM_DOWNLOAD_LINK_MAX_AGE", 3600))
+
+# How long, in seconds, the statistics of a model (/stats/<model>) are cached.
+STATISTICS_CACHE_TIME = int(os.environ.get("HUBUUM_STATISTICS_CACHE_TIME", 60))
+
+# The number of logins kept in the login history of every user (/api/auth/logins/).
+LOGIN_HISTORY_SIZE = int(os.environ.get("HUBUUM_LOGIN_HISTORY_SIZE", 100))
+
+# How long, in seconds, the responses to create requests with an Idempotency-Key are
+# kept, so that retried requests get the same response rather than a duplicate.
+IDEMPOTENCY_WINDOW = int(os.environ.get("HUBUUM_IDEMPOTENCY_WINDOW", 86400))
+
+# The number of combinations of user, method, route, namespace, and status that
+# denied requests are counted for (see /api/denials/), per process.
+DENIAL_TRACKING_SIZE = int(os.environ.get("HUBUUM_DENIAL_TRACKING_SIZE", 1000))
+
+# How long, in seconds, the read-only (maintenance) modes of the server and of the
+# namespaces are cached (see /api/read-only/). Changes made through other processes
+# apply within this time.
+READ_ONLY_CACHE_TIME = int(os.environ.get("HUBUUM_READ_ONLY_CACHE_TIME", 10))
+
+# How long, in seconds, the settings changed at runtime are cached (see
+# /api/runtime-settings/). Changes made through other processes apply within this
+# time.
+RUNTIME_SETTINGS_CACHE_TIME = int(
+    os.environ.get("HUBUUM_RUNTIME_SETTINGS_CACHE_TIME", 10)
+)
+
+# The budget for JSON searches (the json_data filters of extension data), per
+# process, see hubuum.search:
+#  - HUBUUM_JSON_SEARCH_CONCURRENCY: the searches run at once, 0 for no limit.
+#  - HUBUUM_JSON_SEARCH_QUEUE_TIMEOUT: the seconds searches wait to run, if busy.
+#  - HUBUUM_JSON_SEARCH_RETRY_AFTER: the seconds clients told 503 should wait.
+#  - HUBUUM_JSON_SEARCH_SLOW_TIME: searches taking longer, in milliseconds, are
+#    logged as slow.
+JSON_SEARCH_CONCURRENCY = int(os.environ.get("HUBUUM_JSON_SEARCH_CONCURRENCY", 4))
+JSON_SEARCH_QUEUE_TIMEOUT = int(os.environ.get("HUBUUM_JSON_SEARCH_QUEUE_TIMEOUT", 2))
+JSON_SEARCH_RETRY_AFTER = int(os.environ.get("HUBUUM_JSON_SEARCH_RETRY_AFTER", 5))
+JSON_SEARCH_SLOW_TIME = int(os.environ.get("HUBUUM_JSON_SEARCH_SLOW_TIME", 1000))
+
+# Namespaces with more objects than this are cloned in the background, by
+# `manage.py clone_namespaces` (see POST /api/v1/namespaces/<namespace>/clone).
+NAMESPACE_CLONE_SIZE = int(os.environ.get("HUBUUM_NAMESPACE_CLONE_SIZE", 1000))
+
+# The number of objects in a page of a list when the client does not ask for a
+# page size (?page_size=), and the largest page size clients may ask for. Larger
+# page sizes get 400.
+PAGE_SIZE = int(os.environ.get("HUBUUM_PAGE_SIZE", 100))
+MAX_PAGE_SIZE = int(os.environ.get("HUBUUM_MAX_PAGE_SIZE", 200))
+
+# Reject unknown filters, and filters without a value, with 400 rather than ignoring
+# them. Requests may ask for this with ?strict=true, or opt out with ?strict=false.
+STRICT_FILTERS = os.environ.get("HUBUUM_STRICT_FILTERS", "").lower() in [
+    "1",
+    "true",
+    "yes",
 ]
 
+# Tuning of the application server (HUBUUM_SERVER_*), see hubuumsite/gunicorn.conf.py.
+# The effective values are reported by /api/server-info/.
+SERVER = hubuumsite.config.server_options()
+
+# Access by the address of the client, see hubuum/middleware/ip_filter.py. All are
+# comma separated lists of addresses or networks (CIDR), empty by default.
+#  - HUBUUM_IP_ALLOW: only allow clients from these networks.
+#  - HUBUUM_IP_DENY: deny clients from these networks.
+#  - HUBUUM_IP_ADMIN_ALLOW: only allow clients from these networks to the
+#    administrative endpoints, given in HUBUUM_IP_ADMIN_ROUTES as the names of
+#    their routes, or of the namespaces of their routes (ie admin, the admin site).
+#  - HUBUUM_IP_TRUSTED_PROXIES: trust X-Forwarded-For from these proxies.
+IP_ALLOW = os.environ.get("HUBUUM_IP_ALLOW", "")
+IP_DENY = os.environ.get("HUBUUM_IP_DENY", "")
+IP_ADMIN_ALLOW = os.environ.get("HUBUUM_IP_ADMIN_ALLOW", "")
+IP_ADMIN_ROUTES = tuple(
+    route.strip()
+    for route in os.environ.get(
+        "HUBUUM_IP_ADMIN_ROUTES",
+        "admin,server_info,maintenance,read_only,read_only_namespace,denials,dump,"
+        "seed,runtime_settings",
+    ).split(",")
+    if route.strip()
+)
+IP_TRUSTED_PROXIES = os.environ.get("HUBUUM_IP_TRUSTED_PROXIES", "")
+
+# The status for requests denied access to an object the user can't read: 403, or
+# 404 to not reveal that the object exists.
+UNAUTHORIZED_STATUS = int(os.environ.get("HUBUUM_UNAUTHORIZED_STATUS", 403))
+
+# A seed file applied when the application starts, see hubuum/seed.py.
+SEED_FILE = os.environ.get("HUBUUM_SEED_FILE", "")
+
 ROOT_URLCONF = "hubuumsite.urls"
 
 TEMPLATES = [
@@ -114,11 +256,27 @@ WSGI_APPLICATION = "hubuumsite.wsgi.application"
 DEFAULT_AUTO_FIELD = "django.db.models.AutoField"
 
 REST_FRAMEWORK = {
-    "DEFAULT_AUTHENTICATION_CLASSES": ("knox.auth.TokenAuthentication",),
+    "DEFAULT_AUTHENTICATION_CLASSES": (
+        "hubuum.api.authentication.ActorTokenAuthentication",
+    ),
     "DEFAULT_PERMISSION_CLASSES": ["rest_framework.permissions.IsAuthenticated"],
     "DEFAULT_FILTER_BACKENDS": ("django_filters.rest_framework.DjangoFilterBackend",),
+    # YAML is negotiated by Content-Type (requests) and Accept or ?format=yaml.
+    "DEFAULT_PARSER_CLASSES": (
+        "rest_framework.parsers.JSONParser",
+        "hubuum.parsers.YAMLParser",
+        "rest_framework.parsers.FormParser",
+        "rest_framework.parsers.MultiPartParser",
+    ),
+    "DEFAULT_RENDERER_CLASSES": (
+        "rest_framework.renderers.JSONRenderer",
+        "rest_framework.renderers.BrowsableAPIRenderer",
+        "hubuum.renderers.YAMLRenderer",
+    ),
     "TEST_REQUEST_DEFAULT_FORMAT": "json",
     "DEFAULT_PAGINATION_CLASS": "hubuum.pagination.HubuumFlexiblePagination",
+    # Violations of database constraints get errors users can act on.
+    "EXCEPTION_HANDLER": "hubuum.integrity.exception_handler",
 }
 
 AUTHENTICATION_BACKENDS = (
@@ -132,6 +290,12 @@ REST_KNOX = {
 
 # Database
 # https://docs.djangoproject.com/en/3.1/ref/settings/#databases
+#
+# The password is given in HUBUUM_DATABASE_PASSWORD, or read from the source in
+# HUBUUM_DATABASE_PASSWORD_SOURCE (file:<path>, env:<name>, or
+# vault:<mount>/<path>#<key>), see hubuumsite/secret_sources.py. Secrets from
+# sources are read again every HUBUUM_SECRET_REFRESH seconds (default 0, never),
+# so a rotated password is used for new connections.
 
 # DATABASES = {
 #    'default': {
@@ -147,12 +311,47 @@ DATABASES = {
         ),
         "NAME": os.environ.get("HUBUUM_DATABASE_NAME", "hubuum"),
         "USER": os.environ.get("HUBUUM_DATABASE_USER", "hubuum"),
-        "PASSWORD": os.environ.get("HUBUUM_DATABASE_PASSWORD"),
+        "PASSWORD": hubuumsite.config.secret("DATABASE_PASSWORD"),
         "HOST": os.environ.get("HUBUUM_DATABASE_HOST", "localhost"),
         "PORT": int(os.environ.get("HUBUUM_DATABASE_PORT", 5432)),
     }
 }
 
+# To run against a non-default (PostgreSQL) schema, set HUBUUM_DATABASE_SCHEMA.
+# The schema must exist, and is placed first in the search_path of every connection,
+# so tables are both created and looked up within it.
+DATABASE_SCHEMA = os.environ.get("HUBUUM_DATABASE_SCHEMA", "")
+
+if DATABASE_SCHEMA:
+    DATABASES["default"]["OPTIONS"] = {
+        "options": f"-c search_path={DATABASE_SCHEMA},public"
+    }
+
+# Changes to objects are written to an outbox and published to external systems
+# (see hubuum.outbox and the publish_outbox command) if HUBUUM_OUTBOX_BACKEND is set.
+# Requests are then atomic, so events are recorded in the transaction of the change.
+OUTBOX_BACKEND = os.environ.get("HUBUUM_OUTBOX_BACKEND", "")
+OUTBOX_URL = os.environ.get("HUBUUM_OUTBOX_URL", "")
+OUTBOX_TOPIC = os.environ.get("HUBUUM_OUTBOX_TOPIC", "hubuum")
+
+if OUTBOX_BACKEND:
+    DATABASES["default"]["ATOMIC_REQUESTS"] = True
+
+# The cache, which holds the write counts of namespaces (write_rate_limit), the
+# statistics, the read-only modes, and the settings changed at runtime. The default,
+# local memory, is per process: with several server processes, each counts writes on
+# its own and allows the full rate. Use a cache shared by the processes then, ie
+# HUBUUM_CACHE_BACKEND=django.core.cache.backends.redis.RedisCache and
+# HUBUUM_CACHE_LOCATION=redis://localhost:6379.
+CACHES = {
+    "default": {
+        "BACKEND": os.environ.get(
+            "HUBUUM_CACHE_BACKEND", "django.core.cache.backends.locmem.LocMemCache"
+        ),
+        "LOCATION": os.environ.get("HUBUUM_CACHE_LOCATION", ""),
+    }
+}
+
 # Password validation
 # https://docs.djangoproject.com/en/3.1/ref/settings/#auth-password-validators
 
@@ -285,5 +484,10 @@ LOGGING = {
             "level": LOGGING_LEVEL_SOURCE["MANUAL"],
             "propagate": False,
         },
+        "hubuum.database": {
+            "handlers": ["console"],
+            "level": LOGGING_LEVEL_SOURCE["DATABASE"],
+            "propagate": False,
+        },
     },
 }
@@ -9,8 +9,15 @@ https://docs.djangoproject.com/en/3.1/howto/deployment/wsgi/
 
 import os
 
+from django.conf import settings
 from django.core.wsgi import get_wsgi_application
 
-os.environ.setdefault("DJANGO_SETTINGS_MODULE", "mysite.settings")
+os.environ.setdefault("DJANGO_SETTINGS_MODULE", "hubuumsite.settings")
 
 application = get_wsgi_application()
+
+# Apply the seed file, if any, now that the models are loaded.
+if settings.SEED_FILE:
+    from hubuum import seed
+
+    seed.apply(seed.read_file(settings.SEED_FILE))
@@ -6,6 +6,11 @@ import sys
 
 def main():
     """Run administrative tasks."""
+    if "--check-config" in sys.argv[1:]:
+        from hubuumsite.config import check  # pylint: disable=import-outside-toplevel
+
+        sys.exit(check())
+
     os.environ.setdefault("DJANGO_SETTINGS_MODULE", "hubuumsite.settings")
     try:
         from django.core.management import execute_from_command_line